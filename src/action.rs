@@ -1,17 +1,237 @@
 use crossterm::event::{KeyCode, KeyModifiers};
 
-use crate::domain::types::{PrContext, PrListItem, RepoListItem, ReviewAction, Story};
+use crate::domain::cache::CacheEntry;
+use crate::domain::stats::RateLimit;
+use crate::domain::types::{
+    BlameRange, CommitRange, DeepDive, Listing, Page, PrActivity, PrCommit, PrContext, PrListItem,
+    PrStateFilter, ProgressEvent, QueueItem, RepoListItem, ReviewAction, Story, ViewedFiles,
+};
 
 #[derive(Debug)]
 pub enum Action {
     Input { code: KeyCode, modifiers: KeyModifiers },
-    RepoListLoaded(Result<Vec<RepoListItem>, String>),
-    PrListLoaded(Result<Vec<PrListItem>, String>),
+    /// The repo selector's first load finished
+    StartupLoaded {
+        repos: Result<Listing<RepoListItem>, String>,
+        orgs: Vec<String>,
+    },
+    RepoListLoaded(Result<Listing<RepoListItem>, String>),
+    /// The next page of repos for the `org` scope
+    MoreReposLoaded {
+        org: Option<String>,
+        result: Result<Page<RepoListItem>, String>,
+    },
+    OrgListLoaded(Vec<String>),
+    PrListLoaded(Result<Listing<PrListItem>, String>),
+    /// The next page of `owner/repo`'s PRs in `states`
+    MorePrsLoaded {
+        owner: String,
+        repo: String,
+        states: PrStateFilter,
+        result: Result<Page<PrListItem>, String>,
+    },
+    ReviewQueueLoaded(Result<Vec<QueueItem>, String>),
+    /// A step of a long-running fetch or generation completed
+    Progress(ProgressEvent),
     PrLoaded(Result<PrContext, String>),
-    StoryGenerated(Result<Story, String>),
-    CacheLoaded(Option<Story>),
+    /// Results for one PR carry its key (`owner/repo#123`), as another tab may be
+    /// showing by the time they arrive
+    StoryGenerated {
+        pr: String,
+        model: String,
+        result: Result<Story, String>,
+    },
+    DeepDiveGenerated {
+        pr: String,
+        feature: usize,
+        result: Result<DeepDive, String>,
+    },
+    HeadFilesLoaded {
+        pr: String,
+        result: Result<Vec<(String, String)>, String>,
+    },
+    BlameLoaded {
+        pr: String,
+        path: String,
+        result: Result<Vec<BlameRange>, String>,
+    },
+    StoryGenerationTimedOut { pr: PrContext, model: String },
+    /// Cached stories found for the PR, keyed by the model they were cached under
+    CacheLoaded {
+        pr: String,
+        stories: Vec<(String, CacheEntry)>,
+    },
     SubmissionResult {
+        pr: String,
         action: ReviewAction,
         result: Result<(), String>,
     },
+    /// Time to check the open PR for new activity
+    PollDue,
+    /// The terminal window gained (true) or lost focus
+    FocusChanged(bool),
+    /// The terminal was resized, and the UI has been laid out again at the new size
+    Resized,
+    ActivityLoaded {
+        pr: String,
+        result: Result<PrActivity, String>,
+    },
+    /// The PR's files marked viewed on GitHub
+    ViewedFilesLoaded {
+        pr: String,
+        result: Result<ViewedFiles, String>,
+    },
+    FileViewedSynced {
+        pr: String,
+        path: String,
+        result: Result<(), String>,
+    },
+    /// Reviews were requested from these users and teams
+    ReviewersRequested {
+        pr: String,
+        result: Result<Vec<String>, String>,
+    },
+    /// The PR's commits, oldest first
+    CommitsLoaded {
+        pr: String,
+        result: Result<Vec<PrCommit>, String>,
+    },
+    /// The diff of just `range`
+    RangeDiffLoaded {
+        pr: String,
+        range: CommitRange,
+        result: Result<String, String>,
+    },
+    /// The diff since the head the reviewer last reviewed
+    ReviewDeltaLoaded {
+        pr: String,
+        reviewed_head: String,
+        result: Result<String, String>,
+    },
+    /// GitHub's rate limit, the tighter of REST and GraphQL
+    RateLimitLoaded(Result<RateLimit, String>),
+    /// Text was copied to the clipboard (Ok holds how)
+    Copied {
+        what: String,
+        result: Result<String, String>,
+    },
+    /// A link was handed to the browser
+    BrowserOpened(Result<(), String>),
+    /// The review session was exported (Ok holds the file's path)
+    SessionExported(Result<String, String>),
+    /// A dry-run submission was written to the outbox (Ok holds its path)
+    DryRunWritten {
+        action: ReviewAction,
+        result: Result<String, String>,
+    },
+}
+
+impl Action {
+    /// The error a command reported, if it failed
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            Action::RepoListLoaded(Err(err))
+            | Action::StartupLoaded { repos: Err(err), .. }
+            | Action::PrListLoaded(Err(err))
+            | Action::MoreReposLoaded { result: Err(err), .. }
+            | Action::MorePrsLoaded { result: Err(err), .. }
+            | Action::ReviewQueueLoaded(Err(err))
+            | Action::PrLoaded(Err(err))
+            | Action::StoryGenerated { result: Err(err), .. }
+            | Action::HeadFilesLoaded { result: Err(err), .. }
+            | Action::BlameLoaded { result: Err(err), .. }
+            | Action::SubmissionResult { result: Err(err), .. }
+            | Action::DryRunWritten { result: Err(err), .. }
+            | Action::ActivityLoaded { result: Err(err), .. }
+            | Action::ViewedFilesLoaded { result: Err(err), .. }
+            | Action::FileViewedSynced { result: Err(err), .. }
+            | Action::ReviewersRequested { result: Err(err), .. }
+            | Action::CommitsLoaded { result: Err(err), .. }
+            | Action::RangeDiffLoaded { result: Err(err), .. }
+            | Action::ReviewDeltaLoaded { result: Err(err), .. }
+            | Action::RateLimitLoaded(Err(err))
+            | Action::Copied { result: Err(err), .. }
+            | Action::SessionExported(Err(err))
+            | Action::BrowserOpened(Err(err)) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// `owner/repo#123` of the PR a result belongs to, for results kept per tab
+    pub fn pr_key(&self) -> Option<String> {
+        match self {
+            Action::StoryGenerated { pr, .. }
+            | Action::DeepDiveGenerated { pr, .. }
+            | Action::HeadFilesLoaded { pr, .. }
+            | Action::BlameLoaded { pr, .. }
+            | Action::ActivityLoaded { pr, .. }
+            | Action::ViewedFilesLoaded { pr, .. }
+            | Action::CommitsLoaded { pr, .. }
+            | Action::RangeDiffLoaded { pr, .. }
+            | Action::CacheLoaded { pr, .. }
+            | Action::SubmissionResult { pr, .. }
+            | Action::FileViewedSynced { pr, .. }
+            | Action::ReviewersRequested { pr, .. }
+            | Action::ReviewDeltaLoaded { pr, .. } => Some(pr.clone()),
+            Action::StoryGenerationTimedOut { pr, .. } => Some(pr.pr_label()),
+            _ => None,
+        }
+    }
+
+    /// The variant's name
+    fn name(&self) -> &'static str {
+        match self {
+            Action::Input { .. } => "Input",
+            Action::StartupLoaded { .. } => "StartupLoaded",
+            Action::RepoListLoaded(_) => "RepoListLoaded",
+            Action::MoreReposLoaded { .. } => "MoreReposLoaded",
+            Action::OrgListLoaded(_) => "OrgListLoaded",
+            Action::PrListLoaded(_) => "PrListLoaded",
+            Action::MorePrsLoaded { .. } => "MorePrsLoaded",
+            Action::ReviewQueueLoaded(_) => "ReviewQueueLoaded",
+            Action::Progress(_) => "Progress",
+            Action::PrLoaded(_) => "PrLoaded",
+            Action::StoryGenerated { .. } => "StoryGenerated",
+            Action::DeepDiveGenerated { .. } => "DeepDiveGenerated",
+            Action::HeadFilesLoaded { .. } => "HeadFilesLoaded",
+            Action::BlameLoaded { .. } => "BlameLoaded",
+            Action::StoryGenerationTimedOut { .. } => "StoryGenerationTimedOut",
+            Action::CacheLoaded { .. } => "CacheLoaded",
+            Action::SubmissionResult { .. } => "SubmissionResult",
+            Action::PollDue => "PollDue",
+            Action::FocusChanged(_) => "FocusChanged",
+            Action::Resized => "Resized",
+            Action::ActivityLoaded { .. } => "ActivityLoaded",
+            Action::ViewedFilesLoaded { .. } => "ViewedFilesLoaded",
+            Action::FileViewedSynced { .. } => "FileViewedSynced",
+            Action::ReviewersRequested { .. } => "ReviewersRequested",
+            Action::CommitsLoaded { .. } => "CommitsLoaded",
+            Action::RangeDiffLoaded { .. } => "RangeDiffLoaded",
+            Action::ReviewDeltaLoaded { .. } => "ReviewDeltaLoaded",
+            Action::RateLimitLoaded(_) => "RateLimitLoaded",
+            Action::Copied { .. } => "Copied",
+            Action::BrowserOpened(_) => "BrowserOpened",
+            Action::SessionExported(_) => "SessionExported",
+            Action::DryRunWritten { .. } => "DryRunWritten",
+        }
+    }
+
+    /// What happened, for crash reports: the key pressed, or which result arrived
+    /// and for which PR and model (results carry PR contents that don't belong in
+    /// a report, and are too big to format on every dispatch)
+    pub fn summary(&self) -> String {
+        match self {
+            Action::Input { code, modifiers } => format!("Input {:?} {:?}", code, modifiers),
+            Action::StoryGenerated { pr, model, .. } => {
+                format!("{} {} {}", self.name(), pr, model)
+            }
+            Action::StoryGenerationTimedOut { pr, model } => {
+                format!("{} {} {}", self.name(), pr.pr_label(), model)
+            }
+            _ => match self.pr_key() {
+                Some(pr) => format!("{} {}", self.name(), pr),
+                None => self.name().to_string(),
+            },
+        }
+    }
 }
@@ -1,6 +1,39 @@
-use std::collections::HashSet;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Instant;
 
-use crate::domain::types::{PrContext, PrListItem, RepoListItem, ReviewAction, Story};
+use crate::domain::attachments::{find_images, Attachment};
+use crate::domain::breaking::ApiReport;
+use crate::domain::cache::CacheEntry;
+use crate::domain::coverage::tests_touching;
+use crate::domain::diff::{
+    block_base_range, index_changed_files, locate_block, parse_diff, FileDiff,
+};
+use crate::domain::export::{block_records, DraftRecord, SessionRecord};
+use crate::domain::history::History;
+use crate::domain::logging::RecentLog;
+use crate::domain::codeowners::owners_of;
+use crate::domain::lint::{counts_match, lint_story, StoryLint};
+use crate::domain::memory::ReviewMemory;
+use crate::domain::stats::SessionStats;
+use crate::domain::reviewed::{block_fingerprint, ReviewedPr, ReviewedPrs};
+use crate::domain::outbox::{Outbox, Submission};
+use crate::domain::preferences::{
+    Preferences, MAX_SIDEBAR_WIDTH, MIN_SIDEBAR_WIDTH, SIDEBAR_WIDTH_STEP,
+};
+use crate::domain::secrets::{SecretFinding, SecretsChoice};
+use crate::domain::session::{Drafts, Session};
+use crate::domain::stack::StackEntry;
+use crate::domain::symbols::{index_symbols, ChangedSymbol};
+use crate::domain::text;
+use crate::domain::timing::{ReportMode, ReviewClock, ReviewReport};
+use crate::domain::types::{
+    ActivityComment, BlameRange, ChangedFile, CoverageGap, DeepDive, ExcludedFile, InlineComment,
+    Listing, ModelParams, Persona, PrActivity, PrCommit, PrContext, PrListItem, PrSort,
+    PrStateFilter, PrStats, ProgressEvent, QueueItem, RepoListItem, ReviewAction, ReviewEvent,
+    Significance, Story, WorkspaceMatch,
+};
+use crate::ui::glyphs::{self, Glyphs};
 
 /// Application state
 #[derive(Debug, Clone)]
@@ -11,14 +44,52 @@ pub enum AppState {
     LoadingRepoList,
     /// PR picker popup
     PrPicker,
+    /// Choosing how the PR picker is ordered
+    ChoosingPrSort,
     /// Loading PR list
     LoadingPrList,
+    /// Cross-repo review queue
+    ReviewQueue,
+    /// Loading review queue
+    LoadingReviewQueue,
     /// Loading PR data from GitHub
     LoadingPr,
     /// Generating story from LLM
     GeneratingStory,
     /// Main story view
     Viewing,
+    /// Typing a search query over the story
+    Searching,
+    /// Typing a search query over all changed files
+    WorkspaceSearch,
+    /// Fetching changed files at the PR head for workspace search
+    LoadingWorkspaceFiles,
+    /// Browsing workspace search results
+    WorkspaceResults,
+    /// Full head-side file overlay for the selected diff block
+    FileView,
+    /// Blame overlay for the lines the selected diff block rewrites
+    BlameView,
+    /// Side-by-side overview of the stories each compared model generated
+    ComparingStories,
+    /// Choosing a review persona to regenerate the story under
+    ChoosingPersona,
+    /// Adjusting reasoning effort, output limit and temperature
+    ModelSettings,
+    /// Typing the path patterns to limit the review to
+    EditingScope,
+    /// Checking the commits to review on their own
+    SelectingCommits,
+    /// Choosing drafted actions to submit together, then watching them go out
+    BatchSubmit,
+    /// Previewing exactly what will be posted, waiting for `y`
+    ConfirmingSubmit,
+    /// The cached story predates the PR's latest changes; view it anyway or regenerate
+    StaleCache,
+    /// The diff looks like it adds secrets; redact or send them before generating
+    SecretsFound,
+    /// Showing exactly what will be sent to the model, waiting for Enter
+    PreviewingPrompt,
     /// Editing an action text
     EditingAction(ReviewAction),
     /// Submitting an action
@@ -27,6 +98,187 @@ pub enum AppState {
     Error(String),
 }
 
+/// How much of a paginated list has been fetched
+#[derive(Debug, Clone, Default)]
+pub struct Paging {
+    /// Items across every page, when GitHub reported it
+    pub total: Option<u32>,
+    /// Cursor to fetch the next page from; None once the last page is in
+    pub next_cursor: Option<String>,
+    /// Whether the next page is being fetched
+    pub loading: bool,
+}
+
+impl Paging {
+    pub fn from_listing<T>(listing: &Listing<T>) -> Self {
+        Paging {
+            total: listing.total,
+            next_cursor: listing.next_cursor.clone(),
+            loading: false,
+        }
+    }
+
+    /// Count for a list's title: "50 of 123" while pages remain, else the total
+    pub fn count_label(&self, loaded: usize) -> Option<String> {
+        let total = self.total?;
+        Some(if (total as usize) > loaded {
+            format!("{} of {}", loaded, total)
+        } else {
+            total.to_string()
+        })
+    }
+}
+
+/// Where a drafted action stands in the batch submit overlay
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchStatus {
+    Draft,
+    Queued,
+    Sent,
+    Failed(String),
+}
+
+/// A drafted action in the batch submit overlay
+#[derive(Debug, Clone)]
+pub struct BatchItem {
+    pub action: ReviewAction,
+    /// Whether it goes out on the next submit
+    pub checked: bool,
+    pub status: BatchStatus,
+}
+
+/// A block note keyed by its block, with the block's file and head-side line
+pub type LocatedNote<'a> = ((usize, usize), Option<(String, u32)>, &'a str);
+
+/// A story generated by one model, with the review progress made on it
+#[derive(Debug, Clone)]
+pub struct StoryVariant {
+    pub model: String,
+    pub story: Story,
+    pub action_texts: ActionTexts,
+    pub viewed_diffs: HashSet<(usize, usize)>,
+    pub follow_up_diffs: HashSet<(usize, usize)>,
+    pub block_notes: BTreeMap<(usize, usize), String>,
+}
+
+impl StoryVariant {
+    pub fn new(model: String, story: Story) -> Self {
+        StoryVariant {
+            model,
+            action_texts: ActionTexts::from_story(&story),
+            story,
+            viewed_diffs: HashSet::new(),
+            follow_up_diffs: HashSet::new(),
+            block_notes: BTreeMap::new(),
+        }
+    }
+}
+
+/// A PR open in a background tab, parked until it's switched back to
+#[derive(Debug, Default)]
+pub struct PrTab {
+    pub pr: Option<PrContext>,
+    pub current_repo: Option<(String, String)>,
+    pub current_pr_number: Option<u32>,
+    pub story: Option<Story>,
+    pub story_variants: Vec<StoryVariant>,
+    pub active_variant: usize,
+    /// Compared models still generating for this PR
+    pub pending_models: Vec<String>,
+    /// What its stories are generated with, kept for ones still generating
+    pub persona: Option<Persona>,
+    pub language: Option<String>,
+    pub model_params: ModelParams,
+    pub custom_prompt_sources: Vec<String>,
+    pub selected_feature: usize,
+    pub selected_diff: usize,
+    pub scroll_offset: u16,
+    pub feature_scroll: HashMap<usize, u16>,
+    pub action_texts: ActionTexts,
+    pub viewed_diffs: HashSet<(usize, usize)>,
+    pub clock: ReviewClock,
+    pub follow_up_diffs: HashSet<(usize, usize)>,
+    pub block_notes: BTreeMap<(usize, usize), String>,
+    pub pending_comments: Vec<InlineComment>,
+    pub review_event: ReviewEvent,
+    pub diff_files: Vec<FileDiff>,
+    pub changed_files: Vec<ChangedFile>,
+    pub file_selected: usize,
+    pub symbols: Vec<ChangedSymbol>,
+    pub api_report: ApiReport,
+    pub deep_dives: HashMap<usize, DeepDive>,
+    pub deep_dive_pending: Option<usize>,
+    pub expanded_context: HashSet<(usize, usize)>,
+    pub head_files: HashMap<String, String>,
+    pub blame: HashMap<String, Vec<BlameRange>>,
+    pub pr_node_id: Option<String>,
+    pub activity: Option<PrActivity>,
+    pub seen_comments: Option<HashSet<String>>,
+    pub submitted_actions: Vec<ReviewAction>,
+}
+
+impl PrTab {
+    /// `owner/repo#123` of the PR, once loaded
+    pub fn pr_key(&self) -> Option<String> {
+        self.pr.as_ref().map(PrContext::pr_label)
+    }
+
+    /// `repo#123`, or just the repo while the PR is still loading
+    pub fn label(&self) -> String {
+        match (&self.current_repo, self.current_pr_number) {
+            (Some((_, repo)), Some(number)) => format!("{}#{}", repo, number),
+            (Some((_, repo)), None) => repo.clone(),
+            _ => "new tab".to_string(),
+        }
+    }
+
+    /// Keep a story generated while the tab was in the background, displaying
+    /// it if the tab had none yet, as [`App::add_story_variant`] does
+    pub fn add_story_variant(&mut self, model: String, story: Story) {
+        self.pending_models.retain(|pending| *pending != model);
+        if self.story.is_none() {
+            self.action_texts = ActionTexts::from_story(&story);
+            self.story = Some(story.clone());
+            self.active_variant = self.story_variants.len();
+        }
+        self.story_variants.push(StoryVariant::new(model, story));
+    }
+}
+
+/// Most PRs open at once, one per `Alt+1`..`Alt+9`
+pub const MAX_TABS: usize = 9;
+
+/// What the viewing sidebar shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidebarMode {
+    /// Features and their diff blocks
+    Features,
+    /// Changed-file tree parsed from the diff
+    Files,
+    /// Functions and types the diff declares or changes
+    Symbols,
+}
+
+/// A jump being typed after `g` in the viewing screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoTo {
+    /// `g` pressed, waiting for what to jump to
+    Prefix,
+    /// `gf`: the feature number typed so far
+    Feature(String),
+    /// `gd`: the number of a diff in the current feature typed so far
+    Diff(String),
+}
+
+/// A count typed before a motion in the viewing screen, like `10j`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Count {
+    pub n: usize,
+    /// The action selected before the count's first digit, which `1`-`3` also
+    /// select; a motion that uses the count puts it back
+    pub action: ReviewAction,
+}
+
 /// The main application
 pub struct App {
     /// Current state
@@ -35,6 +287,43 @@ pub struct App {
     pub pr: Option<PrContext>,
     /// Generated story (after LLM call)
     pub story: Option<Story>,
+    /// Model used for the in-flight story generation
+    pub generation_model: Option<String>,
+    /// Provider in the chain serving it and the model asked of that provider
+    pub generation_provider: Option<(String, String)>,
+    /// The model that ran over budget and its budget in seconds, while the
+    /// fallback model generates in its place
+    pub fallback_for: Option<(String, u64)>,
+    /// Stories generated so far, one per model (several with `--compare`)
+    pub story_variants: Vec<StoryVariant>,
+    /// Index of the displayed story in `story_variants`
+    pub active_variant: usize,
+    /// Models whose story is still being generated
+    pub pending_models: Vec<String>,
+    /// Sources of custom instructions merged into the prompt for the current PR
+    pub custom_prompt_sources: Vec<String>,
+    /// Reviewer perspective stories are generated under (None = general review)
+    pub persona: Option<Persona>,
+    /// Selected row in the persona chooser (0 = general review)
+    pub persona_selected: usize,
+    /// Parameters sent with the next story or deep dive
+    pub model_params: ModelParams,
+    /// Language stories and deep dives are written in (None = the model's default)
+    pub language: Option<String>,
+    /// Parameters being edited in the model settings overlay
+    pub settings_draft: ModelParams,
+    /// Language being chosen in the model settings overlay
+    pub settings_language: Option<String>,
+    /// Selected row in the model settings overlay
+    pub settings_selected: usize,
+    /// Steps completed by the in-flight fetch/generation, oldest first
+    pub progress: Vec<ProgressEvent>,
+    /// When the in-flight fetch/generation started
+    pub progress_started: Option<Instant>,
+    /// Set to abort the command currently in flight
+    pub cancel_in_flight: bool,
+    /// Set to hand the terminal back to the shell until `fg` (Ctrl+Z)
+    pub suspend_requested: bool,
     /// Currently selected feature index
     pub selected_feature: usize,
     /// Currently selected diff index within feature
@@ -43,30 +332,266 @@ pub struct App {
     pub selected_action: ReviewAction,
     /// Scroll offset for the feature view
     pub scroll_offset: u16,
+    /// Scroll offset last left at in each feature, restored on returning to it
+    pub feature_scroll: HashMap<usize, u16>,
+    /// Rendered document height in lines (measured during render)
+    pub document_height: Cell<u16>,
+    /// Visible height of the document area (measured during render)
+    pub viewport_height: Cell<u16>,
+    /// Story search query (empty when no search is active)
+    pub search_query: String,
+    /// Rendered rows of lines matching the search query (measured during render)
+    pub search_matches: RefCell<Vec<u16>>,
+    /// Rendered row of each feature's heading (measured during render)
+    pub feature_rows: RefCell<Vec<u16>>,
+    /// Index of the current match in `search_matches`
+    pub search_current: usize,
     /// Text content for each action
     pub action_texts: ActionTexts,
     /// Cursor position in text editor
     pub cursor_pos: usize,
+    /// Whether the rendered draft is shown beside the editor
+    pub draft_preview: bool,
+    /// Whether the preview points out likely typos
+    pub spellcheck: bool,
     /// Status message
     pub status: Option<String>,
+    /// Banner shown when a listing is served from the offline cache
+    pub listing_banner: Option<String>,
     /// Should quit
     pub should_quit: bool,
+    /// Persisted layout preferences (sidebar width/visibility)
+    pub preferences: Preferences,
+    /// Symbols used when rendering (Unicode, or ASCII with `--ascii`)
+    pub glyphs: &'static Glyphs,
+    /// Draw for screen readers: one column, no colours (`--screen-reader`)
+    pub screen_reader: bool,
     /// Set of viewed diffs: (feature_idx, diff_idx)
     pub viewed_diffs: HashSet<(usize, usize)>,
+    /// GraphQL node id of the current PR, once fetched
+    pub pr_node_id: Option<String>,
+    /// Latest commits and comments polled for the open PR
+    pub activity: Option<PrActivity>,
+    /// Ids of comments already read (None until the first poll)
+    pub seen_comments: Option<HashSet<String>>,
+    /// Whether the new-comments overlay is open
+    pub show_activity: bool,
+    /// Lines scrolled in the new-comments overlay
+    pub activity_scroll: u16,
+    /// Set of diffs flagged as needing follow-up: (feature_idx, diff_idx)
+    pub follow_up_diffs: HashSet<(usize, usize)>,
+    /// Private notes on diff blocks: (feature_idx, diff_idx) -> note
+    pub block_notes: BTreeMap<(usize, usize), String>,
+    /// Block whose note is being written, and the text so far
+    pub note_editing: Option<(usize, usize)>,
+    pub note_draft: String,
+    /// Whether the notes panel is open, and its selected row
+    pub show_notes: bool,
+    pub note_selected: usize,
+    /// Inline comments held for the pending review, posted with its body in one go
+    pub pending_comments: Vec<InlineComment>,
+    /// The verdict the pending review is submitted with
+    pub review_event: ReviewEvent,
+    /// File and line of the inline comment being written, and the text so far
+    pub comment_editing: Option<(String, u32)>,
+    pub comment_draft: String,
+    /// What the sidebar shows
+    pub sidebar_mode: SidebarMode,
+    /// Files of the PR diff, parsed once for anchoring comments and context
+    pub diff_files: Vec<FileDiff>,
+    /// Files changed by the PR, sorted by path
+    pub changed_files: Vec<ChangedFile>,
+    /// Selected index in the file tree
+    pub file_selected: usize,
+    /// Symbols the diff declares or changes, in diff order
+    pub symbols: Vec<ChangedSymbol>,
+    /// Breaking API changes the story and the diff show, with the semver bump
+    pub api_report: ApiReport,
+    /// Deep dives generated on request, by feature
+    pub deep_dives: HashMap<usize, DeepDive>,
+    /// Feature a deep dive is being generated for
+    pub deep_dive_pending: Option<usize>,
+    /// Selected index in the symbol index
+    pub symbol_selected: usize,
+    /// Block of the selected symbol that Enter jumps to next
+    pub symbol_block: usize,
+    /// Contents of changed files at the PR head, fetched with the PR and on demand
+    pub head_files: HashMap<String, String>,
+    /// Diff blocks showing surrounding head code inline: (feature_idx, diff_idx)
+    pub expanded_context: HashSet<(usize, usize)>,
+    /// Lines of surrounding code shown either side of a block
+    pub context_lines: usize,
+    /// File shown in the full-file overlay
+    pub file_view_path: Option<String>,
+    /// Scroll offset within the full-file overlay
+    pub file_view_scroll: u16,
+    /// Base-side blame per file path, fetched with the PR or on demand
+    pub blame: HashMap<String, Vec<BlameRange>>,
+    /// File and base-side line range shown in the blame overlay
+    pub blame_view: Option<(String, u32, u32)>,
+    /// Workspace search query
+    pub workspace_query: String,
+    /// Workspace search results
+    pub workspace_results: Vec<WorkspaceMatch>,
+    /// Selected index in workspace search results
+    pub workspace_selected: usize,
+    /// Path patterns reviews are limited to (`--paths`); empty means every file
+    pub scope: Vec<String>,
+    /// Comma-separated patterns being typed in the scope editor
+    pub scope_input: String,
+    /// Whether diff navigation skips noise-significance blocks
+    pub skip_noise: bool,
+    /// Whether the risk panel is open
+    pub show_risks: bool,
+    /// Selected row in the risk panel
+    pub risk_selected: usize,
+    /// Whether `n`/`p` follow the story's reading order instead of feature order
+    pub guided: bool,
+    /// A `gf`/`gd` jump being typed
+    pub go_to: Option<GoTo>,
+    /// A count being typed before a motion
+    pub count: Option<Count>,
+    /// Whether the terminal window has focus, once it has said
+    pub terminal_focused: Option<bool>,
+    /// Title last set on the terminal window
+    pub terminal_title: String,
+    /// Lines last printed with `--announce`
+    pub announced: Vec<String>,
+    /// Related-block links followed with `r`, newest last: the block left and
+    /// which of its links was taken
+    pub related_trail: Vec<((usize, usize), usize)>,
+    /// Whether clarification questions are posted as inline threads per block
+    pub thread_questions: bool,
+    /// Write submissions to the dry-run outbox instead of posting them
+    pub dry_run: bool,
     /// PR list for picker
     pub pr_list: Vec<PrListItem>,
+    pub pr_paging: Paging,
+    /// Which PRs the picker lists
+    pub pr_filter: PrStateFilter,
+    /// Whether the picker leaves out drafts
+    pub hide_drafts: bool,
+    /// How the picker orders PRs
+    pub pr_sort: PrSort,
+    /// Highlighted row of the sort menu
+    pub pr_sort_selected: usize,
     /// Selected index in PR picker
     pub picker_selected: usize,
+    /// First visible row of the PR list (kept in step with the selection during render)
+    pub picker_scroll: Cell<u16>,
     /// Whether picker is showing
     pub show_picker: bool,
     /// Repo list for selector
     pub repo_list: Vec<RepoListItem>,
+    pub repo_paging: Paging,
     /// Selected index in repo selector
     pub repo_selected: usize,
+    /// First visible row of the repo list (kept in step with the selection during render)
+    pub repo_scroll: Cell<u16>,
+    /// Last session, listed above the repos until a PR is opened
+    pub resume: Option<Session>,
+    /// Whether the resume entry is selected rather than a repo
+    pub resume_selected: bool,
+    /// Organizations available in the repo selector (None until fetched)
+    pub orgs: Option<Vec<String>>,
+    /// Org whose repos the selector lists (None = the user's own and collaborator repos)
+    pub repo_org: Option<String>,
+    /// Review-requested PRs across repos
+    pub queue: Vec<QueueItem>,
+    /// Selected index in review queue
+    pub queue_selected: usize,
+    /// First visible row of the review queue (kept in step with the selection during render)
+    pub queue_scroll: Cell<u16>,
+    /// Recently opened and favorite repos/PRs (persisted)
+    pub history: History,
     /// Currently selected repo (owner, name)
     pub current_repo: Option<(String, String)>,
     /// Currently selected PR number (if known)
     pub current_pr_number: Option<u32>,
+    /// Drafted actions in the batch submit overlay
+    pub batch: Vec<BatchItem>,
+    /// Highlighted row in the batch submit overlay
+    pub batch_selected: usize,
+    /// Actions already posted to the current PR
+    pub submitted_actions: Vec<ReviewAction>,
+    /// Actions the confirmation overlay is asking about
+    pub confirm_actions: Vec<ReviewAction>,
+    /// Whether confirming came from batch submit (else from the editor)
+    pub confirm_batch: bool,
+    /// Lines scrolled in the confirmation preview
+    pub confirm_scroll: u16,
+    /// Time spent on each block of the current story
+    pub clock: ReviewClock,
+    /// Attachment last opened in the browser; `i` opens the one after it
+    pub attachment_opened: Option<usize>,
+    /// What happens to the review report when requested changes are posted
+    pub report_mode: ReportMode,
+    /// Submissions that failed to post (persisted)
+    pub outbox: Outbox,
+    /// Posts sent but not yet answered, oldest first
+    pub in_flight: Vec<Submission>,
+    /// Whether the outbox overlay is open
+    pub show_outbox: bool,
+    /// Highlighted row in the outbox overlay
+    pub outbox_selected: usize,
+    /// Past review feedback per repo (persisted)
+    pub memory: ReviewMemory,
+    /// The head each PR was last reviewed at (persisted)
+    pub reviewed: ReviewedPrs,
+    /// Model, cache, token and rate-limit figures for the header
+    pub stats: SessionStats,
+    /// Whether the review memory screen is open
+    pub show_memory: bool,
+    /// Highlighted row on the memory screen: 0 is the themes, then each suggestion
+    pub memory_selected: usize,
+    /// Whether the themes are being edited, and the cursor's byte offset in them
+    pub memory_cursor: Option<usize>,
+    /// The open PR's commits, oldest first, while choosing a range of them
+    pub commits: Vec<PrCommit>,
+    /// Which of `commits` are checked for the range
+    pub commit_checked: Vec<bool>,
+    /// Highlighted row in the commit list
+    pub commit_selected: usize,
+    /// Whether the CODEOWNERS reviewer suggestions are open
+    pub show_owners: bool,
+    /// Whether the template picker is open over the draft being edited
+    pub show_templates: bool,
+    /// Templates on offer: name and text with the PR's placeholders filled in
+    pub template_choices: Vec<(String, String)>,
+    pub template_selected: usize,
+    /// Whether the key reference is open, and how far it's scrolled
+    pub show_help: bool,
+    pub help_scroll: u16,
+    /// Cached stories waiting to be checked against the live PR before they're shown
+    pub pending_cache: Vec<(String, CacheEntry)>,
+    /// The shown cached story is out of date: the head it was generated at, and when
+    pub stale_cache: Option<(String, u64)>,
+    /// Likely secrets in the lines the open PR adds
+    pub secret_findings: Vec<SecretFinding>,
+    /// Whether to redact or send them, once decided for this PR
+    pub secrets_choice: Option<SecretsChoice>,
+    /// The prompts about to be sent, shown for approval under `--preview-prompt`
+    pub prompt_preview: String,
+    pub prompt_preview_scroll: u16,
+    /// The previewed prompt was approved; the next generation sends it
+    pub prompt_approved: bool,
+    /// Feature whose deep dive prompt is being previewed, rather than the story's
+    pub deep_dive_preview: Option<usize>,
+    /// Suggested owners and whether each is checked for the review request
+    pub reviewer_choices: Vec<(String, bool)>,
+    /// Highlighted row in the reviewer suggestions
+    pub reviewer_selected: usize,
+    /// Recent warnings and errors, filled by the logging layer as they happen
+    pub recent_log: RecentLog,
+    /// Whether the log overlay is open
+    pub show_log: bool,
+    /// Entries scrolled back from the newest in the log overlay
+    pub log_scroll: usize,
+    /// Open PRs when more than one is; the active tab's entry is empty while
+    /// its state lives in the fields above
+    pub tabs: Vec<PrTab>,
+    /// Index of the displayed PR in `tabs`
+    pub active_tab: usize,
 }
 
 /// Text content for the three review actions
@@ -77,101 +602,1036 @@ pub struct ActionTexts {
     pub next_pr: String,
 }
 
+impl ActionTexts {
+    /// Drafts suggested by a generated story
+    pub fn from_story(story: &Story) -> Self {
+        Self {
+            request_changes: story.suggested_changes.clone(),
+            clarification: story.clarification_questions.clone(),
+            next_pr: story.next_pr.clone(),
+        }
+    }
+
+    pub fn get(&self, action: ReviewAction) -> &str {
+        match action {
+            ReviewAction::RequestChanges => &self.request_changes,
+            ReviewAction::ClarificationQuestions => &self.clarification,
+            ReviewAction::NextPr => &self.next_pr,
+        }
+    }
+
+    pub fn get_mut(&mut self, action: ReviewAction) -> &mut String {
+        match action {
+            ReviewAction::RequestChanges => &mut self.request_changes,
+            ReviewAction::ClarificationQuestions => &mut self.clarification,
+            ReviewAction::NextPr => &mut self.next_pr,
+        }
+    }
+}
+
 impl App {
     pub fn new() -> Self {
         Self {
             state: AppState::LoadingPr,
             pr: None,
             story: None,
+            generation_model: None,
+            fallback_for: None,
+            generation_provider: None,
+            story_variants: Vec::new(),
+            active_variant: 0,
+            pending_models: Vec::new(),
+            custom_prompt_sources: Vec::new(),
+            persona: None,
+            persona_selected: 0,
+            model_params: ModelParams::default(),
+            language: None,
+            settings_draft: ModelParams::default(),
+            settings_language: None,
+            settings_selected: 0,
+            progress: Vec::new(),
+            progress_started: None,
+            cancel_in_flight: false,
+            suspend_requested: false,
             selected_feature: 0,
             selected_diff: 0,
             selected_action: ReviewAction::RequestChanges,
             scroll_offset: 0,
+            feature_scroll: HashMap::new(),
+            document_height: Cell::new(0),
+            viewport_height: Cell::new(0),
+            search_query: String::new(),
+            search_matches: RefCell::new(Vec::new()),
+            feature_rows: RefCell::new(Vec::new()),
+            search_current: 0,
             action_texts: ActionTexts::default(),
             cursor_pos: 0,
+            draft_preview: false,
+            spellcheck: true,
             status: None,
+            listing_banner: None,
+            preferences: Preferences::default(),
+            glyphs: &glyphs::UNICODE,
+            screen_reader: false,
             should_quit: false,
             viewed_diffs: HashSet::new(),
+            pr_node_id: None,
+            activity: None,
+            seen_comments: None,
+            show_activity: false,
+            activity_scroll: 0,
+            follow_up_diffs: HashSet::new(),
+            block_notes: BTreeMap::new(),
+            note_editing: None,
+            note_draft: String::new(),
+            show_notes: false,
+            note_selected: 0,
+            pending_comments: Vec::new(),
+            review_event: ReviewEvent::default(),
+            comment_editing: None,
+            comment_draft: String::new(),
+            sidebar_mode: SidebarMode::Features,
+            diff_files: Vec::new(),
+            changed_files: Vec::new(),
+            file_selected: 0,
+            symbols: Vec::new(),
+            api_report: ApiReport::default(),
+            deep_dives: HashMap::new(),
+            deep_dive_pending: None,
+            symbol_selected: 0,
+            symbol_block: 0,
+            head_files: HashMap::new(),
+            expanded_context: HashSet::new(),
+            context_lines: 10,
+            file_view_path: None,
+            file_view_scroll: 0,
+            blame: HashMap::new(),
+            blame_view: None,
+            workspace_query: String::new(),
+            workspace_results: Vec::new(),
+            workspace_selected: 0,
+            scope: Vec::new(),
+            scope_input: String::new(),
+            skip_noise: false,
+            show_risks: false,
+            risk_selected: 0,
+            guided: false,
+            go_to: None,
+            count: None,
+            terminal_focused: None,
+            terminal_title: String::new(),
+            announced: Vec::new(),
+            related_trail: Vec::new(),
+            thread_questions: false,
+            dry_run: false,
             pr_list: Vec::new(),
+            pr_paging: Paging::default(),
+            pr_filter: PrStateFilter::default(),
+            hide_drafts: false,
+            pr_sort: PrSort::default(),
+            pr_sort_selected: 0,
             picker_selected: 0,
+            picker_scroll: Cell::new(0),
             show_picker: false,
             repo_list: Vec::new(),
+            repo_paging: Paging::default(),
             repo_selected: 0,
+            repo_scroll: Cell::new(0),
+            resume: None,
+            resume_selected: false,
+            orgs: None,
+            repo_org: None,
+            queue: Vec::new(),
+            history: History::default(),
+            queue_selected: 0,
+            queue_scroll: Cell::new(0),
             current_repo: None,
             current_pr_number: None,
+            batch: Vec::new(),
+            batch_selected: 0,
+            submitted_actions: Vec::new(),
+            confirm_actions: Vec::new(),
+            confirm_batch: false,
+            confirm_scroll: 0,
+            clock: ReviewClock::default(),
+            attachment_opened: None,
+            report_mode: ReportMode::default(),
+            outbox: Outbox::default(),
+            in_flight: Vec::new(),
+            show_outbox: false,
+            outbox_selected: 0,
+            memory: ReviewMemory::default(),
+            reviewed: ReviewedPrs::default(),
+            stats: SessionStats::default(),
+            show_memory: false,
+            memory_selected: 0,
+            memory_cursor: None,
+            commits: Vec::new(),
+            commit_checked: Vec::new(),
+            commit_selected: 0,
+            show_owners: false,
+            show_templates: false,
+            template_choices: Vec::new(),
+            template_selected: 0,
+            show_help: false,
+            help_scroll: 0,
+            pending_cache: Vec::new(),
+            stale_cache: None,
+            secret_findings: Vec::new(),
+            secrets_choice: None,
+            prompt_preview: String::new(),
+            prompt_preview_scroll: 0,
+            prompt_approved: false,
+            deep_dive_preview: None,
+            reviewer_choices: Vec::new(),
+            reviewer_selected: 0,
+            recent_log: RecentLog::default(),
+            show_log: false,
+            log_scroll: 0,
+            tabs: Vec::new(),
+            active_tab: 0,
         }
     }
 
     /// Get the current action text
     pub fn current_action_text(&self) -> &str {
-        match self.selected_action {
-            ReviewAction::RequestChanges => &self.action_texts.request_changes,
-            ReviewAction::ClarificationQuestions => &self.action_texts.clarification,
-            ReviewAction::NextPr => &self.action_texts.next_pr,
-        }
+        self.action_text(self.selected_action)
+    }
+
+    pub fn action_text(&self, action: ReviewAction) -> &str {
+        self.action_texts.get(action)
+    }
+
+    /// List the three actions for batch submit, checking drafts not yet posted
+    pub fn open_batch(&mut self) {
+        self.batch = ReviewAction::ALL
+            .into_iter()
+            .map(|action| {
+                let sent = self.submitted_actions.contains(&action);
+                BatchItem {
+                    action,
+                    checked: !sent && !self.action_text(action).trim().is_empty(),
+                    status: if sent { BatchStatus::Sent } else { BatchStatus::Draft },
+                }
+            })
+            .collect();
+        self.batch_selected = 0;
+        self.state = AppState::BatchSubmit;
+    }
+
+    /// Ask for confirmation before posting these actions
+    pub fn open_confirm(&mut self, actions: Vec<ReviewAction>, batch: bool) {
+        self.confirm_actions = actions;
+        self.confirm_batch = batch;
+        self.confirm_scroll = 0;
+        self.state = AppState::ConfirmingSubmit;
+    }
+
+    /// Whether any batch-submitted action is still waiting for its result
+    pub fn batch_running(&self) -> bool {
+        self.batch.iter().any(|item| item.status == BatchStatus::Queued)
     }
 
     /// Get mutable reference to current action text
     pub fn current_action_text_mut(&mut self) -> &mut String {
-        match self.selected_action {
-            ReviewAction::RequestChanges => &mut self.action_texts.request_changes,
-            ReviewAction::ClarificationQuestions => &mut self.action_texts.clarification,
-            ReviewAction::NextPr => &mut self.action_texts.next_pr,
-        }
+        self.action_texts.get_mut(self.selected_action)
     }
 
     /// Populate action texts from story
     pub fn populate_from_story(&mut self, story: &Story) {
-        self.action_texts.request_changes = story.suggested_changes.clone();
-        self.action_texts.clarification = story.clarification_questions.clone();
-        self.action_texts.next_pr = story.next_pr.clone();
+        self.action_texts = ActionTexts::from_story(story);
+    }
+
+    /// Keep the story `model` generated, displaying it if no story is shown yet.
+    /// Returns whether it became the displayed story
+    pub fn add_story_variant(&mut self, model: String, story: Story) -> bool {
+        self.pending_models.retain(|pending| *pending != model);
+        let first = self.story.is_none();
+        if first {
+            self.populate_from_story(&story);
+            self.story = Some(story.clone());
+            self.active_variant = self.story_variants.len();
+            self.index_changed_files();
+        }
+        self.story_variants.push(StoryVariant::new(model, story));
+        first
+    }
+
+    /// Model that generated the displayed story
+    pub fn active_model(&self) -> Option<&str> {
+        self.story_variants
+            .get(self.active_variant)
+            .map(|variant| variant.model.as_str())
+    }
+
+    /// Display the next model's story. Viewed/follow-up marks and edited action
+    /// texts stay with the story they were made on. Returns the model now shown
+    pub fn cycle_story_variant(&mut self) -> Option<String> {
+        if self.story_variants.len() < 2 {
+            return None;
+        }
+        let current = &mut self.story_variants[self.active_variant];
+        current.viewed_diffs = std::mem::take(&mut self.viewed_diffs);
+        current.follow_up_diffs = std::mem::take(&mut self.follow_up_diffs);
+        current.block_notes = std::mem::take(&mut self.block_notes);
+        current.action_texts = std::mem::take(&mut self.action_texts);
+
+        self.active_variant = (self.active_variant + 1) % self.story_variants.len();
+        let next = &mut self.story_variants[self.active_variant];
+        self.viewed_diffs = std::mem::take(&mut next.viewed_diffs);
+        self.follow_up_diffs = std::mem::take(&mut next.follow_up_diffs);
+        self.block_notes = std::mem::take(&mut next.block_notes);
+        self.action_texts = std::mem::take(&mut next.action_texts);
+        self.story = Some(next.story.clone());
+        let model = next.model.clone();
+
+        self.selected_feature = 0;
+        self.selected_diff = 0;
+        self.scroll_offset = 0;
+        self.feature_scroll.clear();
+        self.expanded_context.clear();
+        self.related_trail.clear();
+        self.index_changed_files();
+        Some(model)
+    }
+
+    /// Largest scroll offset that still keeps the document filling the viewport
+    pub fn max_scroll(&self) -> u16 {
+        self.document_height
+            .get()
+            .saturating_sub(self.viewport_height.get())
+    }
+
+    /// Scroll the document down, stopping at the bottom
+    pub fn scroll_down(&mut self, amount: u16) {
+        self.scroll_offset = self
+            .scroll_offset
+            .saturating_add(amount)
+            .min(self.max_scroll());
+    }
+
+    /// Scroll the document up, stopping at the top
+    pub fn scroll_up(&mut self, amount: u16) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(amount);
+    }
+
+    /// Scroll to the next feature's heading and select it, or back to the
+    /// selected feature's heading (the previous one's if already there)
+    pub fn feature_boundary(&mut self, forward: bool) {
+        let rows = self.feature_rows.borrow().clone();
+        let current = rows.get(self.selected_feature).copied().unwrap_or(0);
+        let feature_idx = if forward {
+            self.selected_feature + 1
+        } else if self.scroll_offset > current.min(self.max_scroll()) {
+            self.selected_feature
+        } else {
+            self.selected_feature.saturating_sub(1)
+        };
+        let Some(&row) = rows.get(feature_idx) else {
+            return;
+        };
+        self.feature_scroll.insert(self.selected_feature, self.scroll_offset);
+        self.selected_feature = feature_idx;
+        self.selected_diff = self.feature_entry_diff(feature_idx).unwrap_or(0);
+        self.scroll_offset = row.min(self.max_scroll());
+    }
+
+    /// Number of diff blocks in a feature
+    fn diff_count(&self, feature_idx: usize) -> usize {
+        self.story
+            .as_ref()
+            .and_then(|story| story.narrative.get(feature_idx))
+            .map_or(0, |feature| feature.diff_blocks.len())
+    }
+
+    /// Whether navigation may land on a diff (noise is skipped when enabled)
+    fn is_navigable(&self, feature_idx: usize, diff_idx: usize) -> bool {
+        let Some(block) = self
+            .story
+            .as_ref()
+            .and_then(|story| story.narrative.get(feature_idx))
+            .and_then(|feature| feature.diff_blocks.get(diff_idx))
+        else {
+            return false;
+        };
+        !(self.skip_noise && block.significance == Significance::Noise)
+    }
+
+    /// Diff to select when entering a feature, or None if the feature is all noise
+    fn feature_entry_diff(&self, feature_idx: usize) -> Option<usize> {
+        let count = self.diff_count(feature_idx);
+        if !self.skip_noise || count == 0 {
+            return Some(0);
+        }
+        (0..count).find(|&diff_idx| self.is_navigable(feature_idx, diff_idx))
+    }
+
+    /// Quality lint of the displayed story against the files the model was shown
+    pub fn story_lint(&self) -> Option<StoryLint> {
+        let story = self.story.as_ref()?;
+        let excluded = self.pr.as_ref().map_or(&[][..], |pr| pr.excluded_files.as_slice());
+        Some(lint_story(story, &self.prompt_files(), excluded.len()))
+    }
+
+    /// Changed files the model was shown, leaving out those excluded from the prompt
+    fn prompt_files(&self) -> Vec<&ChangedFile> {
+        let excluded = self.pr.as_ref().map_or(&[][..], |pr| pr.excluded_files.as_slice());
+        self.changed_files
+            .iter()
+            .filter(|file| excluded.iter().all(|excluded| excluded.path != file.path))
+            .collect()
+    }
+
+    /// The PR's file and line counts from the diff, and whether the story's own
+    /// disagree with them. None until the diff has been indexed.
+    pub fn diff_stats(&self) -> Option<(PrStats, bool)> {
+        let story = self.story.as_ref()?;
+        if self.changed_files.is_empty() {
+            return None;
+        }
+        let excluded = self.pr.as_ref().map_or(0, |pr| pr.excluded_files.len());
+        let shown = PrStats::from_files(self.prompt_files());
+        let disagrees = !counts_match(&story.data, &shown, excluded);
+        Some((PrStats::from_files(&self.changed_files), disagrees))
+    }
+
+    /// CODEOWNERS owners of the files a feature's diff blocks touch, in first-seen order
+    pub fn feature_owners(&self, feature_idx: usize) -> Vec<&str> {
+        let Some(pr) = &self.pr else {
+            return Vec::new();
+        };
+        let mut owners: Vec<&str> = Vec::new();
+        for file in self
+            .changed_files
+            .iter()
+            .filter(|file| file.blocks.iter().any(|&(i, _)| i == feature_idx))
+        {
+            for owner in owners_of(&pr.code_owners, &file.path) {
+                if !owners.contains(&owner.as_str()) {
+                    owners.push(owner);
+                }
+            }
+        }
+        owners
+    }
+
+    /// Owners to request reviews from, each with the features they own; the
+    /// PR's author is left out
+    pub fn suggested_reviewers(&self) -> Vec<(String, Vec<usize>)> {
+        let author = self.pr.as_ref().map(|pr| format!("@{}", pr.author));
+        let features = self.story.as_ref().map_or(0, |story| story.narrative.len());
+        let mut reviewers: Vec<(String, Vec<usize>)> = Vec::new();
+        for i in 0..features {
+            for owner in self.feature_owners(i) {
+                if author.as_deref().is_some_and(|author| author.eq_ignore_ascii_case(owner)) {
+                    continue;
+                }
+                match reviewers.iter_mut().find(|(existing, _)| existing == owner) {
+                    Some((_, owned)) => owned.push(i),
+                    None => reviewers.push((owner.to_string(), vec![i])),
+                }
+            }
+        }
+        reviewers
+    }
+
+    /// The PR `offset` steps up (towards the top) or down the open PR's stack
+    pub fn stack_neighbor(&self, offset: isize) -> Option<&StackEntry> {
+        let pr = self.pr.as_ref()?;
+        let position = pr.stack.iter().position(|entry| entry.number == pr.number)?;
+        pr.stack.get(position.checked_add_signed(offset)?)
+    }
+
+    /// `owner/repo#123` of the open PR
+    pub fn open_pr_key(&self) -> Option<String> {
+        self.pr.as_ref().map(PrContext::pr_label)
+    }
+
+    /// The open PR's head with the blocks viewed at it, once any are
+    pub fn reviewed_version(&self) -> Option<ReviewedPr> {
+        let (Some(pr), Some(story)) = (&self.pr, &self.story) else {
+            return None;
+        };
+        if self.viewed_diffs.is_empty() {
+            return None;
+        }
+        let mut viewed_blocks: Vec<String> = self
+            .viewed_diffs
+            .iter()
+            .filter_map(|&(i, j)| story.narrative.get(i)?.diff_blocks.get(j))
+            .map(block_fingerprint)
+            .collect();
+        viewed_blocks.sort();
+        viewed_blocks.dedup();
+        Some(ReviewedPr {
+            head_sha: pr.head_sha.clone(),
+            viewed_blocks,
+        })
+    }
+
+    /// Mark viewed every block whose changes were viewed in an earlier version;
+    /// returns how many were
+    pub fn restore_viewed_blocks(&mut self, fingerprints: &[String]) -> usize {
+        let Some(story) = &self.story else {
+            return 0;
+        };
+        let unchanged: Vec<(usize, usize)> = story
+            .narrative
+            .iter()
+            .enumerate()
+            .flat_map(|(i, feature)| {
+                feature.diff_blocks.iter().enumerate().map(move |(j, block)| (i, j, block))
+            })
+            .filter(|(_, _, block)| fingerprints.contains(&block_fingerprint(block)))
+            .map(|(i, j, _)| (i, j))
+            .collect();
+        let restored = unchanged.len();
+        self.viewed_diffs.extend(unchanged);
+        restored
+    }
+
+    /// `owner/repo` whose review memory applies to the open PR
+    pub fn memory_repo(&self) -> Option<String> {
+        match (&self.current_repo, &self.pr) {
+            (Some((owner, repo)), _) => Some(format!("{}/{}", owner, repo)),
+            (None, Some(pr)) => Some(format!("{}/{}", pr.owner, pr.repo)),
+            (None, None) => None,
+        }
+    }
+
+    /// The story's coverage gaps, each with any changed test files that may cover it after all
+    pub fn coverage_gaps(&self) -> Vec<(&CoverageGap, Vec<&str>)> {
+        let Some(story) = &self.story else {
+            return Vec::new();
+        };
+        let changed: Vec<&str> = self.changed_files.iter().map(|file| file.path.as_str()).collect();
+        story
+            .coverage_gaps
+            .iter()
+            .map(|gap| (gap, tests_touching(gap.path(), &changed)))
+            .collect()
+    }
+
+    /// Blocks in guided reading order, less noise while it's skipped
+    pub fn guided_path(&self) -> Vec<(usize, usize)> {
+        let Some(story) = &self.story else {
+            return Vec::new();
+        };
+        story
+            .reading_order()
+            .into_iter()
+            .filter(|&(i, j)| self.is_navigable(i, j))
+            .collect()
+    }
+
+    /// The selected block's step on the guided path and the path's length (1-based)
+    pub fn guided_step(&self) -> Option<(usize, usize)> {
+        let path = self.guided_path();
+        let step = path
+            .iter()
+            .position(|&p| p == (self.selected_feature, self.selected_diff))?;
+        Some((step + 1, path.len()))
+    }
+
+    /// Turn guided reading on or off. Turning it on starts at the first unviewed
+    /// step. Returns whether it's now on
+    pub fn toggle_guided(&mut self) -> bool {
+        self.guided = !self.guided;
+        if self.guided {
+            let path = self.guided_path();
+            let start = path
+                .iter()
+                .find(|&&(i, j)| !self.is_diff_viewed(i, j))
+                .or(path.first());
+            if let Some(&(feature_idx, diff_idx)) = start {
+                self.select_diff(feature_idx, diff_idx);
+            }
+        }
+        self.guided
+    }
+
+    /// Move one step along the guided path (backwards when `forward` is false)
+    pub fn guided_move(&mut self, forward: bool) {
+        let path = self.guided_path();
+        let target = match self.guided_step() {
+            Some((step, _)) if forward => path.get(step),
+            Some((step, _)) => step.checked_sub(2).and_then(|prev| path.get(prev)),
+            // Off the path (a skipped noise block): rejoin it at the start
+            None => path.first(),
+        };
+        if let Some(&(feature_idx, diff_idx)) = target {
+            self.select_diff(feature_idx, diff_idx);
+        }
+    }
+
+    /// Where link `link` of the block at `from` points, if that block exists
+    pub fn related_target(&self, from: (usize, usize), link: usize) -> Option<(usize, usize)> {
+        let story = self.story.as_ref()?;
+        let target = story
+            .narrative
+            .get(from.0)?
+            .diff_blocks
+            .get(from.1)?
+            .related_blocks
+            .get(link)?
+            .position()?;
+        story
+            .narrative
+            .get(target.0)?
+            .diff_blocks
+            .get(target.1)
+            .map(|_| target)
+    }
+
+    /// Jump to the selected block's first related block. Pressed again straight
+    /// after a jump, moves on to the block it came from's next link instead.
+    /// Returns whether it moved
+    pub fn follow_related(&mut self) -> bool {
+        let here = (self.selected_feature, self.selected_diff);
+        let (from, link) = match self.related_trail.last() {
+            Some(&(from, link))
+                if self.related_target(from, link) == Some(here)
+                    && self.related_target(from, link + 1).is_some() =>
+            {
+                self.related_trail.pop();
+                (from, link + 1)
+            }
+            _ => (here, 0),
+        };
+        let Some((feature_idx, diff_idx)) = self.related_target(from, link) else {
+            return false;
+        };
+        self.related_trail.push((from, link));
+        self.select_diff(feature_idx, diff_idx);
+        true
+    }
+
+    /// Why the last followed link was worth following
+    pub fn related_note(&self) -> Option<&str> {
+        let &((feature_idx, diff_idx), link) = self.related_trail.last()?;
+        let block = self
+            .story
+            .as_ref()?
+            .narrative
+            .get(feature_idx)?
+            .diff_blocks
+            .get(diff_idx)?;
+        Some(block.related_blocks.get(link)?.note.as_str())
+    }
+
+    /// Go back to the block the last related link was followed from
+    pub fn return_from_related(&mut self) -> bool {
+        let Some(((feature_idx, diff_idx), _)) = self.related_trail.pop() else {
+            return false;
+        };
+        self.select_diff(feature_idx, diff_idx);
+        true
+    }
+
+    /// Select a feature and diff, resetting scroll when the feature changes
+    fn select_diff(&mut self, feature_idx: usize, diff_idx: usize) {
+        if feature_idx != self.selected_feature {
+            self.feature_scroll.insert(self.selected_feature, self.scroll_offset);
+            self.scroll_offset = self.feature_scroll.get(&feature_idx).copied().unwrap_or(0);
+        }
+        self.selected_feature = feature_idx;
+        self.selected_diff = diff_idx;
+    }
+
+    /// The changed file's entry among those left out of the prompt, if it was
+    pub fn excluded_file(&self, path: &str) -> Option<&ExcludedFile> {
+        self.pr
+            .as_ref()?
+            .excluded_files
+            .iter()
+            .find(|file| file.path == path)
+    }
+
+    /// Rebuild the changed-file index from the PR diff and current story
+    pub fn index_changed_files(&mut self) {
+        self.diff_files = self.pr.as_ref().map(|pr| parse_diff(&pr.diff)).unwrap_or_default();
+        (self.changed_files, self.symbols, self.api_report) = match (&self.pr, &self.story) {
+            (Some(pr), Some(story)) => {
+                let files: Vec<FileDiff> = self
+                    .diff_files
+                    .iter()
+                    .filter(|file| !pr.is_out_of_scope(&file.path))
+                    .cloned()
+                    .collect();
+                (
+                    index_changed_files(&files, story),
+                    index_symbols(&files, story),
+                    ApiReport::new(&files, &story.breaking_changes),
+                )
+            }
+            _ => Default::default(),
+        };
+        self.file_selected = 0;
+        self.symbol_selected = 0;
+        self.symbol_block = 0;
+    }
+
+    /// Cycle the sidebar between features, the file tree and the symbol index
+    pub fn toggle_sidebar_mode(&mut self) {
+        self.sidebar_mode = match self.sidebar_mode {
+            SidebarMode::Features => SidebarMode::Files,
+            SidebarMode::Files => SidebarMode::Symbols,
+            SidebarMode::Symbols => SidebarMode::Features,
+        };
+    }
+
+    /// Widen the sidebar by one step
+    pub fn widen_sidebar(&mut self) {
+        self.preferences.sidebar_hidden = false;
+        self.preferences.sidebar_width =
+            (self.preferences.sidebar_width + SIDEBAR_WIDTH_STEP).min(MAX_SIDEBAR_WIDTH);
+    }
+
+    /// Narrow the sidebar by one step
+    pub fn narrow_sidebar(&mut self) {
+        self.preferences.sidebar_hidden = false;
+        self.preferences.sidebar_width = self
+            .preferences
+            .sidebar_width
+            .saturating_sub(SIDEBAR_WIDTH_STEP)
+            .max(MIN_SIDEBAR_WIDTH);
+    }
+
+    /// Hide or show the sidebar. Hiding falls back to feature navigation so
+    /// j/k don't move an invisible file tree.
+    pub fn toggle_sidebar_hidden(&mut self) {
+        self.preferences.sidebar_hidden = !self.preferences.sidebar_hidden;
+        if self.preferences.sidebar_hidden {
+            self.sidebar_mode = SidebarMode::Features;
+        }
+    }
+
+    /// Move file tree selection down
+    pub fn file_tree_down(&mut self) {
+        if self.file_selected < self.changed_files.len().saturating_sub(1) {
+            self.file_selected += 1;
+        }
+    }
+
+    /// Move file tree selection up
+    pub fn file_tree_up(&mut self) {
+        self.file_selected = self.file_selected.saturating_sub(1);
+    }
+
+    /// Move the symbol index selection by `delta`, clamped
+    pub fn move_symbol(&mut self, delta: isize) {
+        let last = self.symbols.len().saturating_sub(1);
+        self.symbol_selected = self.symbol_selected.saturating_add_signed(delta).min(last);
+        self.symbol_block = 0;
+    }
+
+    /// Select the next story block touching the selected symbol, cycling through
+    /// them on repeated calls. Returns which block of how many, or None if none do
+    pub fn jump_to_selected_symbol(&mut self) -> Option<(usize, usize)> {
+        let blocks = &self.symbols.get(self.symbol_selected)?.blocks;
+        let &(feature_idx, diff_idx) = blocks.get(self.symbol_block % blocks.len().max(1))?;
+        let position = (self.symbol_block % blocks.len() + 1, blocks.len());
+        self.symbol_block = position.0 % blocks.len();
+        self.select_diff(feature_idx, diff_idx);
+        Some(position)
+    }
+
+    /// Block notes in the order their blocks appear in the diff, each with its
+    /// block's file and head-side line; notes on blocks that can't be found come last
+    pub fn notes_in_diff_order(&self) -> Vec<LocatedNote<'_>> {
+        let paths: Vec<String> = self
+            .pr
+            .as_ref()
+            .map(|pr| parse_diff(&pr.diff).into_iter().map(|file| file.path).collect())
+            .unwrap_or_default();
+        let mut notes: Vec<_> = self
+            .block_notes
+            .iter()
+            .map(|(&key, note)| (key, self.block_location(key.0, key.1), note.as_str()))
+            .collect();
+        notes.sort_by_key(|(key, location, _)| {
+            let order = location.as_ref().map(|(path, line)| {
+                (paths.iter().position(|p| p == path).unwrap_or(usize::MAX), *line)
+            });
+            (order.is_none(), order, *key)
+        });
+        notes
+    }
+
+    /// "pending review (4 comments)", while inline comments wait to be submitted
+    pub fn pending_review_label(&self) -> Option<String> {
+        match self.pending_comments.len() {
+            0 => None,
+            1 => Some("pending review (1 comment)".to_string()),
+            n => Some(format!("pending review ({} comments)", n)),
+        }
+    }
+
+    /// Select the block the highlighted note in the notes panel is on
+    pub fn jump_to_selected_note(&mut self) -> bool {
+        let Some(&((feature_idx, diff_idx), _, _)) =
+            self.notes_in_diff_order().get(self.note_selected)
+        else {
+            return false;
+        };
+        self.select_diff(feature_idx, diff_idx);
+        true
+    }
+
+    /// Whether every story block touching a file has been viewed
+    pub fn is_file_viewed(&self, file: &ChangedFile) -> bool {
+        !file.blocks.is_empty() && file.blocks.iter().all(|&(i, j)| self.is_diff_viewed(i, j))
+    }
+
+    /// Select the first diff block touching the selected file.
+    /// Returns false if no block in the story covers that file.
+    pub fn jump_to_selected_file(&mut self) -> bool {
+        let Some(&(feature_idx, diff_idx)) = self
+            .changed_files
+            .get(self.file_selected)
+            .and_then(|file| file.blocks.first())
+        else {
+            return false;
+        };
+        self.select_diff(feature_idx, diff_idx);
+        true
+    }
+
+    /// Begin typing a new search query
+    pub fn start_search(&mut self) {
+        self.search_query.clear();
+        self.search_matches.borrow_mut().clear();
+        self.search_current = 0;
+        self.state = AppState::Searching;
+    }
+
+    /// Clear the active search
+    pub fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.search_matches.borrow_mut().clear();
+        self.search_current = 0;
+    }
+
+    /// Whether a search query is active (n/N navigate matches)
+    pub fn has_active_search(&self) -> bool {
+        !self.search_query.is_empty() && !matches!(self.state, AppState::Searching)
+    }
+
+    /// Jump to the first match at or below the current scroll position
+    pub fn jump_to_first_match(&mut self) -> bool {
+        let matches = self.search_matches.borrow();
+        let Some(idx) = matches
+            .iter()
+            .position(|&row| row >= self.scroll_offset)
+            .or((!matches.is_empty()).then_some(0))
+        else {
+            return false;
+        };
+        let row = matches[idx];
+        drop(matches);
+        self.search_current = idx;
+        self.scroll_offset = row.min(self.max_scroll());
+        true
+    }
+
+    /// Jump to the next (or previous) search match, wrapping around
+    pub fn jump_to_match(&mut self, forward: bool) -> bool {
+        let matches = self.search_matches.borrow();
+        let count = matches.len();
+        if count == 0 {
+            return false;
+        }
+        let idx = if forward {
+            (self.search_current + 1) % count
+        } else {
+            (self.search_current + count - 1) % count
+        };
+        let row = matches[idx];
+        drop(matches);
+        self.search_current = idx;
+        self.scroll_offset = row.min(self.max_scroll());
+        true
+    }
+
+    /// Describe the current match position, e.g. "Match 2/5 for 'foo'"
+    pub fn search_summary(&self) -> String {
+        let count = self.search_matches.borrow().len();
+        if count == 0 {
+            format!("No matches for '{}'", self.search_query)
+        } else {
+            format!(
+                "Match {}/{} for '{}'",
+                self.search_current + 1,
+                count,
+                self.search_query
+            )
+        }
+    }
+
+    /// Run the workspace query against the fetched head files
+    pub fn run_workspace_search(&mut self) {
+        self.workspace_results =
+            crate::domain::workspace::search_files(&self.head_files, &self.workspace_query);
+        self.workspace_selected = 0;
+        self.state = AppState::WorkspaceResults;
+    }
+
+    /// Move workspace result selection down
+    pub fn workspace_down(&mut self) {
+        if self.workspace_selected < self.workspace_results.len().saturating_sub(1) {
+            self.workspace_selected += 1;
+        }
+    }
+
+    /// Move workspace result selection up
+    pub fn workspace_up(&mut self) {
+        self.workspace_selected = self.workspace_selected.saturating_sub(1);
+    }
+
+    /// Select the first diff block touching the selected result's file.
+    /// Returns false if no block in the story covers that file.
+    pub fn jump_to_workspace_match(&mut self) -> bool {
+        let Some(path) = self
+            .workspace_results
+            .get(self.workspace_selected)
+            .map(|m| m.path.clone())
+        else {
+            return false;
+        };
+        let Some(idx) = self.changed_files.iter().position(|f| f.path == path) else {
+            return false;
+        };
+        self.file_selected = idx;
+        self.jump_to_selected_file()
     }
 
     /// Move to next feature
     pub fn next_feature(&mut self) {
-        if let Some(story) = &self.story
-            && self.selected_feature < story.narrative.len().saturating_sub(1)
-        {
-            self.selected_feature += 1;
-            self.selected_diff = 0;
-            self.scroll_offset = 0;
+        let Some(story) = &self.story else {
+            return;
+        };
+        let target = (self.selected_feature + 1..story.narrative.len())
+            .find_map(|i| self.feature_entry_diff(i).map(|j| (i, j)));
+        if let Some((feature_idx, diff_idx)) = target {
+            self.select_diff(feature_idx, diff_idx);
+        }
+    }
+
+    /// Select feature `number` (from 1) at its first navigable diff
+    pub fn go_to_feature(&mut self, number: usize) -> bool {
+        let feature_idx = number.wrapping_sub(1);
+        if self.story.as_ref().is_none_or(|story| feature_idx >= story.narrative.len()) {
+            return false;
+        }
+        let diff_idx = self.feature_entry_diff(feature_idx).unwrap_or(0);
+        self.select_diff(feature_idx, diff_idx);
+        true
+    }
+
+    /// Select diff `number` (from 1) in the current feature
+    pub fn go_to_diff(&mut self, number: usize) -> bool {
+        let diff_idx = number.wrapping_sub(1);
+        if diff_idx >= self.diff_count(self.selected_feature) {
+            return false;
         }
+        self.selected_diff = diff_idx;
+        true
     }
 
     /// Move to previous feature
     pub fn prev_feature(&mut self) {
-        if self.selected_feature > 0 {
-            self.selected_feature -= 1;
-            self.selected_diff = 0;
-            self.scroll_offset = 0;
+        let target = (0..self.selected_feature)
+            .rev()
+            .find_map(|i| self.feature_entry_diff(i).map(|j| (i, j)));
+        if let Some((feature_idx, diff_idx)) = target {
+            self.select_diff(feature_idx, diff_idx);
         }
     }
 
     /// Move to next diff within current feature
     pub fn next_diff(&mut self) {
-        if let Some(story) = &self.story
-            && let Some(feature) = story.narrative.get(self.selected_feature)
-            && self.selected_diff < feature.diff_blocks.len().saturating_sub(1)
+        let feature_idx = self.selected_feature;
+        if let Some(diff_idx) = (self.selected_diff + 1..self.diff_count(feature_idx))
+            .find(|&j| self.is_navigable(feature_idx, j))
         {
-            self.selected_diff += 1;
+            self.selected_diff = diff_idx;
         }
     }
 
     /// Move to previous diff within current feature
     pub fn prev_diff(&mut self) {
-        if self.selected_diff > 0 {
-            self.selected_diff -= 1;
+        let feature_idx = self.selected_feature;
+        if let Some(diff_idx) = (0..self.selected_diff)
+            .rev()
+            .find(|&j| self.is_navigable(feature_idx, j))
+        {
+            self.selected_diff = diff_idx;
+        }
+    }
+
+    /// Jump to the next unviewed diff, wrapping around to the start.
+    /// Returns false when every navigable diff has been viewed.
+    pub fn next_unviewed(&mut self) -> bool {
+        let Some(story) = &self.story else {
+            return false;
+        };
+        let positions: Vec<(usize, usize)> = story
+            .narrative
+            .iter()
+            .enumerate()
+            .flat_map(|(i, feature)| (0..feature.diff_blocks.len()).map(move |j| (i, j)))
+            .collect();
+        let current = positions
+            .iter()
+            .position(|&p| p == (self.selected_feature, self.selected_diff))
+            .unwrap_or(0);
+        let target = positions
+            .iter()
+            .cycle()
+            .skip(current + 1)
+            .take(positions.len())
+            .find(|&&(i, j)| !self.is_diff_viewed(i, j) && self.is_navigable(i, j))
+            .copied();
+
+        match target {
+            Some((feature_idx, diff_idx)) => {
+                self.select_diff(feature_idx, diff_idx);
+                true
+            }
+            None => false,
         }
     }
 
-    /// Toggle viewed status for current diff
-    pub fn toggle_viewed(&mut self) {
+    /// Toggle whether navigation skips noise blocks
+    pub fn toggle_skip_noise(&mut self) {
+        self.skip_noise = !self.skip_noise;
+    }
+
+    /// Toggle viewed for the current diff. When that changes whether its whole
+    /// file is viewed, returns the file and its new state to sync to GitHub
+    pub fn toggle_viewed(&mut self) -> Option<(String, bool)> {
         let key = (self.selected_feature, self.selected_diff);
+        let file = self.changed_files.iter().position(|file| file.blocks.contains(&key));
+        let was_viewed = file.map(|i| self.is_file_viewed(&self.changed_files[i]));
         if self.viewed_diffs.contains(&key) {
             self.viewed_diffs.remove(&key);
         } else {
             self.viewed_diffs.insert(key);
         }
+
+        let file = &self.changed_files[file?];
+        let viewed = self.is_file_viewed(file);
+        if was_viewed == Some(viewed) {
+            return None;
+        }
+        Some((file.path.clone(), viewed))
+    }
+
+    /// Mark every block of files viewed on GitHub as viewed in the shown story
+    pub fn mark_files_viewed(&mut self, paths: &[String]) {
+        for file in self.changed_files.iter().filter(|file| paths.contains(&file.path)) {
+            self.viewed_diffs.extend(file.blocks.iter().copied());
+        }
     }
 
     /// Check if a diff is viewed
@@ -179,6 +1639,125 @@ impl App {
         self.viewed_diffs.contains(&(feature_idx, diff_idx))
     }
 
+    /// Toggle the needs-follow-up flag for current diff
+    pub fn toggle_follow_up(&mut self) {
+        let key = (self.selected_feature, self.selected_diff);
+        if !self.follow_up_diffs.remove(&key) {
+            self.follow_up_diffs.insert(key);
+        }
+    }
+
+    /// Toggle inline surrounding code for the current diff. Returns the block's
+    /// file when it still needs fetching from the PR head.
+    pub fn toggle_expanded_context(&mut self) -> Option<String> {
+        let key = (self.selected_feature, self.selected_diff);
+        if self.expanded_context.remove(&key) {
+            return None;
+        }
+        self.expanded_context.insert(key);
+
+        let (path, _) = self.block_location(key.0, key.1)?;
+        (!self.head_files.contains_key(&path)).then_some(path)
+    }
+
+    /// File and head-side line a story diff block points at in the raw diff
+    pub fn block_location(&self, feature_idx: usize, diff_idx: usize) -> Option<(String, u32)> {
+        let block = self
+            .story
+            .as_ref()?
+            .narrative
+            .get(feature_idx)?
+            .diff_blocks
+            .get(diff_idx)?;
+        let files = parse_diff(&self.pr.as_ref()?.diff);
+        locate_block(&files, block)
+    }
+
+    /// Open the full-file overlay on the current diff's file, scrolled to the block.
+    /// Returns the path when the file still needs fetching from the PR head.
+    pub fn open_file_view(&mut self) -> Option<String> {
+        let Some((path, line)) = self.block_location(self.selected_feature, self.selected_diff)
+        else {
+            self.status = Some("Couldn't find this block's file in the PR diff".to_string());
+            return None;
+        };
+        // Leave a few lines of lead-in above the block
+        self.file_view_scroll = u16::try_from(line.saturating_sub(6)).unwrap_or(u16::MAX);
+        self.file_view_path = Some(path.clone());
+        self.state = AppState::FileView;
+        (!self.head_files.contains_key(&path)).then_some(path)
+    }
+
+    /// Open the blame overlay for the lines the current diff rewrites.
+    /// Returns the path when its blame still needs fetching.
+    pub fn open_blame_view(&mut self) -> Option<String> {
+        let block = self
+            .story
+            .as_ref()?
+            .narrative
+            .get(self.selected_feature)?
+            .diff_blocks
+            .get(self.selected_diff)?;
+        let files = parse_diff(&self.pr.as_ref()?.diff);
+        let Some((path, start, end)) = block_base_range(&files, block) else {
+            self.status = Some("Couldn't find this block's lines in the PR diff".to_string());
+            return None;
+        };
+        self.blame_view = Some((path.clone(), start, end));
+        self.state = AppState::BlameView;
+        (!self.blame.contains_key(&path)).then_some(path)
+    }
+
+    /// Scroll the full-file overlay down, stopping at the last line
+    pub fn file_view_down(&mut self, amount: u16) {
+        let line_count = self
+            .file_view_path
+            .as_ref()
+            .and_then(|path| self.head_files.get(path))
+            .map_or(0, |contents| contents.lines().count());
+        let max = u16::try_from(line_count.saturating_sub(1)).unwrap_or(u16::MAX);
+        self.file_view_scroll = self.file_view_scroll.saturating_add(amount).min(max);
+    }
+
+    /// Scroll the full-file overlay up
+    pub fn file_view_up(&mut self, amount: u16) {
+        self.file_view_scroll = self.file_view_scroll.saturating_sub(amount);
+    }
+
+    /// Files of key blocks that haven't been fetched yet, so their context is ready to expand
+    pub fn missing_key_block_files(&self) -> Vec<String> {
+        let Some(story) = &self.story else {
+            return Vec::new();
+        };
+        let mut paths: Vec<String> = Vec::new();
+        for (i, feature) in story.narrative.iter().enumerate() {
+            for (j, block) in feature.diff_blocks.iter().enumerate() {
+                if block.significance != Significance::Key {
+                    continue;
+                }
+                if let Some((path, _)) = self.block_location(i, j)
+                    && !self.head_files.contains_key(&path)
+                    && !paths.contains(&path)
+                {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+
+    /// Check if a diff is flagged for follow-up
+    pub fn is_diff_flagged(&self, feature_idx: usize, diff_idx: usize) -> bool {
+        self.follow_up_diffs.contains(&(feature_idx, diff_idx))
+    }
+
+    /// Flagged diffs in story order as (feature_idx, diff_idx)
+    pub fn flagged_diffs(&self) -> Vec<(usize, usize)> {
+        let mut flagged: Vec<(usize, usize)> = self.follow_up_diffs.iter().copied().collect();
+        flagged.sort_unstable();
+        flagged
+    }
+
     /// Get viewed/total diff counts for a feature
     pub fn feature_progress(&self, feature_idx: usize) -> (usize, usize) {
         if let Some(story) = &self.story
@@ -203,6 +1782,78 @@ impl App {
         (0, 0)
     }
 
+    /// Time the selected block while the story is being read, pausing elsewhere
+    pub fn tick_clock(&mut self, now: Instant) {
+        let reading = matches!(
+            self.state,
+            AppState::Viewing | AppState::Searching | AppState::FileView | AppState::BlameView
+        );
+        let block = (reading && self.story.is_some())
+            .then_some((self.selected_feature, self.selected_diff));
+        self.clock.tick(block, now);
+    }
+
+    /// The review report for the current story, if one is loaded
+    pub fn review_report(&self, submitted_at: u64) -> Option<ReviewReport> {
+        let story = self.story.as_ref()?;
+        let pr = self.pr.as_ref()?;
+        Some(ReviewReport::new(
+            format!("{}/{}#{}", pr.owner, pr.repo, pr.number),
+            submitted_at,
+            story,
+            &self.clock,
+            &self.viewed_diffs,
+        ))
+    }
+
+    /// Images linked from the PR body
+    pub fn attachments(&self) -> Vec<Attachment> {
+        self.pr.as_ref().map_or_else(Vec::new, |pr| find_images(&pr.body))
+    }
+
+    /// Step to the next attachment, wrapping, and return its URL
+    pub fn next_attachment(&mut self) -> Option<(usize, Attachment)> {
+        let attachments = self.attachments();
+        if attachments.is_empty() {
+            return None;
+        }
+        let next = self.attachment_opened.map_or(0, |last| (last + 1) % attachments.len());
+        self.attachment_opened = Some(next);
+        attachments.into_iter().nth(next).map(|attachment| (next, attachment))
+    }
+
+    /// The review so far as an analytics record, if a story is loaded
+    pub fn session_record(&self, exported_at: u64) -> Option<SessionRecord> {
+        let story = self.story.as_ref()?;
+        let pr = self.pr.as_ref()?;
+        let suggested = ActionTexts::from_story(story);
+        let drafts = ReviewAction::ALL
+            .into_iter()
+            .map(|action| {
+                let suggested = suggested.get(action);
+                let text = self.action_text(action);
+                DraftRecord {
+                    action: action.title().to_string(),
+                    suggested: suggested.to_string(),
+                    text: text.to_string(),
+                    edited: text != suggested,
+                    submitted: self.submitted_actions.contains(&action),
+                }
+            })
+            .collect();
+        Some(SessionRecord {
+            pr: format!("{}/{}#{}", pr.owner, pr.repo, pr.number),
+            title: pr.title.clone(),
+            head_sha: pr.head_sha.clone(),
+            model: self.active_model().map(str::to_string),
+            exported_at,
+            seconds: self.clock.total().as_secs(),
+            drafts,
+            blocks: block_records(story, &self.viewed_diffs, &self.follow_up_diffs, &self.clock),
+            story: story.clone(),
+        })
+    }
+
     /// Enter edit mode for current action
     pub fn start_editing(&mut self) {
         self.cursor_pos = self.current_action_text().len();
@@ -220,31 +1871,37 @@ impl App {
         let text = self.current_action_text_mut();
         if cursor <= text.len() {
             text.insert(cursor, c);
-            self.cursor_pos += 1;
+            self.cursor_pos += c.len_utf8();
         }
     }
 
-    /// Delete character before cursor
-    pub fn delete_char(&mut self) {
-        if self.cursor_pos > 0 {
-            let cursor = self.cursor_pos;
-            let text = self.current_action_text_mut();
-            text.remove(cursor - 1);
-            self.cursor_pos -= 1;
+    /// Insert text at the cursor, leaving the cursor after it
+    pub fn insert_text(&mut self, insert: &str) {
+        let cursor = self.cursor_pos;
+        let text = self.current_action_text_mut();
+        if cursor <= text.len() {
+            text.insert_str(cursor, insert);
+            self.cursor_pos += insert.len();
         }
     }
 
+    /// Delete the character (grapheme cluster) before the cursor
+    pub fn delete_char(&mut self) {
+        let cursor = self.cursor_pos;
+        let draft = self.current_action_text_mut();
+        let start = text::prev_boundary(draft, cursor);
+        draft.replace_range(start..cursor.min(draft.len()), "");
+        self.cursor_pos = start;
+    }
+
     /// Move cursor left
     pub fn cursor_left(&mut self) {
-        self.cursor_pos = self.cursor_pos.saturating_sub(1);
+        self.cursor_pos = text::prev_boundary(self.current_action_text(), self.cursor_pos);
     }
 
     /// Move cursor right
     pub fn cursor_right(&mut self) {
-        let len = self.current_action_text().len();
-        if self.cursor_pos < len {
-            self.cursor_pos += 1;
-        }
+        self.cursor_pos = text::next_boundary(self.current_action_text(), self.cursor_pos);
     }
 
     /// Move picker selection down
@@ -274,39 +1931,462 @@ impl App {
 
     /// Move repo selector selection down
     pub fn repo_selector_down(&mut self) {
-        if self.repo_selected < self.repo_list.len().saturating_sub(1) {
+        if self.resume_selected {
+            self.resume_selected = false;
+        } else if self.repo_selected < self.repo_list.len().saturating_sub(1) {
             self.repo_selected += 1;
         }
     }
 
     /// Move repo selector selection up
     pub fn repo_selector_up(&mut self) {
+        if self.repo_selected == 0 {
+            self.resume_selected = self.resume.is_some();
+        }
         self.repo_selected = self.repo_selected.saturating_sub(1);
     }
 
     /// Get currently selected repo in selector
     pub fn selected_repo(&self) -> Option<&RepoListItem> {
+        if self.resume_selected {
+            return None;
+        }
         self.repo_list.get(self.repo_selected)
     }
 
+    /// Cycle the repo selector scope through "mine" and each org.
+    /// Returns true if the scope changed.
+    pub fn cycle_repo_org(&mut self, forward: bool) -> bool {
+        let Some(orgs) = &self.orgs else {
+            return false;
+        };
+        if orgs.is_empty() {
+            return false;
+        }
+
+        // Scope 0 is the user's own repos; 1..=n are orgs
+        let scopes = orgs.len() + 1;
+        let current = self
+            .repo_org
+            .as_ref()
+            .and_then(|org| orgs.iter().position(|o| o == org))
+            .map_or(0, |i| i + 1);
+        let next = if forward {
+            (current + 1) % scopes
+        } else {
+            (current + scopes - 1) % scopes
+        };
+
+        self.repo_org = next.checked_sub(1).map(|i| orgs[i].clone());
+        true
+    }
+
+    /// Move review queue selection down
+    pub fn queue_down(&mut self) {
+        if self.queue_selected < self.queue.len().saturating_sub(1) {
+            self.queue_selected += 1;
+        }
+    }
+
+    /// Move review queue selection up
+    pub fn queue_up(&mut self) {
+        self.queue_selected = self.queue_selected.saturating_sub(1);
+    }
+
+    /// Get currently selected PR in review queue
+    pub fn selected_queue_item(&self) -> Option<&QueueItem> {
+        self.queue.get(self.queue_selected)
+    }
+
+    /// History key for a PR in the current repo
+    pub fn pr_key(&self, number: u32) -> Option<String> {
+        self.current_repo
+            .as_ref()
+            .map(|(owner, repo)| format!("{}/{}#{}", owner, repo, number))
+    }
+
+    /// Order repos favorites-first, then recent, keeping the selection on the same repo
+    pub fn sort_repo_list(&mut self) {
+        let selected = self.selected_repo().map(|r| r.full_name());
+        let history = &self.history;
+        self.repo_list
+            .sort_by_cached_key(|r| history.repo_rank(&r.full_name()));
+        if let Some(selected) = selected {
+            self.repo_selected = self
+                .repo_list
+                .iter()
+                .position(|r| r.full_name() == selected)
+                .unwrap_or(0);
+        }
+    }
+
+    /// Order PRs favorites-first, then recent, then by `pr_sort`, keeping the selection
+    /// on the same PR
+    pub fn sort_pr_list(&mut self) {
+        let selected = self.selected_pr().map(|pr| pr.number);
+        let ranks: Vec<(u8, usize)> = self
+            .pr_list
+            .iter()
+            .map(|pr| {
+                self.pr_key(pr.number)
+                    .map_or((2, 0), |key| self.history.pr_rank(&key))
+            })
+            .collect();
+        let mut ranked: Vec<_> = ranks.into_iter().zip(self.pr_list.drain(..)).collect();
+        let sort = self.pr_sort;
+        ranked.sort_by(|(rank_a, a), (rank_b, b)| {
+            rank_a.cmp(rank_b).then_with(|| sort.compare(a, b))
+        });
+        self.pr_list = ranked.into_iter().map(|(_, pr)| pr).collect();
+        if let Some(selected) = selected {
+            self.picker_selected = self
+                .pr_list
+                .iter()
+                .position(|pr| pr.number == selected)
+                .unwrap_or(0);
+        }
+    }
+
+    /// Drop drafts from the PR list when they're hidden, keeping the selection in range
+    pub fn drop_hidden_prs(&mut self) {
+        if self.hide_drafts {
+            self.pr_list.retain(|pr| !pr.is_draft);
+            self.picker_selected = self.picker_selected.min(self.pr_list.len().saturating_sub(1));
+        }
+    }
+
     /// Go back to repo selector from PR picker
     pub fn back_to_repo_selector(&mut self) {
         self.show_picker = false;
         self.pr_list.clear();
+        self.pr_paging = Paging::default();
         self.picker_selected = 0;
         self.state = AppState::RepoSelector;
     }
 
-    /// Reset for loading a new PR
-    pub fn reset_for_new_pr(&mut self) {
+    /// Record a progress step. A new PR fetch restarts the log and timer;
+    /// streamed token counts update the previous count in place.
+    pub fn record_progress(&mut self, event: ProgressEvent) {
+        if matches!(event, ProgressEvent::FetchingPr { .. }) {
+            self.progress.clear();
+            self.progress_started = None;
+        }
+        // Running counts replace their last report
+        let running = |event: &ProgressEvent| {
+            matches!(
+                event,
+                ProgressEvent::ReceivedTokens { .. } | ProgressEvent::SummarizedFile { .. }
+            )
+        };
+        if self.progress.last().is_some_and(|last| {
+            running(last) && std::mem::discriminant(last) == std::mem::discriminant(&event)
+        }) {
+            self.progress.pop();
+        }
+        self.progress_started.get_or_insert_with(Instant::now);
+        self.progress.push(event);
+    }
+
+    /// Drop the generated stories and the review progress made on them
+    pub fn reset_story(&mut self) {
         self.story = None;
+        self.story_variants.clear();
+        self.active_variant = 0;
+        self.pending_models.clear();
+        self.generation_provider = None;
+        self.fallback_for = None;
         self.selected_feature = 0;
         self.selected_diff = 0;
         self.scroll_offset = 0;
+        self.feature_scroll.clear();
         self.viewed_diffs.clear();
+        self.clock = ReviewClock::default();
+        self.follow_up_diffs.clear();
+        self.block_notes.clear();
+        self.note_editing = None;
+        self.show_notes = false;
+        self.pending_comments.clear();
+        self.review_event = ReviewEvent::default();
+        self.comment_editing = None;
+        self.diff_files.clear();
+        self.changed_files.clear();
+        self.file_selected = 0;
+        self.symbols.clear();
+        self.api_report = ApiReport::default();
+        self.deep_dives.clear();
+        self.deep_dive_pending = None;
+        self.deep_dive_preview = None;
+        self.symbol_selected = 0;
+        self.symbol_block = 0;
+        self.expanded_context.clear();
+        self.related_trail.clear();
+        self.go_to = None;
+        self.count = None;
         self.action_texts = ActionTexts::default();
+    }
+
+    /// Reset for loading a new PR
+    pub fn reset_for_new_pr(&mut self) {
+        self.reset_story();
+        self.custom_prompt_sources.clear();
+        self.head_files.clear();
+        self.blame.clear();
+        self.workspace_results.clear();
         self.show_picker = false;
         self.current_pr_number = None;
+        self.batch.clear();
+        self.submitted_actions.clear();
+        self.pr_node_id = None;
+        self.activity = None;
+        self.seen_comments = None;
+        self.show_activity = false;
+        self.attachment_opened = None;
+        self.secret_findings.clear();
+        self.secrets_choice = None;
+    }
+
+    /// Snapshot of the displayed story and the review progress on it
+    pub fn session(&self) -> Option<Session> {
+        let (Some(pr), Some(story), Some(model)) = (&self.pr, &self.story, self.active_model())
+        else {
+            return None;
+        };
+        Some(Session {
+            pr: pr.clone(),
+            model: model.to_string(),
+            story: story.clone(),
+            drafts: Drafts {
+                request_changes: self.action_texts.request_changes.clone(),
+                clarification: self.action_texts.clarification.clone(),
+                next_pr: self.action_texts.next_pr.clone(),
+            },
+            viewed_diffs: self.viewed_diffs.iter().copied().collect(),
+            follow_up_diffs: self.follow_up_diffs.iter().copied().collect(),
+            block_notes: self.block_notes.clone().into_iter().collect(),
+            pending_comments: self.pending_comments.clone(),
+            selected_feature: self.selected_feature,
+            selected_diff: self.selected_diff,
+            scroll_offset: self.scroll_offset,
+            feature_scroll: self.feature_scroll.iter().map(|(&i, &row)| (i, row)).collect(),
+        })
+    }
+
+    /// Reopen a saved session where it left off
+    pub fn restore_session(&mut self, session: Session) {
+        self.reset_for_new_pr();
+        self.current_repo = Some((session.pr.owner.clone(), session.pr.repo.clone()));
+        self.current_pr_number = Some(session.pr.number);
+        self.head_files = session.pr.head_files.clone();
+        self.pr = Some(session.pr);
+        self.add_story_variant(session.model, session.story);
+        self.action_texts = ActionTexts {
+            request_changes: session.drafts.request_changes,
+            clarification: session.drafts.clarification,
+            next_pr: session.drafts.next_pr,
+        };
+        self.viewed_diffs = session.viewed_diffs.into_iter().collect();
+        self.follow_up_diffs = session.follow_up_diffs.into_iter().collect();
+        self.block_notes = session.block_notes.into_iter().collect();
+        self.pending_comments = session.pending_comments;
+        if self.diff_count(session.selected_feature) > session.selected_diff {
+            self.selected_feature = session.selected_feature;
+            self.selected_diff = session.selected_diff;
+        }
+        self.scroll_offset = session.scroll_offset;
+        self.feature_scroll = session.feature_scroll.into_iter().collect();
+    }
+
+    /// Move the displayed PR's state out of the app, leaving it empty
+    fn park_tab(&mut self) -> PrTab {
+        // Browsing to another repo rewrites the current repo before a PR is picked
+        if let Some(pr) = &self.pr {
+            self.current_repo = Some((pr.owner.clone(), pr.repo.clone()));
+            self.current_pr_number = Some(pr.number);
+        }
+        PrTab {
+            pr: self.pr.take(),
+            current_repo: self.current_repo.take(),
+            current_pr_number: self.current_pr_number.take(),
+            story: self.story.take(),
+            story_variants: std::mem::take(&mut self.story_variants),
+            active_variant: std::mem::take(&mut self.active_variant),
+            pending_models: std::mem::take(&mut self.pending_models),
+            // Copied, not taken: a new tab starts with the same settings
+            persona: self.persona,
+            language: self.language.clone(),
+            model_params: self.model_params,
+            custom_prompt_sources: std::mem::take(&mut self.custom_prompt_sources),
+            selected_feature: std::mem::take(&mut self.selected_feature),
+            selected_diff: std::mem::take(&mut self.selected_diff),
+            scroll_offset: std::mem::take(&mut self.scroll_offset),
+            feature_scroll: std::mem::take(&mut self.feature_scroll),
+            action_texts: std::mem::take(&mut self.action_texts),
+            viewed_diffs: std::mem::take(&mut self.viewed_diffs),
+            clock: std::mem::take(&mut self.clock),
+            follow_up_diffs: std::mem::take(&mut self.follow_up_diffs),
+            block_notes: std::mem::take(&mut self.block_notes),
+            pending_comments: std::mem::take(&mut self.pending_comments),
+            review_event: std::mem::take(&mut self.review_event),
+            diff_files: std::mem::take(&mut self.diff_files),
+            changed_files: std::mem::take(&mut self.changed_files),
+            file_selected: std::mem::take(&mut self.file_selected),
+            symbols: std::mem::take(&mut self.symbols),
+            api_report: std::mem::take(&mut self.api_report),
+            deep_dives: std::mem::take(&mut self.deep_dives),
+            deep_dive_pending: self.deep_dive_pending.take(),
+            expanded_context: std::mem::take(&mut self.expanded_context),
+            head_files: std::mem::take(&mut self.head_files),
+            blame: std::mem::take(&mut self.blame),
+            pr_node_id: self.pr_node_id.take(),
+            activity: self.activity.take(),
+            seen_comments: self.seen_comments.take(),
+            submitted_actions: std::mem::take(&mut self.submitted_actions),
+        }
+    }
+
+    /// Display a parked tab
+    fn unpark_tab(&mut self, tab: PrTab) {
+        self.reset_for_new_pr();
+        self.pr = tab.pr;
+        self.current_repo = tab.current_repo;
+        self.current_pr_number = tab.current_pr_number;
+        self.story = tab.story;
+        self.story_variants = tab.story_variants;
+        self.active_variant = tab.active_variant;
+        self.pending_models = tab.pending_models;
+        self.persona = tab.persona;
+        self.language = tab.language;
+        self.model_params = tab.model_params;
+        self.custom_prompt_sources = tab.custom_prompt_sources;
+        self.selected_feature = tab.selected_feature;
+        self.selected_diff = tab.selected_diff;
+        self.scroll_offset = tab.scroll_offset;
+        self.feature_scroll = tab.feature_scroll;
+        self.action_texts = tab.action_texts;
+        self.viewed_diffs = tab.viewed_diffs;
+        self.clock = tab.clock;
+        self.follow_up_diffs = tab.follow_up_diffs;
+        self.block_notes = tab.block_notes;
+        self.pending_comments = tab.pending_comments;
+        self.review_event = tab.review_event;
+        self.diff_files = tab.diff_files;
+        self.changed_files = tab.changed_files;
+        self.file_selected = tab.file_selected;
+        self.symbols = tab.symbols;
+        self.api_report = tab.api_report;
+        self.deep_dives = tab.deep_dives;
+        self.deep_dive_pending = tab.deep_dive_pending;
+        self.expanded_context = tab.expanded_context;
+        self.head_files = tab.head_files;
+        self.blame = tab.blame;
+        self.pr_node_id = tab.pr_node_id;
+        self.activity = tab.activity;
+        self.seen_comments = tab.seen_comments;
+        self.submitted_actions = tab.submitted_actions;
+        if self.story.is_some() {
+            self.state = AppState::Viewing;
+            // A story that arrived in the background hasn't had its files indexed
+            if self.changed_files.is_empty() {
+                self.index_changed_files();
+            }
+        }
+    }
+
+    /// Park the displayed PR and start an empty tab for the current repo.
+    /// Returns false when every tab is taken
+    pub fn open_tab(&mut self) -> bool {
+        if self.tabs.len() >= MAX_TABS {
+            return false;
+        }
+        let repo = self.current_repo.clone();
+        let parked = self.park_tab();
+        if self.tabs.is_empty() {
+            self.tabs.push(parked);
+        } else {
+            self.tabs[self.active_tab] = parked;
+        }
+        self.tabs.push(PrTab::default());
+        self.active_tab = self.tabs.len() - 1;
+        self.reset_for_new_pr();
+        self.current_repo = repo;
+        true
+    }
+
+    /// Display the tab at `index`. Returns false if it's already shown or doesn't exist
+    pub fn switch_tab(&mut self, index: usize) -> bool {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return false;
+        }
+        let parked = self.park_tab();
+        self.tabs[self.active_tab] = parked;
+        self.active_tab = index;
+        let tab = std::mem::take(&mut self.tabs[index]);
+        self.unpark_tab(tab);
+        true
+    }
+
+    /// Drop the displayed PR and show its neighbour. Returns false for the last tab
+    pub fn close_tab(&mut self) -> bool {
+        if self.tabs.len() < 2 {
+            return false;
+        }
+        self.tabs.remove(self.active_tab);
+        self.active_tab = self.active_tab.min(self.tabs.len() - 1);
+        let tab = std::mem::take(&mut self.tabs[self.active_tab]);
+        self.unpark_tab(tab);
+        if self.tabs.len() == 1 {
+            self.tabs.clear();
+            self.active_tab = 0;
+        }
+        true
+    }
+
+    /// The background tab showing PR `key`
+    pub fn parked_tab_mut(&mut self, key: &str) -> Option<&mut PrTab> {
+        let active = self.active_tab;
+        self.tabs
+            .iter_mut()
+            .enumerate()
+            .find(|(i, tab)| *i != active && tab.pr_key().as_deref() == Some(key))
+            .map(|(_, tab)| tab)
+    }
+
+    /// Tab bar labels, in order
+    pub fn tab_labels(&self) -> Vec<String> {
+        let active = PrTab {
+            current_repo: self.current_repo.clone(),
+            current_pr_number: self.current_pr_number,
+            ..PrTab::default()
+        };
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| if i == self.active_tab { active.label() } else { tab.label() })
+            .collect()
+    }
+
+    /// Commits pushed since the story's diff was fetched
+    pub fn new_commits(&self) -> usize {
+        let (Some(pr), Some(activity)) = (&self.pr, &self.activity) else {
+            return 0;
+        };
+        match activity.commits.iter().position(|sha| *sha == pr.head_sha) {
+            Some(head) => activity.commits.len() - head - 1,
+            // A force-push replaced the commit the story was made from
+            None => activity.commits.len(),
+        }
+    }
+
+    /// Comments posted since reading began and not yet opened
+    pub fn new_comments(&self) -> Vec<&ActivityComment> {
+        let (Some(seen), Some(activity)) = (&self.seen_comments, &self.activity) else {
+            return Vec::new();
+        };
+        activity
+            .comments
+            .iter()
+            .filter(|comment| !seen.contains(&comment.id))
+            .collect()
     }
 }
 
@@ -1,17 +1,109 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use serde_json::json;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn};
+
 use crate::action::Action;
-use crate::config::AppConfig;
-use crate::domain::types::{PrContext, ReviewAction, Story};
-use crate::domain::{github, llm};
+use crate::config::{AppConfig, Forge};
+use crate::domain::types::{
+    CommitRange, FileSummary, InlineComment, ModelParams, Persona, PrContext, ReviewAction,
+    ReviewEvent, Story,
+};
+use crate::domain::history::History;
+use crate::domain::memory::ReviewMemory;
+use crate::domain::reviewed::ReviewedPrs;
+use crate::domain::export::SessionRecord;
+use crate::domain::timing::ReviewReport;
+use crate::domain::outbox::{Outbox, Submission, SubmissionKind};
+use crate::domain::preferences::Preferences;
+use crate::domain::prompt::{build_system_prompt, build_user_prompt};
+use crate::domain::types::{
+    Listing, Page, PrActivity, PrStateFilter, ProgressEvent, ViewedFiles,
+};
+use crate::domain::anonymize::Pseudonyms;
+use crate::domain::bitbucket::BitbucketServer;
+use crate::domain::cache::CacheEntry;
+use crate::domain::{
+    attachments, cache, clipboard, dry_run, github, listings, llm, llm_cache, mock, notify,
+    summarize,
+};
 
 pub enum Command {
-    FetchRepoList,
-    FetchPrList { owner: String, repo: String },
+    /// The repo selector's first load: repos and orgs fetched side by side
+    FetchStartup { org: Option<String> },
+    FetchRepoList { org: Option<String> },
+    FetchOrgList,
+    FetchPrList {
+        owner: String,
+        repo: String,
+        states: PrStateFilter,
+    },
+    /// The next page of the repo selector's list
+    FetchMoreRepos { org: Option<String>, cursor: String },
+    /// The next page of the PR picker's list
+    FetchMorePrs {
+        owner: String,
+        repo: String,
+        states: PrStateFilter,
+        cursor: String,
+    },
+    FetchReviewQueue,
     FetchPr { owner: String, repo: String, number: u32 },
-    GenerateStory { pr: PrContext },
-    LoadCache { path: String },
-    SaveCache { path: String, story: Story },
-    PostReview { owner: String, repo: String, number: u32, body: String },
+    GenerateStory {
+        pr: Box<PrContext>,
+        model: String,
+        persona: Option<Persona>,
+        language: Option<String>,
+        params: ModelParams,
+    },
+    /// A closer look at one feature of the story, from ready-built (system, user) prompts
+    GenerateDeepDive {
+        pr: Box<PrContext>,
+        feature: usize,
+        model: String,
+        prompts: (String, String),
+        params: ModelParams,
+    },
+    FetchHeadFiles { owner: String, repo: String, number: u32, sha: String, paths: Vec<String> },
+    FetchBlame { owner: String, repo: String, number: u32, sha: String, path: String },
+    /// Load a PR's cached stories: (model, path) pairs
+    LoadCache { owner: String, repo: String, number: u32, entries: Vec<(String, String)> },
+    SaveCache { path: String, entry: Box<CacheEntry> },
+    SaveHistory { history: History },
+    SavePreferences { preferences: Preferences },
+    SaveOutbox { outbox: Outbox },
+    SaveMemory { memory: ReviewMemory },
+    SaveReviewed { reviewed: ReviewedPrs },
+    /// Keep a review report in the local reports file
+    SaveReviewReport { report: ReviewReport },
+    /// Tell a team channel what was posted, e.g. "requested changes on payments#412"
+    NotifyWebhook { url: String, summary: String },
+    /// Pop up a desktop notification, or ring the terminal bell where there's none
+    NotifyDesktop { title: String, body: String },
+    /// A review with its inline comments, submitted with `event` as its verdict
+    PostReview {
+        owner: String,
+        repo: String,
+        number: u32,
+        event: ReviewEvent,
+        body: String,
+        commit_id: String,
+        comments: Vec<InlineComment>,
+    },
     PostComment { owner: String, repo: String, number: u32, body: String },
+    PostInlineQuestions {
+        owner: String,
+        repo: String,
+        number: u32,
+        commit_id: String,
+        comments: Vec<InlineComment>,
+        /// Questions that couldn't be anchored, posted as one regular comment
+        fallback_body: Option<String>,
+    },
     CreateNextPrIssue {
         owner: String,
         repo: String,
@@ -19,39 +111,595 @@ pub enum Command {
         title: String,
         body: String,
     },
+    FetchViewedFiles { owner: String, repo: String, number: u32 },
+    FetchActivity { owner: String, repo: String, number: u32 },
+    /// Mark or unmark a file viewed in GitHub's review UI
+    SetFileViewed {
+        owner: String,
+        repo: String,
+        number: u32,
+        pr_id: String,
+        path: String,
+        viewed: bool,
+    },
+    /// Request reviews from users and teams (`org/team`)
+    RequestReviewers { owner: String, repo: String, number: u32, reviewers: Vec<String> },
+    FetchCommits { owner: String, repo: String, number: u32 },
+    /// Fetch the diff of a range of the PR's commits
+    FetchRangeDiff { owner: String, repo: String, number: u32, range: CommitRange },
+    /// Fetch what changed since the head the reviewer last reviewed
+    FetchReviewDelta {
+        owner: String,
+        repo: String,
+        number: u32,
+        reviewed_head: String,
+        head: String,
+    },
+    FetchRateLimit,
+    /// Copy text to the system clipboard; `what` names it in the status line
+    CopyToClipboard { text: String, what: String },
+    /// Write a post command's payload to the dry-run outbox instead of sending it
+    DryRun(Box<Command>),
+    /// Open a link, e.g. a screenshot from the PR body, in the browser
+    OpenInBrowser { url: String },
+    /// Write the review session as JSON to `.dstl/exports/`
+    ExportSession { record: Box<SessionRecord> },
+}
+
+impl Command {
+    /// Name for logs
+    pub fn label(&self) -> &'static str {
+        match self {
+            Command::FetchStartup { .. } => "fetch startup lists",
+            Command::FetchRepoList { .. } => "fetch repo list",
+            Command::FetchOrgList => "fetch org list",
+            Command::FetchPrList { .. } => "fetch PR list",
+            Command::FetchMoreRepos { .. } => "fetch more repos",
+            Command::FetchMorePrs { .. } => "fetch more PRs",
+            Command::FetchReviewQueue => "fetch review queue",
+            Command::FetchPr { .. } => "fetch PR",
+            Command::GenerateStory { .. } => "generate story",
+            Command::GenerateDeepDive { .. } => "generate deep dive",
+            Command::FetchHeadFiles { .. } => "fetch head files",
+            Command::FetchBlame { .. } => "fetch blame",
+            Command::LoadCache { .. } => "load cache",
+            Command::SaveCache { .. } => "save cache",
+            Command::SaveHistory { .. } => "save history",
+            Command::SavePreferences { .. } => "save preferences",
+            Command::SaveOutbox { .. } => "save outbox",
+            Command::SaveMemory { .. } => "save review memory",
+            Command::SaveReviewed { .. } => "save reviewed heads",
+            Command::SaveReviewReport { .. } => "save review report",
+            Command::NotifyWebhook { .. } => "notify webhook",
+            Command::NotifyDesktop { .. } => "notify desktop",
+            Command::PostReview { .. } => "post review",
+            Command::PostComment { .. } => "post comment",
+            Command::PostInlineQuestions { .. } => "post inline questions",
+            Command::CreateNextPrIssue { .. } => "create next-PR issue",
+            Command::FetchViewedFiles { .. } => "fetch viewed files",
+            Command::FetchActivity { .. } => "fetch PR activity",
+            Command::SetFileViewed { .. } => "set file viewed",
+            Command::RequestReviewers { .. } => "request reviewers",
+            Command::FetchCommits { .. } => "fetch commits",
+            Command::FetchRangeDiff { .. } => "fetch commit range diff",
+            Command::FetchReviewDelta { .. } => "fetch changes since last review",
+            Command::FetchRateLimit => "fetch rate limit",
+            Command::CopyToClipboard { .. } => "copy to clipboard",
+            Command::DryRun(_) => "dry-run submission",
+            Command::ExportSession { .. } => "export review session",
+            Command::OpenInBrowser { .. } => "open in browser",
+        }
+    }
+}
+
+impl Command {
+    /// What a post command would submit, so a failure can be kept in the outbox
+    pub fn submission(&self) -> Option<Submission> {
+        let (owner, repo, number, kind) = match self {
+            Command::PostReview {
+                owner,
+                repo,
+                number,
+                event,
+                body,
+                commit_id,
+                comments,
+            } => (
+                owner,
+                repo,
+                number,
+                SubmissionKind::Review {
+                    body: body.clone(),
+                    event: *event,
+                    commit_id: commit_id.clone(),
+                    comments: comments.clone(),
+                },
+            ),
+            Command::PostComment {
+                owner,
+                repo,
+                number,
+                body,
+            } => (owner, repo, number, SubmissionKind::Comment { body: body.clone() }),
+            Command::PostInlineQuestions {
+                owner,
+                repo,
+                number,
+                commit_id,
+                comments,
+                fallback_body,
+            } => (
+                owner,
+                repo,
+                number,
+                SubmissionKind::InlineQuestions {
+                    commit_id: commit_id.clone(),
+                    comments: comments.clone(),
+                    fallback_body: fallback_body.clone(),
+                },
+            ),
+            Command::CreateNextPrIssue {
+                owner,
+                repo,
+                number,
+                title,
+                body,
+            } => (
+                owner,
+                repo,
+                number,
+                SubmissionKind::NextPrIssue {
+                    title: title.clone(),
+                    body: body.clone(),
+                },
+            ),
+            _ => return None,
+        };
+        Some(Submission {
+            owner: owner.clone(),
+            repo: repo.clone(),
+            number: *number,
+            kind,
+        })
+    }
+
+    /// The post command that makes a submission
+    pub fn post(submission: Submission) -> Command {
+        let Submission {
+            owner,
+            repo,
+            number,
+            kind,
+        } = submission;
+        match kind {
+            SubmissionKind::Review {
+                body,
+                event,
+                commit_id,
+                comments,
+            } => Command::PostReview {
+                owner,
+                repo,
+                number,
+                event,
+                body,
+                commit_id,
+                comments,
+            },
+            SubmissionKind::Comment { body } => Command::PostComment {
+                owner,
+                repo,
+                number,
+                body,
+            },
+            SubmissionKind::InlineQuestions {
+                commit_id,
+                comments,
+                fallback_body,
+            } => Command::PostInlineQuestions {
+                owner,
+                repo,
+                number,
+                commit_id,
+                comments,
+                fallback_body,
+            },
+            SubmissionKind::NextPrIssue { title, body } => Command::CreateNextPrIssue {
+                owner,
+                repo,
+                number,
+                title,
+                body,
+            },
+        }
+    }
+}
+
+/// Channel for actions emitted while a command is still running
+pub type ProgressSender = UnboundedSender<Action>;
+
+pub async fn execute_command(
+    command: Command,
+    config: &AppConfig,
+    progress: &ProgressSender,
+) -> Option<Action> {
+    let label = command.label();
+    debug!(command = label, mock = config.mock, "Executing command");
+    let action = match &config.forge {
+        _ if config.mock => execute_mock_command(command, config, progress),
+        Forge::GitHub => execute_live_command(command, config, progress).await,
+        Forge::Bitbucket(server) => {
+            execute_bitbucket_command(command, server, config, progress).await
+        }
+    };
+    if let Some(err) = action.as_ref().and_then(Action::error) {
+        error!(command = label, error = err, "Command failed");
+    }
+    action
+}
+
+/// Summarize each file of a diff too large to send whole, `map_concurrency` calls
+/// at a time, reporting each as it lands
+async fn summarize_files(
+    pr: &PrContext,
+    config: &AppConfig,
+    pseudonyms: &Pseudonyms,
+    on_progress: impl Fn(ProgressEvent),
+) -> anyhow::Result<Vec<FileSummary>> {
+    let requests = summarize::file_requests(pr, |path| !config.redaction.withholds(path));
+    let total = requests.len();
+    let system_prompt = pseudonyms.hide(&config.redaction.replace(summarize::MAP_SYSTEM_PROMPT));
+    let limit = Arc::new(Semaphore::new(config.map_concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+    for (i, request) in requests.into_iter().enumerate() {
+        let prompts = (
+            system_prompt.clone(),
+            pseudonyms.hide(&config.redaction.replace(&request.prompt)),
+        );
+        let (label, providers, model) =
+            (pr.pr_label(), config.providers.clone(), config.map_model.clone());
+        let (timeout, redaction, limit) =
+            (config.llm_timeout, config.redaction.clone(), limit.clone());
+        tasks.spawn(async move {
+            // Never closed, so waiting only ends with a permit
+            let _permit = limit.acquire_owned().await;
+            let summary =
+                llm::summarize_file(&label, &providers, &model, timeout, prompts, &redaction).await;
+            (i, request, summary)
+        });
+    }
+
+    on_progress(ProgressEvent::SummarizedFile { done: 0, total });
+    // In diff order, however they finish
+    let mut summaries: Vec<Option<FileSummary>> = vec![None; total];
+    let mut done = 0;
+    while let Some(joined) = tasks.join_next().await {
+        let (i, request, summary) = joined?;
+        let summary = summary.with_context(|| format!("Failed to summarize {}", request.path))?;
+        summaries[i] = Some(FileSummary {
+            path: request.path,
+            summary,
+            hunks: request.hunks,
+        });
+        done += 1;
+        on_progress(ProgressEvent::SummarizedFile { done, total });
+    }
+    Ok(summaries.into_iter().flatten().collect())
 }
 
-pub async fn execute_command(command: Command, config: &AppConfig) -> Option<Action> {
+async fn execute_live_command(
+    command: Command,
+    config: &AppConfig,
+    progress: &ProgressSender,
+) -> Option<Action> {
+    let on_progress = |event| {
+        // The receiver only goes away when the app is exiting
+        let _ = progress.send(Action::Progress(event));
+    };
+
     match command {
-        Command::FetchRepoList => {
-            let result = github::fetch_repo_list().map_err(|e| e.to_string());
+        Command::FetchStartup { org } => {
+            // Each gh call blocks, so give each its own thread; the user lookup
+            // warms the cache PR listings read from later
+            let user = tokio::task::spawn_blocking(github::current_user);
+            let retries = config.gh_retries;
+            let repos = tokio::task::spawn_blocking(move || {
+                let fetch = listings::with_retries(retries, || {
+                    github::fetch_repo_list(org.as_deref(), None)
+                });
+                listings::repos_with_fallback(org.as_deref().unwrap_or_default(), fetch)
+                    .map_err(|e| e.to_string())
+            });
+            let orgs = tokio::task::spawn_blocking(fetch_orgs);
+            let (_, repos, orgs) = tokio::join!(user, repos, orgs);
+            Some(Action::StartupLoaded {
+                repos: repos.unwrap_or_else(|err| Err(err.to_string())),
+                orgs: orgs.unwrap_or_default(),
+            })
+        }
+        Command::FetchRepoList { org } => {
+            let fetch = listings::with_retries(config.gh_retries, || {
+                github::fetch_repo_list(org.as_deref(), None)
+            });
+            let result = listings::repos_with_fallback(org.as_deref().unwrap_or_default(), fetch)
+                .map_err(|e| e.to_string());
             Some(Action::RepoListLoaded(result))
         }
-        Command::FetchPrList { owner, repo } => {
-            let result = github::fetch_pr_list(&owner, &repo).map_err(|e| e.to_string());
+        Command::FetchOrgList => Some(Action::OrgListLoaded(fetch_orgs())),
+        Command::FetchPrList {
+            owner,
+            repo,
+            states,
+        } => {
+            let fetch = listings::with_retries(config.gh_retries, || {
+                github::fetch_pr_list(&owner, &repo, states, None)
+            });
+            // Each filter falls back to its own cached copy
+            let scope = match states {
+                PrStateFilter::Open => format!("{}/{}", owner, repo),
+                _ => format!("{}/{} ({})", owner, repo, states.label()),
+            };
+            let result = listings::prs_with_fallback(&scope, fetch).map_err(|e| e.to_string());
             Some(Action::PrListLoaded(result))
         }
+        Command::FetchMoreRepos { org, cursor } => {
+            let result = listings::with_retries(config.gh_retries, || {
+                github::fetch_repo_list(org.as_deref(), Some(&cursor))
+            })
+            .map_err(|e| e.to_string());
+            Some(Action::MoreReposLoaded { org, result })
+        }
+        Command::FetchMorePrs {
+            owner,
+            repo,
+            states,
+            cursor,
+        } => {
+            let result = listings::with_retries(config.gh_retries, || {
+                github::fetch_pr_list(&owner, &repo, states, Some(&cursor))
+            })
+            .map_err(|e| e.to_string());
+            Some(Action::MorePrsLoaded {
+                owner,
+                repo,
+                states,
+                result,
+            })
+        }
+        Command::FetchReviewQueue => {
+            let result = github::fetch_review_queue(&config.queue_repos).map_err(|e| e.to_string());
+            Some(Action::ReviewQueueLoaded(result))
+        }
         Command::FetchPr { owner, repo, number } => {
-            let result = github::fetch_pr(&owner, &repo, number)
+            let result = github::fetch_pr(&owner, &repo, number, config.fetch_options(), on_progress)
                 .await
                 .map_err(|e| e.to_string());
             Some(Action::PrLoaded(result))
         }
-        Command::GenerateStory { pr } => {
-            let result = llm::generate_story(&pr, &config.api_key, &config.model)
+        Command::GenerateStory {
+            pr,
+            model,
+            persona,
+            language,
+            params,
+        } => {
+            let key = pr.pr_label();
+            let (pseudonyms, prompts) = config.outbound_prompts(&pr, persona, language.as_deref());
+            let cache_key = llm_cache::key(&model, &params, &prompts.0, &prompts.1);
+            if config.llm_cache
+                && let Some(story) = llm_cache::load::<Story>(&cache_key)
+            {
+                info!(model, "Reusing the response to an identical prompt");
+                on_progress(ProgressEvent::ReusedResponse);
+                return Some(Action::StoryGenerated {
+                    pr: key,
+                    model,
+                    result: pseudonyms.reveal(story).map_err(|e| e.to_string()),
+                });
+            }
+            let mapped = summarize::needs_map(&pr, config.map_threshold);
+            let generation = async {
+                // Too large to send whole: the story is built from a summary of each file
+                let prompts = if mapped {
+                    let mut summarized = (*pr).clone();
+                    summarized.file_summaries =
+                        summarize_files(&pr, config, &pseudonyms, on_progress).await?;
+                    config.outbound_prompts(&summarized, persona, language.as_deref()).1
+                } else {
+                    prompts
+                };
+                llm::generate_with_fallback(
+                    &pr,
+                    &config.providers,
+                    &model,
+                    config.llm_timeout,
+                    prompts,
+                    &config.redaction,
+                    &params,
+                    on_progress,
+                )
                 .await
-                .map_err(|e| e.to_string());
-            Some(Action::StoryGenerated(result))
+            };
+            let result = match config.generation_budget {
+                Some(budget) => {
+                    let outcome = tokio::time::timeout(budget, generation).await;
+                    match outcome {
+                        Ok(result) => result,
+                        Err(_) => {
+                            warn!(model, budget_secs = budget.as_secs(), "Generation ran over budget");
+                            return Some(Action::StoryGenerationTimedOut { pr: *pr, model });
+                        }
+                    }
+                }
+                None => generation.await,
+            };
+            let result = result.map(|mut story| {
+                if mapped {
+                    summarize::fill_hunks(&mut story, &pseudonyms.hide(&pr.diff));
+                }
+                story
+            });
+            if config.llm_cache
+                && let Ok(story) = &result
+                && let Err(err) = llm_cache::save(&cache_key, story)
+            {
+                warn!(error = %err, "Failed to cache the model's response");
+            }
+            Some(Action::StoryGenerated {
+                pr: key,
+                model,
+                result: result
+                    .and_then(|story| pseudonyms.reveal(story))
+                    .map_err(|e| e.to_string()),
+            })
+        }
+        Command::GenerateDeepDive {
+            pr,
+            feature,
+            model,
+            prompts,
+            params,
+        } => {
+            let pseudonyms = config.pseudonyms(&pr);
+            let prompts = config.outbound_deep_dive_prompts(&pr, &prompts);
+            let cache_key = llm_cache::key(&model, &params, &prompts.0, &prompts.1);
+            let cached = config.llm_cache.then(|| llm_cache::load(&cache_key)).flatten();
+            let result = match cached {
+                Some(deep_dive) => Ok(deep_dive),
+                None => {
+                    let result = llm::generate_deep_dive(
+                        &pr.pr_label(),
+                        &config.providers,
+                        &model,
+                        config.llm_timeout,
+                        prompts,
+                        &config.redaction,
+                        &params,
+                    )
+                    .await;
+                    if config.llm_cache
+                        && let Ok(deep_dive) = &result
+                        && let Err(err) = llm_cache::save(&cache_key, deep_dive)
+                    {
+                        warn!(error = %err, "Failed to cache the deep dive");
+                    }
+                    result
+                }
+            };
+            Some(Action::DeepDiveGenerated {
+                pr: pr.pr_label(),
+                feature,
+                result: result
+                    .and_then(|deep_dive| pseudonyms.reveal(deep_dive))
+                    .map_err(|e| e.to_string()),
+            })
         }
-        Command::LoadCache { path } => {
-            let story = std::fs::read_to_string(path)
-                .ok()
-                .and_then(|contents| serde_json::from_str(&contents).ok());
-            Some(Action::CacheLoaded(story))
+        Command::FetchHeadFiles {
+            owner,
+            repo,
+            number,
+            sha,
+            paths,
+        } => {
+            // Deleted and binary files can't be fetched; use whatever is available
+            let files = github::fetch_files_at(&owner, &repo, &sha, paths);
+            let result = if files.is_empty() {
+                Err("Could not fetch any changed files at the PR head".to_string())
+            } else {
+                Ok(files)
+            };
+            Some(Action::HeadFilesLoaded {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                result,
+            })
         }
-        Command::SaveCache { path, story } => {
-            if let Ok(json) = serde_json::to_string_pretty(&story) {
-                let _ = std::fs::write(path, json);
+        Command::FetchBlame {
+            owner,
+            repo,
+            number,
+            sha,
+            path,
+        } => {
+            let result = github::fetch_blame(&owner, &repo, &sha, &path).map_err(|e| e.to_string());
+            Some(Action::BlameLoaded {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                path,
+                result,
+            })
+        }
+        Command::LoadCache {
+            owner,
+            repo,
+            number,
+            entries,
+        } => {
+            let policy = config.cache_policy();
+            let stories = entries
+                .into_iter()
+                .filter_map(|(model, path)| {
+                    let entry = cache::find(&path, &owner, &repo, number, &policy)?;
+                    Some((model, entry))
+                })
+                .collect();
+            Some(Action::CacheLoaded {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                stories,
+            })
+        }
+        Command::SaveCache { path, entry } => {
+            if let Err(err) = cache::save(&config.cache_file, &path, *entry, &config.cache_policy())
+            {
+                warn!(path, error = %err, "Could not save story cache");
+            }
+            None
+        }
+        Command::SaveHistory { history } => {
+            if let Err(err) = history.save() {
+                warn!(error = %err, "Could not save history");
+            }
+            None
+        }
+        Command::SavePreferences { preferences } => {
+            if let Err(err) = preferences.save() {
+                warn!(error = %err, "Could not save preferences");
+            }
+            None
+        }
+        Command::SaveOutbox { outbox } => {
+            if let Err(err) = outbox.save() {
+                warn!(error = %err, "Could not save outbox");
+            }
+            None
+        }
+        Command::SaveMemory { memory } => {
+            if let Err(err) = memory.save() {
+                warn!(error = %err, "Could not save review memory");
+            }
+            None
+        }
+        Command::SaveReviewed { reviewed } => {
+            if let Err(err) = reviewed.save() {
+                warn!(error = %err, "Could not save reviewed heads");
+            }
+            None
+        }
+        Command::SaveReviewReport { report } => {
+            if let Err(err) = report.save() {
+                warn!(error = %err, "Could not save review report");
+            }
+            None
+        }
+        Command::NotifyWebhook { url, summary } => {
+            // The review is already posted; a missed notification is only logged
+            if let Err(err) = notify::send(&url, &github::current_user(), &summary).await {
+                warn!(error = %err, "Could not notify webhook");
+            }
+            None
+        }
+        Command::NotifyDesktop { title, body } => {
+            if let Err(err) = notify::desktop(&title, &body) {
+                debug!(error = %err, "No desktop notification; ringing the bell");
+                notify::bell();
             }
             None
         }
@@ -59,12 +707,17 @@ pub async fn execute_command(command: Command, config: &AppConfig) -> Option<Act
             owner,
             repo,
             number,
+            event,
             body,
+            commit_id,
+            comments,
         } => {
-            let result = github::post_review(&owner, &repo, number, &body)
-                .map(|_| ())
+            let result =
+                github::post_review(&owner, &repo, number, event, &body, &commit_id, &comments)
+                    .map(|_| ())
                 .map_err(|e| e.to_string());
             Some(Action::SubmissionResult {
+                pr: format!("{}/{}#{}", owner, repo, number),
                 action: ReviewAction::RequestChanges,
                 result,
             })
@@ -79,10 +732,32 @@ pub async fn execute_command(command: Command, config: &AppConfig) -> Option<Act
                 .map(|_| ())
                 .map_err(|e| e.to_string());
             Some(Action::SubmissionResult {
+                pr: format!("{}/{}#{}", owner, repo, number),
                 action: ReviewAction::ClarificationQuestions,
                 result,
             })
         }
+        Command::PostInlineQuestions {
+            owner,
+            repo,
+            number,
+            commit_id,
+            comments,
+            fallback_body,
+        } => {
+            let mut result = Ok(());
+            if !comments.is_empty() {
+                result = github::post_inline_comments(&owner, &repo, number, &commit_id, &comments);
+            }
+            if let (Ok(()), Some(body)) = (&result, fallback_body) {
+                result = github::post_comment(&owner, &repo, number, &body);
+            }
+            Some(Action::SubmissionResult {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                action: ReviewAction::ClarificationQuestions,
+                result: result.map_err(|e| e.to_string()),
+            })
+        }
         Command::CreateNextPrIssue {
             owner,
             repo,
@@ -94,9 +769,708 @@ pub async fn execute_command(command: Command, config: &AppConfig) -> Option<Act
                 .map(|_| ())
                 .map_err(|e| e.to_string());
             Some(Action::SubmissionResult {
+                pr: format!("{}/{}#{}", owner, repo, number),
                 action: ReviewAction::NextPr,
                 result,
             })
         }
+        Command::FetchActivity { owner, repo, number } => Some(Action::ActivityLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: github::fetch_activity(&owner, &repo, number).map_err(|e| e.to_string()),
+        }),
+        Command::FetchViewedFiles { owner, repo, number } => Some(Action::ViewedFilesLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: github::fetch_viewed_files(&owner, &repo, number).map_err(|e| e.to_string()),
+        }),
+        Command::SetFileViewed {
+            owner,
+            repo,
+            number,
+            pr_id,
+            path,
+            viewed,
+        } => {
+            let result = github::set_file_viewed(&pr_id, &path, viewed).map_err(|e| e.to_string());
+            Some(Action::FileViewedSynced {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                path,
+                result,
+            })
+        }
+        Command::RequestReviewers {
+            owner,
+            repo,
+            number,
+            reviewers,
+        } => {
+            let result = github::request_reviewers(&owner, &repo, number, &reviewers)
+                .map(|_| reviewers)
+                .map_err(|e| e.to_string());
+            Some(Action::ReviewersRequested {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                result,
+            })
+        }
+        Command::FetchCommits {
+            owner,
+            repo,
+            number,
+        } => {
+            let result = github::fetch_commits(&owner, &repo, number).map_err(|e| e.to_string());
+            Some(Action::CommitsLoaded {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                result,
+            })
+        }
+        Command::FetchRangeDiff {
+            owner,
+            repo,
+            number,
+            range,
+        } => {
+            let head = range.commits.last().map_or("", |commit| commit.sha.as_str());
+            let result = github::fetch_compare_diff(&owner, &repo, &range.base, head)
+                .map_err(|e| e.to_string());
+            Some(Action::RangeDiffLoaded {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                range,
+                result,
+            })
+        }
+        Command::FetchReviewDelta {
+            owner,
+            repo,
+            number,
+            reviewed_head,
+            head,
+        } => {
+            let result = github::fetch_compare_diff(&owner, &repo, &reviewed_head, &head)
+                .map_err(|e| e.to_string());
+            Some(Action::ReviewDeltaLoaded {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                reviewed_head,
+                result,
+            })
+        }
+        Command::FetchRateLimit => Some(Action::RateLimitLoaded(
+            github::fetch_rate_limit().map_err(|e| e.to_string()),
+        )),
+        Command::CopyToClipboard { text, what } => Some(copy_to_clipboard(&text, what)),
+        Command::DryRun(command) => write_dry_run(*command),
+        Command::ExportSession { record } => Some(export_session(&record)),
+        Command::OpenInBrowser { url } => Some(Action::BrowserOpened(
+            attachments::open_in_browser(&url).map_err(|e| e.to_string()),
+        )),
+    }
+}
+
+/// Serve forge commands from Bitbucket Server, leaving the model, local saves and
+/// notifications to the live path. Features only GitHub has fail with a note.
+/// Every command is named here, so a new forge command can't reach GitHub
+/// unnoticed.
+async fn execute_bitbucket_command(
+    command: Command,
+    server: &BitbucketServer,
+    config: &AppConfig,
+    progress: &ProgressSender,
+) -> Option<Action> {
+    let on_progress = |event| {
+        let _ = progress.send(Action::Progress(event));
+    };
+    let unavailable = |feature: &str| format!("{} isn't available on Bitbucket Server", feature);
+
+    match command {
+        Command::FetchStartup { org } => {
+            let (repos, orgs) = tokio::join!(
+                server.fetch_repo_list(org.as_deref(), None),
+                server.fetch_project_list()
+            );
+            let repos = listings::repos_with_fallback(org.as_deref().unwrap_or_default(), repos)
+                .map_err(|e| e.to_string());
+            Some(Action::StartupLoaded {
+                repos,
+                orgs: orgs
+                    .inspect_err(|err| warn!(error = %err, "Could not list projects"))
+                    .unwrap_or_default(),
+            })
+        }
+        Command::FetchRepoList { org } => {
+            let fetch = server.fetch_repo_list(org.as_deref(), None).await;
+            let result = listings::repos_with_fallback(org.as_deref().unwrap_or_default(), fetch)
+                .map_err(|e| e.to_string());
+            Some(Action::RepoListLoaded(result))
+        }
+        Command::FetchOrgList => Some(Action::OrgListLoaded(
+            server
+                .fetch_project_list()
+                .await
+                .inspect_err(|err| warn!(error = %err, "Could not list projects"))
+                .unwrap_or_default(),
+        )),
+        Command::FetchPrList {
+            owner,
+            repo,
+            states,
+        } => {
+            let fetch = server.fetch_pr_list(&owner, &repo, states, None).await;
+            let scope = match states {
+                PrStateFilter::Open => format!("{}/{}", owner, repo),
+                _ => format!("{}/{} ({})", owner, repo, states.label()),
+            };
+            let result = listings::prs_with_fallback(&scope, fetch).map_err(|e| e.to_string());
+            Some(Action::PrListLoaded(result))
+        }
+        Command::FetchMoreRepos { org, cursor } => {
+            let result = server
+                .fetch_repo_list(org.as_deref(), Some(&cursor))
+                .await
+                .map_err(|e| e.to_string());
+            Some(Action::MoreReposLoaded { org, result })
+        }
+        Command::FetchMorePrs {
+            owner,
+            repo,
+            states,
+            cursor,
+        } => {
+            let result = server
+                .fetch_pr_list(&owner, &repo, states, Some(&cursor))
+                .await
+                .map_err(|e| e.to_string());
+            Some(Action::MorePrsLoaded {
+                owner,
+                repo,
+                states,
+                result,
+            })
+        }
+        Command::FetchReviewQueue => Some(Action::ReviewQueueLoaded(
+            server.fetch_review_queue(&config.queue_repos).await.map_err(|e| e.to_string()),
+        )),
+        Command::FetchPr { owner, repo, number } => {
+            let result = server
+                .fetch_pr(&owner, &repo, number, config.fetch_options(), on_progress)
+                .await
+                .map_err(|e| e.to_string());
+            Some(Action::PrLoaded(result))
+        }
+        Command::FetchHeadFiles {
+            owner,
+            repo,
+            number,
+            sha,
+            paths,
+        } => {
+            let files = server.fetch_files_at(&owner, &repo, &sha, paths).await;
+            let result = if files.is_empty() {
+                Err("Could not fetch any changed files at the PR head".to_string())
+            } else {
+                Ok(files)
+            };
+            Some(Action::HeadFilesLoaded {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                result,
+            })
+        }
+        Command::PostReview {
+            owner,
+            repo,
+            number,
+            event,
+            body,
+            comments,
+            ..
+        } => {
+            let result = server
+                .post_review(&owner, &repo, number, event, &body, &comments)
+                .await
+                .map_err(|e| e.to_string());
+            Some(Action::SubmissionResult {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                action: ReviewAction::RequestChanges,
+                result,
+            })
+        }
+        Command::PostComment {
+            owner,
+            repo,
+            number,
+            body,
+        } => {
+            let result = server
+                .post_comment(&owner, &repo, number, &body)
+                .await
+                .map_err(|e| e.to_string());
+            Some(Action::SubmissionResult {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                action: ReviewAction::ClarificationQuestions,
+                result,
+            })
+        }
+        Command::PostInlineQuestions {
+            owner,
+            repo,
+            number,
+            comments,
+            fallback_body,
+            ..
+        } => {
+            let mut result = server.post_inline_comments(&owner, &repo, number, &comments).await;
+            if let (Ok(()), Some(body)) = (&result, fallback_body) {
+                result = server.post_comment(&owner, &repo, number, &body).await;
+            }
+            Some(Action::SubmissionResult {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                action: ReviewAction::ClarificationQuestions,
+                result: result.map_err(|e| e.to_string()),
+            })
+        }
+        Command::CreateNextPrIssue {
+            owner,
+            repo,
+            number,
+            ..
+        } => Some(Action::SubmissionResult {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            action: ReviewAction::NextPr,
+            result: Err("Bitbucket Server has no issue tracker to file the next PR in".to_string()),
+        }),
+        Command::NotifyWebhook { url, summary } => {
+            let user = server.current_user().await.unwrap_or_default();
+            if let Err(err) = notify::send(&url, &user, &summary).await {
+                warn!(error = %err, "Could not notify webhook");
+            }
+            None
+        }
+        Command::FetchBlame {
+            owner,
+            repo,
+            number,
+            path,
+            ..
+        } => Some(Action::BlameLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            path,
+            result: Err(unavailable("Blame")),
+        }),
+        // Background checks with nothing to find here, skipped rather than failing
+        // on every poll
+        Command::FetchActivity { .. }
+        | Command::FetchViewedFiles { .. }
+        | Command::FetchRateLimit => None,
+        Command::SetFileViewed {
+            owner,
+            repo,
+            number,
+            path,
+            ..
+        } => Some(Action::FileViewedSynced {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            path,
+            result: Err(unavailable("Syncing viewed files")),
+        }),
+        Command::RequestReviewers {
+            owner,
+            repo,
+            number,
+            ..
+        } => Some(Action::ReviewersRequested {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: Err(unavailable("Requesting reviewers")),
+        }),
+        Command::FetchCommits { owner, repo, number } => Some(Action::CommitsLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: Err(unavailable("Reviewing by commit")),
+        }),
+        Command::FetchRangeDiff {
+            owner,
+            repo,
+            number,
+            range,
+        } => Some(Action::RangeDiffLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            range,
+            result: Err(unavailable("Reviewing by commit")),
+        }),
+        Command::FetchReviewDelta {
+            owner,
+            repo,
+            number,
+            reviewed_head,
+            ..
+        } => Some(Action::ReviewDeltaLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            reviewed_head,
+            result: Err(unavailable("Changes since your last review")),
+        }),
+        command @ (Command::GenerateStory { .. }
+        | Command::GenerateDeepDive { .. }
+        | Command::LoadCache { .. }
+        | Command::SaveCache { .. }
+        | Command::SaveHistory { .. }
+        | Command::SavePreferences { .. }
+        | Command::SaveOutbox { .. }
+        | Command::SaveMemory { .. }
+        | Command::SaveReviewed { .. }
+        | Command::SaveReviewReport { .. }
+        | Command::NotifyDesktop { .. }
+        | Command::CopyToClipboard { .. }
+        | Command::DryRun(_)
+        | Command::ExportSession { .. }
+        | Command::OpenInBrowser { .. }) => execute_live_command(command, config, progress).await,
+    }
+}
+
+/// Exports are local, so mock mode writes them for real too
+fn export_session(record: &SessionRecord) -> Action {
+    let result = record
+        .write()
+        .map(|path| path.display().to_string())
+        .map_err(|e| e.to_string());
+    Action::SessionExported(result)
+}
+
+/// Orgs only drive the selector's scope switcher, so treat failure as "no orgs"
+fn fetch_orgs() -> Vec<String> {
+    github::fetch_org_list()
+        .inspect_err(|err| warn!(error = %err, "Could not list orgs"))
+        .unwrap_or_default()
+}
+
+/// Copying is local, so mock mode copies for real too
+fn copy_to_clipboard(text: &str, what: String) -> Action {
+    let result = clipboard::copy(text).map(str::to_string).map_err(|e| e.to_string());
+    Action::Copied { what, result }
+}
+
+/// Write what a post command would send to the outbox
+fn write_dry_run(command: Command) -> Option<Action> {
+    let (action, owner, repo, number, kind, payload) = match command {
+        Command::PostReview {
+            owner,
+            repo,
+            number,
+            event,
+            body,
+            commit_id,
+            comments,
+        } => {
+            let comments: Vec<_> = comments
+                .iter()
+                .map(|c| json!({ "path": c.path, "line": c.line, "side": "RIGHT", "body": c.body }))
+                .collect();
+            let payload = json!({
+                "event": event.api_name(),
+                "body": body,
+                "commit_id": commit_id,
+                "comments": comments,
+            });
+            (ReviewAction::RequestChanges, owner, repo, number, "review", payload)
+        }
+        Command::PostComment {
+            owner,
+            repo,
+            number,
+            body,
+        } => (
+            ReviewAction::ClarificationQuestions,
+            owner,
+            repo,
+            number,
+            "comment",
+            json!({ "body": body }),
+        ),
+        Command::PostInlineQuestions {
+            owner,
+            repo,
+            number,
+            commit_id,
+            comments,
+            fallback_body,
+        } => {
+            let comments: Vec<_> = comments
+                .iter()
+                .map(|c| json!({ "path": c.path, "line": c.line, "side": "RIGHT", "body": c.body }))
+                .collect();
+            let payload = json!({
+                "event": "COMMENT",
+                "commit_id": commit_id,
+                "comments": comments,
+                "fallback_comment": fallback_body,
+            });
+            (ReviewAction::ClarificationQuestions, owner, repo, number, "inline-review", payload)
+        }
+        Command::CreateNextPrIssue {
+            owner,
+            repo,
+            number,
+            title,
+            body,
+        } => (
+            ReviewAction::NextPr,
+            owner,
+            repo,
+            number,
+            "issue",
+            json!({ "title": title, "body": body, "linked_from_pr": number }),
+        ),
+        other => {
+            warn!(command = other.label(), "Only submissions can be dry-run");
+            return None;
+        }
+    };
+    let result = dry_run::write(&owner, &repo, number, kind, &payload)
+        .map(|path| path.display().to_string())
+        .map_err(|e| format!("{:#}", e));
+    Some(Action::DryRunWritten { action, result })
+}
+
+/// A freshly fetched listing, all on one page
+fn live<T>(items: Vec<T>) -> Listing<T> {
+    Listing {
+        total: Some(items.len() as u32),
+        items,
+        cached_at: None,
+        next_cursor: None,
+    }
+}
+
+fn last_page<T>() -> Page<T> {
+    Page {
+        items: Vec::new(),
+        total: 0,
+        next_cursor: None,
+    }
+}
+
+/// Serve a command from the `--mock` fixtures. Writes to GitHub succeed without
+/// doing anything, and local saves are skipped so real caches stay untouched.
+fn execute_mock_command(
+    command: Command,
+    config: &AppConfig,
+    progress: &ProgressSender,
+) -> Option<Action> {
+    let on_progress = |event| {
+        let _ = progress.send(Action::Progress(event));
+    };
+
+    match command {
+        Command::FetchStartup { org } => Some(Action::StartupLoaded {
+            repos: mock::repo_list(org.as_deref())
+                .map(live)
+                .map_err(|e| e.to_string()),
+            orgs: mock::ORGS.iter().map(|org| org.to_string()).collect(),
+        }),
+        Command::FetchRepoList { org } => Some(Action::RepoListLoaded(
+            mock::repo_list(org.as_deref())
+                .map(live)
+                .map_err(|e| e.to_string()),
+        )),
+        Command::FetchOrgList => Some(Action::OrgListLoaded(
+            mock::ORGS.iter().map(|org| org.to_string()).collect(),
+        )),
+        Command::FetchPrList { .. } => Some(Action::PrListLoaded(
+            mock::pr_list().map(live).map_err(|e| e.to_string()),
+        )),
+        // The fixtures fit on one page, so there's never a next one to ask for
+        Command::FetchMoreRepos { org, .. } => Some(Action::MoreReposLoaded {
+            org,
+            result: Ok(last_page()),
+        }),
+        Command::FetchMorePrs {
+            owner,
+            repo,
+            states,
+            ..
+        } => Some(Action::MorePrsLoaded {
+            owner,
+            repo,
+            states,
+            result: Ok(last_page()),
+        }),
+        Command::FetchReviewQueue => Some(Action::ReviewQueueLoaded(
+            mock::review_queue(&config.queue_repos).map_err(|e| e.to_string()),
+        )),
+        Command::FetchPr { owner, repo, number } => {
+            on_progress(ProgressEvent::FetchingPr { number });
+            let result = mock::pr_context(&owner, &repo, number, &config.fetch_options().filter);
+            if let Ok(pr) = &result {
+                on_progress(ProgressEvent::FetchedMetadata);
+                on_progress(ProgressEvent::FetchedDiff {
+                    bytes: pr.diff.len(),
+                });
+            }
+            Some(Action::PrLoaded(result.map_err(|e| e.to_string())))
+        }
+        Command::GenerateStory {
+            pr,
+            model,
+            persona,
+            language,
+            ..
+        } => {
+            let prompt =
+                build_system_prompt(persona, language.as_deref(), &config.custom_prompts(&pr));
+            let prompt_tokens = (prompt.len() + build_user_prompt(&pr).len()) / 4;
+            on_progress(ProgressEvent::PromptBuilt {
+                tokens: prompt_tokens,
+            });
+            on_progress(ProgressEvent::AwaitingModel {
+                model: model.clone(),
+            });
+            on_progress(ProgressEvent::TokensUsed {
+                input: prompt_tokens as u64,
+                output: mock::story_tokens(),
+            });
+            Some(Action::StoryGenerated {
+                pr: pr.pr_label(),
+                model,
+                result: mock::story().map_err(|e| e.to_string()),
+            })
+        }
+        Command::GenerateDeepDive { pr, feature, .. } => Some(Action::DeepDiveGenerated {
+            pr: pr.pr_label(),
+            feature,
+            result: Ok(mock::deep_dive(&pr)),
+        }),
+        Command::FetchHeadFiles {
+            owner,
+            repo,
+            number,
+            ..
+        } => Some(Action::HeadFilesLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: Err("File contents aren't available in mock mode".to_string()),
+        }),
+        Command::FetchBlame {
+            owner,
+            repo,
+            number,
+            path,
+            ..
+        } => Some(Action::BlameLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            path,
+            result: Ok(Vec::new()),
+        }),
+        // Nothing is cached, so a --cache run fetches the fixture PR instead
+        Command::LoadCache {
+            owner,
+            repo,
+            number,
+            ..
+        } => Some(Action::CacheLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            stories: Vec::new(),
+        }),
+        Command::SaveCache { .. }
+        | Command::SaveHistory { .. }
+        | Command::SavePreferences { .. }
+        | Command::SaveOutbox { .. }
+        | Command::SaveMemory { .. }
+        | Command::SaveReviewed { .. }
+        | Command::SaveReviewReport { .. }
+        | Command::NotifyWebhook { .. }
+        | Command::NotifyDesktop { .. } => None,
+        Command::PostReview {
+            owner,
+            repo,
+            number,
+            ..
+        } => Some(Action::SubmissionResult {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            action: ReviewAction::RequestChanges,
+            result: Ok(()),
+        }),
+        Command::PostComment {
+            owner,
+            repo,
+            number,
+            ..
+        }
+        | Command::PostInlineQuestions {
+            owner,
+            repo,
+            number,
+            ..
+        } => {
+            Some(Action::SubmissionResult {
+                pr: format!("{}/{}#{}", owner, repo, number),
+                action: ReviewAction::ClarificationQuestions,
+                result: Ok(()),
+            })
+        }
+        Command::CreateNextPrIssue {
+            owner,
+            repo,
+            number,
+            ..
+        } => Some(Action::SubmissionResult {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            action: ReviewAction::NextPr,
+            result: Ok(()),
+        }),
+        Command::FetchActivity { owner, repo, number } => Some(Action::ActivityLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: Ok(PrActivity::default()),
+        }),
+        Command::FetchViewedFiles { owner, repo, number } => Some(Action::ViewedFilesLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: Ok(ViewedFiles {
+                pr_id: "mock".to_string(),
+                paths: Vec::new(),
+            }),
+        }),
+        Command::SetFileViewed {
+            owner,
+            repo,
+            number,
+            path,
+            ..
+        } => Some(Action::FileViewedSynced {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            path,
+            result: Ok(()),
+        }),
+        Command::RequestReviewers {
+            owner,
+            repo,
+            number,
+            reviewers,
+        } => Some(Action::ReviewersRequested {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: Ok(reviewers),
+        }),
+        Command::FetchCommits { owner, repo, number } => Some(Action::CommitsLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            result: mock::commits().map_err(|e| e.to_string()),
+        }),
+        Command::FetchRangeDiff {
+            owner,
+            repo,
+            number,
+            range,
+        } => Some(Action::RangeDiffLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            range,
+            result: Ok(mock::compare_diff()),
+        }),
+        Command::FetchReviewDelta {
+            owner,
+            repo,
+            number,
+            reviewed_head,
+            ..
+        } => Some(Action::ReviewDeltaLoaded {
+            pr: format!("{}/{}#{}", owner, repo, number),
+            reviewed_head,
+            result: Ok(mock::compare_diff()),
+        }),
+        Command::FetchRateLimit => Some(Action::RateLimitLoaded(Ok(mock::rate_limit()))),
+        Command::CopyToClipboard { text, what } => Some(copy_to_clipboard(&text, what)),
+        Command::DryRun(command) => write_dry_run(*command),
+        Command::ExportSession { record } => Some(export_session(&record)),
+        // Nothing to open without a real PR
+        Command::OpenInBrowser { .. } => Some(Action::BrowserOpened(Ok(()))),
     }
 }
@@ -1,7 +1,181 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::domain::anonymize::Pseudonyms;
+use crate::domain::bitbucket::BitbucketServer;
+use crate::domain::cache::CachePolicy;
+use crate::domain::filter::DiffFilter;
+use crate::domain::github::PrFetchOptions;
+use crate::domain::privacy::Redaction;
+use crate::domain::providers::Provider;
+use crate::domain::types::{CustomPrompt, ModelParams, Persona, PrContext};
+
+/// The code host PRs are read from and reviews are posted to
+#[derive(Debug, Clone, Default)]
+pub enum Forge {
+    /// github.com or GitHub Enterprise, through `gh`
+    #[default]
+    GitHub,
+    /// Bitbucket Server or Data Center, over its REST API
+    Bitbucket(BitbucketServer),
+}
+
 #[derive(Clone)]
 pub struct AppConfig {
-    pub api_key: String,
+    /// Where to send prompts, tried in order until one accepts
+    pub providers: Vec<Provider>,
     pub model: String,
+    pub fallback_model: String,
+    pub generation_budget: Option<Duration>,
     pub use_cache: bool,
+    /// Reuse the model's answer to a prompt identical to an earlier one
+    pub llm_cache: bool,
     pub cache_file: String,
+    /// How long cached stories stay usable (None = until evicted)
+    pub cache_ttl: Option<Duration>,
+    /// Total size the cache files may grow to before the oldest stories are evicted
+    pub cache_max_bytes: u64,
+    pub skip_noise: bool,
+    pub thread_questions: bool,
+    /// How often to check the open PR for new commits and comments (None = never)
+    pub poll_interval: Option<Duration>,
+    /// Start with submissions written to `.dstl/outbox/` instead of posted
+    pub dry_run: bool,
+    /// Point out likely typos in the draft preview
+    pub spellcheck: bool,
+    /// Notify the desktop when a story is ready or a submission finishes while
+    /// the terminal isn't focused
+    pub notify: bool,
+    /// Print state changes as plain lines on the main screen, for screen readers
+    pub announce: bool,
+    pub queue_repos: Vec<String>,
+    pub gh_retries: u32,
+    pub llm_timeout: Duration,
+    pub context_lines: usize,
+    pub blame_in_prompt: bool,
+    /// Search the repo for callers of modified functions before generating
+    pub impact: bool,
+    /// Send added lines that look like secrets to the model instead of asking
+    pub allow_secrets: bool,
+    /// Paths withheld from the model and replacements applied to the prompt
+    pub redaction: Redaction,
+    /// Swap file paths and string literals in the prompt for placeholders
+    pub anonymize: bool,
+    /// Show the exact prompt and wait for confirmation before each generation
+    pub preview_prompt: bool,
+    /// Cheap model that summarizes each file of a diff too large to send whole
+    pub map_model: String,
+    /// Diff size in bytes past which files are summarized first (0 = never)
+    pub map_threshold: usize,
+    /// Summary calls in flight at once
+    pub map_concurrency: usize,
+    /// Models to generate side-by-side stories with (empty unless `--compare`)
+    pub compare_models: Vec<String>,
+    /// The user's own review instructions (`~/.config/dstl/prompt.md`)
+    pub user_prompt: Option<CustomPrompt>,
+    /// Reviewer perspective to generate stories under at startup
+    pub persona: Option<Persona>,
+    /// Language the story's prose is written in, None for the model's default
+    pub language: Option<String>,
+    /// Reasoning effort, output limit and temperature to start with
+    pub model_params: ModelParams,
+    /// Patterns for changed files to leave out of the prompt, on top of the defaults
+    pub ignore_patterns: Vec<String>,
+    /// Patterns for changed files to analyze even if an ignore pattern matches
+    pub include_patterns: Vec<String>,
+    /// Webhooks notified after a submission, keyed by `owner/repo` or `*`
+    pub webhooks: BTreeMap<String, String>,
+    /// Review snippets offered by Ctrl+T in a draft, by name
+    pub templates: BTreeMap<String, String>,
+    /// Serve canned fixtures instead of calling GitHub and OpenAI
+    pub mock: bool,
+    /// Where PRs come from and reviews go
+    pub forge: Forge,
+}
+
+impl AppConfig {
+    /// Extra context to gather when fetching a PR
+    pub fn fetch_options(&self) -> PrFetchOptions {
+        PrFetchOptions {
+            context_lines: self.context_lines,
+            blame: self.blame_in_prompt,
+            impact: self.impact,
+            filter: DiffFilter::new(&self.ignore_patterns, &self.include_patterns),
+        }
+    }
+
+    /// Custom instructions to merge into the system prompt for this PR, user's first,
+    /// then the repo's, then what the reviewer asked for there before
+    pub fn custom_prompts(&self, pr: &PrContext) -> Vec<CustomPrompt> {
+        self.user_prompt
+            .iter()
+            .chain(pr.repo_prompt.iter())
+            .chain(pr.review_memory.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Placeholders for `pr`'s paths and literals, if prompts are anonymized
+    pub fn pseudonyms(&self, pr: &PrContext) -> Pseudonyms {
+        if self.anonymize {
+            Pseudonyms::for_pr(pr)
+        } else {
+            Pseudonyms::default()
+        }
+    }
+
+    /// The prompts exactly as they'll be sent for `pr`, and the pseudonyms to map
+    /// the story back with
+    pub fn outbound_prompts(
+        &self,
+        pr: &PrContext,
+        persona: Option<Persona>,
+        language: Option<&str>,
+    ) -> (Pseudonyms, (String, String)) {
+        let pseudonyms = self.pseudonyms(pr);
+        let (system, user) =
+            self.redaction.prompts(pr, persona, language, &self.custom_prompts(pr));
+        let prompts = (pseudonyms.hide(&system), pseudonyms.hide(&user));
+        (pseudonyms, prompts)
+    }
+
+    /// A deep dive's prompts exactly as they'll be sent for `pr`
+    pub fn outbound_deep_dive_prompts(
+        &self,
+        pr: &PrContext,
+        (system, user): &(String, String),
+    ) -> (String, String) {
+        let pseudonyms = self.pseudonyms(pr);
+        (
+            pseudonyms.hide(&self.redaction.replace(system)),
+            pseudonyms.hide(&self.redaction.replace(user)),
+        )
+    }
+
+    /// Models to generate a story with, in display order
+    pub fn story_models(&self) -> Vec<String> {
+        if self.compare_models.is_empty() {
+            vec![self.model.clone()]
+        } else {
+            self.compare_models.clone()
+        }
+    }
+
+    pub fn cache_policy(&self) -> CachePolicy {
+        CachePolicy {
+            ttl: self.cache_ttl,
+            max_bytes: self.cache_max_bytes,
+        }
+    }
+
+    /// Cache file for a model's story; each compared model gets its own entry
+    pub fn cache_path(&self, model: &str) -> String {
+        if self.compare_models.is_empty() {
+            return self.cache_file.clone();
+        }
+        match self.cache_file.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => format!("{}.{}.{}", stem, model, ext),
+            _ => format!("{}.{}", self.cache_file, model),
+        }
+    }
 }
@@ -0,0 +1,160 @@
+//! Reversible pseudonyms for the file paths and string literals in a prompt, so a
+//! hosted model never sees them and the story it returns can be mapped back
+//! locally before it's shown.
+
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use regex::{Captures, Regex};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::diff::parse_diff;
+use super::types::PrContext;
+
+/// Shortest string literal worth hiding; `""` and single characters say nothing
+const MIN_LITERAL_LEN: usize = 2;
+
+/// Placeholders, with word boundaries so `ANON_STR_1` never matches inside `ANON_STR_12`
+fn placeholder() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\bANON_(PATH|STR)_(\d+)\b").expect("valid pattern"))
+}
+
+fn literal() -> &'static Regex {
+    static LITERAL: OnceLock<Regex> = OnceLock::new();
+    LITERAL.get_or_init(|| Regex::new(r#""((?:[^"\\\n]|\\.)*)""#).expect("valid pattern"))
+}
+
+/// The pseudonyms for one PR. The default hides nothing
+#[derive(Debug, Clone, Default)]
+pub struct Pseudonyms {
+    /// Paths without their extension, which stays visible so the language is clear
+    paths: Vec<String>,
+    literals: Vec<String>,
+}
+
+impl Pseudonyms {
+    /// Pseudonyms for every changed path and every string literal in the code
+    /// the prompt includes for `pr`
+    pub fn for_pr(pr: &PrContext) -> Self {
+        let files = parse_diff(&pr.diff);
+        let mut paths: Vec<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        paths.extend(pr.surrounding_code.iter().map(|window| window.path.as_str()));
+        paths.extend(pr.excluded_files.iter().map(|file| file.path.as_str()));
+        paths.extend(pr.callers.iter().flat_map(|callers| &callers.files).map(String::as_str));
+        let mut stems: Vec<String> = Vec::new();
+        for path in paths {
+            let stem = split_extension(path).0.to_string();
+            if !stems.contains(&stem) {
+                stems.push(stem);
+            }
+        }
+
+        let code = files
+            .iter()
+            .flat_map(|file| &file.hunks)
+            .flat_map(|hunk| hunk.lines.iter().map(|line| line.get(1..).unwrap_or("")))
+            .chain(pr.surrounding_code.iter().flat_map(|w| w.lines.iter().map(String::as_str)));
+        let mut literals: Vec<String> = Vec::new();
+        for line in code {
+            for found in literal().captures_iter(line) {
+                let text = &found[1];
+                if text.len() >= MIN_LITERAL_LEN && !literals.iter().any(|seen| seen == text) {
+                    literals.push(text.to_string());
+                }
+            }
+        }
+        Pseudonyms {
+            paths: stems,
+            literals,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.literals.is_empty()
+    }
+
+    /// Replace the real paths and literals in `text` with placeholders
+    pub fn hide(&self, text: &str) -> String {
+        if self.is_empty() {
+            return text.to_string();
+        }
+        // Longest first, so a path never loses its tail to a shorter one it contains
+        let mut replacements: Vec<(String, String)> = Vec::new();
+        for (i, literal) in self.literals.iter().enumerate() {
+            replacements.push((format!("\"{}\"", literal), format!("\"ANON_STR_{}\"", i + 1)));
+        }
+        for (i, stem) in self.paths.iter().enumerate() {
+            replacements.push((stem.clone(), format!("ANON_PATH_{}", i + 1)));
+        }
+        replacements.sort_by_key(|(real, _)| std::cmp::Reverse(real.len()));
+        let mut hidden = text.to_string();
+        for (real, placeholder) in replacements {
+            hidden = replace_whole(&hidden, &real, &placeholder);
+        }
+        hidden
+    }
+
+    /// Put the real paths and literals back into `text`, each passed through `escape`
+    fn reveal_with(&self, text: &str, escape: impl Fn(&str) -> String) -> String {
+        placeholder()
+            .replace_all(text, |found: &Captures| {
+                let index = found[2].parse::<usize>().unwrap_or(0).wrapping_sub(1);
+                let real = match &found[1] {
+                    "PATH" => self.paths.get(index),
+                    _ => self.literals.get(index),
+                };
+                real.map_or_else(|| found[0].to_string(), |real| escape(real))
+            })
+            .into_owned()
+    }
+
+    /// A story (or any model output) with every placeholder the model wrote mapped back
+    pub fn reveal<T: Serialize + DeserializeOwned>(&self, output: T) -> Result<T> {
+        if self.is_empty() {
+            return Ok(output);
+        }
+        let json = serde_json::to_string(&output).context("Failed to serialize the story")?;
+        // Revealed text lands inside JSON strings, so it has to be escaped for them
+        let revealed = self.reveal_with(&json, |real| {
+            let quoted = serde_json::to_string(real).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        });
+        serde_json::from_str(&revealed).context("Failed to restore the anonymized story")
+    }
+}
+
+/// `text` with every whole occurrence of `real` replaced. Where `real` starts or
+/// ends with a word character it only matches at a word boundary there, the same
+/// rule `reveal` finds placeholders by, so the stem `app` hides `app.ts` but
+/// leaves `application` alone
+fn replace_whole(text: &str, real: &str, placeholder: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let check_start = is_word(real.chars().next());
+    let check_end = is_word(real.chars().next_back());
+    let mut replaced = String::with_capacity(text.len());
+    let mut copied = 0;
+    for (start, _) in text.match_indices(real) {
+        let end = start + real.len();
+        if (check_start && is_word(text[..start].chars().next_back()))
+            || (check_end && is_word(text[end..].chars().next()))
+        {
+            continue;
+        }
+        replaced.push_str(&text[copied..start]);
+        replaced.push_str(placeholder);
+        copied = end;
+    }
+    replaced.push_str(&text[copied..]);
+    replaced
+}
+
+/// (`src/limit`, `.rs`) for `src/limit.rs`; dotfiles and extensionless files keep
+/// their whole name as the stem
+fn split_extension(path: &str) -> (&str, &str) {
+    let name_start = path.rfind('/').map_or(0, |slash| slash + 1);
+    match path[name_start..].rfind('.') {
+        Some(dot) if dot > 0 => path.split_at(name_start + dot),
+        _ => (path, ""),
+    }
+}
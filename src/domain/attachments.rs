@@ -0,0 +1,101 @@
+//! Screenshots and other images embedded in a PR body, which the model can't see
+//! but the reviewer can open in a browser.
+
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+const IMAGE_EXTENSIONS: &[&str] =
+    &[".png", ".jpg", ".jpeg", ".gif", ".webp", ".svg", ".mp4", ".mov"];
+
+/// Where GitHub keeps files dragged into a PR description
+const UPLOAD_PREFIXES: &[&str] = &[
+    "https://github.com/user-attachments/assets/",
+    "https://user-images.githubusercontent.com/",
+    "https://private-user-images.githubusercontent.com/",
+];
+
+/// An image linked from the PR body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    /// Alt text, or the file name when there is none
+    pub label: String,
+    pub url: String,
+}
+
+/// Images in a PR body, in order and without repeats: Markdown `![alt](url)`,
+/// HTML `<img src="url">`, and bare links to uploads or image files
+pub fn find_images(body: &str) -> Vec<Attachment> {
+    let mut found: Vec<Attachment> = Vec::new();
+    let mut push = |label: &str, url: &str| {
+        let url = url.trim();
+        if url.is_empty() || found.iter().any(|image| image.url == url) {
+            return;
+        }
+        let label = match label.trim() {
+            "" => url.rsplit('/').next().unwrap_or(url),
+            label => label,
+        };
+        found.push(Attachment {
+            label: label.to_string(),
+            url: url.to_string(),
+        });
+    };
+
+    for (start, _) in body.match_indices("![") {
+        let rest = &body[start + 2..];
+        if let Some((alt, rest)) = rest.split_once("](")
+            && let Some((url, _)) = rest.split_once(')')
+        {
+            // A title may follow the URL: ![alt](url "title")
+            push(alt, url.split_whitespace().next().unwrap_or(""));
+        }
+    }
+    for (start, _) in body.match_indices("<img") {
+        let tag = &body[start..body[start..].find('>').map_or(body.len(), |end| start + end)];
+        if let Some(url) = attribute(tag, "src") {
+            push(attribute(tag, "alt").unwrap_or(""), url);
+        }
+    }
+    for word in body.split_whitespace() {
+        let word = word.trim_matches(|c: char| matches!(c, '<' | '>' | '(' | ')' | '"'));
+        let lower = word.to_ascii_lowercase();
+        let is_upload = UPLOAD_PREFIXES.iter().any(|prefix| word.starts_with(prefix));
+        let is_image = word.starts_with("http")
+            && IMAGE_EXTENSIONS
+                .iter()
+                .any(|ext| lower.split(['?', '#']).next().unwrap_or("").ends_with(ext));
+        if is_upload || is_image {
+            push("", word);
+        }
+    }
+    found
+}
+
+/// Value of a quoted HTML attribute, e.g. `src="..."`
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=", name))? + name.len() + 1;
+    let quote = tag[start..].chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let value = &tag[start + 1..];
+    value.split(quote).next()
+}
+
+/// Open a URL with the platform's opener
+pub fn open_in_browser(url: &str) -> Result<()> {
+    let (program, args): (&str, &[&str]) = if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else if cfg!(windows) {
+        ("cmd", &["/C", "start", ""])
+    } else {
+        ("xdg-open", &[])
+    };
+    let status = Command::new(program)
+        .args(args)
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("Failed to run {}", program))?;
+    anyhow::ensure!(status.success(), "{} exited with {}", program, status);
+    Ok(())
+}
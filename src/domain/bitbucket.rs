@@ -0,0 +1,664 @@
+//! Bitbucket Server and Data Center, for teams hosting their own: the repo and
+//! PR lists, a PR's diff, comments and approvals over the REST API, with an
+//! HTTP access token. Used instead of GitHub when `bitbucket.url` is set in
+//! `config.toml`; a repo's project key stands where GitHub has its owner.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use reqwest::{Method, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+use super::export;
+use super::github::PrFetchOptions;
+use super::listings::{self, format_timestamp};
+use super::types::{
+    CiStatus, InlineComment, Page, PrContext, PrListItem, PrState, PrStateFilter, ProgressEvent,
+    QueueItem, RepoListItem, ReviewEvent,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Items per page of a listing
+const PAGE_SIZE: u32 = 50;
+
+/// Characters escaped in each segment of a file path put in a URL
+const PATH_SEGMENT: &AsciiSet =
+    &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'%').add(b'<').add(b'>').add(b'?').add(b'`');
+
+/// Where the server is and the HTTP access token to call it with
+#[derive(Debug, Clone)]
+pub struct BitbucketServer {
+    pub url: String,
+    token: String,
+    client: reqwest::Client,
+    /// Who the token belongs to, asked once and shared by clones
+    user: Arc<OnceCell<String>>,
+}
+
+/// One page of a paged response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BbPage<T> {
+    values: Vec<T>,
+    #[serde(default)]
+    is_last_page: bool,
+    next_page_start: Option<u32>,
+    #[serde(default)]
+    start: u32,
+}
+
+impl<T> BbPage<T> {
+    /// Where the next page starts, or None on the last
+    fn next_start(&self) -> Option<String> {
+        self.next_page_start.filter(|_| !self.is_last_page).map(|start| start.to_string())
+    }
+
+    /// Bitbucket doesn't count every page, so the total is what's been seen so far
+    pub(crate) fn into_page<U>(self, convert: impl FnMut(T) -> U) -> Page<U> {
+        let next_cursor = self.next_start();
+        let items: Vec<U> = self.values.into_iter().map(convert).collect();
+        Page {
+            total: self.start + items.len() as u32,
+            next_cursor,
+            items,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BbRepo {
+    slug: String,
+    project: BbProject,
+    description: Option<String>,
+    #[serde(default)]
+    public: bool,
+    /// Only forks have one
+    origin: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BbProject {
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BbPullRequest {
+    id: u32,
+    title: String,
+    description: Option<String>,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    created_date: u64,
+    updated_date: u64,
+    from_ref: BbRef,
+    to_ref: BbRef,
+    author: BbParticipant,
+    #[serde(default)]
+    reviewers: Vec<BbParticipant>,
+    #[serde(default)]
+    properties: BbProperties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BbRef {
+    display_id: String,
+    latest_commit: String,
+    repository: Option<BbRefRepo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BbRefRepo {
+    slug: String,
+    project: BbProject,
+}
+
+#[derive(Debug, Deserialize)]
+struct BbParticipant {
+    user: BbUser,
+    #[serde(default)]
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BbUser {
+    name: String,
+    slug: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BbProperties {
+    #[serde(default)]
+    comment_count: u32,
+}
+
+/// An entry in a PR's activity feed; only comments are read
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BbActivity {
+    action: String,
+    comment: Option<BbComment>,
+    comment_anchor: Option<BbAnchor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BbComment {
+    id: u64,
+    /// Edits must name the version they replace
+    #[serde(default)]
+    version: u32,
+    text: String,
+    author: BbUser,
+}
+
+#[derive(Debug, Deserialize)]
+struct BbAnchor {
+    path: Option<String>,
+    line: Option<u32>,
+}
+
+impl BbPullRequest {
+    pub(crate) fn into_list_item(self, current_user: &str) -> PrListItem {
+        let review_requested = self
+            .reviewers
+            .iter()
+            .any(|reviewer| reviewer.user.name == current_user && reviewer.status == "UNAPPROVED");
+        PrListItem {
+            number: self.id,
+            title: self.title,
+            author: self.author.user.name,
+            head_branch: self.from_ref.display_id,
+            base_branch: self.to_ref.display_id,
+            is_draft: self.draft,
+            review_requested,
+            // Build status lives in a separate API, and line counts aren't listed
+            ci_status: CiStatus::Unknown,
+            additions: 0,
+            deletions: 0,
+            created_at: format_timestamp(self.created_date / 1000),
+            state: match self.state.as_str() {
+                "MERGED" => PrState::Merged,
+                "DECLINED" => PrState::Closed,
+                _ => PrState::Open,
+            },
+            updated_at: format_timestamp(self.updated_date / 1000),
+            labels: Vec::new(),
+            milestone: None,
+            comments: self.properties.comment_count,
+        }
+    }
+}
+
+impl BitbucketServer {
+    pub fn new(url: String, token: String) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to build HTTP client")?;
+        Ok(Self {
+            url,
+            token,
+            client,
+            user: Arc::default(),
+        })
+    }
+
+    fn api(&self, path: &str) -> String {
+        format!("{}/rest/api/1.0/{}", self.url.trim_end_matches('/'), path)
+    }
+
+    fn request(&self, method: Method, url: Url) -> Result<reqwest::RequestBuilder> {
+        Ok(self.client.request(method, url).bearer_auth(&self.token))
+    }
+
+    async fn get_text(&self, path: &str, params: &[(&str, String)]) -> Result<String> {
+        let url = Url::parse_with_params(&self.api(path), params)
+            .with_context(|| format!("Invalid Bitbucket URL {}", self.url))?;
+        let response = self
+            .request(Method::GET, url)?
+            .send()
+            .await
+            .with_context(|| format!("Could not reach Bitbucket at {}", self.url))?;
+        let status = response.status();
+        let text = response.text().await.context("Failed to read Bitbucket's response")?;
+        anyhow::ensure!(
+            status.is_success(),
+            "Bitbucket GET {} failed ({}): {}",
+            path,
+            status,
+            text
+        );
+        Ok(text)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str, params: &[(&str, String)]) -> Result<T> {
+        let text = self.get_text(path, params).await?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse Bitbucket {}", path))
+    }
+
+    /// Every page of a paged listing
+    async fn get_all<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<Vec<T>> {
+        let mut values = Vec::new();
+        let mut start = None;
+        loop {
+            let mut page_params = page_params(start.as_deref());
+            page_params.extend(params.iter().cloned());
+            let page: BbPage<T> = self.get(path, &page_params).await?;
+            start = page.next_start();
+            values.extend(page.values);
+            if start.is_none() {
+                return Ok(values);
+            }
+        }
+    }
+
+    async fn send(&self, method: Method, path: &str, body: serde_json::Value) -> Result<()> {
+        let url = Url::parse(&self.api(path))
+            .with_context(|| format!("Invalid Bitbucket URL {}", self.url))?;
+        let response = self
+            .request(method.clone(), url)?
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("Could not reach Bitbucket at {}", self.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Bitbucket {} {} failed ({}): {}", method, path, status, text);
+        }
+        Ok(())
+    }
+
+    /// The username the token belongs to
+    pub async fn current_user(&self) -> Result<String> {
+        self.user.get_or_try_init(|| self.whoami()).await.cloned()
+    }
+
+    async fn whoami(&self) -> Result<String> {
+        let url = format!("{}/plugins/servlet/applinks/whoami", self.url.trim_end_matches('/'));
+        let url = Url::parse(&url).with_context(|| format!("Invalid Bitbucket URL {}", self.url))?;
+        let response = self
+            .request(Method::GET, url)?
+            .send()
+            .await
+            .with_context(|| format!("Could not reach Bitbucket at {}", self.url))?
+            .error_for_status()
+            .context("Bitbucket couldn't say who the token belongs to")?;
+        Ok(response.text().await.unwrap_or_default().trim().to_string())
+    }
+
+    /// The current user's slug, which participant updates are addressed by
+    async fn current_user_slug(&self) -> Result<String> {
+        let name = self.current_user().await?;
+        let users: BbPage<BbUser> = self.get("users", &[("filter", name.clone())]).await?;
+        users
+            .values
+            .into_iter()
+            .find(|user| user.name == name)
+            .map(|user| user.slug)
+            .with_context(|| format!("Bitbucket has no user {:?}", name))
+    }
+
+    /// A page of the project's repos, or of every repo the token can read
+    pub async fn fetch_repo_list(
+        &self,
+        project: Option<&str>,
+        start: Option<&str>,
+    ) -> Result<Page<RepoListItem>> {
+        let path = match project {
+            Some(project) => format!("projects/{}/repos", project),
+            None => "repos".to_string(),
+        };
+        let page: BbPage<BbRepo> = self.get(&path, &page_params(start)).await?;
+        Ok(page.into_page(|repo| RepoListItem {
+            owner: repo.project.key,
+            name: repo.slug,
+            description: repo.description.unwrap_or_default(),
+            is_fork: repo.origin.is_some(),
+            is_private: !repo.public,
+        }))
+    }
+
+    /// Keys of the projects the token can see, which stand in for GitHub's orgs
+    pub async fn fetch_project_list(&self) -> Result<Vec<String>> {
+        let page: BbPage<BbProject> = self.get("projects", &page_params(None)).await?;
+        Ok(page.values.into_iter().map(|project| project.key).collect())
+    }
+
+    /// Open PRs the user reviews, limited to `repos` (`PROJECT/repo`) unless empty
+    pub async fn fetch_review_queue(&self, repos: &[String]) -> Result<Vec<QueueItem>> {
+        let current_user = self.current_user().await?;
+        let params = [("role", "REVIEWER".to_string()), ("state", "OPEN".to_string())];
+        let pull_requests: Vec<BbPullRequest> =
+            self.get_all("dashboard/pull-requests", &params).await?;
+        let mut items: Vec<QueueItem> = pull_requests
+            .into_iter()
+            .filter_map(|pr| {
+                let target = pr.to_ref.repository.as_ref()?;
+                let (owner, repo) = (target.project.key.clone(), target.slug.clone());
+                let wanted = repos.is_empty() || repos.contains(&format!("{}/{}", owner, repo));
+                wanted.then(|| QueueItem {
+                    owner,
+                    repo,
+                    pr: pr.into_list_item(&current_user),
+                })
+            })
+            .collect();
+        // Ready before draft and oldest first, grouped by repo as GitHub's queue is
+        items.sort_by(|a, b| {
+            (a.pr.is_draft, &a.pr.created_at).cmp(&(b.pr.is_draft, &b.pr.created_at))
+        });
+        listings::group_by_repo(&mut items);
+        Ok(items)
+    }
+
+    /// A page of the repo's PRs in `states`, those awaiting the user's review first
+    pub async fn fetch_pr_list(
+        &self,
+        project: &str,
+        repo: &str,
+        states: PrStateFilter,
+        start: Option<&str>,
+    ) -> Result<Page<PrListItem>> {
+        let current_user = self.current_user().await.unwrap_or_default();
+        let state = match states {
+            PrStateFilter::Open => "OPEN",
+            PrStateFilter::Merged => "MERGED",
+            PrStateFilter::Closed => "DECLINED",
+            PrStateFilter::All => "ALL",
+        };
+        let mut params = page_params(start);
+        params.push(("state", state.to_string()));
+        let path = format!("projects/{}/repos/{}/pull-requests", project, repo);
+        let page: BbPage<BbPullRequest> = self.get(&path, &params).await?;
+        let mut page = page.into_page(|pr| pr.into_list_item(&current_user));
+        page.items.sort_by_key(|pr| pr.priority());
+        Ok(page)
+    }
+
+    /// The PR's description and diff. GitHub-only context (linked issues, bot
+    /// comments, surrounding code, blame, stacks) is left empty
+    pub async fn fetch_pr(
+        &self,
+        project: &str,
+        repo: &str,
+        number: u32,
+        options: PrFetchOptions,
+        on_progress: impl Fn(ProgressEvent),
+    ) -> Result<PrContext> {
+        on_progress(ProgressEvent::FetchingPr { number });
+        let path = format!("projects/{}/repos/{}/pull-requests/{}", project, repo, number);
+        let pr: BbPullRequest = self.get(&path, &[]).await?;
+        on_progress(ProgressEvent::FetchedMetadata);
+
+        let diff = normalize_diff(&self.get_text(&format!("{}.diff", path), &[]).await?);
+        on_progress(ProgressEvent::FetchedDiff { bytes: diff.len() });
+        let (_, excluded_files) = options.filter.apply(&diff);
+
+        Ok(PrContext {
+            owner: project.to_string(),
+            repo: repo.to_string(),
+            number: pr.id,
+            title: pr.title,
+            body: pr.description.unwrap_or_default(),
+            diff,
+            author: pr.author.user.name,
+            base_branch: pr.to_ref.display_id,
+            head_branch: pr.from_ref.display_id,
+            head_sha: pr.from_ref.latest_commit,
+            base_sha: pr.to_ref.latest_commit,
+            bot_comments: Vec::new(),
+            linked_issues: Vec::new(),
+            head_files: Default::default(),
+            surrounding_code: Vec::new(),
+            base_blame: Default::default(),
+            callers: Vec::new(),
+            file_summaries: Vec::new(),
+            repo_prompt: None,
+            review_memory: None,
+            style_guide: None,
+            scope: Vec::new(),
+            out_of_scope: Vec::new(),
+            code_owners: Vec::new(),
+            stack: Vec::new(),
+            parent: None,
+            commit_range: None,
+            review_delta: None,
+            excluded_files,
+        })
+    }
+
+    /// Contents of each file at `sha`, skipping any that can't be fetched
+    pub async fn fetch_files_at(
+        &self,
+        project: &str,
+        repo: &str,
+        sha: &str,
+        paths: Vec<String>,
+    ) -> Vec<(String, String)> {
+        let mut files = Vec::new();
+        for path in paths {
+            let raw = format!("projects/{}/repos/{}/raw/{}", project, repo, encode_path(&path));
+            match self.get_text(&raw, &[("at", sha.to_string())]).await {
+                Ok(contents) => files.push((path, contents)),
+                Err(err) => debug!(path, error = %err, "Could not fetch file"),
+            }
+        }
+        files
+    }
+
+    pub async fn post_comment(
+        &self,
+        project: &str,
+        repo: &str,
+        number: u32,
+        body: &str,
+    ) -> Result<()> {
+        let path = format!("projects/{}/repos/{}/pull-requests/{}/comments", project, repo, number);
+        self.send(Method::POST, &path, serde_json::json!({ "text": body })).await
+    }
+
+    /// Post a PR comment, or edit the earlier one containing `marker` so re-runs
+    /// update it in place. Returns true when an existing comment was edited
+    pub async fn upsert_marked_comment(
+        &self,
+        project: &str,
+        repo: &str,
+        number: u32,
+        marker: &str,
+        body: &str,
+    ) -> Result<bool> {
+        let activities = self.fetch_activities(project, repo, number).await?;
+        let Some((id, version)) = marked_comment(&activities, marker) else {
+            self.post_comment(project, repo, number, body).await?;
+            return Ok(false);
+        };
+        let path = format!(
+            "projects/{}/repos/{}/pull-requests/{}/comments/{}",
+            project, repo, number, id
+        );
+        let payload = serde_json::json!({ "text": body, "version": version });
+        self.send(Method::PUT, &path, payload).await?;
+        Ok(true)
+    }
+
+    /// Comments anchored to added lines, one request each
+    pub async fn post_inline_comments(
+        &self,
+        project: &str,
+        repo: &str,
+        number: u32,
+        comments: &[InlineComment],
+    ) -> Result<()> {
+        let path = format!("projects/{}/repos/{}/pull-requests/{}/comments", project, repo, number);
+        for comment in comments {
+            self.send(Method::POST, &path, inline_comment_payload(comment)).await?;
+        }
+        Ok(())
+    }
+
+    /// The PR's comments, from its activity feed
+    async fn fetch_activities(
+        &self,
+        project: &str,
+        repo: &str,
+        number: u32,
+    ) -> Result<Vec<BbActivity>> {
+        let path =
+            format!("projects/{}/repos/{}/pull-requests/{}/activities", project, repo, number);
+        self.get_all(&path, &[]).await
+    }
+
+    /// Post the review's comments, then mark the PR approved or needing work.
+    /// Bitbucket has no review object, so a plain comment review only comments.
+    /// Each part is its own request, so a retry after a partial failure skips
+    /// the comments the user already has on the PR
+    pub async fn post_review(
+        &self,
+        project: &str,
+        repo: &str,
+        number: u32,
+        event: ReviewEvent,
+        body: &str,
+        comments: &[InlineComment],
+    ) -> Result<()> {
+        let user = self.current_user().await?;
+        let activities = self.fetch_activities(project, repo, number).await?;
+        let (body, comments) = unposted(&activities, &user, body, comments);
+        if let Some(body) = body {
+            self.post_comment(project, repo, number, body).await?;
+        }
+        self.post_inline_comments(project, repo, number, &comments).await?;
+        let status = match event {
+            ReviewEvent::RequestChanges => "NEEDS_WORK",
+            ReviewEvent::Approve => "APPROVED",
+            ReviewEvent::Comment => return Ok(()),
+        };
+        let slug = self.current_user_slug().await?;
+        let path = format!(
+            "projects/{}/repos/{}/pull-requests/{}/participants/{}",
+            project, repo, number, slug
+        );
+        self.send(Method::PUT, &path, serde_json::json!({ "status": status })).await
+    }
+}
+
+fn page_params(start: Option<&str>) -> Vec<(&'static str, String)> {
+    let mut params = vec![("limit", PAGE_SIZE.to_string())];
+    if let Some(start) = start {
+        params.push(("start", start.to_string()));
+    }
+    params
+}
+
+/// A repo file's path as it goes in a URL, each segment escaped
+pub(crate) fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| utf8_percent_encode(segment, PATH_SEGMENT).to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// A comment anchored to an added line, as the comments API takes it
+pub(crate) fn inline_comment_payload(comment: &InlineComment) -> serde_json::Value {
+    serde_json::json!({
+        "text": comment.body,
+        "anchor": {
+            "path": comment.path,
+            "line": comment.line,
+            "lineType": "ADDED",
+            "fileType": "TO",
+            "diffType": "EFFECTIVE",
+        },
+    })
+}
+
+/// What of a review is still to post, given the PR's activity: the body unless
+/// `user` already left it as a general comment, and the inline comments they
+/// haven't left on the same line
+pub(crate) fn unposted<'a>(
+    activities: &[BbActivity],
+    user: &str,
+    body: &'a str,
+    comments: &[InlineComment],
+) -> (Option<&'a str>, Vec<InlineComment>) {
+    let posted: Vec<(&BbComment, Option<&BbAnchor>)> = activities
+        .iter()
+        .filter(|activity| activity.action == "COMMENTED")
+        .filter_map(|activity| Some((activity.comment.as_ref()?, activity.comment_anchor.as_ref())))
+        .filter(|(comment, _)| comment.author.name == user)
+        .collect();
+    let body_posted = posted
+        .iter()
+        .any(|(comment, anchor)| anchor.is_none() && comment.text == body);
+    let comments = comments
+        .iter()
+        .filter(|inline| {
+            !posted.iter().any(|(comment, anchor)| {
+                comment.text == inline.body
+                    && anchor.is_some_and(|anchor| {
+                        anchor.path.as_deref() == Some(inline.path.as_str())
+                            && anchor.line == Some(inline.line)
+                    })
+            })
+        })
+        .cloned()
+        .collect();
+    ((!body.is_empty() && !body_posted).then_some(body), comments)
+}
+
+/// The id and version of the comment holding `marker`, if any
+pub(crate) fn marked_comment(activities: &[BbActivity], marker: &str) -> Option<(u64, u32)> {
+    let comments = activities
+        .iter()
+        .filter(|activity| activity.action == "COMMENTED")
+        .filter_map(|activity| activity.comment.as_ref())
+        .map(|comment| ((comment.id, comment.version), comment.text.clone()));
+    export::marked_comment(comments, marker)
+}
+
+/// Bitbucket's raw diffs name sides `src://` and `dst://`; the diff parser
+/// reads git's `a/` and `b/`
+pub(crate) fn normalize_diff(raw: &str) -> String {
+    let mut diff = String::with_capacity(raw.len());
+    for line in raw.lines() {
+        let line = if line.starts_with("diff --git ") {
+            line.replacen(" src://", " a/", 1).replacen(" dst://", " b/", 1)
+        } else if let Some(path) = line.strip_prefix("--- src://") {
+            format!("--- a/{}", path)
+        } else if let Some(path) = line.strip_prefix("+++ dst://") {
+            format!("+++ b/{}", path)
+        } else {
+            line.to_string()
+        };
+        diff.push_str(&line);
+        diff.push('\n');
+    }
+    diff
+}
+
+/// `(project, repo, number)` from a PR's web URL, e.g.
+/// `https://bitbucket.example.com/projects/PAY/repos/api/pull-requests/12/overview`
+pub fn parse_pr_url(input: &str) -> Option<(String, String, u32)> {
+    let parts: Vec<&str> = input.trim_end_matches('/').split('/').collect();
+    let at = parts.iter().position(|&part| part == "projects")?;
+    match parts.get(at + 1..at + 6)? {
+        [project, "repos", repo, "pull-requests", number] => {
+            Some((project.to_string(), repo.to_string(), number.parse().ok()?))
+        }
+        _ => None,
+    }
+}
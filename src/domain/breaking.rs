@@ -0,0 +1,167 @@
+//! Changes to a PR's public API that can break its users: public Rust items,
+//! TypeScript/JavaScript exports and Python module-level names that the diff
+//! removes or re-declares with a different signature, merged with what the model
+//! flagged.
+
+use std::collections::HashMap;
+
+use super::diff::FileDiff;
+use super::types::{BreakingChange, Severity};
+
+/// The semver bump a PR's API changes call for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SemverBump {
+    #[default]
+    Patch,
+    Minor,
+    Major,
+}
+
+impl SemverBump {
+    pub fn label(self) -> &'static str {
+        match self {
+            SemverBump::Patch => "patch",
+            SemverBump::Minor => "minor",
+            SemverBump::Major => "major",
+        }
+    }
+}
+
+/// Breaking changes for the whole PR and the bump they call for
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiReport {
+    /// Most severe first
+    pub breaking: Vec<BreakingChange>,
+    pub bump: SemverBump,
+}
+
+impl ApiReport {
+    /// The model's breaking changes plus any the diff shows that it didn't mention
+    pub fn new(files: &[FileDiff], flagged: &[BreakingChange]) -> Self {
+        let (detected, additions) = detect(files);
+        let mut breaking = flagged.to_vec();
+        for (name, change) in detected {
+            let known = flagged.iter().any(|flagged| mentions(&flagged.item, &name));
+            if !known {
+                breaking.push(change);
+            }
+        }
+        breaking.sort_by_key(|change| change.severity);
+        let bump = if !breaking.is_empty() {
+            SemverBump::Major
+        } else if additions > 0 {
+            SemverBump::Minor
+        } else {
+            SemverBump::Patch
+        };
+        ApiReport { breaking, bump }
+    }
+}
+
+/// Public items the diff removes or changes the signature of, by name, and how many
+/// it adds
+fn detect(files: &[FileDiff]) -> (Vec<(String, BreakingChange)>, usize) {
+    // name -> (path, signature), on each side of the diff
+    let mut removed: Vec<(String, String, String)> = Vec::new();
+    let mut added: HashMap<String, (String, String)> = HashMap::new();
+    for file in files {
+        for line in file.hunks.iter().flat_map(|hunk| &hunk.lines) {
+            let (sign, code) = line.split_at(line.len().min(1));
+            let Some((name, signature)) = public_declaration(&file.path, code) else {
+                continue;
+            };
+            match sign {
+                "-" => removed.push((name, file.path.clone(), signature)),
+                "+" => {
+                    added.insert(name, (file.path.clone(), signature));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut breaking = Vec::new();
+    for (name, path, old) in &removed {
+        match added.get(name) {
+            None => breaking.push((
+                name.clone(),
+                BreakingChange {
+                    item: format!("{} in {}", name, path),
+                    description: format!("`{}` is no longer public", old),
+                    severity: Severity::High,
+                    detected: true,
+                },
+            )),
+            Some((_, new)) if new != old => breaking.push((
+                name.clone(),
+                BreakingChange {
+                    item: format!("{} in {}", name, path),
+                    description: format!("Signature changed from `{}` to `{}`", old, new),
+                    severity: Severity::Medium,
+                    detected: true,
+                },
+            )),
+            // Moved or touched without changing the signature
+            Some(_) => {}
+        }
+    }
+    let additions = added
+        .keys()
+        .filter(|name| removed.iter().all(|(removed, _, _)| removed != *name))
+        .count();
+    (breaking, additions)
+}
+
+/// The name and whitespace-normalized signature a line declares publicly, if any
+fn public_declaration(path: &str, code: &str) -> Option<(String, String)> {
+    let ext = path.rsplit_once('.').map_or("", |(_, ext)| ext);
+    let (rest, keywords): (&str, &[&str]) = match ext {
+        "rs" => (
+            code.trim_start().strip_prefix("pub ")?,
+            &["fn", "struct", "enum", "trait", "type", "const", "static", "mod", "union"],
+        ),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => (
+            code.trim_start().strip_prefix("export ")?,
+            &["function", "class", "interface", "type", "enum", "const", "let", "var"],
+        ),
+        // Only module level, and a leading underscore marks a name private
+        "py" if !code.starts_with(char::is_whitespace) => (code, &["def", "class"]),
+        _ => return None,
+    };
+
+    let mut tokens = rest.split_whitespace().peekable();
+    let name = loop {
+        let token = tokens.next()?;
+        // `const` is also a qualifier, as in `pub const fn`
+        if keywords.contains(&token) && !tokens.peek().is_some_and(|next| keywords.contains(next))
+        {
+            break tokens.next()?;
+        }
+        // Qualifiers that may come before the keyword
+        if !matches!(
+            token,
+            "async" | "const" | "unsafe" | "extern" | "\"C\"" | "default" | "declare"
+                | "abstract"
+        ) {
+            return None;
+        }
+    };
+    let name: String =
+        name.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+    if name.is_empty() || (ext == "py" && name.starts_with('_')) {
+        return None;
+    }
+    let signature = code
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['{', ':', ';'])
+        .trim_end()
+        .to_string();
+    Some((name, signature))
+}
+
+/// Whether `text` uses `name` as a word
+fn mentions(text: &str, name: &str) -> bool {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_')).any(|word| word == name)
+}
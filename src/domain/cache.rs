@@ -0,0 +1,296 @@
+//! The story cache: one file per model, next to where dstl was run
+//! (`.dstl-cache.json`, and `.dstl-cache.<model>.json` for compared models).
+//! Each file holds an entry per PR, stamped with when it was generated and at
+//! which head commit, so stale entries can expire and the files stay bounded.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::listings::now_secs;
+use super::types::{PrContext, Story};
+
+/// One PR's story from one model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub owner: String,
+    pub repo: String,
+    pub number: u32,
+    pub model: String,
+    /// PR head the story was generated at (empty when unknown)
+    pub head_sha: String,
+    /// `diff_hash` of the diff the story was generated from (empty when unknown)
+    #[serde(default)]
+    pub diff_hash: String,
+    /// `--language` the story was written in, None for the model's default
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+    pub story: Story,
+}
+
+impl CacheEntry {
+    pub fn pr_label(&self) -> String {
+        format!("{}/{}#{}", self.owner, self.repo, self.number)
+    }
+
+    /// Whether `pr` has moved on since the story was generated; unknowns count as current
+    pub fn is_stale(&self, pr: &PrContext) -> bool {
+        (!self.head_sha.is_empty() && self.head_sha != pr.head_sha)
+            || (!self.diff_hash.is_empty() && self.diff_hash != diff_hash(&pr.diff))
+    }
+
+    fn is_for(&self, owner: &str, repo: &str, number: u32) -> bool {
+        self.owner == owner && self.repo == repo && self.number == number
+    }
+
+    fn is_expired(&self, policy: &CachePolicy) -> bool {
+        policy
+            .ttl
+            .is_some_and(|ttl| now_secs().saturating_sub(self.created_at) > ttl.as_secs())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    entries: Vec<CacheEntry>,
+}
+
+/// How long entries live and how much disk the cache files may take together
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicy {
+    /// None keeps entries until they're evicted or their PR moves on
+    pub ttl: Option<Duration>,
+    pub max_bytes: u64,
+}
+
+/// Why prune dropped an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneReason {
+    Expired,
+    HeadMoved,
+    Evicted,
+}
+
+impl PruneReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            PruneReason::Expired => "expired",
+            PruneReason::HeadMoved => "PR has new commits",
+            PruneReason::Evicted => "over the size limit",
+        }
+    }
+}
+
+/// SHA-256 of a diff, hex-encoded
+pub fn diff_hash(diff: &str) -> String {
+    format!("{:x}", Sha256::digest(diff.as_bytes()))
+}
+
+/// A commit's abbreviated SHA, as GitHub shows it
+pub fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+/// The unexpired story cached in `path` for a PR, if any
+pub fn find(
+    path: &str,
+    owner: &str,
+    repo: &str,
+    number: u32,
+    policy: &CachePolicy,
+) -> Option<CacheEntry> {
+    read(Path::new(path))
+        .entries
+        .into_iter()
+        .filter(|entry| entry.is_for(owner, repo, number) && !entry.is_expired(policy))
+        .max_by_key(|entry| entry.created_at)
+}
+
+/// Cache a story in `path`, replacing the PR's previous one, then drop expired
+/// entries and evict the oldest across `cache_file`'s files until they fit
+pub fn save(
+    cache_file: &str,
+    path: &str,
+    mut entry: CacheEntry,
+    policy: &CachePolicy,
+) -> Result<()> {
+    entry.created_at = now_secs();
+    let mut file = read(Path::new(path));
+    file.entries.retain(|cached| {
+        !cached.is_for(&entry.owner, &entry.repo, entry.number) && !cached.is_expired(policy)
+    });
+    file.entries.push(entry);
+    write(Path::new(path), &file)?;
+    evict(cache_file, policy.max_bytes)?;
+    Ok(())
+}
+
+/// Every cached entry across `cache_file`'s files, newest first
+pub fn list(cache_file: &str) -> Vec<(PathBuf, CacheEntry)> {
+    let mut entries: Vec<(PathBuf, CacheEntry)> = files(cache_file)
+        .into_iter()
+        .flat_map(|path| {
+            read(&path)
+                .entries
+                .into_iter()
+                .map(move |entry| (path.clone(), entry))
+        })
+        .collect();
+    entries.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.created_at));
+    entries
+}
+
+/// Drop expired entries, entries whose PR head is no longer the one they were
+/// generated at (`head_of` looks it up; None leaves the entry be), then evict
+/// down to the size limit. Returns what was dropped and why.
+pub fn prune(
+    cache_file: &str,
+    policy: &CachePolicy,
+    mut head_of: impl FnMut(&CacheEntry) -> Option<String>,
+) -> Result<Vec<(CacheEntry, PruneReason)>> {
+    let mut dropped = Vec::new();
+    for path in files(cache_file) {
+        let mut file = read(&path);
+        let before = file.entries.len();
+        file.entries.retain(|entry| {
+            let reason = if entry.is_expired(policy) {
+                Some(PruneReason::Expired)
+            } else if !entry.head_sha.is_empty()
+                && head_of(entry).is_some_and(|head| head != entry.head_sha)
+            {
+                Some(PruneReason::HeadMoved)
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                dropped.push((entry.clone(), reason));
+            }
+            reason.is_none()
+        });
+        if file.entries.len() != before {
+            write(&path, &file)?;
+        }
+    }
+    let evicted = evict(cache_file, policy.max_bytes)?;
+    dropped.extend(evicted.into_iter().map(|entry| (entry, PruneReason::Evicted)));
+    Ok(dropped)
+}
+
+/// Remove the oldest entries until `cache_file`'s files fit in `max_bytes`
+fn evict(cache_file: &str, max_bytes: u64) -> Result<Vec<CacheEntry>> {
+    let mut contents: BTreeMap<PathBuf, CacheFile> = files(cache_file)
+        .into_iter()
+        .map(|path| {
+            let file = read(&path);
+            (path, file)
+        })
+        .collect();
+    let size = |contents: &BTreeMap<PathBuf, CacheFile>| -> u64 {
+        contents.values().map(|file| serialize(file).len() as u64).sum()
+    };
+
+    let mut evicted = Vec::new();
+    let mut changed = Vec::new();
+    while size(&contents) > max_bytes {
+        let oldest = contents
+            .iter()
+            .flat_map(|(path, file)| {
+                file.entries
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, entry)| (entry.created_at, path.clone(), i))
+            })
+            .min();
+        let Some((_, path, i)) = oldest else {
+            break;
+        };
+        if let Some(file) = contents.get_mut(&path) {
+            evicted.push(file.entries.remove(i));
+        }
+        changed.push(path);
+    }
+    changed.dedup();
+    for path in changed {
+        if let Some(file) = contents.get(&path) {
+            write(&path, file)?;
+        }
+    }
+    Ok(evicted)
+}
+
+/// A cache file's entries; empty when it's missing or from an older dstl
+fn read(path: &Path) -> CacheFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn serialize(file: &CacheFile) -> String {
+    serde_json::to_string_pretty(file).unwrap_or_default()
+}
+
+/// Write a cache file, deleting it once it holds nothing
+fn write(path: &Path, file: &CacheFile) -> Result<()> {
+    if file.entries.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to delete {}", path.display()))?;
+        }
+        return Ok(());
+    }
+    std::fs::write(path, serialize(file))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Every cache file belonging to `cache_file`: the file itself and its per-model siblings
+pub fn files(cache_file: &str) -> Vec<PathBuf> {
+    let path = Path::new(cache_file);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Vec::new();
+    };
+    // Mirrors AppConfig::cache_path: `stem.model.ext`, or `name.model` without an extension
+    let (stem, suffix) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+        .filter(|entry| {
+            let file = entry.file_name();
+            let file = file.to_string_lossy();
+            file == name
+                || file
+                    .strip_prefix(stem)
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .and_then(|rest| rest.strip_suffix(suffix.as_str()))
+                    .is_some_and(|model| !model.is_empty())
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Delete every cache file, returning what was removed
+pub fn clear(cache_file: &str) -> Result<Vec<PathBuf>> {
+    let files = files(cache_file);
+    for file in &files {
+        std::fs::remove_file(file).with_context(|| format!("Failed to delete {}", file.display()))?;
+    }
+    Ok(files)
+}
@@ -0,0 +1,73 @@
+//! Copy text to the system clipboard: the platform's copy tool where there is
+//! one, otherwise an OSC 52 escape the terminal copies for us (which also works
+//! over SSH, where a local tool would only reach the remote machine's clipboard).
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// Copy tools to try, in order, with their arguments
+const TOOLS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+/// Copy `text`, returning how it was copied
+pub fn copy(text: &str) -> Result<&'static str> {
+    if !over_ssh() {
+        for (tool, args) in TOOLS {
+            if pipe_to(tool, args, text) {
+                return Ok(tool);
+            }
+        }
+    }
+    osc52(text)?;
+    Ok("OSC 52")
+}
+
+fn over_ssh() -> bool {
+    std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some()
+}
+
+/// Whether `tool` exists and accepted `text` on stdin
+fn pipe_to(tool: &str, args: &[&str], text: &str) -> bool {
+    // Output is discarded rather than read: xclip and wl-copy leave a child
+    // holding the selection, which would keep a piped stdout open
+    let Ok(mut child) = Command::new(tool)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let written = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    let succeeded = child.wait().is_ok_and(|status| status.success());
+    written && succeeded
+}
+
+/// Ask the terminal to set its clipboard
+fn osc52(text: &str) -> Result<()> {
+    let payload = STANDARD.encode(text);
+    let sequence = if std::env::var_os("TMUX").is_some() {
+        // tmux passes the escape through to the outer terminal when wrapped
+        format!("\x1bPtmux;\x1b\x1b]52;c;{}\x07\x1b\\", payload)
+    } else {
+        format!("\x1b]52;c;{}\x07", payload)
+    };
+    let mut stdout = std::io::stdout().lock();
+    stdout
+        .write_all(sequence.as_bytes())
+        .and_then(|_| stdout.flush())
+        .context("Failed to write the clipboard escape to the terminal")
+}
@@ -0,0 +1,56 @@
+//! The repo's CODEOWNERS file, used to suggest who should review each feature.
+
+use serde::{Deserialize, Serialize};
+
+use super::filter::pattern_matches;
+
+/// Where GitHub looks for CODEOWNERS, in the order it checks them
+pub const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// One `pattern @owner...` line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnerRule {
+    pub pattern: String,
+    /// `@user` or `@org/team`, as written; emails are dropped since they can't be requested
+    pub owners: Vec<String>,
+}
+
+/// Parse CODEOWNERS lines, skipping comments and blanks
+pub fn parse(text: &str) -> Vec<OwnerRule> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?.to_string();
+            let owners = fields
+                .filter(|owner| owner.starts_with('@'))
+                .map(str::to_string)
+                .collect();
+            Some(OwnerRule { pattern, owners })
+        })
+        .collect()
+}
+
+/// Owners of `path`: the last matching rule wins, as on GitHub
+pub fn owners_of<'a>(rules: &'a [OwnerRule], path: &str) -> &'a [String] {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule_matches(&rule.pattern, path))
+        .map_or(&[], |rule| rule.owners.as_slice())
+}
+
+/// CODEOWNERS patterns follow gitignore rules; `*` on its own matches everything
+fn rule_matches(pattern: &str, path: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    // `/docs` (no trailing slash) names a file or a directory
+    pattern_matches(pattern, path) || pattern_matches(&format!("{}/", pattern), path)
+}
+
+/// The name `gh pr edit --add-reviewer` takes for an owner (`@org/team` → `org/team`)
+pub fn reviewer_name(owner: &str) -> &str {
+    owner.trim_start_matches('@')
+}
@@ -0,0 +1,51 @@
+//! Cross-checks the coverage gaps the model reports against the files the PR changes
+
+use std::path::Path;
+
+/// Whether a path looks like a test file or lives in a test directory
+pub fn is_test_path(path: &str) -> bool {
+    let path = Path::new(path);
+    let in_test_dir = path.parent().is_some_and(|dir| {
+        dir.components().any(|part| {
+            matches!(
+                part.as_os_str().to_str(),
+                Some("test" | "tests" | "spec" | "specs" | "__tests__")
+            )
+        })
+    });
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+    let stem = name.split('.').next().unwrap_or("");
+    in_test_dir
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || stem.ends_with("Test")
+}
+
+/// Changed test files whose name mentions the source file's, so may cover it after all
+pub fn tests_touching<'a>(source: &str, changed: &[&'a str]) -> Vec<&'a str> {
+    let Some(subject) = subject_name(source) else {
+        return Vec::new();
+    };
+    changed
+        .iter()
+        .copied()
+        .filter(|path| is_test_path(path))
+        .filter(|path| path.to_ascii_lowercase().contains(&subject))
+        .collect()
+}
+
+/// What tests for a file would be named after: its stem, or its directory for
+/// entry-point files like `mod.rs` and `index.ts`
+fn subject_name(source: &str) -> Option<String> {
+    let path = Path::new(source);
+    let stem = path.file_stem()?.to_str()?;
+    let subject = if matches!(stem, "mod" | "lib" | "main" | "index" | "__init__") {
+        path.parent()?.file_name()?.to_str()?
+    } else {
+        stem
+    };
+    Some(subject.to_ascii_lowercase())
+}
@@ -0,0 +1,84 @@
+//! Crash reports: the last actions the app handled are kept in memory so that,
+//! if it panics, what led up to it can be written to `<data dir>/crashes/`.
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+
+use super::logging::log_dir;
+use super::storage::data_dir;
+
+/// Actions kept for the report
+const RECENT_ACTIONS: usize = 50;
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Remember an action the app is about to handle, dropping the oldest
+pub fn record(action: String) {
+    if let Ok(mut recent) = RECENT.lock() {
+        if recent.len() == RECENT_ACTIONS {
+            recent.pop_front();
+        }
+        recent.push_back(action);
+    }
+}
+
+/// The panic message and where it was raised, on one line
+pub fn describe(info: &PanicHookInfo) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(no message)".to_string());
+    let location = info
+        .location()
+        .map_or_else(String::new, |location| format!(" at {}", location));
+    format!("{}{}", message, location)
+}
+
+/// The report for a panic: what and where, the recent actions, oldest first,
+/// and the backtrace
+pub fn report(info: &PanicHookInfo) -> String {
+    let thread = std::thread::current().name().unwrap_or("unnamed").to_string();
+    let mut out = format!(
+        "dstl {} panicked in thread '{}': {}\n\n",
+        env!("CARGO_PKG_VERSION"),
+        thread,
+        describe(info)
+    );
+    // Locked with try_lock: the panic may have happened while recording
+    let recent: Vec<String> = RECENT
+        .try_lock()
+        .map(|recent| recent.iter().cloned().collect())
+        .unwrap_or_default();
+    out.push_str(&format!("Last {} actions, oldest first:\n", recent.len()));
+    for action in recent {
+        out.push_str(&format!("  {}\n", action));
+    }
+    if let Some(dir) = log_dir() {
+        out.push_str(&format!("\nLogs: {}\n", dir.display()));
+    }
+    out.push_str(&format!("\nBacktrace:\n{}\n", Backtrace::force_capture()));
+    out
+}
+
+/// Write a report to `<data dir>/crashes/crash-<unix seconds>.txt`
+pub fn save(report: &str) -> Result<PathBuf> {
+    let dir = data_dir()
+        .context("Could not determine data directory (HOME not set)")?
+        .join("crashes");
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let path = dir.join(format!("crash-{}.txt", secs));
+    std::fs::write(&path, report).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
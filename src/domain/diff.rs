@@ -0,0 +1,377 @@
+use std::collections::HashSet;
+
+use super::types::{ChangeType, ChangedFile, CodeWindow, DiffBlock, Story};
+
+/// One file's section of a unified diff
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    pub additions: u32,
+    pub deletions: u32,
+    pub hunks: Vec<DiffHunk>,
+    pub change: ChangeType,
+    /// Git reported the file as binary instead of showing hunks
+    pub binary: bool,
+    /// New size in bytes, when a binary patch states it
+    pub binary_size: Option<u64>,
+    /// Size of this file's section of the diff in bytes
+    pub bytes: usize,
+}
+
+/// A hunk within a file diff
+#[derive(Debug, Clone)]
+pub struct DiffHunk {
+    /// First line number on the old (base) side
+    pub old_start: u32,
+    /// Number of lines on the old (base) side
+    pub old_len: u32,
+    /// First line number on the new (head) side
+    pub new_start: u32,
+    /// Raw hunk lines including their `+`/`-`/` ` prefix
+    pub lines: Vec<String>,
+}
+
+/// Parse a unified diff (as produced by `gh pr diff`) into per-file sections
+pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: header_path(rest),
+                additions: 0,
+                deletions: 0,
+                hunks: Vec::new(),
+                change: ChangeType::Modified,
+                binary: false,
+                binary_size: None,
+                bytes: line.len() + 1,
+            });
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        file.bytes += line.len() + 1;
+
+        if line.starts_with("@@") {
+            let (old_start, old_len) = parse_old_range(line).unwrap_or((1, 0));
+            file.hunks.push(DiffHunk {
+                old_start,
+                old_len,
+                new_start: parse_new_start(line).unwrap_or(1),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+
+        // File headers (---/+++/index/mode) precede the first hunk
+        let Some(hunk) = file.hunks.last_mut() else {
+            parse_file_header(file, line);
+            continue;
+        };
+
+        if line.starts_with('+') {
+            file.additions += 1;
+        } else if line.starts_with('-') {
+            file.deletions += 1;
+        }
+        hunk.lines.push(line.to_string());
+    }
+
+    files
+}
+
+/// The new-side path from the rest of a `diff --git a/<path> b/<path>` line
+fn header_path(rest: &str) -> String {
+    rest.split_once(" b/")
+        .map(|(_, b)| b)
+        .unwrap_or(rest)
+        .to_string()
+}
+
+/// Note the change type and binary markers from a file header line
+fn parse_file_header(file: &mut FileDiff, line: &str) {
+    if line.starts_with("new file mode") {
+        file.change = ChangeType::Added;
+    } else if line.starts_with("deleted file mode") {
+        file.change = ChangeType::Deleted;
+    } else if line.starts_with("rename from") {
+        file.change = ChangeType::Renamed;
+    } else if line.starts_with("Binary files") || line == "GIT binary patch" {
+        file.binary = true;
+    } else if file.binary
+        && file.binary_size.is_none()
+        && let Some(size) = line.strip_prefix("literal ")
+    {
+        file.binary_size = size.trim().parse().ok();
+    }
+}
+
+/// The diff with only the file sections whose path passes `keep`
+pub fn retain_files(diff: &str, keep: impl Fn(&str) -> bool) -> String {
+    let mut kept = String::with_capacity(diff.len());
+    let mut keeping = true;
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            keeping = keep(&header_path(rest));
+        }
+        if keeping {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    kept
+}
+
+/// Extract the new-side start line from a `@@ -a,b +c,d @@` header
+pub fn parse_new_start(header: &str) -> Option<u32> {
+    header
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))
+        .and_then(|range| range.split(',').next())
+        .and_then(|start| start.parse().ok())
+}
+
+/// Extract the old-side start line and length from a `@@ -a,b +c,d @@` header
+pub fn parse_old_range(header: &str) -> Option<(u32, u32)> {
+    let range = header
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('-'))?;
+    let mut parts = range.split(',');
+    let start = parts.next()?.parse().ok()?;
+    let len = parts.next().map_or(Some(1), |len| len.parse().ok())?;
+    Some((start, len))
+}
+
+/// Base-side line range (inclusive) a hunk replaces; pure additions give the line they follow
+pub fn base_range(hunk: &DiffHunk) -> (u32, u32) {
+    (hunk.old_start, hunk.old_start + hunk.old_len.saturating_sub(1))
+}
+
+/// The file and base-side line range of the raw hunk a story diff block shows
+pub fn block_base_range(files: &[FileDiff], block: &DiffBlock) -> Option<(String, u32, u32)> {
+    let (path, line) = locate_block(files, block)?;
+    let file = files.iter().find(|file| file.path == path)?;
+    let hunk = file.hunks.iter().find(|hunk| {
+        let len = new_side_len(&hunk.lines) as u32;
+        line >= hunk.new_start && line < hunk.new_start + len.max(1)
+    })?;
+    let (start, end) = base_range(hunk);
+    Some((path, start, end))
+}
+
+/// Number of lines a hunk covers on the new (head) side
+fn new_side_len(lines: impl IntoIterator<Item = impl AsRef<str>>) -> usize {
+    lines
+        .into_iter()
+        .filter(|line| !line.as_ref().starts_with('-') && !line.as_ref().starts_with('\\'))
+        .count()
+}
+
+/// Windows of head-side code around each of a file's hunks, merging any that overlap
+pub fn surrounding_windows(file: &FileDiff, contents: &str, context: usize) -> Vec<CodeWindow> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for hunk in &file.hunks {
+        let hunk_start = (hunk.new_start as usize).saturating_sub(1);
+        let start = hunk_start.saturating_sub(context);
+        let end = (hunk_start + new_side_len(&hunk.lines) + context).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| CodeWindow {
+            path: file.path.clone(),
+            start_line: start as u32 + 1,
+            lines: lines[start..end].iter().map(|line| line.to_string()).collect(),
+        })
+        .collect()
+}
+
+/// Head-side window around a story diff block: its located line, the block's
+/// length, and `context` lines either side
+pub fn block_window(
+    files: &[FileDiff],
+    block: &DiffBlock,
+    contents: &str,
+    context: usize,
+) -> Option<CodeWindow> {
+    let (path, line) = locate_block(files, block)?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let block_len = new_side_len(block.hunks.iter().flat_map(|hunk| hunk.lines.lines()));
+
+    let start = (line as usize).saturating_sub(1).saturating_sub(context);
+    let end = (line as usize + block_len + context).min(lines.len());
+    if start >= end {
+        return None;
+    }
+    Some(CodeWindow {
+        path,
+        start_line: start as u32 + 1,
+        lines: lines[start..end].iter().map(|line| line.to_string()).collect(),
+    })
+}
+
+/// Head-side line numbers of the lines a file's diff adds
+pub fn added_lines(files: &[FileDiff], path: &str) -> HashSet<u32> {
+    let mut added = HashSet::new();
+    for hunk in files.iter().filter(|file| file.path == path).flat_map(|file| &file.hunks) {
+        let mut new_line = hunk.new_start;
+        for line in &hunk.lines {
+            if line.starts_with('-') || line.starts_with('\\') {
+                continue;
+            }
+            if line.starts_with('+') {
+                added.insert(new_line);
+            }
+            new_line += 1;
+        }
+    }
+    added
+}
+
+/// Find the file and new-side line number a story diff block refers to, by matching
+/// its added or context lines against the raw diff. Added lines are preferred.
+pub fn locate_block(files: &[FileDiff], block: &DiffBlock) -> Option<(String, u32)> {
+    let candidates: Vec<&str> = block
+        .hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.lines())
+        .filter(|line| line.starts_with('+') || line.starts_with(' '))
+        .filter(|line| line[1..].trim().len() > 3)
+        .collect();
+
+    let added = candidates.iter().filter(|line| line.starts_with('+'));
+    let context = candidates.iter().filter(|line| line.starts_with(' '));
+
+    added
+        .chain(context)
+        .find_map(|wanted| locate_line(files, wanted.trim_end()))
+}
+
+/// The one changed file a story diff block shows: the file a hunk header names,
+/// else the file sharing the most of the block's added and removed lines (the
+/// earliest in the diff on a tie)
+pub fn block_file<'a>(files: &'a [FileDiff], block: &DiffBlock) -> Option<&'a FileDiff> {
+    let named = files
+        .iter()
+        .find(|file| block.hunks.iter().any(|hunk| names_path(&hunk.header, &file.path)));
+    if named.is_some() {
+        return named;
+    }
+
+    let changed: Vec<&str> = block
+        .hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.lines())
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+        .map(str::trim_end)
+        .collect();
+    files
+        .iter()
+        .rev()
+        .map(|file| {
+            let lines: HashSet<&str> = file
+                .hunks
+                .iter()
+                .flat_map(|hunk| &hunk.lines)
+                .map(|line| line.trim_end())
+                .collect();
+            (changed.iter().filter(|line| lines.contains(*line)).count(), file)
+        })
+        .filter(|(matches, _)| *matches > 0)
+        .max_by_key(|(matches, _)| *matches)
+        .map(|(_, file)| file)
+}
+
+/// Head-side line range (inclusive) a story diff block shows of `file`, from the
+/// raw hunks holding any of its added or removed lines
+pub fn block_head_range(file: &FileDiff, block: &DiffBlock) -> Option<(u32, u32)> {
+    let shown: HashSet<&str> =
+        block.hunks.iter().flat_map(|hunk| hunk.lines.lines()).map(str::trim_end).collect();
+    let changed = |line: &str| line.starts_with('+') || line.starts_with('-');
+    let mut range: Option<(u32, u32)> = None;
+    for hunk in &file.hunks {
+        if !hunk.lines.iter().any(|line| changed(line) && shown.contains(line.trim_end())) {
+            continue;
+        }
+        let mut new_line = hunk.new_start;
+        for line in &hunk.lines {
+            if line.starts_with('-') || line.starts_with('\\') {
+                continue;
+            }
+            if shown.contains(line.trim_end()) {
+                range = Some(range.map_or((new_line, new_line), |(start, end)| {
+                    (start.min(new_line), end.max(new_line))
+                }));
+            }
+            new_line += 1;
+        }
+    }
+    range
+}
+
+/// Whether a story diff block shows this file, of all the PR's `files`
+pub fn block_touches(files: &[FileDiff], file: &FileDiff, block: &DiffBlock) -> bool {
+    block_file(files, block).is_some_and(|found| found.path == file.path)
+}
+
+/// Whether a hunk header names `path` as a whole word, e.g. `@@ -1 +1 @@ src/lib.rs`
+fn names_path(header: &str, path: &str) -> bool {
+    header.split_whitespace().any(|word| {
+        let word = word.trim_matches(|c: char| "`\"',:()".contains(c));
+        let word = word.strip_prefix("a/").or_else(|| word.strip_prefix("b/")).unwrap_or(word);
+        word == path
+    })
+}
+
+/// Index each changed file with the story diff blocks that show it, sorted by path
+pub fn index_changed_files(files: &[FileDiff], story: &Story) -> Vec<ChangedFile> {
+    let mut changed: Vec<ChangedFile> = files
+        .iter()
+        .map(|file| ChangedFile {
+            path: file.path.clone(),
+            additions: file.additions,
+            deletions: file.deletions,
+            blocks: Vec::new(),
+        })
+        .collect();
+    for (i, feature) in story.narrative.iter().enumerate() {
+        for (j, block) in feature.diff_blocks.iter().enumerate() {
+            let Some(file) = block_file(files, block) else {
+                continue;
+            };
+            if let Some(entry) = changed.iter_mut().find(|entry| entry.path == file.path) {
+                entry.blocks.push((i, j));
+            }
+        }
+    }
+    changed.sort_by(|a, b| a.path.cmp(&b.path));
+    changed
+}
+
+/// Find the file and new-side line number of an exact diff line
+fn locate_line(files: &[FileDiff], wanted: &str) -> Option<(String, u32)> {
+    for file in files {
+        for hunk in &file.hunks {
+            let mut new_line = hunk.new_start;
+            for line in &hunk.lines {
+                if line.starts_with('-') || line.starts_with('\\') {
+                    continue;
+                }
+                if line.trim_end() == wanted {
+                    return Some((file.path.clone(), new_line));
+                }
+                new_line += 1;
+            }
+        }
+    }
+    None
+}
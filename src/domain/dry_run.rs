@@ -0,0 +1,27 @@
+//! Dry runs: submissions are written to `.dstl/outbox/` as the payload they
+//! would have sent, and nothing reaches GitHub.
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+/// Where would-be submissions are written, relative to the working directory
+pub const OUTBOX_DIR: &str = ".dstl/outbox";
+
+/// Write a would-be submission to the outbox and return its path.
+/// `kind` names what it would have been, e.g. `review` or `issue`.
+pub fn write(owner: &str, repo: &str, number: u32, kind: &str, payload: &Value) -> Result<PathBuf> {
+    let dir = PathBuf::from(OUTBOX_DIR);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = dir.join(format!("{}-{}-{}-{}-{}.json", owner, repo, number, kind, millis));
+    let json = serde_json::to_string_pretty(payload).context("Failed to serialize payload")?;
+    std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
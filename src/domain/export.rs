@@ -0,0 +1,236 @@
+//! Stories and diff blocks as Markdown, for pasting into issues, chats and docs,
+//! and whole review sessions as JSON for analytics tooling.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::timing::ReviewClock;
+use super::types::{DiffBlock, PrContext, Significance, Story};
+
+/// Where in-app session exports are written, relative to the working directory
+pub const EXPORT_DIR: &str = ".dstl/exports";
+
+/// Hidden marker identifying the comment `dstl bot` posts, so re-runs edit it
+pub const BOT_MARKER: &str = "<!-- dstl-bot -->";
+
+/// One diff block: its number, label and file, why it's there, and its hunks
+pub fn block_markdown(
+    feature_idx: usize,
+    block_idx: usize,
+    block: &DiffBlock,
+    path: Option<&str>,
+) -> String {
+    let mut out = format!("### {}.{} {}\n\n", feature_idx + 1, block_idx + 1, block.label);
+    if let Some(path) = path {
+        out.push_str(&format!("`{}`\n\n", path));
+    }
+    if !block.context.trim().is_empty() {
+        out.push_str(&format!("{}\n\n", block.context.trim()));
+    }
+    out.push_str("```diff\n");
+    for hunk in &block.hunks {
+        out.push_str(&hunk.header);
+        out.push('\n');
+        out.push_str(hunk.lines.trim_end_matches('\n'));
+        out.push('\n');
+    }
+    out.push_str("```\n");
+    out
+}
+
+/// The whole story, headed by the PR it's for
+pub fn story_markdown(pr: Option<&PrContext>, story: &Story) -> String {
+    let mut out = match pr {
+        Some(pr) => format!("# {}/{}#{}: {}\n\n", pr.owner, pr.repo, pr.number, pr.title),
+        None => "# PR story\n\n".to_string(),
+    };
+    out.push_str(&format!("{}\n\n", story.summary.trim()));
+    out.push_str(&format!("**Focus:** {}\n\n", story.focus.key_change));
+    if !story.whats_new.is_empty() {
+        out.push_str("## What's new since the last review\n\n");
+        push_list(&mut out, &story.whats_new);
+    }
+
+    for (i, feature) in story.narrative.iter().enumerate() {
+        out.push_str(&format!("## {}. {}\n\n{}\n\n", i + 1, feature.title, feature.why));
+        if !feature.changes.is_empty() {
+            out.push_str("**Changes**\n\n");
+            push_list(&mut out, &feature.changes);
+        }
+        if !feature.risks.is_empty() {
+            out.push_str("**Risks**\n\n");
+            let risks: Vec<String> = feature
+                .risks
+                .iter()
+                .map(|risk| match risk.severity {
+                    Some(severity) => format!("**{}** {}", severity.label(), risk.text),
+                    None => risk.text.clone(),
+                })
+                .collect();
+            push_list(&mut out, &risks);
+        }
+        if !feature.tests.is_empty() {
+            out.push_str("**Tests**\n\n");
+            push_list(&mut out, &feature.tests);
+        }
+        for (j, block) in feature.diff_blocks.iter().enumerate() {
+            out.push_str(&block_markdown(i, j, block, None));
+            out.push('\n');
+        }
+    }
+
+    if !story.open_questions.is_empty() {
+        out.push_str("## Open questions\n\n");
+        push_list(&mut out, &story.open_questions);
+    }
+    if !story.coverage_gaps.is_empty() {
+        out.push_str("## Coverage gaps\n\n");
+        let gaps: Vec<String> = story
+            .coverage_gaps
+            .iter()
+            .map(|gap| format!("`{}`: {}", gap.target, gap.reason))
+            .collect();
+        push_list(&mut out, &gaps);
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+/// Which of a PR's comments, given as `(id, body)`, an earlier `dstl bot` run
+/// posted: the first holding `marker`
+pub fn marked_comment<Id>(
+    comments: impl IntoIterator<Item = (Id, String)>,
+    marker: &str,
+) -> Option<Id> {
+    comments.into_iter().find(|(_, body)| body.contains(marker)).map(|(id, _)| id)
+}
+
+/// The PR comment `dstl bot` posts: summary, focus, risks by severity and open
+/// questions, headed by [`BOT_MARKER`]
+pub fn bot_comment(pr: &PrContext, story: &Story) -> String {
+    let mut out = format!("{}\n## PR story\n\n{}\n\n", BOT_MARKER, story.summary.trim());
+    out.push_str(&format!("**Focus:** {}\n\n", story.focus.key_change));
+    if !story.focus.review_these.is_empty() {
+        out.push_str("**Review closely**\n\n");
+        let files: Vec<String> =
+            story.focus.review_these.iter().map(|item| format!("`{}`", item)).collect();
+        push_list(&mut out, &files);
+    }
+
+    let mut risks: Vec<_> = story
+        .narrative
+        .iter()
+        .flat_map(|feature| feature.risks.iter().map(move |risk| (feature, risk)))
+        .collect();
+    // Most severe first, unrated last
+    risks.sort_by_key(|(_, risk)| (risk.severity.is_none(), risk.severity));
+    if !risks.is_empty() {
+        out.push_str("### Risks\n\n");
+        let risks: Vec<String> = risks
+            .iter()
+            .map(|(feature, risk)| match risk.severity {
+                Some(severity) => {
+                    format!("**{}** {} ({})", severity.label(), risk.text, feature.title)
+                }
+                None => format!("{} ({})", risk.text, feature.title),
+            })
+            .collect();
+        push_list(&mut out, &risks);
+    }
+    if !story.open_questions.is_empty() {
+        out.push_str("### Open questions\n\n");
+        push_list(&mut out, &story.open_questions);
+    }
+    let head = pr.head_sha.get(..7).unwrap_or(&pr.head_sha);
+    out.push_str(&format!("<sub>Generated by dstl at {}</sub>\n", head));
+    out
+}
+
+fn push_list(out: &mut String, items: &[String]) {
+    for item in items {
+        out.push_str(&format!("- {}\n", item));
+    }
+    out.push('\n');
+}
+
+/// A review session as one machine-readable record: the story, how each draft
+/// was edited, and which blocks were viewed and for how long
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRecord {
+    pub pr: String,
+    pub title: String,
+    pub head_sha: String,
+    pub model: Option<String>,
+    pub exported_at: u64,
+    pub seconds: u64,
+    pub drafts: Vec<DraftRecord>,
+    pub blocks: Vec<BlockRecord>,
+    pub story: Story,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftRecord {
+    pub action: String,
+    /// What the story suggested
+    pub suggested: String,
+    /// The draft as it stands
+    pub text: String,
+    pub edited: bool,
+    pub submitted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockRecord {
+    /// `feature.block`, numbered from 1 as in the TUI
+    pub block: String,
+    pub label: String,
+    pub significance: Significance,
+    pub viewed: bool,
+    pub follow_up: bool,
+    pub seconds: u64,
+}
+
+/// Every block of the story with the reviewer's marks and time spent on it
+pub fn block_records(
+    story: &Story,
+    viewed: &HashSet<(usize, usize)>,
+    follow_up: &HashSet<(usize, usize)>,
+    clock: &ReviewClock,
+) -> Vec<BlockRecord> {
+    story
+        .narrative
+        .iter()
+        .enumerate()
+        .flat_map(|(i, feature)| {
+            feature.diff_blocks.iter().enumerate().map(move |(j, block)| BlockRecord {
+                block: format!("{}.{}", i + 1, j + 1),
+                label: block.label.clone(),
+                significance: block.significance.clone(),
+                viewed: viewed.contains(&(i, j)),
+                follow_up: follow_up.contains(&(i, j)),
+                seconds: clock.spent_on((i, j)).as_secs(),
+            })
+        })
+        .collect()
+}
+
+impl SessionRecord {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize review session")
+    }
+
+    /// Write the record to [`EXPORT_DIR`] and return its path
+    pub fn write(&self) -> Result<PathBuf> {
+        let dir = PathBuf::from(EXPORT_DIR);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let name = format!("{}-{}.json", self.pr.replace(['/', '#'], "-"), self.exported_at);
+        let path = dir.join(name);
+        std::fs::write(&path, self.to_json()?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,191 @@
+use super::diff::{parse_diff, retain_files, FileDiff};
+use super::storage::config_dir;
+use super::types::{ExcludedFile, ExclusionReason, PrContext};
+
+/// Largest single-file diff sent to the model (~15k tokens); bigger ones become a stub
+pub const MAX_FILE_DIFF_BYTES: usize = 60_000;
+
+/// Files that are almost always machine-written and drown out the real change
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    "*.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "pnpm-lock.yaml",
+    "go.sum",
+    "*.min.js",
+    "*.min.css",
+    "*.map",
+    "*.pb.go",
+    "*_pb2.py",
+    "*.generated.*",
+    "vendor/",
+    "node_modules/",
+    "third_party/",
+];
+
+/// Decides which changed files are left out of the prompt
+#[derive(Debug, Clone, Default)]
+pub struct DiffFilter {
+    ignore: Vec<String>,
+    include: Vec<String>,
+}
+
+impl DiffFilter {
+    /// The default ignore list plus `ignore`; files matching any `include`
+    /// pattern are always analyzed
+    pub fn new(ignore: &[String], include: &[String]) -> Self {
+        Self {
+            ignore: DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .chain(ignore.iter().cloned())
+                .collect(),
+            include: include.to_vec(),
+        }
+    }
+
+    /// The first ignore pattern matching `path`
+    fn ignore_pattern(&self, path: &str) -> Option<&str> {
+        self.ignore
+            .iter()
+            .find(|pattern| pattern_matches(pattern, path))
+            .map(String::as_str)
+    }
+
+    /// Why `file` should be left out of the prompt, if it should. Binary files
+    /// always are; force-included files are otherwise kept even when oversized
+    fn exclusion(&self, file: &FileDiff) -> Option<ExclusionReason> {
+        if file.binary {
+            return Some(ExclusionReason::Binary {
+                size: file.binary_size,
+            });
+        }
+        if self.include.iter().any(|pattern| pattern_matches(pattern, &file.path)) {
+            return None;
+        }
+        if let Some(pattern) = self.ignore_pattern(&file.path) {
+            return Some(ExclusionReason::Ignored {
+                pattern: pattern.to_string(),
+            });
+        }
+        (file.bytes > MAX_FILE_DIFF_BYTES).then_some(ExclusionReason::TooLarge {
+            bytes: file.bytes,
+        })
+    }
+
+    /// Split a diff into the part to analyze and the files left out of it
+    pub fn apply(&self, diff: &str) -> (String, Vec<ExcludedFile>) {
+        let excluded: Vec<ExcludedFile> = parse_diff(diff)
+            .into_iter()
+            .filter_map(|file| {
+                let reason = self.exclusion(&file)?;
+                Some(ExcludedFile {
+                    path: file.path,
+                    additions: file.additions,
+                    deletions: file.deletions,
+                    change: file.change,
+                    reason,
+                })
+            })
+            .collect();
+        let analyzed = retain_files(diff, |path| excluded.iter().all(|file| file.path != path));
+        (analyzed, excluded)
+    }
+}
+
+/// Limit the review to files matching any of `scope` (every file when it's empty).
+/// The rest are left out of the prompt altogether and only counted.
+pub fn apply_scope(pr: &mut PrContext, scope: &[String]) {
+    pr.scope = scope.to_vec();
+    pr.out_of_scope = parse_diff(&pr.diff)
+        .into_iter()
+        .map(|file| file.path)
+        .filter(|path| !in_scope(scope, path))
+        .collect();
+}
+
+/// Comma-separated patterns, as typed
+pub fn parse_patterns(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn in_scope(scope: &[String], path: &str) -> bool {
+    scope.is_empty() || scope.iter().any(|pattern| pattern_matches(pattern, path))
+}
+
+/// Patterns from `~/.config/dstl/ignore`, one per line (`#` starts a comment).
+/// Lines starting with `!` force-include matching files. Returns (ignore, include)
+pub fn load_user_patterns() -> (Vec<String>, Vec<String>) {
+    let contents = config_dir()
+        .and_then(|dir| std::fs::read_to_string(dir.join("ignore")).ok())
+        .unwrap_or_default();
+
+    let mut ignore = Vec::new();
+    let mut include = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.strip_prefix('!') {
+            Some(pattern) => include.push(pattern.to_string()),
+            None => ignore.push(line.to_string()),
+        }
+    }
+    (ignore, include)
+}
+
+/// Match a path against a gitattributes-style pattern. Patterns without a `/`
+/// match the file name at any depth; others match from the repo root. `*` and
+/// `?` stay within one directory, `**` spans directories, and a trailing `/`
+/// matches everything under that directory.
+pub fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim();
+    let path: Vec<&str> = path.split('/').collect();
+
+    if let Some(dir) = pattern.strip_suffix('/') {
+        let anchored = dir.contains('/');
+        let dir = dir.trim_start_matches('/');
+        let glob = if anchored {
+            format!("{}/**", dir)
+        } else {
+            format!("**/{}/**", dir)
+        };
+        return match_segments(&glob.split('/').collect::<Vec<_>>(), &path);
+    }
+    if !pattern.contains('/') {
+        return path
+            .last()
+            .is_some_and(|name| match_wildcard(pattern.as_bytes(), name.as_bytes()));
+    }
+    let glob: Vec<&str> = pattern.trim_start_matches('/').split('/').collect();
+    match_segments(&glob, &path)
+}
+
+/// Match path segments, with `**` standing for any number of them
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path)| {
+            match_wildcard(segment.as_bytes(), name.as_bytes()) && match_segments(rest, path)
+        }),
+    }
+}
+
+/// Match one path segment against `*`/`?` wildcards
+fn match_wildcard(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.split_first(), text.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            match_wildcard(rest, text) || (!text.is_empty() && match_wildcard(pattern, &text[1..]))
+        }
+        (Some((b'?', rest)), Some((_, text))) => match_wildcard(rest, text),
+        (Some((p, rest)), Some((t, text))) if p == t => match_wildcard(rest, text),
+        _ => false,
+    }
+}
@@ -1,8 +1,150 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::process::Command;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 
-use super::types::{CiStatus, PrContext, PrListItem, RepoListItem};
+use super::diff::{parse_diff, surrounding_windows};
+use super::export;
+use super::filter::DiffFilter;
+use super::listings;
+use super::prompt::REPO_PROMPT_PATH;
+use super::codeowners::{self, CODEOWNERS_PATHS};
+use super::stack::{self, ParentPr, StackEntry};
+use super::stats::RateLimit;
+use super::style_guide;
+use super::symbols::{changed_symbols, SymbolChange};
+use super::types::{
+    ActivityComment, BlameRange, BotComment, CiStatus, CodeWindow, CustomPrompt, InlineComment,
+    LinkedIssue, Page, PrActivity, PrCommit, PrContext, PrLabel, PrListItem, PrState,
+    PrStateFilter, ProgressEvent, QueueItem, RepoListItem, ReviewEvent, SymbolCallers,
+    ViewedFiles,
+};
+
+/// How long a gh subprocess may run when no timeout has been configured
+const DEFAULT_GH_TIMEOUT: Duration = Duration::from_secs(60);
+
+static GH_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// Most gh processes to run at once when listing several repos
+const MAX_PARALLEL_GH: usize = 8;
+
+/// Set how long any gh subprocess may run before it is killed. Call once at startup.
+pub fn set_gh_timeout(timeout: Duration) {
+    let _ = GH_TIMEOUT.set(timeout);
+}
+
+trait RunWithTimeout {
+    /// Like `Command::output`, but kills gh if it runs past the configured timeout
+    fn run_with_timeout(&mut self, label: &str) -> Result<Output>;
+}
+
+impl RunWithTimeout for Command {
+    fn run_with_timeout(&mut self, label: &str) -> Result<Output> {
+        let child = self
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to execute {}", label))?;
+        wait_with_timeout(child, label)
+    }
+}
+
+/// Wait for a spawned gh process, killing it once the timeout elapses
+fn wait_with_timeout(mut child: Child, label: &str) -> Result<Output> {
+    let timeout = GH_TIMEOUT.get().copied().unwrap_or(DEFAULT_GH_TIMEOUT);
+    let started = Instant::now();
+    let deadline = started + timeout;
+
+    // Drain the pipes as we go so a large response can't block gh on a full pipe
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .with_context(|| format!("Failed to wait for {}", label))?
+        {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            warn!(command = label, timeout_secs = timeout.as_secs(), "gh timed out");
+            anyhow::bail!(
+                "{} timed out after {}s. Check your network and `gh auth status`, \
+                 or raise --gh-timeout, then press r to retry",
+                label,
+                timeout.as_secs()
+            );
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    let collect = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+    let output = Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    };
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    if output.status.success() {
+        debug!(command = label, elapsed_ms, "gh finished");
+    } else {
+        warn!(
+            command = label,
+            elapsed_ms,
+            status = %output.status,
+            stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+            "gh failed"
+        );
+    }
+    Ok(output)
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+/// Most-changed files fetched in full to give the prompt surrounding code
+const MAX_CONTEXT_FILES: usize = 8;
+
+/// Most-changed files whose base-side blame goes into the prompt
+const MAX_BLAME_FILES: usize = 5;
+
+/// Most modified functions to search the repo for callers of; code search allows
+/// only a handful of requests a minute
+const MAX_IMPACT_SYMBOLS: usize = 5;
+
+/// Optional extra context gathered alongside a PR
+#[derive(Debug, Clone)]
+pub struct PrFetchOptions {
+    /// Lines of head-side code around each hunk (0 to skip)
+    pub context_lines: usize,
+    /// Fetch base-side blame for the most heavily changed files
+    pub blame: bool,
+    /// Search the repo for callers of functions the PR modifies or removes
+    pub impact: bool,
+    /// Which changed files to leave out of the prompt
+    pub filter: DiffFilter,
+}
+
+/// Most issue references from a PR body to fetch as prompt context
+const MAX_LINKED_ISSUES: usize = 5;
 
 /// Response from `gh pr view --json`
 #[derive(Debug, Deserialize)]
@@ -15,6 +157,10 @@ struct GhPrView {
     base_ref_name: String,
     #[serde(rename = "headRefName")]
     head_ref_name: String,
+    #[serde(rename = "headRefOid")]
+    head_ref_oid: String,
+    #[serde(rename = "baseRefOid")]
+    base_ref_oid: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,6 +168,74 @@ struct GhAuthor {
     login: String,
 }
 
+/// Response from `gh pr view --json commits,comments,reviews`
+#[derive(Debug, Deserialize)]
+struct GhPrActivity {
+    commits: Vec<GhCommit>,
+    comments: Vec<GhComment>,
+    reviews: Vec<GhComment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCommit {
+    oid: String,
+    #[serde(rename = "messageHeadline", default)]
+    message_headline: String,
+    #[serde(default)]
+    authors: Vec<GhCommitAuthor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCommitAuthor {
+    #[serde(default)]
+    login: String,
+    #[serde(default)]
+    name: String,
+}
+
+/// Response from `gh pr view --json commits`
+#[derive(Debug, Deserialize)]
+struct GhPrCommits {
+    commits: Vec<GhCommit>,
+}
+
+/// A conversation comment or review; reviews have `submittedAt` instead of `createdAt`
+#[derive(Debug, Deserialize)]
+struct GhComment {
+    id: String,
+    author: GhAuthor,
+    body: String,
+    #[serde(rename = "createdAt", alias = "submittedAt")]
+    created_at: String,
+}
+
+/// Response from `gh issue view --json`
+#[derive(Debug, Deserialize)]
+struct GhIssueView {
+    number: u32,
+    title: String,
+    body: Option<String>,
+}
+
+/// Comment from `gh api repos/{owner}/{repo}/issues/{number}/comments`
+#[derive(Debug, Deserialize)]
+struct GhIssueComment {
+    id: u64,
+    #[serde(default)]
+    body: String,
+}
+
+/// Review comment from `gh api repos/{owner}/{repo}/pulls/{number}/comments`
+#[derive(Debug, Deserialize)]
+struct GhReviewComment {
+    user: GhAuthor,
+    path: String,
+    line: Option<u32>,
+    original_line: Option<u32>,
+    body: String,
+    diff_hunk: String,
+}
+
 /// Response from `gh pr list --json`
 #[derive(Debug, Deserialize)]
 struct GhPrListItem {
@@ -30,6 +244,8 @@ struct GhPrListItem {
     author: GhAuthor,
     #[serde(rename = "headRefName")]
     head_ref_name: String,
+    #[serde(rename = "baseRefName")]
+    base_ref_name: String,
     #[serde(rename = "isDraft")]
     is_draft: bool,
     additions: u32,
@@ -38,6 +254,25 @@ struct GhPrListItem {
     review_requests: Vec<GhReviewRequest>,
     #[serde(rename = "statusCheckRollup")]
     status_check_rollup: Option<Vec<GhStatusCheck>>,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    /// `OPEN`, `CLOSED` or `MERGED`; open when not asked for
+    #[serde(default)]
+    state: String,
+    #[serde(rename = "updatedAt", default)]
+    updated_at: String,
+    #[serde(default)]
+    labels: Vec<PrLabel>,
+    #[serde(default)]
+    milestone: Option<GhMilestone>,
+    /// Only filled in from the GraphQL PR list
+    #[serde(skip)]
+    comment_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhMilestone {
+    title: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -66,11 +301,18 @@ impl GhPrListItem {
             title: self.title,
             author: self.author.login,
             head_branch: self.head_ref_name,
+            base_branch: self.base_ref_name,
             is_draft: self.is_draft,
             review_requested,
             ci_status,
             additions: self.additions,
             deletions: self.deletions,
+            created_at: self.created_at,
+            state: PrState::from_github(&self.state),
+            updated_at: self.updated_at,
+            labels: self.labels,
+            milestone: self.milestone.map(|milestone| milestone.title),
+            comments: self.comment_count,
         }
     }
 
@@ -128,9 +370,16 @@ impl GhPrListItem {
     }
 }
 
-/// Fetch PR metadata and diff using gh CLI
-pub async fn fetch_pr(owner: &str, repo: &str, number: u32) -> Result<PrContext> {
+/// Fetch PR metadata and diff using gh CLI, reporting each step
+pub async fn fetch_pr(
+    owner: &str,
+    repo: &str,
+    number: u32,
+    options: PrFetchOptions,
+    on_progress: impl Fn(ProgressEvent),
+) -> Result<PrContext> {
     let repo_spec = format!("{}/{}", owner, repo);
+    on_progress(ProgressEvent::FetchingPr { number });
 
     // Fetch PR metadata
     let output = Command::new("gh")
@@ -141,10 +390,9 @@ pub async fn fetch_pr(owner: &str, repo: &str, number: u32) -> Result<PrContext>
             "--repo",
             &repo_spec,
             "--json",
-            "number,title,body,author,baseRefName,headRefName",
+            "number,title,body,author,baseRefName,headRefName,headRefOid,baseRefOid",
         ])
-        .output()
-        .context("Failed to execute gh pr view")?;
+        .run_with_timeout("gh pr view")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -153,12 +401,12 @@ pub async fn fetch_pr(owner: &str, repo: &str, number: u32) -> Result<PrContext>
 
     let pr_view: GhPrView =
         serde_json::from_slice(&output.stdout).context("Failed to parse gh pr view output")?;
+    on_progress(ProgressEvent::FetchedMetadata);
 
     // Fetch diff
     let diff_output = Command::new("gh")
         .args(["pr", "diff", &number.to_string(), "--repo", &repo_spec])
-        .output()
-        .context("Failed to execute gh pr diff")?;
+        .run_with_timeout("gh pr diff")?;
 
     if !diff_output.status.success() {
         let stderr = String::from_utf8_lossy(&diff_output.stderr);
@@ -166,253 +414,1488 @@ pub async fn fetch_pr(owner: &str, repo: &str, number: u32) -> Result<PrContext>
     }
 
     let diff = String::from_utf8_lossy(&diff_output.stdout).to_string();
+    on_progress(ProgressEvent::FetchedDiff { bytes: diff.len() });
+    // Extra context is only worth fetching for files the model will see
+    let (analyzed_diff, excluded_files) = options.filter.apply(&diff);
+
+    // Bot feedback is supplementary; a failure here shouldn't block the review
+    let bot_comments = fetch_bot_comments(&repo_spec, number).unwrap_or_default();
+
+    let body = pr_view.body.unwrap_or_default();
+    let linked_issues = fetch_linked_issues(owner, repo, number, &body);
+    on_progress(ProgressEvent::FetchedContext {
+        bot_comments: bot_comments.len(),
+        linked_issues: linked_issues.len(),
+    });
+
+    let (head_files, surrounding_code) = if options.context_lines > 0 {
+        fetch_surrounding_code(
+            owner,
+            repo,
+            &pr_view.head_ref_oid,
+            &analyzed_diff,
+            options.context_lines,
+        )
+    } else {
+        Default::default()
+    };
+    on_progress(ProgressEvent::FetchedFiles {
+        count: head_files.len(),
+    });
+
+    let base_blame = if options.blame {
+        let blame = fetch_base_blame(owner, repo, &pr_view.base_ref_oid, &analyzed_diff);
+        on_progress(ProgressEvent::FetchedBlame { count: blame.len() });
+        blame
+    } else {
+        HashMap::new()
+    };
+
+    let callers = if options.impact {
+        let callers = fetch_callers(&repo_spec, &analyzed_diff);
+        on_progress(ProgressEvent::FetchedCallers { count: callers.len() });
+        callers
+    } else {
+        Vec::new()
+    };
+
+    // Read from the base so a PR can't rewrite the instructions it's reviewed under
+    let repo_prompt = fetch_file_at(owner, repo, REPO_PROMPT_PATH, &pr_view.base_ref_oid)
+        .ok()
+        .filter(|text| !text.trim().is_empty())
+        .map(|text| CustomPrompt {
+            source: REPO_PROMPT_PATH.to_string(),
+            text,
+        });
+    let style_guide = style_guide::load(owner, repo, &pr_view.base_ref_oid);
+    let code_owners = CODEOWNERS_PATHS
+        .iter()
+        .find_map(|path| fetch_file_at(owner, repo, path, &pr_view.base_ref_oid).ok())
+        .map(|text| codeowners::parse(&text))
+        .unwrap_or_default();
+    // Stack context is supplementary too
+    let current = StackEntry {
+        number: pr_view.number,
+        title: pr_view.title.clone(),
+        head_branch: pr_view.head_ref_name.clone(),
+        base_branch: pr_view.base_ref_name.clone(),
+    };
+    let stack = fetch_stack(&repo_spec, current).unwrap_or_default();
+    let parent = stack
+        .iter()
+        .take_while(|entry| entry.number != pr_view.number)
+        .last()
+        .and_then(|entry| fetch_parent(&repo_spec, entry.number).ok());
 
     Ok(PrContext {
         owner: owner.to_string(),
         repo: repo.to_string(),
         number: pr_view.number,
         title: pr_view.title,
-        body: pr_view.body.unwrap_or_default(),
+        body,
         diff,
         author: pr_view.author.login,
         base_branch: pr_view.base_ref_name,
         head_branch: pr_view.head_ref_name,
+        head_sha: pr_view.head_ref_oid,
+        base_sha: pr_view.base_ref_oid,
+        bot_comments,
+        linked_issues,
+        head_files,
+        surrounding_code,
+        base_blame,
+        callers,
+        file_summaries: Vec::new(),
+        repo_prompt,
+        review_memory: None,
+        style_guide,
+        scope: Vec::new(),
+        out_of_scope: Vec::new(),
+        code_owners,
+        stack,
+        parent,
+        commit_range: None,
+        review_delta: None,
+        excluded_files,
     })
 }
 
-/// Post a review requesting changes
-pub fn post_review(owner: &str, repo: &str, number: u32, body: &str) -> Result<()> {
-    let repo_spec = format!("{}/{}", owner, repo);
+/// The stack the PR belongs to, from the repo's open PRs
+fn fetch_stack(repo_spec: &str, current: StackEntry) -> Result<Vec<StackEntry>> {
+    let open: Vec<StackEntry> = list_prs(repo_spec, None)?
+        .into_iter()
+        .map(|pr| pr.into_list_item(""))
+        .map(|pr| StackEntry::from(&pr))
+        .collect();
+    Ok(stack::build(&open, current))
+}
 
+/// Title and description of the PR this one is stacked on
+fn fetch_parent(repo_spec: &str, number: u32) -> Result<ParentPr> {
     let output = Command::new("gh")
         .args([
             "pr",
-            "review",
+            "view",
             &number.to_string(),
             "--repo",
-            &repo_spec,
-            "--request-changes",
-            "--body",
-            body,
+            repo_spec,
+            "--json",
+            "number,title,body",
         ])
-        .output()
-        .context("Failed to execute gh pr review")?;
+        .run_with_timeout("gh pr view")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("gh pr review failed: {}", stderr);
+        anyhow::bail!("gh pr view failed: {}", stderr);
     }
 
-    Ok(())
+    // Same shape as an issue
+    let parent: GhIssueView =
+        serde_json::from_slice(&output.stdout).context("Failed to parse gh pr view output")?;
+    Ok(ParentPr {
+        number: parent.number,
+        title: parent.title,
+        body: parent.body.unwrap_or_default(),
+    })
 }
 
-/// Post a comment on the PR
-pub fn post_comment(owner: &str, repo: &str, number: u32, body: &str) -> Result<()> {
+/// Fetch issues referenced from the PR body, skipping any that can't be resolved
+/// (e.g. references that are actually PRs, or repos we can't access)
+fn fetch_linked_issues(owner: &str, repo: &str, pr_number: u32, body: &str) -> Vec<LinkedIssue> {
+    parse_issue_references(body, owner, repo)
+        .into_iter()
+        .filter(|(o, r, n)| !(o == owner && r == repo && *n == pr_number))
+        .take(MAX_LINKED_ISSUES)
+        .filter_map(|(o, r, n)| fetch_issue(&o, &r, n).ok())
+        .collect()
+}
+
+/// Fetch an issue's title and body
+fn fetch_issue(owner: &str, repo: &str, number: u32) -> Result<LinkedIssue> {
     let repo_spec = format!("{}/{}", owner, repo);
 
     let output = Command::new("gh")
         .args([
-            "pr",
-            "comment",
+            "issue",
+            "view",
             &number.to_string(),
             "--repo",
             &repo_spec,
-            "--body",
-            body,
+            "--json",
+            "number,title,body",
         ])
-        .output()
-        .context("Failed to execute gh pr comment")?;
+        .run_with_timeout("gh issue view")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("gh pr comment failed: {}", stderr);
+        anyhow::bail!("gh issue view failed: {}", stderr);
     }
 
-    Ok(())
+    let issue: GhIssueView =
+        serde_json::from_slice(&output.stdout).context("Failed to parse gh issue view output")?;
+
+    Ok(LinkedIssue {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        number: issue.number,
+        title: issue.title,
+        body: issue.body.unwrap_or_default(),
+    })
 }
 
-/// Create an issue and return the issue number
-pub fn create_issue(owner: &str, repo: &str, title: &str, body: &str) -> Result<u32> {
+/// Fetch a PR's commits and its comments and reviews, for spotting activity since it was opened
+pub fn fetch_activity(owner: &str, repo: &str, number: u32) -> Result<PrActivity> {
     let repo_spec = format!("{}/{}", owner, repo);
-
     let output = Command::new("gh")
         .args([
-            "issue",
-            "create",
+            "pr",
+            "view",
+            &number.to_string(),
             "--repo",
             &repo_spec,
-            "--title",
-            title,
-            "--body",
-            body,
+            "--json",
+            "commits,comments,reviews",
         ])
-        .output()
-        .context("Failed to execute gh issue create")?;
+        .run_with_timeout("gh pr view activity")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("gh issue create failed: {}", stderr);
+        anyhow::bail!("gh pr view activity failed: {}", stderr);
     }
 
-    // Parse the issue URL to get the number
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let issue_url = stdout.trim();
-
-    // URL format: https://github.com/owner/repo/issues/123
-    let issue_number = issue_url
-        .rsplit('/')
-        .next()
-        .and_then(|s| s.parse().ok())
-        .context("Failed to parse issue number from URL")?;
+    let activity: GhPrActivity =
+        serde_json::from_slice(&output.stdout).context("Failed to parse gh pr view output")?;
+    let mut comments: Vec<ActivityComment> = activity
+        .comments
+        .into_iter()
+        .chain(activity.reviews)
+        // Approvals without a summary carry no discussion
+        .filter(|comment| !comment.body.trim().is_empty())
+        .map(|comment| ActivityComment {
+            id: comment.id,
+            author: comment.author.login,
+            body: comment.body,
+            created_at: comment.created_at,
+        })
+        .collect();
+    comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
 
-    Ok(issue_number)
+    Ok(PrActivity {
+        commits: activity.commits.into_iter().map(|commit| commit.oid).collect(),
+        comments,
+    })
 }
 
-/// Create issue and post comment linking to it
-pub fn create_next_pr_issue(
-    owner: &str,
-    repo: &str,
-    pr_number: u32,
-    issue_title: &str,
-    issue_body: &str,
-) -> Result<u32> {
-    // Create the issue
-    let issue_number = create_issue(owner, repo, issue_title, issue_body)?;
-
-    // Post a comment on the PR linking to the issue
-    let comment = format!(
-        "Follow-up work tracked in #{}\n\n_Created via [Distillery](https://github.com/rosssaunders/distillery)_",
-        issue_number
-    );
-    post_comment(owner, repo, pr_number, &comment)?;
-
-    Ok(issue_number)
-}
+/// The commit a PR's head branch points at
+pub fn fetch_head_sha(owner: &str, repo: &str, number: u32) -> Result<String> {
+    let repo_spec = format!("{}/{}", owner, repo);
 
-/// Fetch the current GitHub user
-pub fn get_current_user() -> Result<String> {
     let output = Command::new("gh")
-        .args(["api", "user", "--jq", ".login"])
-        .output()
-        .context("Failed to execute gh api user")?;
+        .args([
+            "pr",
+            "view",
+            &number.to_string(),
+            "--repo",
+            &repo_spec,
+            "--json",
+            "headRefOid",
+            "--jq",
+            ".headRefOid",
+        ])
+        .run_with_timeout("gh pr view headRefOid")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("gh api user failed: {}", stderr);
+        anyhow::bail!("gh pr view headRefOid failed: {}", stderr);
     }
 
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
-/// Fetch all open PRs for a repo, sorted by priority:
-/// 1. Review requested from current user (non-draft)
-/// 2. Other open PRs (non-draft)
-/// 3. Draft PRs
-pub fn fetch_pr_list(owner: &str, repo: &str) -> Result<Vec<PrListItem>> {
+/// A PR's commits, oldest first
+pub fn fetch_commits(owner: &str, repo: &str, number: u32) -> Result<Vec<PrCommit>> {
     let repo_spec = format!("{}/{}", owner, repo);
-    let current_user = get_current_user().unwrap_or_default();
 
     let output = Command::new("gh")
         .args([
             "pr",
-            "list",
+            "view",
+            &number.to_string(),
             "--repo",
             &repo_spec,
-            "--limit",
-            "50",
             "--json",
-            "number,title,author,headRefName,isDraft,additions,deletions,reviewRequests,statusCheckRollup",
+            "commits",
         ])
-        .output()
-        .context("Failed to execute gh pr list")?;
+        .run_with_timeout("gh pr view commits")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("gh pr list failed: {}", stderr);
+        anyhow::bail!("gh pr view commits failed: {}", stderr);
     }
 
-    let pr_list: Vec<GhPrListItem> =
-        serde_json::from_slice(&output.stdout).context("Failed to parse gh pr list output")?;
-
-    let mut items: Vec<PrListItem> = pr_list
+    let pr: GhPrCommits =
+        serde_json::from_slice(&output.stdout).context("Failed to parse gh pr view output")?;
+    Ok(pr
+        .commits
         .into_iter()
-        .map(|p| p.into_list_item(&current_user))
-        .collect();
+        .map(|commit| PrCommit {
+            sha: commit.oid,
+            headline: commit.message_headline,
+            author: commit
+                .authors
+                .into_iter()
+                .next()
+                .map(|author| if author.login.is_empty() { author.name } else { author.login })
+                .unwrap_or_default(),
+        })
+        .collect())
+}
 
-    // Sort: review_requested + non-draft first, then non-draft, then drafts
-    items.sort_by(|a, b| {
-        // Priority order: review_requested non-draft > non-draft > draft
-        let priority_a = if a.is_draft {
-            2
-        } else if a.review_requested {
-            0
-        } else {
-            1
-        };
-        let priority_b = if b.is_draft {
-            2
-        } else if b.review_requested {
-            0
-        } else {
-            1
-        };
+/// The diff from `base` to `head`, in the same format as `gh pr diff`
+pub fn fetch_compare_diff(owner: &str, repo: &str, base: &str, head: &str) -> Result<String> {
+    let endpoint = format!("repos/{}/{}/compare/{}...{}", owner, repo, base, head);
 
-        priority_a.cmp(&priority_b).then_with(|| a.number.cmp(&b.number))
-    });
+    let output = Command::new("gh")
+        .args(["api", "-H", "Accept: application/vnd.github.diff", &endpoint])
+        .run_with_timeout("gh api compare")?;
 
-    Ok(items)
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api compare failed: {}", stderr);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-/// Response from `gh repo list --json`
+/// Fetch review comments authored by bots (GitHub Apps, Copilot, `*[bot]` accounts)
+fn fetch_bot_comments(repo_spec: &str, number: u32) -> Result<Vec<BotComment>> {
+    let endpoint = format!("repos/{}/pulls/{}/comments", repo_spec, number);
+
+    let output = Command::new("gh")
+        .args([
+            "api",
+            &endpoint,
+            "--paginate",
+            "--jq",
+            r#".[] | select(.user.type == "Bot" or (.user.login | endswith("[bot]")))"#,
+        ])
+        .run_with_timeout("gh api pulls comments")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api pulls comments failed: {}", stderr);
+    }
+
+    // --jq emits one JSON object per line
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let comment: GhReviewComment =
+                serde_json::from_str(line).context("Failed to parse review comment")?;
+            Ok(BotComment {
+                author: comment.user.login,
+                path: comment.path,
+                line: comment.line.or(comment.original_line),
+                body: comment.body,
+                diff_hunk: comment.diff_hunk,
+            })
+        })
+        .collect()
+}
+
+/// Fetch a file's raw contents at a specific commit
+pub fn fetch_file_at(owner: &str, repo: &str, path: &str, sha: &str) -> Result<String> {
+    let endpoint = format!(
+        "repos/{}/{}/contents/{}?ref={}",
+        owner,
+        repo,
+        path.replace(' ', "%20"),
+        sha
+    );
+
+    let output = Command::new("gh")
+        .args(["api", "-H", "Accept: application/vnd.github.raw", &endpoint])
+        .run_with_timeout("gh api contents")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api contents failed for {}: {}", path, stderr);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Blame at the base commit for the most heavily changed files that existed there
+fn fetch_base_blame(
+    owner: &str,
+    repo: &str,
+    sha: &str,
+    diff: &str,
+) -> HashMap<String, Vec<BlameRange>> {
+    let mut files = parse_diff(diff);
+    files.retain(|file| file.deletions > 0);
+    files.sort_by_key(|file| Reverse(file.additions + file.deletions));
+    files
+        .into_iter()
+        .take(MAX_BLAME_FILES)
+        .filter_map(|file| {
+            fetch_blame(owner, repo, sha, &file.path)
+                .ok()
+                .map(|ranges| (file.path, ranges))
+        })
+        .collect()
+}
+
+/// Callers of the functions the diff modifies or removes, skipping names too short
+/// to search for meaningfully
+fn fetch_callers(repo_spec: &str, diff: &str) -> Vec<SymbolCallers> {
+    changed_symbols(&parse_diff(diff))
+        .into_iter()
+        .filter(|symbol| symbol.kind == "fn" && symbol.change != SymbolChange::Added)
+        .filter(|symbol| symbol.name.len() >= 4)
+        .take(MAX_IMPACT_SYMBOLS)
+        .filter_map(|symbol| search_callers(repo_spec, &symbol.name, &symbol.path).ok())
+        .collect()
+}
+
+/// Code search hits as flattened by the `--jq` filter in `search_callers`
 #[derive(Debug, Deserialize)]
-struct GhRepoListItem {
-    #[serde(rename = "nameWithOwner")]
-    name_with_owner: String,
-    description: Option<String>,
-    #[serde(rename = "isFork")]
-    is_fork: bool,
-    #[serde(rename = "isPrivate")]
-    is_private: bool,
+struct GhCodeSearch {
+    total: u32,
+    paths: Vec<String>,
 }
 
-/// Fetch repositories the user has access to, sorted by most recently pushed
-pub fn fetch_repo_list() -> Result<Vec<RepoListItem>> {
-    // Fetch repos the user owns (gh repo list returns them sorted by push date by default)
+/// Files on the default branch that mention `symbol`, other than where it's defined
+fn search_callers(repo_spec: &str, symbol: &str, path: &str) -> Result<SymbolCallers> {
     let output = Command::new("gh")
         .args([
-            "repo",
-            "list",
-            "--limit",
-            "50",
-            "--json",
-            "nameWithOwner,description,isFork,isPrivate",
+            "api",
+            "-X",
+            "GET",
+            "search/code",
+            "-f",
+            &format!("q={} repo:{}", symbol, repo_spec),
+            "-F",
+            "per_page=20",
+            "--jq",
+            "{total: .total_count, paths: [.items[].path]}",
         ])
-        .output()
-        .context("Failed to execute gh repo list")?;
+        .run_with_timeout("gh api search code")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("gh repo list failed: {}", stderr);
+        anyhow::bail!("gh api search code failed for {}: {}", symbol, stderr);
     }
 
-    let repo_list: Vec<GhRepoListItem> =
-        serde_json::from_slice(&output.stdout).context("Failed to parse gh repo list output")?;
+    let found: GhCodeSearch =
+        serde_json::from_slice(&output.stdout).context("Failed to parse code search results")?;
+    let defined_here = found.paths.iter().any(|p| p == path);
+    Ok(SymbolCallers {
+        symbol: symbol.to_string(),
+        path: path.to_string(),
+        total: found.total.saturating_sub(u32::from(defined_here)),
+        files: found.paths.into_iter().filter(|p| p != path).collect(),
+    })
+}
 
-    let items: Vec<RepoListItem> = repo_list
-        .into_iter()
-        .map(|r| {
-            let (owner, name) = r.name_with_owner.split_once('/').unwrap_or(("", &r.name_with_owner));
-            RepoListItem {
-                owner: owner.to_string(),
-                name: name.to_string(),
-                description: r.description.unwrap_or_default(),
-                is_fork: r.is_fork,
-                is_private: r.is_private,
+/// One blame range as flattened by the `--jq` filter in `fetch_blame`
+#[derive(Debug, Deserialize)]
+struct GhBlameRange {
+    start: u32,
+    end: u32,
+    commit: String,
+    author: Option<String>,
+    date: String,
+    summary: String,
+    pr: Option<u32>,
+}
+
+const BLAME_QUERY: &str = r#"query($owner: String!, $name: String!, $expr: String!, $path: String!) {
+  repository(owner: $owner, name: $name) {
+    object(expression: $expr) {
+      ... on Commit {
+        blame(path: $path) {
+          ranges {
+            startingLine
+            endingLine
+            commit {
+              abbreviatedOid
+              committedDate
+              messageHeadline
+              author { name user { login } }
+              associatedPullRequests(first: 1) { nodes { number } }
             }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+/// Fetch `git blame` for a file at a commit via the GraphQL API
+pub fn fetch_blame(owner: &str, repo: &str, sha: &str, path: &str) -> Result<Vec<BlameRange>> {
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", BLAME_QUERY),
+            "-f",
+            &format!("owner={}", owner),
+            "-f",
+            &format!("name={}", repo),
+            "-f",
+            &format!("expr={}", sha),
+            "-f",
+            &format!("path={}", path),
+            "--jq",
+            r#".data.repository.object.blame.ranges[] | {start: .startingLine, end: .endingLine, commit: .commit.abbreviatedOid, author: (.commit.author.user.login // .commit.author.name), date: .commit.committedDate, summary: .commit.messageHeadline, pr: .commit.associatedPullRequests.nodes[0].number}"#,
+        ])
+        .run_with_timeout("gh api graphql blame")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api graphql blame failed for {}: {}", path, stderr);
+    }
+
+    // --jq emits one JSON object per line
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let range: GhBlameRange =
+                serde_json::from_str(line).context("Failed to parse blame range")?;
+            Ok(BlameRange {
+                start_line: range.start,
+                end_line: range.end,
+                commit: range.commit,
+                author: range.author.unwrap_or_else(|| "unknown".to_string()),
+                date: range.date,
+                summary: range.summary,
+                pr_number: range.pr,
+            })
         })
-        .collect();
+        .collect()
+}
 
-    Ok(items)
+const VIEWED_FILES_QUERY: &str = r#"query($owner: String!, $name: String!, $number: Int!, $first: Int!, $after: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      id
+      files(first: $first, after: $after) {
+        totalCount
+        pageInfo { hasNextPage endCursor }
+        nodes { path viewerViewedState }
+      }
+    }
+  }
+}"#;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GhViewedPr {
+    id: String,
+    files: GhConnection<GhViewedFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhViewedFile {
+    path: String,
+    #[serde(rename = "viewerViewedState")]
+    viewer_viewed_state: String,
+}
+
+/// The PR's node id and the files the user has marked viewed in GitHub's review UI,
+/// a page at a time
+pub fn fetch_viewed_files(owner: &str, repo: &str, number: u32) -> Result<ViewedFiles> {
+    collect_viewed_files(|after| {
+        let mut variables = vec![("owner", owner.to_string()), ("name", repo.to_string())];
+        variables.extend(after.map(|after| ("after", after)));
+        graphql(
+            VIEWED_FILES_QUERY,
+            &variables,
+            &[("number", number)],
+            ".data.repository.pullRequest",
+            "gh api graphql viewed files",
+        )
+    })
+}
+
+/// Follow `fetch_page` from the first page (no cursor) until the last
+pub(crate) fn collect_viewed_files(
+    mut fetch_page: impl FnMut(Option<String>) -> Result<GhViewedPr>,
+) -> Result<ViewedFiles> {
+    let mut viewed = ViewedFiles::default();
+    let mut after: Option<String> = None;
+    loop {
+        let pr = fetch_page(after)?;
+        viewed.pr_id = pr.id;
+        viewed.paths.extend(
+            pr.files
+                .nodes
+                .into_iter()
+                .filter(|file| file.viewer_viewed_state == "VIEWED")
+                .map(|file| file.path),
+        );
+        match pr.files.page_info {
+            GhPageInfo {
+                has_next_page: true,
+                end_cursor: Some(cursor),
+            } => after = Some(cursor),
+            _ => return Ok(viewed),
+        }
+    }
+}
+
+/// Mark or unmark a file as viewed in GitHub's review UI
+pub fn set_file_viewed(pr_id: &str, path: &str, viewed: bool) -> Result<()> {
+    let mutation = if viewed { "markFileAsViewed" } else { "unmarkFileAsViewed" };
+    let query = format!(
+        "mutation($id: ID!, $path: String!) {{ {}(input: {{pullRequestId: $id, path: $path}}) {{ clientMutationId }} }}",
+        mutation
+    );
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", query),
+            "-f",
+            &format!("id={}", pr_id),
+            "-f",
+            &format!("path={}", path),
+        ])
+        .run_with_timeout("gh api graphql set viewed")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api graphql {} failed for {}: {}", mutation, path, stderr);
+    }
+    Ok(())
+}
+
+/// Fetch several files at a commit, skipping any that can't be fetched (deleted or binary)
+pub fn fetch_files_at(
+    owner: &str,
+    repo: &str,
+    sha: &str,
+    paths: impl IntoIterator<Item = String>,
+) -> Vec<(String, String)> {
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            fetch_file_at(owner, repo, &path, sha)
+                .ok()
+                .map(|contents| (path, contents))
+        })
+        .collect()
+}
+
+/// Fetch the most heavily changed files at the head commit and cut windows of
+/// `context_lines` around their hunks
+fn fetch_surrounding_code(
+    owner: &str,
+    repo: &str,
+    sha: &str,
+    diff: &str,
+    context_lines: usize,
+) -> (HashMap<String, String>, Vec<CodeWindow>) {
+    let mut files = parse_diff(diff);
+    files.sort_by_key(|file| Reverse(file.additions + file.deletions));
+    files.truncate(MAX_CONTEXT_FILES);
+
+    let paths = files.iter().map(|file| file.path.clone());
+    let head_files: HashMap<String, String> =
+        fetch_files_at(owner, repo, sha, paths).into_iter().collect();
+    let windows = files
+        .iter()
+        .filter_map(|file| {
+            head_files
+                .get(&file.path)
+                .map(|contents| surrounding_windows(file, contents, context_lines))
+        })
+        .flatten()
+        .collect();
+
+    (head_files, windows)
+}
+
+/// Ask users and teams (`org/team`) to review the PR
+pub fn request_reviewers(owner: &str, repo: &str, number: u32, reviewers: &[String]) -> Result<()> {
+    let repo_spec = format!("{}/{}", owner, repo);
+
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "edit",
+            &number.to_string(),
+            "--repo",
+            &repo_spec,
+            "--add-reviewer",
+            &reviewers.join(","),
+        ])
+        .run_with_timeout("gh pr edit")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr edit failed: {}", stderr);
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct GhRateLimits {
+    resources: GhRateResources,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhRateResources {
+    core: RateLimit,
+    graphql: RateLimit,
+}
+
+/// The tighter of the REST and GraphQL rate limits. Checking costs no requests.
+pub fn fetch_rate_limit() -> Result<RateLimit> {
+    let output = Command::new("gh")
+        .args(["api", "rate_limit"])
+        .run_with_timeout("gh api rate_limit")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api rate_limit failed: {}", stderr);
+    }
+
+    let limits: GhRateLimits =
+        serde_json::from_slice(&output.stdout).context("Failed to parse rate limits")?;
+    Ok(limits.resources.core.tighter(limits.resources.graphql))
+}
+
+/// Post a comment on the PR
+pub fn post_comment(owner: &str, repo: &str, number: u32, body: &str) -> Result<()> {
+    let repo_spec = format!("{}/{}", owner, repo);
+
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "comment",
+            &number.to_string(),
+            "--repo",
+            &repo_spec,
+            "--body",
+            body,
+        ])
+        .run_with_timeout("gh pr comment")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr comment failed: {}", stderr);
+    }
+
+    Ok(())
+}
+
+/// Post a PR comment, or edit the earlier one containing `marker` so re-runs
+/// update it in place. Returns true when an existing comment was edited
+pub fn upsert_marked_comment(
+    owner: &str,
+    repo: &str,
+    number: u32,
+    marker: &str,
+    body: &str,
+) -> Result<bool> {
+    let endpoint = format!("repos/{}/{}/issues/{}/comments", owner, repo, number);
+    let output = Command::new("gh")
+        .args(["api", &endpoint, "--paginate"])
+        .run_with_timeout("gh api issues comments")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api issues comments failed: {}", stderr);
+    }
+
+    // --paginate prints each page's array one after another
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut comments = Vec::new();
+    for page in serde_json::Deserializer::from_str(&stdout).into_iter::<Vec<GhIssueComment>>() {
+        let page = page.context("Failed to parse PR comments")?;
+        comments.extend(page.into_iter().map(|comment| (comment.id, comment.body)));
+    }
+    let existing = export::marked_comment(comments, marker);
+    let (endpoint, method) = match existing {
+        Some(id) => (format!("repos/{}/{}/issues/comments/{}", owner, repo, id), "PATCH"),
+        None => (endpoint, "POST"),
+    };
+
+    let output = Command::new("gh")
+        .args(["api", &endpoint, "--method", method, "-f", &format!("body={}", body)])
+        .run_with_timeout("gh api issues comment")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api issues comment failed: {}", stderr);
+    }
+
+    Ok(existing.is_some())
+}
+
+/// Post a comment-only review with one inline thread per comment
+pub fn post_inline_comments(
+    owner: &str,
+    repo: &str,
+    number: u32,
+    commit_id: &str,
+    comments: &[InlineComment],
+) -> Result<()> {
+    post_review(owner, repo, number, ReviewEvent::Comment, "", commit_id, comments)
+}
+
+/// Post a review in one go: its body, its inline comments and the verdict
+pub fn post_review(
+    owner: &str,
+    repo: &str,
+    number: u32,
+    event: ReviewEvent,
+    body: &str,
+    commit_id: &str,
+    comments: &[InlineComment],
+) -> Result<()> {
+    let endpoint = format!("repos/{}/{}/pulls/{}/reviews", owner, repo, number);
+
+    let mut payload = serde_json::json!({
+        "event": event.api_name(),
+        "comments": comments
+            .iter()
+            .map(|c| serde_json::json!({
+                "path": c.path,
+                "line": c.line,
+                "side": "RIGHT",
+                "body": c.body,
+            }))
+            .collect::<Vec<_>>(),
+    });
+    if !body.is_empty() {
+        payload["body"] = serde_json::Value::from(body);
+    }
+    // Without a commit GitHub anchors to the latest head
+    if !commit_id.is_empty() {
+        payload["commit_id"] = serde_json::Value::from(commit_id);
+    }
+
+    let mut child = Command::new("gh")
+        .args(["api", &endpoint, "--method", "POST", "--input", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute gh api pulls reviews")?;
+
+    child
+        .stdin
+        .take()
+        .context("Failed to open gh stdin")?
+        .write_all(payload.to_string().as_bytes())
+        .context("Failed to write review payload")?;
+
+    let output = wait_with_timeout(child, "gh api pulls reviews")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api pulls reviews failed: {}", stderr);
+    }
+
+    Ok(())
+}
+
+/// Create an issue and return the issue number
+pub fn create_issue(owner: &str, repo: &str, title: &str, body: &str) -> Result<u32> {
+    let repo_spec = format!("{}/{}", owner, repo);
+
+    let output = Command::new("gh")
+        .args([
+            "issue",
+            "create",
+            "--repo",
+            &repo_spec,
+            "--title",
+            title,
+            "--body",
+            body,
+        ])
+        .run_with_timeout("gh issue create")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh issue create failed: {}", stderr);
+    }
+
+    // Parse the issue URL to get the number
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let issue_url = stdout.trim();
+
+    // URL format: https://github.com/owner/repo/issues/123
+    let issue_number = issue_url
+        .rsplit('/')
+        .next()
+        .and_then(|s| s.parse().ok())
+        .context("Failed to parse issue number from URL")?;
+
+    Ok(issue_number)
+}
+
+/// Create issue and post comment linking to it
+pub fn create_next_pr_issue(
+    owner: &str,
+    repo: &str,
+    pr_number: u32,
+    issue_title: &str,
+    issue_body: &str,
+) -> Result<u32> {
+    // Create the issue
+    let issue_number = create_issue(owner, repo, issue_title, issue_body)?;
+
+    // Post a comment on the PR linking to the issue
+    let comment = format!(
+        "Follow-up work tracked in #{}\n\n_Created via [Distillery](https://github.com/rosssaunders/distillery)_",
+        issue_number
+    );
+    post_comment(owner, repo, pr_number, &comment)?;
+
+    Ok(issue_number)
+}
+
+/// Items fetched per page of a paginated list
+const PAGE_SIZE: u32 = 50;
+
+/// A GraphQL connection: one page of nodes and where the next page starts
+#[derive(Debug, Deserialize)]
+struct GhConnection<T> {
+    #[serde(rename = "totalCount")]
+    total_count: u32,
+    #[serde(rename = "pageInfo")]
+    page_info: GhPageInfo,
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhPageInfo {
+    #[serde(rename = "hasNextPage")]
+    has_next_page: bool,
+    #[serde(rename = "endCursor")]
+    end_cursor: Option<String>,
+}
+
+impl<T> GhConnection<T> {
+    fn into_page<U>(self, convert: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.nodes.into_iter().map(convert).collect(),
+            total: self.total_count,
+            next_cursor: self
+                .page_info
+                .end_cursor
+                .filter(|_| self.page_info.has_next_page),
+        }
+    }
+}
+
+/// Run a paginated GraphQL query and extract the connection at `path` (a jq path).
+/// String `variables` are passed as-is; `$first` is always [`PAGE_SIZE`].
+fn graphql_page<T: serde::de::DeserializeOwned>(
+    query: &str,
+    variables: &[(&str, String)],
+    path: &str,
+    label: &str,
+) -> Result<GhConnection<T>> {
+    graphql(query, variables, &[], path, label)
+}
+
+/// Run a GraphQL query asking for `PAGE_SIZE` items, parsing what `path` selects.
+/// `numbers` are sent as integers, `variables` as strings.
+fn graphql<T: serde::de::DeserializeOwned>(
+    query: &str,
+    variables: &[(&str, String)],
+    numbers: &[(&str, u32)],
+    path: &str,
+    label: &str,
+) -> Result<T> {
+    let mut args = vec![
+        "api".to_string(),
+        "graphql".to_string(),
+        "-f".to_string(),
+        format!("query={}", query),
+        "-F".to_string(),
+        format!("first={}", PAGE_SIZE),
+    ];
+    for (name, value) in variables {
+        args.push("-f".to_string());
+        args.push(format!("{}={}", name, value));
+    }
+    for (name, value) in numbers {
+        args.push("-F".to_string());
+        args.push(format!("{}={}", name, value));
+    }
+    args.extend(["--jq".to_string(), path.to_string()]);
+
+    let output = Command::new("gh").args(&args).run_with_timeout(label)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{} failed: {}", label, stderr);
+    }
+    serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Failed to parse {} output", label))
+}
+
+static CURRENT_USER: OnceLock<String> = OnceLock::new();
+
+/// The current GitHub user, looked up once per session (empty if the lookup fails)
+pub fn current_user() -> String {
+    if let Some(user) = CURRENT_USER.get() {
+        return user.clone();
+    }
+    match get_current_user() {
+        Ok(user) => CURRENT_USER.get_or_init(|| user).clone(),
+        Err(err) => {
+            warn!(error = %err, "Could not look up the current user");
+            String::new()
+        }
+    }
+}
+
+/// Fetch the current GitHub user
+fn get_current_user() -> Result<String> {
+    let output = Command::new("gh")
+        .args(["api", "user", "--jq", ".login"])
+        .run_with_timeout("gh api user")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api user failed: {}", stderr);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// First line of `gh --version`; fails when gh isn't on PATH
+pub fn gh_version() -> Result<String> {
+    let output = Command::new("gh")
+        .arg("--version")
+        .run_with_timeout("gh --version")?;
+
+    if !output.status.success() {
+        anyhow::bail!("gh --version exited with {}", output.status);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
+/// Who `gh auth status` says is logged in (e.g. "github.com account octocat")
+pub fn auth_status() -> Result<String> {
+    let output = Command::new("gh")
+        .args(["auth", "status"])
+        .run_with_timeout("gh auth status")?;
+
+    // gh has printed this to stdout or stderr depending on version
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if !output.status.success() {
+        let reason = text.lines().map(str::trim).find(|line| !line.is_empty());
+        anyhow::bail!("{}", reason.unwrap_or("gh auth status failed"));
+    }
+
+    let account = text
+        .lines()
+        .find_map(|line| line.split_once("Logged in to ").map(|(_, rest)| rest))
+        .map(|rest| rest.split(" (").next().unwrap_or(rest).trim().to_string());
+    Ok(account.unwrap_or_else(|| "logged in".to_string()))
+}
+
+/// Run `gh pr list` for a repo, optionally narrowed by a search query
+fn list_prs(repo_spec: &str, search: Option<&str>) -> Result<Vec<GhPrListItem>> {
+    let mut args = vec![
+        "pr",
+        "list",
+        "--repo",
+        repo_spec,
+        "--limit",
+        "50",
+        "--json",
+        "number,title,author,headRefName,baseRefName,isDraft,additions,deletions,reviewRequests,statusCheckRollup,createdAt",
+    ];
+    if let Some(query) = search {
+        args.extend(["--search", query]);
+    }
+
+    let output = Command::new("gh")
+        .args(&args)
+        .run_with_timeout("gh pr list")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr list failed: {}", stderr);
+    }
+
+    serde_json::from_slice(&output.stdout).context("Failed to parse gh pr list output")
+}
+
+const PR_LIST_QUERY: &str = r#"query($owner: String!, $name: String!, $states: [PullRequestState!], $first: Int!, $after: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequests(states: $states, first: $first, after: $after, orderBy: {field: CREATED_AT, direction: DESC}) {
+      totalCount
+      pageInfo { hasNextPage endCursor }
+      nodes {
+        number title author { login } headRefName baseRefName isDraft additions deletions createdAt state
+        updatedAt labels(first: 10) { nodes { name color } } milestone { title } comments { totalCount }
+        reviewRequests(first: 20) {
+          nodes { requestedReviewer { ... on User { login } ... on Team { name } } }
+        }
+        commits(last: 1) {
+          nodes { commit { statusCheckRollup { contexts(first: 100) {
+            nodes { ... on CheckRun { status conclusion } ... on StatusContext { state } }
+          } } } }
+        }
+      }
+    }
+  }
+}"#;
+
+/// A PR node in the PR list query
+#[derive(Debug, Deserialize)]
+struct GhPrNode {
+    number: u32,
+    title: String,
+    /// None when the author's account was deleted
+    author: Option<GhAuthor>,
+    #[serde(rename = "headRefName")]
+    head_ref_name: String,
+    #[serde(rename = "baseRefName")]
+    base_ref_name: String,
+    #[serde(rename = "isDraft")]
+    is_draft: bool,
+    additions: u32,
+    deletions: u32,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    state: String,
+    #[serde(rename = "updatedAt")]
+    updated_at: String,
+    labels: GhNodes<PrLabel>,
+    milestone: Option<GhMilestone>,
+    comments: GhTotal,
+    #[serde(rename = "reviewRequests")]
+    review_requests: GhNodes<GhRequestedReviewer>,
+    commits: GhNodes<GhCommitNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhNodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhTotal {
+    #[serde(rename = "totalCount")]
+    total_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhRequestedReviewer {
+    #[serde(rename = "requestedReviewer")]
+    requested_reviewer: Option<GhReviewRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCommitNode {
+    commit: GhRollupCommit,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhRollupCommit {
+    #[serde(rename = "statusCheckRollup")]
+    status_check_rollup: Option<GhRollup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhRollup {
+    contexts: GhNodes<GhStatusCheck>,
+}
+
+impl GhPrNode {
+    /// The same shape `gh pr list --json` gives, so both share CI and review logic
+    fn into_gh_list_item(self) -> GhPrListItem {
+        let checks = self
+            .commits
+            .nodes
+            .into_iter()
+            .next()
+            .and_then(|node| node.commit.status_check_rollup)
+            .map(|rollup| rollup.contexts.nodes);
+        GhPrListItem {
+            number: self.number,
+            title: self.title,
+            author: self.author.unwrap_or(GhAuthor {
+                login: "ghost".to_string(),
+            }),
+            head_ref_name: self.head_ref_name,
+            base_ref_name: self.base_ref_name,
+            is_draft: self.is_draft,
+            additions: self.additions,
+            deletions: self.deletions,
+            review_requests: self
+                .review_requests
+                .nodes
+                .into_iter()
+                .filter_map(|request| request.requested_reviewer)
+                .collect(),
+            status_check_rollup: checks,
+            created_at: self.created_at,
+            state: self.state,
+            updated_at: self.updated_at,
+            labels: self.labels.nodes,
+            milestone: self.milestone,
+            comment_count: self.comments.total_count,
+        }
+    }
+}
+
+/// Fetch a page of a repo's PRs in `states`, newest first, then sorted by priority:
+/// 1. Review requested from current user (non-draft)
+/// 2. Other open PRs (non-draft)
+/// 3. Draft PRs
+/// 4. Closed and merged PRs
+pub fn fetch_pr_list(
+    owner: &str,
+    repo: &str,
+    states: PrStateFilter,
+    after: Option<&str>,
+) -> Result<Page<PrListItem>> {
+    let current_user = current_user();
+    let mut variables = vec![("owner", owner.to_string()), ("name", repo.to_string())];
+    // gh sends repeated `name[]` fields as a list
+    variables.extend(states.github_states().iter().map(|state| ("states[]", state.to_string())));
+    if let Some(after) = after {
+        variables.push(("after", after.to_string()));
+    }
+    let connection: GhConnection<GhPrNode> = graphql_page(
+        PR_LIST_QUERY,
+        &variables,
+        ".data.repository.pullRequests",
+        "gh api graphql pull requests",
+    )?;
+    let mut page =
+        connection.into_page(|node| node.into_gh_list_item().into_list_item(&current_user));
+    page.items.sort_by_key(|pr| pr.priority());
+    Ok(page)
+}
+
+/// Response from `gh search prs --json repository`
+#[derive(Debug, Deserialize)]
+struct GhSearchPr {
+    repository: GhSearchRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhSearchRepository {
+    #[serde(rename = "nameWithOwner")]
+    name_with_owner: String,
+}
+
+/// Find every repo with an open PR awaiting the current user's review
+fn fetch_review_requested_repos() -> Result<Vec<String>> {
+    let output = Command::new("gh")
+        .args([
+            "search",
+            "prs",
+            "--review-requested=@me",
+            "--state=open",
+            "--limit",
+            "100",
+            "--json",
+            "repository",
+        ])
+        .run_with_timeout("gh search prs")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh search prs failed: {}", stderr);
+    }
+
+    let results: Vec<GhSearchPr> =
+        serde_json::from_slice(&output.stdout).context("Failed to parse gh search prs output")?;
+
+    let mut repos: Vec<String> = results
+        .into_iter()
+        .map(|r| r.repository.name_with_owner)
+        .collect();
+    repos.sort();
+    repos.dedup();
+
+    Ok(repos)
+}
+
+/// Fetch review-requested PRs across repos, sorted by urgency:
+/// 1. Non-draft before draft
+/// 2. CI passing, then pending, then unknown, then failing
+/// 3. Oldest first
+///
+/// Searches all repos when `repos` is empty, otherwise only the listed `owner/repo` specs.
+pub fn fetch_review_queue(repos: &[String]) -> Result<Vec<QueueItem>> {
+    // The user lookup and the search don't depend on each other
+    let (current_user, repos) = thread::scope(|scope| {
+        let user = scope.spawn(current_user);
+        let repos = if repos.is_empty() {
+            fetch_review_requested_repos()
+        } else {
+            Ok(repos.to_vec())
+        };
+        (user.join().unwrap_or_default(), repos)
+    });
+    let repos = repos?;
+    let mut specs = Vec::new();
+    for repo_spec in &repos {
+        let Some((owner, repo)) = repo_spec.split_once('/') else {
+            anyhow::bail!("Invalid queue repo '{}'. Use owner/repo", repo_spec);
+        };
+        specs.push((repo_spec, owner, repo));
+    }
+
+    let lists = in_parallel(&specs, |(repo_spec, _, _)| {
+        list_prs(repo_spec, Some("review-requested:@me"))
+    });
+    let mut items = Vec::new();
+    for ((repo_spec, owner, repo), pr_list) in specs.iter().zip(lists) {
+        let pr_list = pr_list.with_context(|| format!("Failed to list PRs for {}", repo_spec))?;
+        items.extend(pr_list.into_iter().map(|p| QueueItem {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            pr: p.into_list_item(&current_user),
+        }));
+    }
+
+    items.sort_by(|a, b| {
+        let ci_rank = |status: &CiStatus| match status {
+            CiStatus::Success => 0,
+            CiStatus::Pending => 1,
+            CiStatus::Unknown => 2,
+            CiStatus::Failure => 3,
+        };
+        a.pr.is_draft
+            .cmp(&b.pr.is_draft)
+            .then_with(|| ci_rank(&a.pr.ci_status).cmp(&ci_rank(&b.pr.ci_status)))
+            .then_with(|| a.pr.created_at.cmp(&b.pr.created_at))
+    });
+    listings::group_by_repo(&mut items);
+
+    Ok(items)
+}
+
+/// `fetch` run on each of `inputs`, up to [`MAX_PARALLEL_GH`] at a time, with
+/// the results in `inputs`' order
+pub(crate) fn in_parallel<I: Sync, T: Send>(
+    inputs: &[I],
+    fetch: impl Fn(&I) -> Result<T> + Sync,
+) -> Vec<Result<T>> {
+    let mut results = Vec::with_capacity(inputs.len());
+    for batch in inputs.chunks(MAX_PARALLEL_GH) {
+        thread::scope(|scope| {
+            let handles: Vec<_> =
+                batch.iter().map(|input| scope.spawn(|| fetch(input))).collect();
+            results.extend(handles.into_iter().map(|handle| {
+                handle.join().unwrap_or_else(|_| Err(anyhow::anyhow!("gh panicked")))
+            }));
+        });
+    }
+    results
+}
+
+/// A repo node in the repo list query
+#[derive(Debug, Deserialize)]
+struct GhRepoListItem {
+    #[serde(rename = "nameWithOwner")]
+    name_with_owner: String,
+    description: Option<String>,
+    #[serde(rename = "isFork")]
+    is_fork: bool,
+    #[serde(rename = "isPrivate")]
+    is_private: bool,
+}
+
+/// Split `owner/name` into a repo list item
+fn repo_list_item(
+    name_with_owner: &str,
+    description: Option<String>,
+    is_fork: bool,
+    is_private: bool,
+) -> RepoListItem {
+    let (owner, name) = name_with_owner.split_once('/').unwrap_or(("", name_with_owner));
+    RepoListItem {
+        owner: owner.to_string(),
+        name: name.to_string(),
+        description: description.unwrap_or_default(),
+        is_fork,
+        is_private,
+    }
+}
+
+const REPO_FIELDS: &str = "totalCount pageInfo { hasNextPage endCursor } \
+    nodes { nameWithOwner description isFork isPrivate }";
+
+/// Fetch a page of repositories, most recently pushed first.
+///
+/// With an org, lists that org's repositories. Without one, lists repos the
+/// user owns plus those they collaborate on.
+pub fn fetch_repo_list(org: Option<&str>, after: Option<&str>) -> Result<Page<RepoListItem>> {
+    let order = "orderBy: {field: PUSHED_AT, direction: DESC}";
+    let (query, path, mut variables) = match org {
+        Some(org) => (
+            format!(
+                "query($org: String!, $first: Int!, $after: String) {{ organization(login: $org) \
+                 {{ repositories(first: $first, after: $after, {}) {{ {} }} }} }}",
+                order, REPO_FIELDS
+            ),
+            ".data.organization.repositories",
+            vec![("org", org.to_string())],
+        ),
+        None => (
+            format!(
+                "query($first: Int!, $after: String) {{ viewer {{ repositories(first: $first, \
+                 after: $after, affiliations: [OWNER, COLLABORATOR], {}) {{ {} }} }} }}",
+                order, REPO_FIELDS
+            ),
+            ".data.viewer.repositories",
+            Vec::new(),
+        ),
+    };
+    if let Some(after) = after {
+        variables.push(("after", after.to_string()));
+    }
+    let connection: GhConnection<GhRepoListItem> =
+        graphql_page(&query, &variables, path, "gh api graphql repos")?;
+    Ok(connection.into_page(|r| {
+        repo_list_item(&r.name_with_owner, r.description, r.is_fork, r.is_private)
+    }))
+}
+
+/// Fetch the organizations the current user belongs to
+pub fn fetch_org_list() -> Result<Vec<String>> {
+    let output = Command::new("gh")
+        .args(["api", "user/orgs", "--jq", ".[].login"])
+        .run_with_timeout("gh api user/orgs")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh api user/orgs failed: {}", stderr);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Extract issue references from text: `#42`, `owner/repo#42`, and GitHub issue URLs.
+/// Bare `#42` resolves against the given owner/repo. Results are deduplicated in order.
+pub fn parse_issue_references(text: &str, owner: &str, repo: &str) -> Vec<(String, String, u32)> {
+    let mut refs: Vec<(String, String, u32)> = Vec::new();
+
+    for word in text.split(|c: char| c.is_whitespace() || "()[]<>,;\"'`".contains(c)) {
+        let word = word.trim_end_matches(['.', ':', '!', '?']);
+
+        let parsed = if let Some(pos) = word.find("github.com/") {
+            let parts: Vec<&str> = word[pos + "github.com/".len()..].split('/').collect();
+            match parts.as_slice() {
+                [o, r, "issues", n, ..] => n
+                    .parse()
+                    .ok()
+                    .map(|n| (o.to_string(), r.to_string(), n)),
+                _ => None,
+            }
+        } else if let Some((prefix, number)) = word.split_once('#') {
+            match (prefix, number.parse::<u32>()) {
+                ("", Ok(n)) => Some((owner.to_string(), repo.to_string(), n)),
+                (spec, Ok(n)) => spec
+                    .split_once('/')
+                    .filter(|(o, r)| !o.is_empty() && !r.is_empty())
+                    .map(|(o, r)| (o.to_string(), r.to_string(), n)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        if let Some(reference) = parsed
+            && !refs.contains(&reference)
+        {
+            refs.push(reference);
+        }
+    }
+
+    refs
 }
 
 /// Parse a PR URL or owner/repo#number format
@@ -0,0 +1,103 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::storage;
+
+const HISTORY_FILE: &str = "history.json";
+const MAX_RECENT: usize = 20;
+
+/// Recently opened and favorite repos (`owner/repo`) and PRs (`owner/repo#123`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    pub recent_repos: Vec<String>,
+    #[serde(default)]
+    pub recent_prs: Vec<String>,
+    #[serde(default)]
+    pub favorite_repos: Vec<String>,
+    #[serde(default)]
+    pub favorite_prs: Vec<String>,
+}
+
+impl History {
+    /// Load persisted history, starting fresh if none exists
+    pub fn load() -> Self {
+        storage::load_json(HISTORY_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        storage::save_json(HISTORY_FILE, self)
+    }
+
+    /// Record a repo as most recently opened
+    pub fn touch_repo(&mut self, key: &str) {
+        push_recent(&mut self.recent_repos, key);
+    }
+
+    /// Record a PR as most recently opened
+    pub fn touch_pr(&mut self, key: &str) {
+        push_recent(&mut self.recent_prs, key);
+    }
+
+    /// Toggle a repo favorite, returning whether it is now a favorite
+    pub fn toggle_favorite_repo(&mut self, key: &str) -> bool {
+        toggle(&mut self.favorite_repos, key)
+    }
+
+    /// Toggle a PR favorite, returning whether it is now a favorite
+    pub fn toggle_favorite_pr(&mut self, key: &str) -> bool {
+        toggle(&mut self.favorite_prs, key)
+    }
+
+    pub fn is_favorite_repo(&self, key: &str) -> bool {
+        self.favorite_repos.iter().any(|k| k == key)
+    }
+
+    pub fn is_favorite_pr(&self, key: &str) -> bool {
+        self.favorite_prs.iter().any(|k| k == key)
+    }
+
+    pub fn is_recent_repo(&self, key: &str) -> bool {
+        self.recent_repos.iter().any(|k| k == key)
+    }
+
+    pub fn is_recent_pr(&self, key: &str) -> bool {
+        self.recent_prs.iter().any(|k| k == key)
+    }
+
+    /// Sort key for a repo: favorites first, then most recent, then everything else
+    pub fn repo_rank(&self, key: &str) -> (u8, usize) {
+        rank(&self.favorite_repos, &self.recent_repos, key)
+    }
+
+    /// Sort key for a PR: favorites first, then most recent, then everything else
+    pub fn pr_rank(&self, key: &str) -> (u8, usize) {
+        rank(&self.favorite_prs, &self.recent_prs, key)
+    }
+}
+
+fn push_recent(list: &mut Vec<String>, key: &str) {
+    list.retain(|k| k != key);
+    list.insert(0, key.to_string());
+    list.truncate(MAX_RECENT);
+}
+
+fn toggle(list: &mut Vec<String>, key: &str) -> bool {
+    if let Some(pos) = list.iter().position(|k| k == key) {
+        list.remove(pos);
+        false
+    } else {
+        list.push(key.to_string());
+        true
+    }
+}
+
+fn rank(favorites: &[String], recent: &[String], key: &str) -> (u8, usize) {
+    if let Some(pos) = favorites.iter().position(|k| k == key) {
+        (0, pos)
+    } else if let Some(pos) = recent.iter().position(|k| k == key) {
+        (1, pos)
+    } else {
+        (2, 0)
+    }
+}
@@ -0,0 +1,123 @@
+//! API keys in the OS keyring: the macOS Keychain through `security`, and the
+//! Secret Service (GNOME Keyring, KWallet) through `secret-tool` elsewhere.
+//! Windows Credential Manager isn't supported: there the key comes from
+//! `OPENAI_API_KEY` or `api_key` in config.toml.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+/// Service the keys are filed under; the account is the provider
+const SERVICE: &str = "dstl";
+
+/// The key saved for `provider`, or None when there isn't one or no keyring is
+/// reachable (a missing key is reported by preflight, not here)
+pub fn get(provider: &str) -> Option<String> {
+    if cfg!(windows) {
+        return None;
+    }
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["find-generic-password", "-s", SERVICE, "-a", provider, "-w"])
+            .stderr(Stdio::null())
+            .output()
+    } else {
+        Command::new("secret-tool")
+            .args(["lookup", "service", SERVICE, "account", provider])
+            .stderr(Stdio::null())
+            .output()
+    }
+    .ok()?;
+    let key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !key.is_empty()).then_some(key)
+}
+
+/// A command that saves a key. The key only ever goes to its stdin: on the
+/// command line any other user could read it from `ps`.
+#[derive(Debug, PartialEq)]
+pub struct StoreCommand {
+    pub program: &'static str,
+    pub args: Vec<String>,
+    pub stdin: String,
+    /// The keyring's name, for telling the user where the key went
+    pub keyring: &'static str,
+}
+
+/// How to save `key` for `provider` in the macOS Keychain or, when `macos` is
+/// false, the Secret Service
+pub fn store_command(provider: &str, key: &str, macos: bool) -> StoreCommand {
+    if macos {
+        // `security -i` reads its commands from stdin, quoted like a shell's
+        let quote = |word: &str| {
+            format!("\"{}\"", word.replace('\\', "\\\\").replace('"', "\\\""))
+        };
+        return StoreCommand {
+            program: "security",
+            args: vec!["-i".to_string()],
+            stdin: format!(
+                "add-generic-password -U -s {} -a {} -w {}\n",
+                quote(SERVICE),
+                quote(provider),
+                quote(key)
+            ),
+            keyring: "the macOS Keychain",
+        };
+    }
+    // secret-tool reads the secret from stdin when it isn't a terminal
+    StoreCommand {
+        program: "secret-tool",
+        args: [
+            "store",
+            "--label",
+            &format!("dstl {} API key", provider),
+            "service",
+            SERVICE,
+            "account",
+            provider,
+        ]
+        .map(str::to_string)
+        .to_vec(),
+        stdin: key.to_string(),
+        keyring: "the Secret Service keyring",
+    }
+}
+
+/// Save `key` for `provider`, replacing any saved before. Returns the keyring's name.
+pub fn set(provider: &str, key: &str) -> Result<&'static str> {
+    if cfg!(windows) {
+        anyhow::bail!(
+            "Windows Credential Manager isn't supported yet; set OPENAI_API_KEY \
+             or api_key in config.toml"
+        );
+    }
+    let command = store_command(provider, key, cfg!(target_os = "macos"));
+    let mut child = Command::new(command.program)
+        .args(&command.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| match command.program {
+            "secret-tool" => {
+                "secret-tool not found; install libsecret-tools (or your distro's equivalent)"
+                    .to_string()
+            }
+            program => format!("Failed to run {}", program),
+        })?;
+    child
+        .stdin
+        .take()
+        .with_context(|| format!("Failed to open {}'s stdin", command.program))?
+        .write_all(command.stdin.as_bytes())?;
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run {}", command.program))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // `security -i` exits 0 even when the command it read fails, and says so on stderr
+    let quiet_failure = command.program == "security" && !stderr.trim().is_empty();
+    if !output.status.success() || quiet_failure {
+        anyhow::bail!("{} {} failed: {}", command.program, command.args[0], stderr.trim());
+    }
+    Ok(command.keyring)
+}
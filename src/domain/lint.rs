@@ -0,0 +1,97 @@
+//! Checks a generated story for signs the model cut corners, so a weak one can
+//! be regenerated before anyone reviews from it.
+
+use super::types::{ChangedFile, PrStats, Story};
+
+/// Scores below this are flagged in the UI
+pub const LOW_QUALITY_SCORE: u32 = 70;
+/// Summaries shorter than this rarely say anything useful
+const MIN_SUMMARY_CHARS: usize = 30;
+/// How far the story's line counts may drift from the diff before it's flagged
+const LINE_COUNT_TOLERANCE: u32 = 10;
+
+/// A story's quality score out of 100 and what cost it points
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoryLint {
+    pub score: u32,
+    pub findings: Vec<String>,
+}
+
+impl StoryLint {
+    pub fn is_low(&self) -> bool {
+        self.score < LOW_QUALITY_SCORE
+    }
+}
+
+/// Whether the counts a story `claimed` agree with the `actual` diff, give or take
+/// line-count drift; the file count may also include the `excluded` files
+pub fn counts_match(claimed: &PrStats, actual: &PrStats, excluded: usize) -> bool {
+    let files = claimed.files_touched as usize;
+    let actual_files = actual.files_touched as usize;
+    (files == actual_files || files == actual_files + excluded)
+        && claimed.additions.abs_diff(actual.additions) <= LINE_COUNT_TOLERANCE
+        && claimed.deletions.abs_diff(actual.deletions) <= LINE_COUNT_TOLERANCE
+}
+
+/// Lint `story` against the files the model was shown; its file count may also
+/// include the `excluded` ones it was only told about. Without files (a story
+/// restored from the cache) the counts aren't checked.
+pub fn lint_story(story: &Story, files: &[&ChangedFile], excluded: usize) -> StoryLint {
+    let mut penalty = 0;
+    let mut findings = Vec::new();
+
+    if story.narrative.is_empty() {
+        return StoryLint {
+            score: 0,
+            findings: vec!["The story has no features".to_string()],
+        };
+    }
+
+    let summary_chars = story.summary.trim().chars().count();
+    if summary_chars < MIN_SUMMARY_CHARS {
+        penalty += 20;
+        findings.push(format!("The summary is only {} characters", summary_chars));
+    }
+
+    for feature in story.narrative.iter().filter(|feature| feature.diff_blocks.is_empty()) {
+        penalty += 15;
+        findings.push(format!("\"{}\" has no diff blocks", feature.title));
+    }
+
+    let empty_contexts = story
+        .narrative
+        .iter()
+        .flat_map(|feature| &feature.diff_blocks)
+        .filter(|block| block.context.trim().is_empty())
+        .count();
+    if empty_contexts > 0 {
+        penalty += (empty_contexts as u32 * 10).min(30);
+        findings.push(format!(
+            "{} diff block{} no context",
+            empty_contexts,
+            if empty_contexts == 1 { " has" } else { "s have" }
+        ));
+    }
+
+    if !files.is_empty() {
+        let actual = PrStats::from_files(files.iter().copied());
+        let data = &story.data;
+        if !counts_match(data, &actual, excluded) {
+            penalty += 20;
+            findings.push(format!(
+                "The story counts {} files, +{} -{}; the diff has {}, +{} -{}",
+                data.files_touched,
+                data.additions,
+                data.deletions,
+                actual.files_touched,
+                actual.additions,
+                actual.deletions
+            ));
+        }
+    }
+
+    StoryLint {
+        score: 100u32.saturating_sub(penalty),
+        findings,
+    }
+}
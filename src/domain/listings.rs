@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::storage;
+use super::types::{Listing, Page, PrListItem, QueueItem, RepoListItem};
+
+const LISTINGS_FILE: &str = "listings.json";
+
+/// Last successful repo and PR listings, served when gh is unreachable
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ListingCache {
+    /// Keyed by org, or "" for the user's own repos
+    #[serde(default)]
+    repos: HashMap<String, CachedListing<RepoListItem>>,
+    /// Keyed by `owner/repo`
+    #[serde(default)]
+    prs: HashMap<String, CachedListing<PrListItem>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedListing<T> {
+    fetched_at: u64,
+    items: Vec<T>,
+}
+
+/// Seconds since the Unix epoch
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Human-readable age of a Unix timestamp, e.g. "2h ago"
+pub fn format_age(timestamp: u64) -> String {
+    let secs = now_secs().saturating_sub(timestamp);
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// Unix seconds of a UTC timestamp as GitHub returns it, e.g. "2024-05-01T12:00:00Z"
+pub fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let (date, time) = timestamp.trim_end_matches('Z').split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let mut time = time.splitn(3, ':').map(|part| part.get(..2)?.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    // Days since the epoch in the proleptic Gregorian calendar
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+/// A Unix timestamp in the form [`parse_timestamp`] reads, for forges that
+/// give epoch times
+pub fn format_timestamp(secs: u64) -> String {
+    let days = i64::try_from(secs / 86_400).unwrap_or_default() + 719_468;
+    let secs = secs % 86_400;
+    // Civil date from days since the epoch, the inverse of parse_timestamp's
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Group an already prioritized review queue by repo, each repo placed by its
+/// most pressing PR and keeping its PRs' order
+pub fn group_by_repo(items: &mut [QueueItem]) {
+    let mut repo_order: Vec<String> = Vec::new();
+    for item in items.iter() {
+        let label = item.repo_label();
+        if !repo_order.contains(&label) {
+            repo_order.push(label);
+        }
+    }
+    items.sort_by_key(|item| repo_order.iter().position(|label| *label == item.repo_label()));
+}
+
+/// Run a gh call, retrying transient failures with a short linear backoff
+pub fn with_retries<T>(retries: u32, mut call: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 0;
+    loop {
+        match call() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < retries => {
+                attempt += 1;
+                warn!(attempt, error = %err, "Retrying gh listing call");
+                std::thread::sleep(Duration::from_millis(500 * u64::from(attempt)));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Fetch a repo listing's first page, caching it on success and falling back to the
+/// cache on failure
+pub fn repos_with_fallback(
+    scope: &str,
+    fetch: Result<Page<RepoListItem>>,
+) -> Result<Listing<RepoListItem>> {
+    with_fallback(fetch, |cache| &mut cache.repos, scope)
+}
+
+/// Fetch a PR listing's first page, caching it on success and falling back to the
+/// cache on failure
+pub fn prs_with_fallback(
+    repo_spec: &str,
+    fetch: Result<Page<PrListItem>>,
+) -> Result<Listing<PrListItem>> {
+    with_fallback(fetch, |cache| &mut cache.prs, repo_spec)
+}
+
+fn with_fallback<T: Clone>(
+    fetch: Result<Page<T>>,
+    entries: impl Fn(&mut ListingCache) -> &mut HashMap<String, CachedListing<T>>,
+    key: &str,
+) -> Result<Listing<T>> {
+    let mut cache: ListingCache = storage::load_json(LISTINGS_FILE).unwrap_or_default();
+
+    match fetch {
+        Ok(page) => {
+            entries(&mut cache).insert(
+                key.to_string(),
+                CachedListing {
+                    fetched_at: now_secs(),
+                    items: page.items.clone(),
+                },
+            );
+            // The cache is a best-effort fallback; a write failure shouldn't fail the fetch
+            if let Err(err) = storage::save_json(LISTINGS_FILE, &cache) {
+                warn!(error = %err, "Could not save listing cache");
+            }
+            Ok(Listing {
+                items: page.items,
+                cached_at: None,
+                total: Some(page.total),
+                next_cursor: page.next_cursor,
+            })
+        }
+        Err(err) => match entries(&mut cache).remove(key) {
+            Some(cached) => {
+                warn!(listing = key, error = %err, "Listing fetch failed; serving cached copy");
+                Ok(Listing {
+                    items: cached.items,
+                    cached_at: Some(cached.fetched_at),
+                    total: None,
+                    next_cursor: None,
+                })
+            }
+            None => Err(err),
+        },
+    }
+}
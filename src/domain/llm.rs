@@ -1,14 +1,77 @@
+use std::time::{Duration, Instant};
+
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::listings::now_secs;
+use super::privacy::{self, Redaction};
+use super::providers::Provider;
+use super::types::{DeepDive, ModelParams, PrContext, ProgressEvent, ReasoningEffort, Story};
 
-use super::prompt::{build_system_prompt, build_user_prompt};
-use super::types::{PrContext, Story};
+/// Who serves the models, for the header
+pub const PROVIDER: &str = "openai";
 
 #[derive(Debug, Serialize)]
 struct OpenAiRequest {
     model: String,
     input: Vec<Message>,
-    text: TextFormat,
+    /// None for plain text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<TextFormat>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning: Option<Reasoning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+}
+
+impl OpenAiRequest {
+    /// A request for `model` with whichever of `params` it accepts
+    fn new(
+        model: String,
+        (system_prompt, user_prompt): (String, String),
+        text: Option<TextFormat>,
+        stream: bool,
+        params: &ModelParams,
+    ) -> Self {
+        let reasoning_model = is_reasoning_model(&model);
+        OpenAiRequest {
+            input: vec![
+                Message {
+                    role: "system".to_string(),
+                    content: system_prompt,
+                },
+                Message {
+                    role: "user".to_string(),
+                    content: user_prompt,
+                },
+            ],
+            text,
+            stream,
+            reasoning: params
+                .reasoning_effort
+                .filter(|_| reasoning_model)
+                .map(|effort| Reasoning { effort }),
+            max_output_tokens: params.max_output_tokens,
+            temperature: params.temperature.filter(|_| !reasoning_model),
+            model,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Reasoning {
+    effort: ReasoningEffort,
+}
+
+/// Whether `model` reasons before answering: those take an effort and reject a
+/// temperature, and the rest the other way round
+pub fn is_reasoning_model(model: &str) -> bool {
+    ["gpt-5", "o1", "o3", "o4"].iter().any(|prefix| model.starts_with(prefix))
+        && !model.contains("-chat")
 }
 
 #[derive(Debug, Serialize)]
@@ -17,12 +80,12 @@ struct Message {
     content: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct TextFormat {
     format: JsonSchemaFormat,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct JsonSchemaFormat {
     #[serde(rename = "type")]
     format_type: String,
@@ -31,30 +94,71 @@ struct JsonSchemaFormat {
     strict: bool,
 }
 
+/// Server-sent event from a streamed Responses API call
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum StreamEvent {
+    #[serde(rename = "response.output_text.delta")]
+    OutputTextDelta { delta: String },
+    #[serde(rename = "response.refusal.delta")]
+    RefusalDelta { delta: String },
+    #[serde(rename = "response.completed")]
+    Completed { response: CompletedResponse },
+    #[serde(rename = "response.failed")]
+    Failed { response: FailedResponse },
+    #[serde(rename = "error")]
+    Error { message: String },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletedResponse {
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct FailedResponse {
+    error: Option<ApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+/// A plain-text Responses API reply, keeping only the text
 #[derive(Debug, Deserialize)]
-struct OpenAiResponse {
+struct TextResponse {
     output: Vec<OutputItem>,
 }
 
 #[derive(Debug, Deserialize)]
 struct OutputItem {
-    content: Vec<ContentItem>,
+    #[serde(default)]
+    content: Vec<OutputText>,
 }
 
 #[derive(Debug, Deserialize)]
-#[serde(tag = "type")]
-enum ContentItem {
-    #[serde(rename = "output_text")]
-    OutputText { text: String },
-    #[serde(rename = "refusal")]
-    Refusal { refusal: String },
+struct OutputText {
+    #[serde(default)]
+    text: String,
 }
 
+/// Streamed deltas between token-count progress updates
+const PROGRESS_EVERY_DELTAS: usize = 20;
+
 fn build_json_schema() -> serde_json::Value {
     serde_json::json!({
         "type": "object",
         "additionalProperties": false,
-        "required": ["summary", "focus", "narrative", "data", "open_questions", "suggested_changes", "clarification_questions", "next_pr"],
+        "required": ["summary", "focus", "narrative", "data", "open_questions", "coverage_gaps", "whats_new", "breaking_changes", "suggested_changes", "clarification_questions", "next_pr"],
         "properties": {
             "summary": { "type": "string" },
             "focus": {
@@ -77,14 +181,29 @@ fn build_json_schema() -> serde_json::Value {
                         "title": { "type": "string" },
                         "why": { "type": "string" },
                         "changes": { "type": "array", "items": { "type": "string" } },
-                        "risks": { "type": "array", "items": { "type": "string" } },
+                        "risks": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "additionalProperties": false,
+                                "required": ["text", "severity", "addressed_by"],
+                                "properties": {
+                                    "text": { "type": "string" },
+                                    "severity": {
+                                        "type": "string",
+                                        "enum": ["high", "medium", "low"]
+                                    },
+                                    "addressed_by": { "type": "array", "items": { "type": "string" } }
+                                }
+                            }
+                        },
                         "tests": { "type": "array", "items": { "type": "string" } },
                         "diff_blocks": {
                             "type": "array",
                             "items": {
                                 "type": "object",
                                 "additionalProperties": false,
-                                "required": ["label", "role", "significance", "context", "hunks"],
+                                "required": ["label", "role", "significance", "context", "hunks", "related_blocks"],
                                 "properties": {
                                     "label": { "type": "string" },
                                     "role": {
@@ -107,6 +226,18 @@ fn build_json_schema() -> serde_json::Value {
                                                 "lines": { "type": "string" }
                                             }
                                         }
+                                    },
+                                    "related_blocks": {
+                                        "type": "array",
+                                        "items": {
+                                            "type": "object",
+                                            "additionalProperties": false,
+                                            "required": ["block", "note"],
+                                            "properties": {
+                                                "block": { "type": "string" },
+                                                "note": { "type": "string" }
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -125,6 +256,35 @@ fn build_json_schema() -> serde_json::Value {
                 }
             },
             "open_questions": { "type": "array", "items": { "type": "string" } },
+            "coverage_gaps": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["target", "reason"],
+                    "properties": {
+                        "target": { "type": "string" },
+                        "reason": { "type": "string" }
+                    }
+                }
+            },
+            "whats_new": { "type": "array", "items": { "type": "string" } },
+            "breaking_changes": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["item", "description", "severity"],
+                    "properties": {
+                        "item": { "type": "string" },
+                        "description": { "type": "string" },
+                        "severity": {
+                            "type": "string",
+                            "enum": ["high", "medium", "low"]
+                        }
+                    }
+                }
+            },
             "suggested_changes": { "type": "string" },
             "clarification_questions": { "type": "string" },
             "next_pr": { "type": "string" }
@@ -132,65 +292,361 @@ fn build_json_schema() -> serde_json::Value {
     })
 }
 
-pub async fn generate_story(pr: &PrContext, api_key: &str, model: &str) -> Result<Story> {
-    let client = reqwest::Client::new();
+/// A provider turned the request away (key, quota or rate limit) or couldn't be
+/// reached, so the next one in the chain is worth trying
+#[derive(Debug)]
+pub struct ProviderUnavailable(pub String);
 
-    let request = OpenAiRequest {
-        model: model.to_string(),
-        input: vec![
-            Message {
-                role: "system".to_string(),
-                content: build_system_prompt(),
-            },
-            Message {
-                role: "user".to_string(),
-                content: build_user_prompt(pr),
-            },
-        ],
-        text: TextFormat {
-            format: JsonSchemaFormat {
-                format_type: "json_schema".to_string(),
-                name: "distillery_review".to_string(),
-                schema: build_json_schema(),
-                strict: true,
-            },
-        },
-    };
+impl std::fmt::Display for ProviderUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ProviderUnavailable {}
+
+/// Map a request to `provider` failing to an error, calling out stalls so the user
+/// knows what to change
+fn request_error(
+    err: reqwest::Error,
+    stall_timeout: Duration,
+    provider: &Provider,
+    what: &str,
+) -> anyhow::Error {
+    if err.is_timeout() {
+        anyhow::anyhow!(
+            "{} request stalled for {}s. Check your connection or raise --llm-timeout, \
+             then press r to retry",
+            provider.name,
+            stall_timeout.as_secs()
+        )
+    } else {
+        anyhow::Error::new(err).context(format!("{} {}", what, provider.name))
+    }
+}
 
+/// Look up a model with the given key. Costs no tokens; the status tells whether
+/// the key was accepted (401 if not) and the model is available to it (404 if not).
+pub async fn probe_model(api_key: &str, model: &str, timeout: Duration) -> Result<reqwest::StatusCode> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .context("Failed to build HTTP client")?;
     let response = client
-        .post("https://api.openai.com/v1/responses")
+        .get(format!("https://api.openai.com/v1/models/{}", model))
         .header("Authorization", format!("Bearer {}", api_key))
+        .send()
+        .await
+        .context("Could not reach OpenAI")?;
+    Ok(response.status())
+}
+
+/// Audit and send a Responses API request, failing on an error status
+async fn send(
+    client: &reqwest::Client,
+    provider: &Provider,
+    request: &OpenAiRequest,
+    pr_label: &str,
+    redaction: &Redaction,
+    stall_timeout: Duration,
+) -> Result<reqwest::Response> {
+    let payload = serde_json::to_vec(request).context("Failed to serialize the request")?;
+    if let Err(err) = privacy::audit(pr_label, &request.model, &payload, now_secs()) {
+        // A redaction policy is only as good as its record of what went out
+        if !redaction.is_empty() {
+            return Err(err.context("Not sending: couldn't record the request in the audit log"));
+        }
+        warn!(error = %err, "Failed to record the request in the audit log");
+    }
+    let response = client
+        .post(format!("{}/responses", provider.base_url))
+        .header("Authorization", format!("Bearer {}", provider.api_key))
         .header("Content-Type", "application/json")
-        .json(&request)
+        .body(payload)
         .send()
         .await
-        .context("Failed to send request to OpenAI")?;
+        .map_err(|e| {
+            if e.is_connect() {
+                let message = format!("Could not reach {}: {}", provider.name, e);
+                return ProviderUnavailable(message).into();
+            }
+            request_error(e, stall_timeout, provider, "Failed to send request to")
+        })?;
 
-    if !response.status().is_success() {
-        let status = response.status();
+    let status = response.status();
+    if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("OpenAI API error ({}): {}", status, body);
+        let model = &request.model;
+        warn!(model, provider = provider.name, %status, %body, "Provider returned an error status");
+        let message = format!("{} API error ({}): {}", provider.name, status, body);
+        if matches!(status.as_u16(), 401 | 403 | 429) {
+            return Err(ProviderUnavailable(message).into());
+        }
+        anyhow::bail!(message);
     }
+    Ok(response)
+}
 
-    let api_response: OpenAiResponse = response
-        .json()
-        .await
-        .context("Failed to parse OpenAI response")?;
-
-    let content = api_response
-        .output
-        .first()
-        .and_then(|o| o.content.first())
-        .context("No content in OpenAI response")?;
-
-    let text = match content {
-        ContentItem::OutputText { text } => text,
-        ContentItem::Refusal { refusal } => {
-            anyhow::bail!("Model refused request: {}", refusal);
+/// Summarize one file of a large PR in plain text
+pub async fn summarize_file(
+    pr_label: &str,
+    providers: &[Provider],
+    model: &str,
+    stall_timeout: Duration,
+    prompts: (String, String),
+    redaction: &Redaction,
+) -> Result<String> {
+    let params = ModelParams::default();
+    complete(pr_label, providers, model, stall_timeout, prompts, redaction, &params, None).await
+}
+
+/// A deeper look at one feature of a story
+pub async fn generate_deep_dive(
+    pr_label: &str,
+    providers: &[Provider],
+    model: &str,
+    stall_timeout: Duration,
+    prompts: (String, String),
+    redaction: &Redaction,
+    params: &ModelParams,
+) -> Result<DeepDive> {
+    let format = TextFormat {
+        format: JsonSchemaFormat {
+            format_type: "json_schema".to_string(),
+            name: "distillery_deep_dive".to_string(),
+            schema: build_deep_dive_schema(),
+            strict: true,
+        },
+    };
+    let text = complete(
+        pr_label,
+        providers,
+        model,
+        stall_timeout,
+        prompts,
+        redaction,
+        params,
+        Some(format),
+    )
+    .await?;
+    serde_json::from_str(&text).context("Failed to parse deep dive JSON")
+}
+
+fn build_deep_dive_schema() -> serde_json::Value {
+    let list = serde_json::json!({ "type": "array", "items": { "type": "string" } });
+    serde_json::json!({
+        "type": "object",
+        "additionalProperties": false,
+        "required": ["analysis", "invariants", "edge_cases", "suggested_tests"],
+        "properties": {
+            "analysis": { "type": "string" },
+            "invariants": list,
+            "edge_cases": list,
+            "suggested_tests": list
+        }
+    })
+}
+
+/// One unstreamed request's output text, from the first provider in `providers`
+/// that accepts it
+#[allow(clippy::too_many_arguments)]
+async fn complete(
+    pr_label: &str,
+    providers: &[Provider],
+    model: &str,
+    stall_timeout: Duration,
+    prompts: (String, String),
+    redaction: &Redaction,
+    params: &ModelParams,
+    text: Option<TextFormat>,
+) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(stall_timeout)
+        .read_timeout(stall_timeout)
+        .build()
+        .context("Failed to build HTTP client")?;
+    let mut providers = providers.iter().peekable();
+    while let Some(provider) = providers.next() {
+        let model = provider.model_for(model);
+        let request = OpenAiRequest::new(model, prompts.clone(), text.clone(), false, params);
+        let response =
+            match send(&client, provider, &request, pr_label, redaction, stall_timeout).await {
+                Err(err) if providers.peek().is_some() && err.is::<ProviderUnavailable>() => {
+                    warn!(provider = provider.name, error = %err, "Trying the next provider");
+                    continue;
+                }
+                result => result?,
+            };
+        let reply: TextResponse = response.json().await.map_err(|e| {
+            request_error(e, stall_timeout, provider, "Failed to read the response from")
+        })?;
+        let output: String = reply
+            .output
+            .iter()
+            .flat_map(|item| &item.content)
+            .map(|content| content.text.as_str())
+            .collect();
+        if output.trim().is_empty() {
+            anyhow::bail!("No content in the {} response", provider.name);
+        }
+        return Ok(output);
+    }
+    anyhow::bail!("No model providers configured")
+}
+
+/// Generate a story with the first provider in `providers` that accepts the
+/// request, announcing each one tried
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_with_fallback(
+    pr: &PrContext,
+    providers: &[Provider],
+    model: &str,
+    stall_timeout: Duration,
+    prompts: (String, String),
+    redaction: &Redaction,
+    params: &ModelParams,
+    on_progress: impl Fn(ProgressEvent),
+) -> Result<Story> {
+    let mut providers = providers.iter().peekable();
+    while let Some(provider) = providers.next() {
+        let model = provider.model_for(model);
+        on_progress(ProgressEvent::UsingProvider {
+            provider: provider.name.clone(),
+            model: model.clone(),
+        });
+        let generation = generate_story(
+            pr,
+            provider,
+            &model,
+            stall_timeout,
+            prompts.clone(),
+            redaction,
+            params,
+            &on_progress,
+        );
+        match generation.await {
+            Err(err) if providers.peek().is_some() && err.is::<ProviderUnavailable>() => {
+                warn!(provider = provider.name, error = %err, "Trying the next provider");
+            }
+            result => return result,
         }
+    }
+    anyhow::bail!("No model providers configured")
+}
+
+/// Generate a story from the (system, user) prompts `redaction` produced for `pr`,
+/// streaming the response. `stall_timeout` bounds connecting and each wait for
+/// data, so a dead connection fails instead of hanging.
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_story(
+    pr: &PrContext,
+    provider: &Provider,
+    model: &str,
+    stall_timeout: Duration,
+    (system_prompt, user_prompt): (String, String),
+    redaction: &Redaction,
+    params: &ModelParams,
+    on_progress: impl Fn(ProgressEvent),
+) -> Result<Story> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(stall_timeout)
+        .read_timeout(stall_timeout)
+        .build()
+        .context("Failed to build HTTP client")?;
+
+    // Rough estimate: ~4 characters per token
+    let prompt_tokens = (system_prompt.len() + user_prompt.len()) / 4;
+    on_progress(ProgressEvent::PromptBuilt {
+        tokens: prompt_tokens,
+    });
+
+    let format = TextFormat {
+        format: JsonSchemaFormat {
+            format_type: "json_schema".to_string(),
+            name: "distillery_review".to_string(),
+            schema: build_json_schema(),
+            strict: true,
+        },
     };
+    let prompts = (system_prompt, user_prompt);
+    let request = OpenAiRequest::new(model.to_string(), prompts, Some(format), true, params);
+
+    on_progress(ProgressEvent::AwaitingModel {
+        model: model.to_string(),
+    });
+    info!(model, prompt_tokens, "Requesting story");
+    let started = Instant::now();
+    let mut response =
+        send(&client, provider, &request, &pr.pr_label(), redaction, stall_timeout).await?;
+
+    // Read the event stream line by line; chunks can split lines (and UTF-8 sequences)
+    let mut pending: Vec<u8> = Vec::new();
+    let mut text = String::new();
+    let mut refusal = String::new();
+    let mut deltas = 0;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| {
+            request_error(e, stall_timeout, provider, "Failed to read the response stream from")
+        })?
+    {
+        pending.extend_from_slice(&chunk);
+        while let Some(newline) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim_end().strip_prefix("data: ") else {
+                continue;
+            };
+            let Ok(event) = serde_json::from_str::<StreamEvent>(data) else {
+                continue;
+            };
+            match event {
+                StreamEvent::OutputTextDelta { delta } => {
+                    text.push_str(&delta);
+                    deltas += 1;
+                    if deltas % PROGRESS_EVERY_DELTAS == 0 {
+                        on_progress(ProgressEvent::ReceivedTokens { tokens: deltas });
+                    }
+                }
+                StreamEvent::RefusalDelta { delta } => refusal.push_str(&delta),
+                StreamEvent::Completed { response } => {
+                    if let Some(usage) = response.usage {
+                        on_progress(ProgressEvent::TokensUsed {
+                            input: usage.input_tokens,
+                            output: usage.output_tokens,
+                        });
+                    }
+                }
+                StreamEvent::Failed { response } => {
+                    let message = response
+                        .error
+                        .map_or_else(|| "unknown error".to_string(), |e| e.message);
+                    anyhow::bail!("{} response failed: {}", provider.name, message);
+                }
+                StreamEvent::Error { message } => {
+                    anyhow::bail!("{} stream error: {}", provider.name, message);
+                }
+                StreamEvent::Other => {}
+            }
+        }
+    }
+    on_progress(ProgressEvent::ReceivedTokens { tokens: deltas });
+    info!(
+        model,
+        tokens = deltas,
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Story received"
+    );
+
+    if !refusal.is_empty() {
+        anyhow::bail!("Model refused request: {}", refusal);
+    }
+    if text.is_empty() {
+        anyhow::bail!("No content in the {} response", provider.name);
+    }
 
-    let story: Story = serde_json::from_str(text).context("Failed to parse story JSON")?;
+    let story: Story = serde_json::from_str(&text).context("Failed to parse story JSON")?;
 
     Ok(story)
 }
@@ -0,0 +1,76 @@
+//! Model responses keyed by a hash of exactly what was asked: the model and the
+//! system and user prompts. Regenerating an unchanged PR with the same model
+//! reuses the answer instead of calling the API again. Kept apart from the story
+//! cache, under the data directory.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::storage::data_dir;
+use super::types::ModelParams;
+
+const DIR: &str = "llm-cache";
+
+/// Responses kept before the least recently written are dropped
+const MAX_ENTRIES: usize = 200;
+
+/// The cache key for a request. Default parameters leave the key as it was
+/// before they could be set, so earlier answers still count
+pub fn key(model: &str, params: &ModelParams, system_prompt: &str, user_prompt: &str) -> String {
+    let params = (!params.is_default()).then(|| serde_json::to_string(params).unwrap_or_default());
+    let mut parts = vec![model, system_prompt, user_prompt];
+    parts.extend(params.as_deref());
+    let mut hasher = Sha256::new();
+    // Length-prefixed, so moving text between the parts changes the key
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(DIR))
+}
+
+/// What a previous identical request returned, if it's still cached
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let contents = std::fs::read_to_string(dir()?.join(format!("{}.json", key))).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Keep the response for `key`, dropping the oldest over the limit
+pub fn save<T: Serialize>(key: &str, response: &T) -> Result<()> {
+    let dir = dir().context("Could not determine data directory (HOME not set)")?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("{}.json", key));
+    let json = serde_json::to_string(response).context("Failed to serialize the response")?;
+    std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let mut entries: Vec<_> = std::fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            Some((entry.metadata().ok()?.modified().ok()?, entry.path()))
+        })
+        .collect();
+    if entries.len() > MAX_ENTRIES {
+        entries.sort();
+        for (_, stale) in &entries[..entries.len() - MAX_ENTRIES] {
+            let _ = std::fs::remove_file(stale);
+        }
+    }
+    Ok(())
+}
+
+/// Delete every cached response, returning the directory if there was one
+pub fn clear() -> Result<Option<PathBuf>> {
+    let Some(dir) = dir().filter(|dir| dir.exists()) else {
+        return Ok(None);
+    };
+    std::fs::remove_dir_all(&dir).with_context(|| format!("Failed to delete {}", dir.display()))?;
+    Ok(Some(dir))
+}
@@ -0,0 +1,186 @@
+//! Tracing setup: events go to a daily log file under the data directory, and
+//! warnings and errors are also kept in memory for the `~` overlay.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use super::storage::data_dir;
+
+/// Environment variable holding an `EnvFilter` directive for the log file
+pub const LOG_FILTER_ENV: &str = "DSTL_LOG";
+
+const DEFAULT_FILTER: &str = "dstl=info";
+
+/// Days of log files kept before the oldest is deleted
+const KEEP_LOG_FILES: usize = 7;
+
+/// Warnings and errors kept for the overlay
+const RECENT_CAPACITY: usize = 200;
+
+/// Directory for log files: `<data dir>/logs`
+pub fn log_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("logs"))
+}
+
+/// A warning or error captured for the overlay
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// UTC wall-clock time, `HH:MM:SS`
+    pub time: String,
+    pub level: Level,
+    /// Module the event came from, e.g. `dstl::domain::github`
+    pub target: String,
+    /// The message followed by any other fields as `name=value`
+    pub message: String,
+}
+
+/// Shared ring buffer of recent warnings and errors
+#[derive(Clone, Default)]
+pub struct RecentLog {
+    entries: Arc<Mutex<VecDeque<LogEntry>>>,
+    /// Where the full log is written, if file logging is on
+    pub dir: Option<PathBuf>,
+}
+
+impl fmt::Debug for RecentLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecentLog")
+            .field("entries", &self.entries().len())
+            .field("dir", &self.dir)
+            .finish()
+    }
+}
+
+impl RecentLog {
+    pub fn push(&self, entry: LogEntry) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() == RECENT_CAPACITY {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+    }
+
+    /// Captured entries, oldest first
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Keeps the log file writer flushing; drop it only on exit
+pub struct Logging {
+    pub recent: RecentLog,
+    _guard: Option<WorkerGuard>,
+}
+
+/// Install the global subscriber. The file gets everything `DSTL_LOG` allows
+/// (default `dstl=info`); the overlay gets warnings and errors regardless.
+/// Without a usable data directory only the overlay is fed.
+pub fn init() -> Logging {
+    let dir = log_dir().filter(|dir| std::fs::create_dir_all(dir).is_ok());
+    let appender = dir.as_ref().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("dstl")
+            .filename_suffix("log")
+            .max_log_files(KEEP_LOG_FILES)
+            .build(dir)
+            .ok()
+    });
+    let recent = RecentLog {
+        dir: appender.as_ref().and(dir),
+        ..RecentLog::default()
+    };
+
+    let (file_layer, guard) = match appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let filter = EnvFilter::try_from_env(LOG_FILTER_ENV)
+                .unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_filter(filter);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let _ = tracing_subscriber::registry()
+        .with(file_layer)
+        .with(RecentLayer(recent.clone()))
+        .try_init();
+
+    Logging {
+        recent,
+        _guard: guard,
+    }
+}
+
+/// Copies warnings and errors into a [`RecentLog`]
+struct RecentLayer(RecentLog);
+
+impl<S: Subscriber> Layer<S> for RecentLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // More verbose levels compare greater
+        if *metadata.level() > Level::WARN {
+            return;
+        }
+
+        let mut fields = FieldText::default();
+        event.record(&mut fields);
+        self.0.push(LogEntry {
+            time: clock_time(SystemTime::now()),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: fields.text.trim_end().to_string(),
+        });
+    }
+}
+
+/// An event's fields flattened to one line, message first
+#[derive(Default)]
+struct FieldText {
+    text: String,
+}
+
+impl FieldText {
+    fn append(&mut self, field: &Field, value: &dyn fmt::Display) {
+        if field.name() == "message" {
+            self.text.insert_str(0, &format!("{} ", value));
+        } else {
+            self.text.push_str(&format!("{}={} ", field.name(), value));
+        }
+    }
+}
+
+impl Visit for FieldText {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.append(field, &value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.append(field, &format_args!("{:?}", value));
+    }
+}
+
+/// `HH:MM:SS` in UTC, matching the timestamps in the log file
+fn clock_time(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
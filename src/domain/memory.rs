@@ -0,0 +1,96 @@
+//! What the reviewer has asked for before, per repo, fed back into the prompt
+//! so the story flags the things they usually care about.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::review::split_list_items;
+use super::storage;
+use super::types::CustomPrompt;
+
+const MEMORY_FILE: &str = "memory.json";
+/// Suggestions kept per repo; the oldest are dropped first
+const MAX_SUGGESTIONS: usize = 30;
+/// Most recent suggestions included in the prompt
+const PROMPT_SUGGESTIONS: usize = 10;
+/// Longest suggestion included in the prompt
+const MAX_SUGGESTION_CHARS: usize = 200;
+
+/// Past review feedback, keyed by `owner/repo`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewMemory {
+    #[serde(default)]
+    pub repos: BTreeMap<String, RepoMemory>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepoMemory {
+    /// Recurring themes, written by the reviewer
+    #[serde(default)]
+    pub themes: String,
+    /// Changes requested in past reviews, oldest first
+    #[serde(default)]
+    pub suggestions: Vec<String>,
+}
+
+impl RepoMemory {
+    pub fn is_empty(&self) -> bool {
+        self.themes.trim().is_empty() && self.suggestions.is_empty()
+    }
+}
+
+impl ReviewMemory {
+    /// Load persisted memory, starting fresh if none exists
+    pub fn load() -> Self {
+        storage::load_json(MEMORY_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        storage::save_json(MEMORY_FILE, self)
+    }
+
+    pub fn repo(&self, repo: &str) -> Option<&RepoMemory> {
+        self.repos.get(repo)
+    }
+
+    pub fn repo_mut(&mut self, repo: &str) -> &mut RepoMemory {
+        self.repos.entry(repo.to_string()).or_default()
+    }
+
+    /// Remember each item of a submitted Request Changes body, skipping repeats
+    pub fn record(&mut self, repo: &str, body: &str) {
+        let memory = self.repo_mut(repo);
+        for item in split_list_items(body) {
+            memory.suggestions.retain(|existing| *existing != item);
+            memory.suggestions.push(item);
+        }
+        let excess = memory.suggestions.len().saturating_sub(MAX_SUGGESTIONS);
+        memory.suggestions.drain(..excess);
+    }
+
+    /// The condensed memory for a repo as extra prompt instructions
+    pub fn prompt(&self, repo: &str) -> Option<CustomPrompt> {
+        let memory = self.repo(repo).filter(|memory| !memory.is_empty())?;
+        let mut text = String::from(
+            "This reviewer has reviewed this repo before. Give extra attention to what they \
+             usually care about, and flag it in the risks when this PR repeats it.\n",
+        );
+        if !memory.themes.trim().is_empty() {
+            text.push_str(&format!("\nRecurring themes:\n{}\n", memory.themes.trim()));
+        }
+        if !memory.suggestions.is_empty() {
+            text.push_str("\nChanges they asked for recently:\n");
+            for suggestion in memory.suggestions.iter().rev().take(PROMPT_SUGGESTIONS) {
+                let line = suggestion.lines().next().unwrap_or("");
+                let line: String = line.chars().take(MAX_SUGGESTION_CHARS).collect();
+                text.push_str(&format!("- {}\n", line));
+            }
+        }
+        Some(CustomPrompt {
+            source: "your past reviews".to_string(),
+            text,
+        })
+    }
+}
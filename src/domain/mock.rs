@@ -0,0 +1,185 @@
+//! Canned forge and LLM responses for `--mock`, so the TUI runs without a
+//! GitHub account or an API key. Fixtures live in `fixtures/mock/`.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::codeowners;
+use super::filter::DiffFilter;
+use super::stack::{self, ParentPr, StackEntry};
+use super::stats::RateLimit;
+use super::types::{
+    DeepDive, PrCommit, PrContext, PrListItem, QueueItem, RepoListItem, Story,
+};
+
+const REPOS: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/mock/repos.json"));
+const PRS: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/mock/prs.json"));
+const PR: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/mock/pr.json"));
+const DIFF: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/mock/pr.diff"));
+const STORY: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/mock/story.json"));
+const COMMITS: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/mock/commits.json"));
+const CODEOWNERS: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/mock/CODEOWNERS"));
+
+/// Orgs offered by the repo selector's scope switcher
+pub const ORGS: &[&str] = &["acme"];
+
+/// Head and base commits reported for every mock PR
+const HEAD_SHA: &str = "0000000000000000000000000000000000c0ffee";
+const BASE_SHA: &str = "00000000000000000000000000000000000ba5e0";
+
+/// PR metadata from `pr.json`; the number and repo come from the request
+#[derive(Deserialize)]
+struct MockPr {
+    title: String,
+    body: String,
+    author: String,
+    base_branch: String,
+    head_branch: String,
+}
+
+/// Repos for the repo selector; an org narrows them to that owner
+pub fn repo_list(org: Option<&str>) -> Result<Vec<RepoListItem>> {
+    let repos: Vec<RepoListItem> = serde_json::from_str(REPOS).context("Invalid mock repos.json")?;
+    Ok(repos
+        .into_iter()
+        .filter(|repo| org.is_none_or(|org| repo.owner == org))
+        .collect())
+}
+
+/// The same open PRs for every repo
+pub fn pr_list() -> Result<Vec<PrListItem>> {
+    serde_json::from_str(PRS).context("Invalid mock prs.json")
+}
+
+/// Review-requested PRs across the mock repos (or `repos`, when given)
+pub fn review_queue(repos: &[String]) -> Result<Vec<QueueItem>> {
+    let repos = if repos.is_empty() {
+        repo_list(None)?.iter().map(RepoListItem::full_name).collect()
+    } else {
+        repos.to_vec()
+    };
+    let prs = pr_list()?;
+
+    let mut items = Vec::new();
+    for repo_spec in &repos {
+        let Some((owner, repo)) = repo_spec.split_once('/') else {
+            anyhow::bail!("Invalid queue repo '{}'. Use owner/repo", repo_spec);
+        };
+        items.extend(prs.iter().filter(|pr| pr.review_requested).map(|pr| QueueItem {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            pr: pr.clone(),
+        }));
+    }
+    Ok(items)
+}
+
+/// The fixture PR under whatever number was asked for, filtered like a real fetch.
+/// A number from `prs.json` takes its title and branches, so stacks can be walked.
+pub fn pr_context(owner: &str, repo: &str, number: u32, filter: &DiffFilter) -> Result<PrContext> {
+    let mut pr: MockPr = serde_json::from_str(PR).context("Invalid mock pr.json")?;
+    let (_, excluded_files) = filter.apply(DIFF);
+    let open: Vec<StackEntry> = pr_list()?.iter().map(StackEntry::from).collect();
+    if let Some(listed) = open.iter().find(|listed| listed.number == number) {
+        pr.title = listed.title.clone();
+        pr.head_branch = listed.head_branch.clone();
+        pr.base_branch = listed.base_branch.clone();
+    }
+    let stack = stack::build(
+        &open,
+        StackEntry {
+            number,
+            title: pr.title.clone(),
+            head_branch: pr.head_branch.clone(),
+            base_branch: pr.base_branch.clone(),
+        },
+    );
+    let parent = stack
+        .iter()
+        .take_while(|entry| entry.number != number)
+        .last()
+        .map(|entry| ParentPr {
+            number: entry.number,
+            title: entry.title.clone(),
+            body: String::new(),
+        });
+
+    Ok(PrContext {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        number,
+        title: pr.title,
+        body: pr.body,
+        diff: DIFF.to_string(),
+        author: pr.author,
+        base_branch: pr.base_branch,
+        head_branch: pr.head_branch,
+        head_sha: HEAD_SHA.to_string(),
+        base_sha: BASE_SHA.to_string(),
+        bot_comments: Vec::new(),
+        linked_issues: Vec::new(),
+        head_files: HashMap::new(),
+        surrounding_code: Vec::new(),
+        base_blame: HashMap::new(),
+        callers: Vec::new(),
+        file_summaries: Vec::new(),
+        repo_prompt: None,
+        review_memory: None,
+        style_guide: None,
+        scope: Vec::new(),
+        out_of_scope: Vec::new(),
+        code_owners: codeowners::parse(CODEOWNERS),
+        stack,
+        parent,
+        commit_range: None,
+        review_delta: None,
+        excluded_files,
+    })
+}
+
+/// The fixture PR's commits, oldest first; the last is its head
+pub fn commits() -> Result<Vec<PrCommit>> {
+    serde_json::from_str(COMMITS).context("Invalid mock commits.json")
+}
+
+/// The diff between any two commits: the fixture PR's, as there's no history to slice
+pub fn compare_diff() -> String {
+    DIFF.to_string()
+}
+
+/// Output tokens the fixture story would have cost, at ~4 characters per token
+pub fn story_tokens() -> u64 {
+    (STORY.len() / 4) as u64
+}
+
+/// A comfortably unspent rate limit
+pub fn rate_limit() -> RateLimit {
+    RateLimit {
+        limit: 5000,
+        remaining: 4987,
+        reset: 0,
+    }
+}
+
+/// A canned deep dive, whichever feature it's for
+pub fn deep_dive(pr: &PrContext) -> DeepDive {
+    DeepDive {
+        analysis: format!(
+            "In mock mode every deep dive is the same. A live one reads the feature's \
+             blocks with the whole files they touch in {}/{}.",
+            pr.owner, pr.repo
+        ),
+        invariants: vec!["Served requests never exceed the configured limit".to_string()],
+        edge_cases: vec!["The limit is reached exactly at a window boundary".to_string()],
+        suggested_tests: vec!["limit_resets_after_the_window: allow() is true again".to_string()],
+    }
+}
+
+/// The fixture story, written for the fixture PR's diff
+pub fn story() -> Result<Story> {
+    serde_json::from_str(STORY).context("Invalid mock story.json")
+}
@@ -1,4 +1,49 @@
+pub mod anonymize;
+pub mod attachments;
+pub mod bitbucket;
+pub mod breaking;
+pub mod cache;
+pub mod clipboard;
+pub mod codeowners;
+pub mod coverage;
+pub mod crash;
+pub mod diff;
+pub mod dry_run;
+pub mod export;
+pub mod filter;
 pub mod github;
+pub mod history;
+pub mod keychain;
+pub mod lint;
+pub mod listings;
 pub mod llm;
+pub mod llm_cache;
+pub mod logging;
+pub mod memory;
+pub mod mock;
+pub mod notify;
+pub mod outbox;
+pub mod preflight;
+pub mod preferences;
+pub mod privacy;
 pub mod prompt;
+pub mod providers;
+pub mod review;
+pub mod reviewed;
+pub mod secrets;
+pub mod session;
+pub mod spelling;
+pub mod stack;
+pub mod stats;
+pub mod storage;
+pub mod style_guide;
+pub mod summarize;
+pub mod symbols;
+pub mod templates;
+#[cfg(test)]
+mod tests;
+pub mod text;
+pub mod timing;
 pub mod types;
+pub mod user_config;
+pub mod workspace;
@@ -0,0 +1,116 @@
+//! Team-channel notifications after a submission, posted to the webhook configured
+//! for the repo in `config.toml` as Slack-compatible JSON, and desktop
+//! notifications for the reviewer when something they waited on finishes.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use super::review::split_list_items;
+use super::types::{ReviewAction, ReviewEvent};
+
+/// The webhook for a repo: its own `webhooks."owner/repo"`, else `webhooks."*"`
+pub fn webhook_for<'a>(
+    webhooks: &'a BTreeMap<String, String>,
+    owner: &str,
+    repo: &str,
+) -> Option<&'a str> {
+    webhooks
+        .get(&format!("{}/{}", owner, repo))
+        .or_else(|| webhooks.get("*"))
+        .map(String::as_str)
+}
+
+/// What was posted, e.g. "requested changes on payments#412: 3 issues"
+pub fn summary(
+    action: ReviewAction,
+    event: ReviewEvent,
+    repo: &str,
+    number: u32,
+    text: &str,
+) -> String {
+    let count = split_list_items(text).len();
+    let plural = if count == 1 { "" } else { "s" };
+    match (action, event) {
+        (ReviewAction::RequestChanges, ReviewEvent::RequestChanges) => {
+            format!("requested changes on {}#{}: {} issue{}", repo, number, count, plural)
+        }
+        (ReviewAction::RequestChanges, ReviewEvent::Comment) => {
+            format!("reviewed {}#{}", repo, number)
+        }
+        (ReviewAction::RequestChanges, ReviewEvent::Approve) => {
+            format!("approved {}#{}", repo, number)
+        }
+        (ReviewAction::ClarificationQuestions, _) => {
+            format!("asked {} question{} on {}#{}", count, plural, repo, number)
+        }
+        (ReviewAction::NextPr, _) => {
+            let title = text.lines().next().unwrap_or("Follow-up work").trim();
+            format!("opened a follow-up issue from {}#{}: {}", repo, number, title)
+        }
+    }
+}
+
+/// POST `{"text": "<user> <summary>"}`, the shape Slack's incoming webhooks take
+pub async fn send(url: &str, user: &str, summary: &str) -> Result<()> {
+    let text = if user.is_empty() {
+        summary.to_string()
+    } else {
+        format!("{} {}", user, summary)
+    };
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .context("Failed to build HTTP client")?;
+    client
+        .post(url)
+        .json(&serde_json::json!({ "text": text }))
+        .send()
+        .await
+        .context("Could not reach the webhook")?
+        .error_for_status()
+        .context("Webhook rejected the notification")?;
+    Ok(())
+}
+
+/// Show a desktop notification with the platform's notifier: `notify-send` on
+/// Linux, AppleScript on macOS
+pub fn desktop(title: &str, body: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title {}",
+            apple_script_string(body),
+            apple_script_string(title)
+        );
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(script);
+        command
+    } else if cfg!(windows) {
+        anyhow::bail!("No desktop notifier on Windows");
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name", "dstl", title, body]);
+        command
+    };
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to run the desktop notifier")?;
+    anyhow::ensure!(status.success(), "The desktop notifier exited with {}", status);
+    Ok(())
+}
+
+/// Ring the terminal bell, which most terminals turn into an urgency hint
+pub fn bell() {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+}
+
+/// A double-quoted AppleScript string literal
+fn apple_script_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
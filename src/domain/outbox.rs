@@ -0,0 +1,112 @@
+//! Submissions that failed to post, kept across launches so their text isn't
+//! lost and they can be retried.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::storage;
+use super::types::{InlineComment, ReviewAction, ReviewEvent};
+
+const OUTBOX_FILE: &str = "outbox.json";
+
+/// What to post, and where
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Submission {
+    pub owner: String,
+    pub repo: String,
+    pub number: u32,
+    pub kind: SubmissionKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SubmissionKind {
+    Review {
+        body: String,
+        #[serde(default)]
+        event: ReviewEvent,
+        #[serde(default)]
+        commit_id: String,
+        #[serde(default)]
+        comments: Vec<InlineComment>,
+    },
+    Comment {
+        body: String,
+    },
+    InlineQuestions {
+        commit_id: String,
+        comments: Vec<InlineComment>,
+        fallback_body: Option<String>,
+    },
+    NextPrIssue {
+        title: String,
+        body: String,
+    },
+}
+
+impl Submission {
+    /// The review action this submission carries out
+    pub fn action(&self) -> ReviewAction {
+        match self.kind {
+            SubmissionKind::Review { .. } => ReviewAction::RequestChanges,
+            SubmissionKind::Comment { .. } | SubmissionKind::InlineQuestions { .. } => {
+                ReviewAction::ClarificationQuestions
+            }
+            SubmissionKind::NextPrIssue { .. } => ReviewAction::NextPr,
+        }
+    }
+
+    /// `owner/repo#123`
+    pub fn target(&self) -> String {
+        format!("{}/{}#{}", self.owner, self.repo, self.number)
+    }
+
+    /// First non-empty line of the text, for previews
+    pub fn summary(&self) -> &str {
+        let text = match &self.kind {
+            SubmissionKind::Review { body, comments, .. } if body.trim().is_empty() => {
+                comments.first().map_or("", |comment| comment.body.as_str())
+            }
+            SubmissionKind::Review { body, .. } | SubmissionKind::Comment { body } => body,
+            SubmissionKind::InlineQuestions {
+                comments,
+                fallback_body,
+                ..
+            } => comments
+                .first()
+                .map(|comment| &comment.body)
+                .or(fallback_body.as_ref())
+                .map_or("", |body| body.as_str()),
+            SubmissionKind::NextPrIssue { title, .. } => title,
+        };
+        text.lines().find(|line| !line.trim().is_empty()).unwrap_or("").trim()
+    }
+}
+
+/// A submission that failed, with why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxItem {
+    pub submission: Submission,
+    pub error: String,
+    /// Unix seconds of the latest failure
+    pub failed_at: u64,
+    /// Whether a retry is waiting for its result
+    #[serde(skip)]
+    pub retrying: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Outbox {
+    #[serde(default)]
+    pub items: Vec<OutboxItem>,
+}
+
+impl Outbox {
+    /// Load the persisted outbox, empty if none exists
+    pub fn load() -> Self {
+        storage::load_json(OUTBOX_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        storage::save_json(OUTBOX_FILE, self)
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::storage;
+
+const PREFERENCES_FILE: &str = "preferences.json";
+
+pub const DEFAULT_SIDEBAR_WIDTH: u16 = 32;
+pub const MIN_SIDEBAR_WIDTH: u16 = 20;
+pub const MAX_SIDEBAR_WIDTH: u16 = 80;
+pub const SIDEBAR_WIDTH_STEP: u16 = 4;
+
+/// Layout preferences remembered between sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preferences {
+    #[serde(default = "default_sidebar_width")]
+    pub sidebar_width: u16,
+    #[serde(default)]
+    pub sidebar_hidden: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            sidebar_width: DEFAULT_SIDEBAR_WIDTH,
+            sidebar_hidden: false,
+        }
+    }
+}
+
+fn default_sidebar_width() -> u16 {
+    DEFAULT_SIDEBAR_WIDTH
+}
+
+impl Preferences {
+    /// Load persisted preferences, falling back to defaults
+    pub fn load() -> Self {
+        let mut prefs: Self = storage::load_json(PREFERENCES_FILE).unwrap_or_default();
+        prefs.sidebar_width = prefs
+            .sidebar_width
+            .clamp(MIN_SIDEBAR_WIDTH, MAX_SIDEBAR_WIDTH);
+        prefs
+    }
+
+    pub fn save(&self) -> Result<()> {
+        storage::save_json(PREFERENCES_FILE, self)
+    }
+}
@@ -0,0 +1,154 @@
+//! Startup checks run before the TUI takes over the terminal, so a missing
+//! tool or bad key is reported with a fix instead of failing mid-fetch.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use super::bitbucket::BitbucketServer;
+use super::{github, llm};
+
+/// How long the OpenAI probe may take before it counts as unreachable
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One line of the checklist
+#[derive(Debug)]
+pub struct Check {
+    pub name: String,
+    /// What was found, or what's wrong and how to fix it
+    pub outcome: Result<String, Problem>,
+}
+
+#[derive(Debug)]
+pub struct Problem {
+    pub message: String,
+    pub fix: String,
+}
+
+impl Check {
+    fn passed(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Check {
+            name: name.into(),
+            outcome: Ok(detail.into()),
+        }
+    }
+
+    fn failed(name: impl Into<String>, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check {
+            name: name.into(),
+            outcome: Err(Problem {
+                message: message.into(),
+                fix: fix.into(),
+            }),
+        }
+    }
+}
+
+/// Check gh is installed and logged in
+pub fn gh_checks() -> Vec<Check> {
+    let mut checks = Vec::new();
+    match github::gh_version() {
+        Ok(version) => {
+            checks.push(Check::passed("gh installed", version));
+            checks.push(match github::auth_status() {
+                Ok(account) => Check::passed("gh authenticated", account),
+                Err(err) => Check::failed("gh authenticated", err.to_string(), "Run `gh auth login`"),
+            });
+        }
+        Err(_) => checks.push(Check::failed(
+            "gh installed",
+            "gh was not found on PATH",
+            "Install the GitHub CLI from https://cli.github.com/ and run `gh auth login`",
+        )),
+    }
+    checks
+}
+
+/// Check the Bitbucket server is reachable and accepts the token
+pub async fn bitbucket_check(server: &BitbucketServer) -> Check {
+    let name = "Bitbucket token";
+    match server.current_user().await {
+        Ok(user) if !user.is_empty() => Check::passed(name, format!("{} on {}", user, server.url)),
+        Ok(_) => Check::failed(
+            name,
+            format!("{} didn't accept the token", server.url),
+            "Create an HTTP access token under Manage account and set BITBUCKET_TOKEN",
+        ),
+        Err(err) => Check::failed(
+            name,
+            format!("{:#}", err),
+            "Check bitbucket.url in config.toml and your connection",
+        ),
+    }
+}
+
+/// Check the key can use each model. Later checks that depend on a failed one
+/// are left out.
+pub async fn openai_checks(api_key: Option<&str>, models: &[String]) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let Some(api_key) = api_key.filter(|key| !key.trim().is_empty()) else {
+        checks.push(Check::failed(
+            "OpenAI API key",
+            "no key in OPENAI_API_KEY, the OS keychain or config.toml",
+            "Run `dstl auth set-key`, or export OPENAI_API_KEY=sk-... \
+             (or add it to a .env file in this directory)",
+        ));
+        return checks;
+    };
+
+    let mut model_checks = Vec::new();
+    for model in models {
+        let name = format!("Model {}", model);
+        let status = match llm::probe_model(api_key, model, PROBE_TIMEOUT).await {
+            Ok(status) => status,
+            Err(err) => {
+                checks.push(Check::failed(
+                    "OpenAI reachable",
+                    format!("{:#}", err),
+                    "Check your connection and any proxy settings",
+                ));
+                return checks;
+            }
+        };
+        model_checks.push(match status {
+            StatusCode::UNAUTHORIZED => {
+                checks.push(Check::failed(
+                    "OpenAI API key",
+                    "OpenAI rejected the key in OPENAI_API_KEY",
+                    "Create a new key at https://platform.openai.com/api-keys",
+                ));
+                return checks;
+            }
+            StatusCode::NOT_FOUND => Check::failed(
+                name,
+                "not available to this API key",
+                "Pick another with --model (or --compare), or check your organization's model access",
+            ),
+            status if status.is_success() => Check::passed(name, "available"),
+            status => Check::failed(
+                name,
+                format!("OpenAI returned {}", status),
+                "Try again shortly; https://status.openai.com/ shows outages",
+            ),
+        });
+    }
+    checks.push(Check::passed("OpenAI API key", "accepted"));
+    checks.extend(model_checks);
+    checks
+}
+
+/// The checklist as plain text, one check per line with fixes indented below failures
+pub fn report(checks: &[Check], passed: &str, failed: &str) -> String {
+    let mut text = String::new();
+    for check in checks {
+        match &check.outcome {
+            Ok(detail) => text.push_str(&format!("  {} {}: {}\n", passed, check.name, detail)),
+            Err(problem) => {
+                text.push_str(&format!("  {} {}: {}\n", failed, check.name, problem.message));
+                text.push_str(&format!("      Fix: {}\n", problem.fix));
+            }
+        }
+    }
+    text
+}
@@ -0,0 +1,116 @@
+//! The redaction policy for what leaves the machine, from `config.toml`: changed
+//! files matching `redact.paths` are withheld from the model entirely, each
+//! `redact.replace` regex is applied to the prompt text, and a hash of every
+//! payload sent is appended to an audit log.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::diff::retain_files;
+use super::filter::pattern_matches;
+use super::prompt::{build_system_prompt, build_user_prompt};
+use super::storage;
+use super::types::{CustomPrompt, Persona, PrContext};
+
+/// Where outbound payload hashes are appended, under the data directory
+pub const AUDIT_FILE: &str = "audit.jsonl";
+
+#[derive(Debug, Clone, Default)]
+pub struct Redaction {
+    /// Gitattributes-style patterns for changed files the model never sees
+    pub paths: Vec<String>,
+    /// Applied to the prompt text in order
+    pub replacements: Vec<(Regex, String)>,
+}
+
+impl Redaction {
+    /// Compile the configured replacements, naming any pattern that isn't a regex
+    pub fn new(paths: &[String], replacements: &BTreeMap<String, String>) -> Result<Self> {
+        let replacements = replacements
+            .iter()
+            .map(|(pattern, replacement)| {
+                Regex::new(pattern)
+                    .with_context(|| format!("Invalid redact.replace pattern {:?}", pattern))
+                    .map(|regex| (regex, replacement.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Redaction {
+            paths: paths.to_vec(),
+            replacements,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.replacements.is_empty()
+    }
+
+    pub fn withholds(&self, path: &str) -> bool {
+        self.paths.iter().any(|pattern| pattern_matches(pattern, path))
+    }
+
+    /// The system and user prompts exactly as they'll be sent for `pr`
+    pub fn prompts(
+        &self,
+        pr: &PrContext,
+        persona: Option<Persona>,
+        language: Option<&str>,
+        custom_prompts: &[CustomPrompt],
+    ) -> (String, String) {
+        let system = build_system_prompt(persona, language, custom_prompts);
+        let user = build_user_prompt(&self.withhold(pr));
+        (self.replace(&system), self.replace(&user))
+    }
+
+    /// `pr` without anything from the withheld files
+    fn withhold(&self, pr: &PrContext) -> PrContext {
+        if self.paths.is_empty() {
+            return pr.clone();
+        }
+        let mut pr = pr.clone();
+        pr.diff = retain_files(&pr.diff, |path| !self.withholds(path));
+        pr.surrounding_code.retain(|window| !self.withholds(&window.path));
+        pr.head_files.retain(|path, _| !self.withholds(path));
+        pr.base_blame.retain(|path, _| !self.withholds(path));
+        pr.callers.retain(|callers| !self.withholds(&callers.path));
+        pr.bot_comments.retain(|comment| !self.withholds(&comment.path));
+        pr.excluded_files.retain(|file| !self.withholds(&file.path));
+        if let Some(delta) = &mut pr.review_delta {
+            delta.diff = retain_files(&delta.diff, |path| !self.withholds(path));
+        }
+        pr
+    }
+
+    /// `text` with every replacement applied
+    pub fn replace(&self, text: &str) -> String {
+        self.replacements.iter().fold(text.to_string(), |text, (regex, replacement)| {
+            regex.replace_all(&text, replacement.as_str()).into_owned()
+        })
+    }
+}
+
+/// One outbound request, as recorded in the audit log
+#[derive(Debug, Serialize)]
+struct AuditEntry<'a> {
+    at: u64,
+    /// `owner/repo#number`
+    pr: String,
+    model: &'a str,
+    sha256: String,
+    bytes: usize,
+}
+
+/// Append the hash of a request body about to be sent for the PR `pr_label`
+pub fn audit(pr_label: &str, model: &str, payload: &[u8], at: u64) -> Result<()> {
+    let entry = AuditEntry {
+        at,
+        pr: pr_label.to_string(),
+        model,
+        sha256: format!("{:x}", Sha256::digest(payload)),
+        bytes: payload.len(),
+    };
+    storage::append_line(AUDIT_FILE, &serde_json::to_string(&entry)?)
+}
@@ -1,7 +1,105 @@
-use super::types::PrContext;
+use super::attachments::find_images;
+use super::diff::{base_range, parse_diff, retain_files};
+use super::filter::in_scope;
+use super::storage::config_dir;
+use super::style_guide::{CITATION_PREFIX, STYLE_GUIDE_PATH};
+use super::types::{
+    CodeWindow, CustomPrompt, Feature, LinkedIssue, Persona, PrContext, SymbolCallers,
+};
 
-pub fn build_system_prompt() -> String {
-    r#"You are a senior staff engineer performing a code review. Your task is to transform a raw PR diff into a structured narrative that helps reviewers understand the changes quickly and thoroughly.
+/// Longest issue or parent PR body to include as background context
+const MAX_ISSUE_BODY_CHARS: usize = 2000;
+
+/// Longest diff since the last review to include
+const MAX_REVIEW_DELTA_CHARS: usize = 30_000;
+
+/// Budget for surrounding code in the prompt; windows beyond it are dropped
+const MAX_SURROUNDING_CODE_CHARS: usize = 40_000;
+
+/// Budget for whole head files in a deep dive; files beyond it are left out
+const MAX_DEEP_DIVE_FILE_CHARS: usize = 60_000;
+
+/// Where a repo keeps extra review instructions, read at the PR's base commit
+pub const REPO_PROMPT_PATH: &str = ".dstl/prompt.md";
+
+/// Languages the model settings step through, by code; `--language` takes any
+pub const LANGUAGES: [(&str, &str); 12] = [
+    ("de", "German"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("sv", "Swedish"),
+    ("uk", "Ukrainian"),
+    ("zh", "Chinese"),
+];
+
+/// The language's name for a code in [`LANGUAGES`], otherwise as given
+pub fn language_name(language: &str) -> &str {
+    LANGUAGES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(language))
+        .map_or(language, |(_, name)| name)
+}
+
+/// Asks for the prose in `language` and everything the UI parses, and all
+/// code, left as it is
+fn language_instructions(language: &str) -> String {
+    format!(
+        "\n\n## Output Language\n\nWrite all prose in {}, including summaries, titles, explanations, diff block labels and contexts, risks, tests, and the review action drafts. Leave code, identifiers, file paths, diff lines and quoted code exactly as they are, and keep JSON keys and enum values (such as roles and severities) in English.\n",
+        language_name(language)
+    )
+}
+
+/// The user's own review instructions from `~/.config/dstl/prompt.md`, if present
+pub fn load_user_prompt() -> Option<CustomPrompt> {
+    let path = config_dir()?.join("prompt.md");
+    let text = std::fs::read_to_string(&path).ok()?;
+    (!text.trim().is_empty()).then(|| CustomPrompt {
+        source: path.display().to_string(),
+        text,
+    })
+}
+
+/// The built-in system prompt, then the persona's emphasis and the output
+/// language, then any custom instructions (user's first, then the repo's,
+/// then the review memory)
+pub fn build_system_prompt(
+    persona: Option<Persona>,
+    language: Option<&str>,
+    custom: &[CustomPrompt],
+) -> String {
+    let mut prompt = BASE_SYSTEM_PROMPT.to_string();
+    if let Some(persona) = persona {
+        prompt.push_str(&format!(
+            "\n\n## Review Persona: {}\n\n{}\n",
+            persona.label(),
+            persona.instructions()
+        ));
+    }
+    if let Some(language) = language {
+        prompt.push_str(&language_instructions(language));
+    }
+    if !custom.is_empty() {
+        prompt.push_str(
+            "\n\n## Additional Instructions\n\nThe reviewing team added these. Follow them alongside the guidance above, but keep the output format unchanged.\n",
+        );
+        for instructions in custom {
+            prompt.push_str(&format!(
+                "\n### From {}\n\n{}\n",
+                instructions.source,
+                instructions.text.trim()
+            ));
+        }
+    }
+    prompt
+}
+
+const BASE_SYSTEM_PROMPT: &str = r#"You are a senior staff engineer performing a code review. Your task is to transform a raw PR diff into a structured narrative that helps reviewers understand the changes quickly and thoroughly.
 
 ## Your Goals
 
@@ -33,6 +131,19 @@ Examples:
 - New API endpoint: handler=KEY, route registration=STANDARD, imports=NOISE
 - Bug fix: the fix=KEY, test proving it=STANDARD, cleanup=NOISE
 
+## Related Blocks
+
+For each diff block, list in "related_blocks" the other blocks a reviewer should look at alongside it, numbered "feature.block" from 1 in your narrative order (the first block of the second feature is "2.1"). Say how they relate in "note", e.g. "Consumes the RateLimiter type defined in 1.1". Leave it empty when nothing relates.
+
+## Risk Severity
+
+Rate each risk's severity by how much harm it would do if it came true:
+- **high**: Data loss, security holes, outages, or wrong results for users.
+- **medium**: Degraded behavior, confusing errors, or costly maintenance.
+- **low**: Cosmetic issues or unlikely edge cases.
+
+List in "addressed_by" the blocks (numbered as for related blocks) whose change guards against the risk, and leave it empty when nothing in the PR does.
+
 ## Focus Section
 
 Generate a "focus" object that tells reviewers where to spend time:
@@ -40,6 +151,18 @@ Generate a "focus" object that tells reviewers where to spend time:
 - **review_these**: 2-4 specific locations deserving careful review (file:function format)
 - **skim_these**: Categories that can be quickly scanned (e.g., "Import reorganization in 3 files")
 
+## Coverage Gaps
+
+List in "coverage_gaps" the changed functions or files whose new behavior no test in the diff exercises. Compare the source files changed with the test files changed (tests/, *_test.*, *.spec.*, test_*.*); don't list pure refactors, config or generated code.
+
+## What's New
+
+When the PR context includes "Changes Since the Last Review", list in "whats_new" what those changes do, one item per change, most important first; the rest of the story still covers the whole PR. Otherwise leave "whats_new" empty.
+
+## Breaking Changes
+
+List in "breaking_changes" every change to the public API that can break code depending on it: removed or renamed public items, changed signatures, types or defaults, stricter validation, changed wire or file formats, removed config options. Rate each high (callers fail to build or crash), medium (behavior changes silently) or low (unlikely to matter in practice). Leave it empty for internal-only changes.
+
 ## Review Actions
 
 Generate three actionable outputs:
@@ -62,7 +185,13 @@ Return ONLY valid JSON matching this schema exactly:
       "title": "Feature or concern name",
       "why": "Why this change exists - the motivation, not the mechanics",
       "changes": ["Bullet points of what changed"],
-      "risks": ["What could go wrong or needs watching"],
+      "risks": [
+        {
+          "text": "What could go wrong or needs watching",
+          "severity": "high|medium|low",
+          "addressed_by": ["feature.block numbers of blocks that mitigate it, e.g. 1.2"]
+        }
+      ],
       "tests": ["How to verify this works - manual steps or automated tests"],
       "diff_blocks": [
         {
@@ -75,6 +204,12 @@ Return ONLY valid JSON matching this schema exactly:
               "header": "@@ line range @@",
               "lines": "The actual diff lines with +/- prefixes"
             }
+          ],
+          "related_blocks": [
+            {
+              "block": "feature.block number of a related block, e.g. 1.2",
+              "note": "How the two blocks relate"
+            }
           ]
         }
       ]
@@ -86,11 +221,24 @@ Return ONLY valid JSON matching this schema exactly:
     "deletions": 0
   },
   "open_questions": ["Questions that came up during review but aren't blockers"],
+  "coverage_gaps": [
+    {
+      "target": "file:function whose new or changed behavior has no test",
+      "reason": "What is left untested"
+    }
+  ],
+  "whats_new": ["What changed since the reviewer's last review, if given"],
+  "breaking_changes": [
+    {
+      "item": "Public item affected, e.g. parse in src/lib.rs",
+      "description": "What breaks for its users",
+      "severity": "high|medium|low"
+    }
+  ],
   "suggested_changes": "Numbered list of specific changes to request",
   "clarification_questions": "Numbered list of questions for the author",
   "next_pr": "Title and description for a follow-up issue"
-}"#.to_string()
-}
+}"#;
 
 pub fn build_user_prompt(pr: &PrContext) -> String {
     format!(
@@ -104,13 +252,9 @@ pub fn build_user_prompt(pr: &PrContext) -> String {
 
 **Description from author:**
 {body}
-
-## Git Diff
-
-```diff
+{attachments}{linked_issues}{stack}{commit_range}
 {diff}
-```
-
+{review_delta}{scope}{excluded_files}{surrounding_code}{change_history}{impact}{style_guide}
 Analyze this PR and return the structured JSON response."#,
         owner = pr.owner,
         repo = pr.repo,
@@ -124,6 +268,352 @@ Analyze this PR and return the structured JSON response."#,
         } else {
             &pr.body
         },
-        diff = pr.diff
+        attachments = build_attachments_section(&pr.body),
+        linked_issues = build_linked_issues_section(&pr.linked_issues),
+        stack = build_stack_section(pr),
+        commit_range = build_commit_range_section(pr),
+        review_delta = build_review_delta_section(pr),
+        diff = build_diff_section(pr),
+        scope = build_scope_section(pr),
+        excluded_files = build_excluded_files_section(pr),
+        surrounding_code = build_surrounding_code_section(&pr.surrounding_code),
+        change_history = build_change_history_section(pr),
+        impact = build_impact_section(&pr.callers),
+        style_guide = build_style_guide_section(pr.style_guide.as_deref()),
+    )
+}
+
+/// The (system, user) prompts for a deep dive into one feature of the story, with
+/// the whole head-side `files` its blocks touch as context
+pub fn build_deep_dive_prompts(
+    pr: &PrContext,
+    feature: &Feature,
+    files: &[(&str, &str)],
+    language: Option<&str>,
+) -> (String, String) {
+    let mut system = "You are a senior engineer doing a deep dive into one feature of a pull request that has already been summarized for a reviewer. Go beyond the summary: explain how the change works and where it could break, state the invariants the code relies on before and after the change, list edge cases the change may mishandle, and suggest concrete tests (name the test and what it asserts). Be specific to this code; skip generic advice.".to_string();
+    if let Some(language) = language {
+        system.push_str(&language_instructions(language));
+    }
+
+    let mut user = format!(
+        "## PR\n\n{}/{}#{}: {}\n\n{}\n\n## Feature: {}\n\n{}\n",
+        pr.owner,
+        pr.repo,
+        pr.number,
+        pr.title,
+        if pr.body.is_empty() { "No description provided" } else { &pr.body },
+        feature.title,
+        feature.why
+    );
+    for change in &feature.changes {
+        user.push_str(&format!("- {}\n", change));
+    }
+    for risk in &feature.risks {
+        user.push_str(&format!("- Risk: {}\n", risk.text));
+    }
+    user.push_str("\n## Diff blocks\n");
+    for block in &feature.diff_blocks {
+        user.push_str(&format!("\n### {}\n\n{}\n\n```diff\n", block.label, block.context));
+        for hunk in &block.hunks {
+            user.push_str(&format!("{}\n{}\n", hunk.header, hunk.lines));
+        }
+        user.push_str("```\n");
+    }
+    let mut budget = MAX_DEEP_DIVE_FILE_CHARS;
+    for (path, contents) in files {
+        if contents.len() > budget {
+            continue;
+        }
+        budget -= contents.len();
+        user.push_str(&format!("\n## `{}` at the PR head\n\n```\n{}\n```\n", path, contents));
+    }
+    user.push_str("\nReturn the deep dive as structured JSON.");
+    (system, user)
+}
+
+/// The diff, or for one too large to send whole, a summary of each file
+/// Whether a changed file's diff goes in the prompt: it's in scope and wasn't
+/// left out as a lockfile, generated, binary or oversized file
+fn is_sent(pr: &PrContext, path: &str) -> bool {
+    pr.excluded_files.iter().all(|file| file.path != path) && in_scope(&pr.scope, path)
+}
+
+fn build_diff_section(pr: &PrContext) -> String {
+    if pr.file_summaries.is_empty() {
+        let diff = retain_files(&pr.diff, |path| is_sent(pr, path));
+        return format!("## Git Diff\n\n```diff\n{}\n```", diff);
+    }
+    let mut section = String::from(
+        "## Changed Files\n\nThe diff is too large to send whole, so each file was summarized separately. Build the story from these summaries. For every hunk in `diff_blocks`, set `header` to one of the hunk headers listed below, exactly as written, and leave `lines` empty: the diff lines are filled in afterwards.\n",
+    );
+    for file in &pr.file_summaries {
+        section.push_str(&format!(
+            "\n### `{}`\n\n{}\n\nHunks: {}\n",
+            file.path,
+            file.summary.trim(),
+            file.hunks.iter().map(|hunk| format!("`{}`", hunk)).collect::<Vec<_>>().join(", ")
+        ));
+    }
+    section
+}
+
+/// The team's style guide, with how to cite it
+fn build_style_guide_section(guide: Option<&str>) -> String {
+    let Some(guide) = guide else {
+        return String::new();
+    };
+    format!(
+        "\n## Team Style Guide\n\nThe repo's style guide ({}). When a suggested change or risk follows from one of its rules, end that item with a citation of the rule as `{}<section or rule>]`, e.g. `{}Error handling]`. Don't cite the guide for anything it doesn't say.\n\n{}\n",
+        STYLE_GUIDE_PATH,
+        CITATION_PREFIX,
+        CITATION_PREFIX,
+        guide.trim()
     )
 }
+
+/// Images in the description, which the model can't see but the reviewer can
+fn build_attachments_section(body: &str) -> String {
+    let images = find_images(body);
+    if images.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from(
+        "\n## Attachments\n\nThe description embeds these images (screenshots or recordings). You can't see them, but the reviewer can. If the diff changes something visual (UI, layout, styles, rendered or terminal output) that none of them plausibly shows, say so in open_questions.\n\n",
+    );
+    for image in images {
+        section.push_str(&format!("- {} ({})\n", image.label, image.url));
+    }
+    section
+}
+
+/// Background context from issues referenced in the PR body
+fn build_linked_issues_section(issues: &[LinkedIssue]) -> String {
+    if issues.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from(
+        "\n## Linked Issues\n\nBackground context from issues referenced by the PR. Use it to judge whether the change addresses the stated problem.\n",
+    );
+    for issue in issues {
+        let body: String = issue.body.chars().take(MAX_ISSUE_BODY_CHARS).collect();
+        section.push_str(&format!(
+            "\n### {}/{}#{}: {}\n\n{}\n",
+            issue.owner,
+            issue.repo,
+            issue.number,
+            issue.title,
+            if body.is_empty() {
+                "No description provided"
+            } else {
+                &body
+            }
+        ));
+    }
+    section
+}
+
+/// Where the PR sits in its stack, and what the PR it builds on does
+fn build_stack_section(pr: &PrContext) -> String {
+    let Some(parent) = &pr.parent else {
+        return String::new();
+    };
+    let position = pr.stack.iter().position(|entry| entry.number == pr.number);
+    let mut section = format!(
+        "\n## Stacked PR\n\nThis PR is {} of a stack of {} and targets `{}`, the branch of #{}, so the diff only shows what it adds on top of that PR. Don't flag what the parent already does as missing here.\n",
+        position.map_or("part".to_string(), |i| format!("step {}", i + 1)),
+        pr.stack.len(),
+        pr.base_branch,
+        parent.number
+    );
+    let body: String = parent.body.chars().take(MAX_ISSUE_BODY_CHARS).collect();
+    section.push_str(&format!(
+        "\n### Parent #{}: {}\n\n{}\n",
+        parent.number,
+        parent.title,
+        if body.is_empty() {
+            "No description provided"
+        } else {
+            &body
+        }
+    ));
+    section
+}
+
+/// Which commits the diff covers, when it's only some of the PR's
+fn build_commit_range_section(pr: &PrContext) -> String {
+    let Some(range) = &pr.commit_range else {
+        return String::new();
+    };
+    let mut section = format!(
+        "\n## Commit Range\n\nThe diff below covers only {} of this PR, not the whole PR. The reviewer has already looked at the other commits, so describe what these ones change; the description may also cover earlier work.\n\n",
+        range.describe()
+    );
+    for commit in &range.commits {
+        section.push_str(&format!("- {} {}\n", commit.short_sha(), commit.headline));
+    }
+    section
+}
+
+/// What changed since the version the reviewer already reviewed
+fn build_review_delta_section(pr: &PrContext) -> String {
+    let Some(delta) = &pr.review_delta else {
+        return String::new();
+    };
+    // Files only the delta touches are held to the same scope as the PR's
+    let sent = retain_files(&delta.diff, |path| is_sent(pr, path));
+    if sent.trim().is_empty() {
+        return String::new();
+    }
+    let mut diff: String = sent.chars().take(MAX_REVIEW_DELTA_CHARS).collect();
+    if diff.len() < sent.len() {
+        diff.push_str("\n[truncated]");
+    }
+    format!(
+        "\n## Changes Since the Last Review\n\nThe reviewer already reviewed this PR at {}. It has been updated since, possibly force-pushed; this is the diff from that version to the current head:\n\n```diff\n{}\n```\n",
+        delta.short_reviewed_head(),
+        diff.trim_end()
+    )
+}
+
+/// The directories the reviewer is limited to, and how much was left out for it
+fn build_scope_section(pr: &PrContext) -> String {
+    if pr.scope.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\n## Review Scope\n\nThe reviewer only covers {}. The {} changed files outside it were left out; don't mention them, and count only the files shown in the stats.\n",
+        pr.scope.join(", "),
+        pr.out_of_scope.len()
+    )
+}
+
+/// Changed files in scope whose diff was left out, so the file list and stats still add up
+fn build_excluded_files_section(pr: &PrContext) -> String {
+    let files = pr.excluded_in_scope();
+    if files.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from(
+        "\n## Files Not Shown\n\nThese files also changed but their diffs were left out: noise matched by ignore patterns, binary files, or diffs too large to include. Don't create diff blocks for them, but call out binary or oversized changes in the summary or risks when they matter.\n\n",
+    );
+    for file in files {
+        section.push_str(&format!(
+            "- {} ({}, +{} -{}, {})\n",
+            file.path,
+            file.change.as_str(),
+            file.additions,
+            file.deletions,
+            file.reason.describe()
+        ));
+    }
+    section
+}
+
+/// Head-revision code around the most heavily changed hunks, with line numbers
+fn build_surrounding_code_section(windows: &[CodeWindow]) -> String {
+    if windows.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from(
+        "\n## Surrounding Code\n\nCode around the changes at the PR head, so you can judge hunks in context. Line numbers are head-side.\n",
+    );
+    for window in windows {
+        let mut block = format!(
+            "\n### {} (lines {}-{})\n\n```\n",
+            window.path,
+            window.start_line,
+            window.start_line as usize + window.lines.len() - 1
+        );
+        for (offset, line) in window.lines.iter().enumerate() {
+            block.push_str(&format!("{:>5} {}\n", window.start_line as usize + offset, line));
+        }
+        block.push_str("```\n");
+
+        if section.len() + block.len() > MAX_SURROUNDING_CODE_CHARS {
+            break;
+        }
+        section.push_str(&block);
+    }
+    section
+}
+
+/// How widely the rest of the repo uses the functions this PR modifies or removes
+fn build_impact_section(callers: &[SymbolCallers]) -> String {
+    if callers.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from(
+        "\n## Impact\n\nFiles elsewhere in the repo that reference functions this PR modifies or removes, from code search on the default branch. Weigh how far a behavior change would reach when rating risks.\n\n",
+    );
+    for entry in callers {
+        let mut examples: Vec<String> =
+            entry.files.iter().take(5).map(|file| format!("`{}`", file)).collect();
+        if entry.total as usize > examples.len() && !examples.is_empty() {
+            examples.push("...".to_string());
+        }
+        let examples = if examples.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", examples.join(", "))
+        };
+        section.push_str(&format!(
+            "- `{}` (changed in `{}`): referenced from {} other file{}{}\n",
+            entry.symbol,
+            entry.path,
+            entry.total,
+            if entry.total == 1 { "" } else { "s" },
+            examples
+        ));
+    }
+    section
+}
+
+/// Who last touched the lines each hunk rewrites, from blame at the base commit
+fn build_change_history_section(pr: &PrContext) -> String {
+    if pr.base_blame.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from(
+        "\n## Change History\n\nWho last modified the lines this PR changes, at the base commit. Recently or repeatedly changed code deserves extra scrutiny.\n\n",
+    );
+    for file in parse_diff(&pr.diff) {
+        let Some(ranges) = pr.base_blame.get(&file.path) else {
+            continue;
+        };
+        for hunk in file.hunks.iter().filter(|hunk| hunk.old_len > 0) {
+            let (start, end) = base_range(hunk);
+            let mut seen: Vec<&str> = Vec::new();
+            for range in ranges
+                .iter()
+                .filter(|range| range.start_line <= end && range.end_line >= start)
+            {
+                if seen.contains(&range.commit.as_str()) {
+                    continue;
+                }
+                seen.push(&range.commit);
+                let pr_ref = range
+                    .pr_number
+                    .map(|n| format!(" (#{})", n))
+                    .unwrap_or_default();
+                section.push_str(&format!(
+                    "- `{}` L{}-{}: {} on {}, {}{} \"{}\"\n",
+                    file.path,
+                    start,
+                    end,
+                    range.author,
+                    range.date.get(..10).unwrap_or(&range.date),
+                    range.commit,
+                    pr_ref,
+                    range.summary
+                ));
+            }
+        }
+    }
+    section
+}
@@ -0,0 +1,72 @@
+//! The ordered chain of model providers from `config.toml`, e.g. a company proxy,
+//! then OpenAI, then a local Ollama. Generation falls through to the next one
+//! when a provider turns the request away or can't be reached.
+
+use anyhow::{Context, Result};
+
+use super::llm::PROVIDER;
+use super::user_config::UserConfig;
+
+pub const OPENAI_URL: &str = "https://api.openai.com/v1";
+
+/// An endpoint speaking OpenAI's Responses API
+#[derive(Debug, Clone, PartialEq)]
+pub struct Provider {
+    pub name: String,
+    /// Up to and including the API version, e.g. `http://localhost:11434/v1`
+    pub base_url: String,
+    /// Empty for endpoints that don't check one
+    pub api_key: String,
+    /// Replaces the requested model, for providers that serve different ones
+    pub model: Option<String>,
+}
+
+impl Provider {
+    pub fn openai(api_key: String) -> Self {
+        Provider {
+            name: PROVIDER.to_string(),
+            base_url: OPENAI_URL.to_string(),
+            api_key,
+            model: None,
+        }
+    }
+
+    /// The model to ask this provider for when `requested` was asked for
+    pub fn model_for(&self, requested: &str) -> String {
+        self.model.clone().unwrap_or_else(|| requested.to_string())
+    }
+}
+
+/// The configured chain in order, or just OpenAI when `providers` isn't set.
+/// `openai` in the list is OpenAI with `openai_key`; any other name needs a
+/// `provider.<name>.url`, and reads its key from the variable `key_env` names
+pub fn chain(user_config: &UserConfig, openai_key: &str) -> Result<Vec<Provider>> {
+    if user_config.providers.is_empty() {
+        return Ok(vec![Provider::openai(openai_key.to_string())]);
+    }
+    user_config
+        .providers
+        .iter()
+        .map(|name| {
+            let settings = user_config.provider_settings.get(name);
+            let setting = |field: &str| settings.and_then(|settings| settings.get(field)).cloned();
+            let mut provider = if name == PROVIDER {
+                Provider::openai(openai_key.to_string())
+            } else {
+                Provider {
+                    name: name.clone(),
+                    base_url: setting("url").with_context(|| {
+                        format!("Provider {:?} has no provider.{}.url in config.toml", name, name)
+                    })?,
+                    api_key: setting("key_env")
+                        .and_then(|var| std::env::var(var).ok())
+                        .unwrap_or_default(),
+                    model: None,
+                }
+            };
+            provider.base_url = provider.base_url.trim_end_matches('/').to_string();
+            provider.model = setting("model");
+            Ok(provider)
+        })
+        .collect()
+}
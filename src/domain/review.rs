@@ -0,0 +1,112 @@
+use super::diff::{locate_block, FileDiff};
+use super::types::{InlineComment, Story};
+
+/// Split a numbered or bulleted list into items, folding continuation lines into
+/// the preceding item. Text without list markers is returned as a single item.
+pub fn split_list_items(text: &str) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(item) = strip_list_marker(trimmed) {
+            items.push(item.to_string());
+        } else if let Some(last) = items.last_mut() {
+            last.push('\n');
+            last.push_str(trimmed);
+        } else {
+            items.push(trimmed.to_string());
+        }
+    }
+
+    items
+}
+
+/// Add `item` to a numbered list as its next entry
+pub fn append_list_item(text: &mut String, item: &str) {
+    let number = split_list_items(text).len() + 1;
+    text.truncate(text.trim_end().len());
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(&format!("{}. {}", number, item));
+}
+
+/// Add `quote` to the end of a draft as a Markdown blockquote, attributed on its
+/// last line
+pub fn append_quote(text: &mut String, quote: &str, attribution: &str) {
+    text.truncate(text.trim_end().len());
+    if !text.is_empty() {
+        text.push_str("\n\n");
+    }
+    for line in quote.lines() {
+        text.push_str(format!("> {}", line).trim_end());
+        text.push('\n');
+    }
+    text.push_str(&format!(">\n> — {}", attribution));
+}
+
+/// Strip a leading `1.`, `1)`, `-`, or `*` marker, returning the item text
+fn strip_list_marker(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(rest.trim());
+    }
+
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..]
+        .strip_prefix('.')
+        .or_else(|| line[digits..].strip_prefix(')'))
+        .map(str::trim)
+}
+
+/// Anchor each question to the diff block it concerns, matched by the block's file
+/// name or label appearing in the question. Returns the inline comments plus any
+/// questions that couldn't be anchored.
+pub fn anchor_questions(
+    questions: Vec<String>,
+    story: &Story,
+    files: &[FileDiff],
+) -> (Vec<InlineComment>, Vec<String>) {
+    // (match keys, path, line) for each block we can place in the diff
+    let anchors: Vec<(Vec<String>, String, u32)> = story
+        .narrative
+        .iter()
+        .flat_map(|feature| &feature.diff_blocks)
+        .filter_map(|block| {
+            let (path, line) = locate_block(files, block)?;
+            let file_name = path.rsplit('/').next().unwrap_or(&path).to_lowercase();
+            let keys = [path.to_lowercase(), file_name, block.label.to_lowercase()]
+                .into_iter()
+                .filter(|key| key.len() >= 3)
+                .collect();
+            Some((keys, path, line))
+        })
+        .collect();
+
+    let mut inline = Vec::new();
+    let mut unanchored = Vec::new();
+
+    for question in questions {
+        let lowered = question.to_lowercase();
+        let anchor = anchors
+            .iter()
+            .find(|(keys, _, _)| keys.iter().any(|key| lowered.contains(key.as_str())));
+
+        match anchor {
+            Some((_, path, line)) => inline.push(InlineComment {
+                path: path.clone(),
+                line: *line,
+                body: question,
+            }),
+            None => unanchored.push(question),
+        }
+    }
+
+    (inline, unanchored)
+}
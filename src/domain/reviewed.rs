@@ -0,0 +1,67 @@
+//! The head each PR was last reviewed at and which blocks were viewed there, so
+//! after a force-push the story can focus on what changed and keep the rest viewed.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::storage;
+use super::types::DiffBlock;
+
+const REVIEWED_FILE: &str = "reviewed.json";
+
+/// Reviewed versions, keyed by `owner/repo#123`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewedPrs {
+    #[serde(default)]
+    pub prs: BTreeMap<String, ReviewedPr>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReviewedPr {
+    /// Head commit the reviewer last marked blocks viewed at
+    pub head_sha: String,
+    /// [`block_fingerprint`]s of the blocks viewed there
+    #[serde(default)]
+    pub viewed_blocks: Vec<String>,
+}
+
+impl ReviewedPrs {
+    /// Load reviewed heads, starting fresh if none were saved
+    pub fn load() -> Self {
+        storage::load_json(REVIEWED_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        storage::save_json(REVIEWED_FILE, self)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ReviewedPr> {
+        self.prs.get(key)
+    }
+
+    pub fn record(&mut self, key: &str, reviewed: ReviewedPr) {
+        self.prs.insert(key.to_string(), reviewed);
+    }
+}
+
+/// Identifies a block by the lines it adds and removes, which survive a rebase
+/// and a regenerated story while line numbers and labels don't
+pub fn block_fingerprint(block: &DiffBlock) -> String {
+    // FNV-1a, stable across runs and toolchains
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let changed = block
+        .hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.lines())
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+        .map(str::trim_end);
+    for line in changed {
+        for byte in line.bytes().chain(std::iter::once(b'\n')) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
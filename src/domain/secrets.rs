@@ -0,0 +1,171 @@
+//! Likely secrets in the lines a PR adds, found by pattern and entropy rules
+//! before anything is sent to the model, and redaction of them from the prompt.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::diff::parse_diff;
+use super::types::PrContext;
+
+/// Credentials with a recognizable shape, by name
+const PATTERNS: &[(&str, &str)] = &[
+    ("AWS access key", r"\b(AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("GitHub token", r"\b(gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})"),
+    ("Slack token", r"\bxox[abposr]-[A-Za-z0-9-]{10,}"),
+    ("private key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+    ("OpenAI key", r"\bsk-(proj-)?[A-Za-z0-9_-]{20,}"),
+    ("Google API key", r"\bAIza[0-9A-Za-z_-]{35}"),
+    ("Stripe key", r"\b[rs]k_live_[0-9A-Za-z]{20,}"),
+];
+
+/// A quoted value assigned to something named like a credential
+const ASSIGNMENT: &str = r#"(?i)(password|passwd|secret|token|api[_-]?key|access[_-]?key|credential)s?["']?\s*[:=]\s*["']([^"'\s]{12,})["']"#;
+
+/// Bits per character above which an assigned value looks random rather than a
+/// placeholder like `changeme-please`
+const MIN_ENTROPY: f64 = 3.5;
+
+/// What to do with likely secrets before the diff goes to the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretsChoice {
+    /// Replace the lines with a marker
+    Redact,
+    /// Send them as they are
+    Send,
+}
+
+/// A line the PR adds that looks like it holds a secret
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecretFinding {
+    pub path: String,
+    /// Head-side line number
+    pub line: u32,
+    /// What it looks like, e.g. "AWS access key"
+    pub rule: &'static str,
+    /// The matched text, for redacting it wherever else it appears
+    pub secret: String,
+}
+
+impl SecretFinding {
+    /// The match with all but its first four characters masked, safe to display
+    pub fn masked(&self) -> String {
+        let shown: String = self.secret.chars().take(4).collect();
+        let hidden = self.secret.chars().count().saturating_sub(4).min(16);
+        format!("{}{}", shown, "*".repeat(hidden))
+    }
+}
+
+fn rules() -> &'static [(&'static str, Regex)] {
+    static RULES: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        PATTERNS
+            .iter()
+            .map(|(rule, pattern)| (*rule, Regex::new(pattern).expect("valid secret pattern")))
+            .collect()
+    })
+}
+
+fn assignment() -> &'static Regex {
+    static ASSIGNMENT_RE: OnceLock<Regex> = OnceLock::new();
+    ASSIGNMENT_RE.get_or_init(|| Regex::new(ASSIGNMENT).expect("valid assignment pattern"))
+}
+
+/// The rule a line of code trips and the text it matched, if any
+fn find_secret(code: &str) -> Option<(&'static str, String)> {
+    for (rule, pattern) in rules() {
+        if let Some(found) = pattern.find(code) {
+            return Some((rule, found.as_str().to_string()));
+        }
+    }
+    assignment()
+        .captures_iter(code)
+        .filter_map(|captures| captures.get(2))
+        .find(|value| entropy(value.as_str()) >= MIN_ENTROPY)
+        .map(|value| ("high-entropy secret", value.as_str().to_string()))
+}
+
+/// Shannon entropy in bits per character
+fn entropy(text: &str) -> f64 {
+    let len = text.chars().count() as f64;
+    let mut counts = std::collections::HashMap::new();
+    for c in text.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Likely secrets on the lines the diff adds, in diff order
+pub fn scan(diff: &str) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+    for file in parse_diff(diff) {
+        for hunk in &file.hunks {
+            let mut line = hunk.new_start;
+            for text in &hunk.lines {
+                if let Some(code) = text.strip_prefix('+')
+                    && let Some((rule, secret)) = find_secret(code)
+                {
+                    findings.push(SecretFinding {
+                        path: file.path.clone(),
+                        line,
+                        rule,
+                        secret,
+                    });
+                }
+                if !text.starts_with('-') {
+                    line += 1;
+                }
+            }
+        }
+    }
+    findings
+}
+
+/// `text` with every finding's secret masked
+pub fn mask(text: &str, findings: &[SecretFinding]) -> String {
+    findings
+        .iter()
+        .fold(text.to_string(), |text, finding| text.replace(&finding.secret, "[redacted]"))
+}
+
+/// `pr` with every added line that looks like a secret replaced by a marker, and
+/// the secrets themselves masked everywhere else the prompt quotes the PR: context
+/// lines, the body, bot comments, linked issues, blame, callers and summaries
+pub fn redact(pr: &PrContext, findings: &[SecretFinding]) -> PrContext {
+    let mut redacted = pr.clone();
+    redacted.diff = pr
+        .diff
+        .lines()
+        .map(|line| match line.strip_prefix('+').and_then(find_secret) {
+            Some((rule, _)) if !line.starts_with("+++") => {
+                format!("+[redacted by dstl: possible {}]", rule)
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n";
+    // Walking the serialized form reaches every text field, including ones added later
+    let mut value = serde_json::to_value(&redacted).expect("PR context serializes");
+    mask_strings(&mut value, findings);
+    serde_json::from_value(value).expect("masking keeps the PR context's shape")
+}
+
+fn mask_strings(value: &mut serde_json::Value, findings: &[SecretFinding]) {
+    match value {
+        serde_json::Value::String(text) => *text = mask(text, findings),
+        serde_json::Value::Array(items) => {
+            items.iter_mut().for_each(|item| mask_strings(item, findings));
+        }
+        serde_json::Value::Object(fields) => {
+            fields.values_mut().for_each(|field| mask_strings(field, findings));
+        }
+        _ => {}
+    }
+}
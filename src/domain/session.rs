@@ -0,0 +1,69 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::storage;
+use super::types::{InlineComment, PrContext, Story};
+
+const SESSION_FILE: &str = "session.json";
+
+/// Where the last review left off, offered for resume in the repo selector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub pr: PrContext,
+    /// Model that generated the displayed story
+    pub model: String,
+    pub story: Story,
+    pub drafts: Drafts,
+    pub viewed_diffs: Vec<(usize, usize)>,
+    #[serde(default)]
+    pub follow_up_diffs: Vec<(usize, usize)>,
+    #[serde(default)]
+    pub block_notes: Vec<((usize, usize), String)>,
+    /// Inline comments of the review not yet submitted
+    #[serde(default)]
+    pub pending_comments: Vec<InlineComment>,
+    pub selected_feature: usize,
+    pub selected_diff: usize,
+    pub scroll_offset: u16,
+    /// Where each feature was last scrolled to, as (feature, offset)
+    #[serde(default)]
+    pub feature_scroll: Vec<(usize, u16)>,
+}
+
+/// Review action texts as last edited
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Drafts {
+    pub request_changes: String,
+    pub clarification: String,
+    pub next_pr: String,
+}
+
+impl Session {
+    /// Load the last session, if one was saved and is still readable
+    pub fn load() -> Option<Self> {
+        storage::load_json(SESSION_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        storage::save_json(SESSION_FILE, self)
+    }
+
+    /// `owner/repo#123`
+    pub fn target(&self) -> String {
+        format!("{}/{}#{}", self.pr.owner, self.pr.repo, self.pr.number)
+    }
+
+    /// Share of the story's diff blocks marked viewed, as a whole percentage
+    pub fn percent_reviewed(&self) -> usize {
+        let total: usize = self
+            .story
+            .narrative
+            .iter()
+            .map(|feature| feature.diff_blocks.len())
+            .sum();
+        if total == 0 {
+            return 0;
+        }
+        self.viewed_diffs.len() * 100 / total
+    }
+}
@@ -0,0 +1,161 @@
+//! A light spell-check for drafts: words found in a list of common misspellings,
+//! with what was probably meant. It only catches the usual slips, so anything it
+//! doesn't know passes.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// Misspelling (lowercase) and its correction
+const MISSPELLINGS: &[(&str, &str)] = &[
+    ("accomodate", "accommodate"),
+    ("acheive", "achieve"),
+    ("adress", "address"),
+    ("agressive", "aggressive"),
+    ("alot", "a lot"),
+    ("amoung", "among"),
+    ("apparant", "apparent"),
+    ("arguement", "argument"),
+    ("asynchonous", "asynchronous"),
+    ("becuase", "because"),
+    ("beggining", "beginning"),
+    ("begining", "beginning"),
+    ("beleive", "believe"),
+    ("calulate", "calculate"),
+    ("compatability", "compatibility"),
+    ("concurent", "concurrent"),
+    ("consistant", "consistent"),
+    ("convienient", "convenient"),
+    ("curent", "current"),
+    ("defintion", "definition"),
+    ("definately", "definitely"),
+    ("dependancy", "dependency"),
+    ("dependancies", "dependencies"),
+    ("desciption", "description"),
+    ("diffrent", "different"),
+    ("doesnt", "doesn't"),
+    ("enviroment", "environment"),
+    ("exectute", "execute"),
+    ("existance", "existence"),
+    ("explicitely", "explicitly"),
+    ("familar", "familiar"),
+    ("finaly", "finally"),
+    ("funtion", "function"),
+    ("garantee", "guarantee"),
+    ("gaurantee", "guarantee"),
+    ("happend", "happened"),
+    ("immediatly", "immediately"),
+    ("implementaion", "implementation"),
+    ("independant", "independent"),
+    ("initalize", "initialize"),
+    ("intial", "initial"),
+    ("isnt", "isn't"),
+    ("lenght", "length"),
+    ("mantain", "maintain"),
+    ("neccessary", "necessary"),
+    ("necessery", "necessary"),
+    ("noticable", "noticeable"),
+    ("occured", "occurred"),
+    ("occurence", "occurrence"),
+    ("occurance", "occurrence"),
+    ("paramater", "parameter"),
+    ("paramter", "parameter"),
+    ("perfomance", "performance"),
+    ("persistant", "persistent"),
+    ("posible", "possible"),
+    ("prefered", "preferred"),
+    ("previos", "previous"),
+    ("probaly", "probably"),
+    ("proccess", "process"),
+    ("recieve", "receive"),
+    ("recieved", "received"),
+    ("recomend", "recommend"),
+    ("refered", "referred"),
+    ("refrence", "reference"),
+    ("relevent", "relevant"),
+    ("reponse", "response"),
+    ("resouce", "resource"),
+    ("retreive", "retrieve"),
+    ("seperate", "separate"),
+    ("seperately", "separately"),
+    ("shouldnt", "shouldn't"),
+    ("similiar", "similar"),
+    ("succesful", "successful"),
+    ("successfull", "successful"),
+    ("sucess", "success"),
+    ("suport", "support"),
+    ("teh", "the"),
+    ("thier", "their"),
+    ("threshhold", "threshold"),
+    ("truely", "truly"),
+    ("unecessary", "unnecessary"),
+    ("untill", "until"),
+    ("usefull", "useful"),
+    ("validaton", "validation"),
+    ("wich", "which"),
+    ("wierd", "weird"),
+    ("writting", "writing"),
+];
+
+/// A likely typo in a draft
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typo {
+    pub word: String,
+    pub suggestion: String,
+}
+
+/// What `word` was probably meant to be, if it's a common misspelling. A
+/// capitalised word gets a capitalised suggestion
+pub fn suggest(word: &str) -> Option<String> {
+    let lower = word.to_lowercase();
+    let &(_, correction) = MISSPELLINGS.iter().find(|(wrong, _)| *wrong == lower)?;
+    let mut chars = word.chars();
+    Some(match chars.next() {
+        Some(first) if first.is_uppercase() => {
+            let mut rest = correction.chars();
+            rest.next().map_or_else(String::new, |c| c.to_uppercase().chain(rest).collect())
+        }
+        _ => correction.to_string(),
+    })
+}
+
+/// Each distinct likely typo in the text, in the order they first appear.
+/// Fenced code and `inline code` are left alone
+pub fn find_typos(text: &str) -> Vec<Typo> {
+    let mut typos: Vec<Typo> = Vec::new();
+    let mut in_code = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        // Odd-numbered pieces between backticks are inline code
+        for prose in line.split('`').step_by(2) {
+            for (_, word) in words(prose) {
+                if typos.iter().any(|typo| typo.word == word) {
+                    continue;
+                }
+                if let Some(suggestion) = suggest(word) {
+                    typos.push(Typo {
+                        word: word.to_string(),
+                        suggestion,
+                    });
+                }
+            }
+        }
+    }
+    typos
+}
+
+/// Each word in the text with its byte offset: letters, joined by apostrophes
+pub fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    static WORD: OnceLock<Regex> = OnceLock::new();
+    let word = WORD.get_or_init(|| {
+        Regex::new(r"\p{Alphabetic}+(?:'\p{Alphabetic}+)*").expect("valid pattern")
+    });
+    word.find_iter(text)
+        .map(|word| (word.start(), word.as_str()))
+}
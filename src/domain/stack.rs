@@ -0,0 +1,77 @@
+//! Stacked PRs: a PR whose base branch is another open PR's head is reviewed
+//! as one step of a stack, with its parent's description as context.
+
+use serde::{Deserialize, Serialize};
+
+use super::types::PrListItem;
+
+/// How many PRs up or down the stack to follow, in case branches form a cycle
+const MAX_STACK_DEPTH: usize = 20;
+
+/// One PR in a stack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StackEntry {
+    pub number: u32,
+    pub title: String,
+    pub head_branch: String,
+    pub base_branch: String,
+}
+
+impl From<&PrListItem> for StackEntry {
+    fn from(pr: &PrListItem) -> Self {
+        StackEntry {
+            number: pr.number,
+            title: pr.title.clone(),
+            head_branch: pr.head_branch.clone(),
+            base_branch: pr.base_branch.clone(),
+        }
+    }
+}
+
+/// The PR the open one is stacked on, for the prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParentPr {
+    pub number: u32,
+    pub title: String,
+    pub body: String,
+}
+
+/// The stack `current` belongs to, bottom first, from the repo's open PRs.
+/// Where several PRs build on the same one, the lowest-numbered is followed.
+/// Empty when `current` isn't stacked.
+pub fn build(open: &[StackEntry], current: StackEntry) -> Vec<StackEntry> {
+    let others: Vec<&StackEntry> = open.iter().filter(|pr| pr.number != current.number).collect();
+    let mut below = Vec::new();
+    let mut base = current.base_branch.clone();
+    while below.len() < MAX_STACK_DEPTH {
+        let Some(parent) = others.iter().find(|pr| pr.head_branch == base) else {
+            break;
+        };
+        if below.iter().any(|pr: &StackEntry| pr.number == parent.number) {
+            break;
+        }
+        base = parent.base_branch.clone();
+        below.push((*parent).clone());
+    }
+    below.reverse();
+
+    let mut above: Vec<StackEntry> = Vec::new();
+    let mut head = current.head_branch.clone();
+    while above.len() < MAX_STACK_DEPTH {
+        let Some(child) = others
+            .iter()
+            .filter(|pr| pr.base_branch == head)
+            .filter(|pr| !below.iter().chain(&above).any(|seen| seen.number == pr.number))
+            .min_by_key(|pr| pr.number)
+        else {
+            break;
+        };
+        head = child.head_branch.clone();
+        above.push((*child).clone());
+    }
+
+    if below.is_empty() && above.is_empty() {
+        return Vec::new();
+    }
+    below.into_iter().chain(std::iter::once(current)).chain(above).collect()
+}
@@ -0,0 +1,111 @@
+//! What the session has cost and where the story came from, for the header,
+//! and what's waiting in the PR picker.
+
+use serde::Deserialize;
+
+use super::types::{CiStatus, PrListItem, PrState};
+
+/// Filled in as command results arrive; nothing is persisted
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    /// Provider and model of the story on screen
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub source: Option<StorySource>,
+    /// Tokens the last model call used, as the provider reported them
+    pub last_usage: Option<TokenUsage>,
+    /// The tighter of GitHub's REST and GraphQL limits, when last checked
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorySource {
+    Generated,
+    Cached,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+}
+
+/// One of GitHub's rate limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    /// When the limit resets, in seconds since the epoch
+    pub reset: u64,
+}
+
+impl SessionStats {
+    pub fn record_story(&mut self, provider: &str, model: &str, source: StorySource) {
+        self.provider = Some(provider.to_string());
+        self.model = Some(model.to_string());
+        self.source = Some(source);
+    }
+}
+
+impl StorySource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StorySource::Generated => "fresh",
+            StorySource::Cached => "cached",
+        }
+    }
+}
+
+impl RateLimit {
+    /// Whichever limit has the smaller share left
+    pub fn tighter(self, other: RateLimit) -> RateLimit {
+        let left = |limit: &RateLimit| limit.remaining as f64 / limit.limit.max(1) as f64;
+        if left(&other) < left(&self) { other } else { self }
+    }
+
+    /// Under a tenth left, worth calling out
+    pub fn is_low(&self) -> bool {
+        self.remaining * 10 < self.limit
+    }
+}
+
+/// What the loaded PR list asks of the reviewer, for the picker header
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Workload {
+    /// Ready PRs requesting the user's review
+    pub review_requested: usize,
+    /// Lines those PRs add and remove
+    pub additions: u64,
+    pub deletions: u64,
+    /// Open PRs whose checks are failing
+    pub failing_ci: usize,
+    pub drafts: usize,
+}
+
+impl Workload {
+    pub fn from_prs(prs: &[PrListItem]) -> Self {
+        let mut workload = Workload::default();
+        for pr in prs.iter().filter(|pr| pr.state == PrState::Open) {
+            if pr.is_draft {
+                workload.drafts += 1;
+            } else if pr.review_requested {
+                workload.review_requested += 1;
+                workload.additions += u64::from(pr.additions);
+                workload.deletions += u64::from(pr.deletions);
+            }
+            if pr.ci_status == CiStatus::Failure {
+                workload.failing_ci += 1;
+            }
+        }
+        workload
+    }
+}
+
+/// Token counts in a few characters: 950, 12.3k, 1.2M
+pub fn compact_count(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", count as f64 / 1_000.0),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+
+/// Directory for persisted state: `$XDG_DATA_HOME/dstl`, falling back to `~/.local/share/dstl`
+pub fn data_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(base.join("dstl"))
+}
+
+/// Directory for user configuration: `$XDG_CONFIG_HOME/dstl`, falling back to `~/.config/dstl`
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("dstl"))
+}
+
+/// Load a JSON file from the data directory, returning None if missing or unreadable
+pub fn load_json<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let path = data_dir()?.join(name);
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Write a JSON file to the data directory, creating it if needed
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    let dir = data_dir().context("Could not determine data directory (HOME not set)")?;
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let path = dir.join(name);
+    let json = serde_json::to_string_pretty(value).context("Failed to serialize state")?;
+    std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Append a line to a file in the data directory, creating both if needed
+pub fn append_line(name: &str, line: &str) -> Result<()> {
+    use std::io::Write;
+
+    let dir = data_dir().context("Could not determine data directory (HOME not set)")?;
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let path = dir.join(name);
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", line).with_context(|| format!("Failed to write {}", path.display()))
+}
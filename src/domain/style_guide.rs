@@ -0,0 +1,85 @@
+//! The team style guide a repo keeps in `.dstl/styleguide.md`, which the model
+//! cites when a suggestion follows from one of its rules.
+
+use std::path::PathBuf;
+
+use tracing::warn;
+
+use super::github::fetch_file_at;
+use super::storage::data_dir;
+
+/// Where a repo keeps its style guide, read at the PR's base commit
+pub const STYLE_GUIDE_PATH: &str = ".dstl/styleguide.md";
+
+/// Longest guide included in the prompt; the rest is cut off
+const MAX_STYLE_GUIDE_CHARS: usize = 20_000;
+
+/// How the model marks a point taken from the guide, e.g. `[style: Errors]`
+pub const CITATION_PREFIX: &str = "[style: ";
+
+/// The repo's style guide at `sha`, read from the local cache when this commit
+/// was fetched before. A repo without one is cached too, so it's only asked once.
+pub fn load(owner: &str, repo: &str, sha: &str) -> Option<String> {
+    let cached = cache_path(owner, repo, sha);
+    if let Some(text) = cached.as_ref().and_then(|path| std::fs::read_to_string(path).ok()) {
+        return limit(text);
+    }
+
+    let text = match fetch_file_at(owner, repo, STYLE_GUIDE_PATH, sha) {
+        Ok(text) => text,
+        // Not there at this commit; anything else may work next time
+        Err(err) if err.to_string().contains("404") => String::new(),
+        Err(_) => return None,
+    };
+    if let Some(path) = &cached {
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(path, &text));
+        if let Err(err) = written {
+            warn!(path = %path.display(), error = %err, "Could not cache style guide");
+        }
+    }
+    limit(text)
+}
+
+/// `<data dir>/styleguides/<owner>/<repo>/<sha>.md`
+fn cache_path(owner: &str, repo: &str, sha: &str) -> Option<PathBuf> {
+    if sha.is_empty() {
+        return None;
+    }
+    Some(data_dir()?.join("styleguides").join(owner).join(repo).join(format!("{}.md", sha)))
+}
+
+/// Drop an empty guide and cut a long one down to the prompt budget
+fn limit(text: String) -> Option<String> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    if text.chars().count() <= MAX_STYLE_GUIDE_CHARS {
+        return Some(text);
+    }
+    let mut text: String = text.chars().take(MAX_STYLE_GUIDE_CHARS).collect();
+    text.push_str("\n\n(The rest of the guide was cut to fit the prompt.)\n");
+    Some(text)
+}
+
+/// Split text into plain runs and `[style: ...]` citations, in order
+pub fn split_citations(text: &str) -> Vec<(&str, bool)> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(CITATION_PREFIX) {
+        let Some(len) = rest[start..].find(']') else {
+            break;
+        };
+        if start > 0 {
+            parts.push((&rest[..start], false));
+        }
+        parts.push((&rest[start..start + len + 1], true));
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push((rest, false));
+    }
+    parts
+}
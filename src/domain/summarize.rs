@@ -0,0 +1,78 @@
+//! Stories for PRs too large to send whole: each changed file is summarized by
+//! its own cheap model call (map), and the story is built from the summaries in
+//! one final call (reduce). The story cites hunks by header, and their lines are
+//! filled back in from the diff.
+
+use super::diff::{parse_diff, parse_new_start, parse_old_range, retain_files, FileDiff};
+use super::types::{PrContext, Story};
+
+pub const MAP_SYSTEM_PROMPT: &str = "You summarize one file's changes from a pull request for a reviewer who will read summaries of every file before the whole story of the PR is written. In at most 8 short bullet points, say what changed and why it seems to have changed, name the functions, types and interfaces touched, and flag anything risky or surprising. Refer to hunks by their `@@` headers where it helps. Plain text only.";
+
+/// One file's summary request
+#[derive(Debug, Clone)]
+pub struct FileRequest {
+    pub path: String,
+    /// Headers the story will cite this file's hunks by
+    pub hunks: Vec<String>,
+    pub prompt: String,
+}
+
+/// Whether `pr`'s diff is past `threshold` bytes (0 never splits)
+pub fn needs_map(pr: &PrContext, threshold: usize) -> bool {
+    threshold > 0 && pr.diff.len() > threshold
+}
+
+/// A summary request for each changed file the story covers that passes `keep`
+pub fn file_requests(pr: &PrContext, keep: impl Fn(&str) -> bool) -> Vec<FileRequest> {
+    parse_diff(&pr.diff)
+        .into_iter()
+        .filter(|file| !file.hunks.is_empty() && keep(&file.path))
+        .filter(|file| {
+            pr.excluded_files.iter().all(|excluded| excluded.path != file.path)
+                && !pr.is_out_of_scope(&file.path)
+        })
+        .map(|file| {
+            let diff = retain_files(&pr.diff, |path| path == file.path);
+            FileRequest {
+                hunks: hunk_headers(&file),
+                prompt: format!(
+                    "PR: {}/{}#{} {}\n\n## Diff of `{}`\n\n```diff\n{}```\n",
+                    pr.owner, pr.repo, pr.number, pr.title, file.path, diff
+                ),
+                path: file.path,
+            }
+        })
+        .collect()
+}
+
+fn hunk_headers(file: &FileDiff) -> Vec<String> {
+    file.hunks
+        .iter()
+        .map(|hunk| format!("@@ -{} +{} @@ {}", hunk.old_start, hunk.new_start, file.path))
+        .collect()
+}
+
+/// Put the diff lines back into story hunks the model cited by header only
+pub fn fill_hunks(story: &mut Story, diff: &str) {
+    let files = parse_diff(diff);
+    let hunks = story
+        .narrative
+        .iter_mut()
+        .flat_map(|feature| &mut feature.diff_blocks)
+        .flat_map(|block| &mut block.hunks)
+        .filter(|hunk| hunk.lines.trim().is_empty());
+    for hunk in hunks {
+        let Some(file) = files.iter().find(|file| hunk.header.ends_with(file.path.as_str())) else {
+            continue;
+        };
+        let old_start = parse_old_range(&hunk.header).map(|(start, _)| start);
+        let new_start = parse_new_start(&hunk.header);
+        if let Some(found) = file
+            .hunks
+            .iter()
+            .find(|found| Some(found.old_start) == old_start && Some(found.new_start) == new_start)
+        {
+            hunk.lines = found.lines.join("\n");
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Functions and types the PR adds, removes or modifies, found from declaration
+//! keywords per language, with the story blocks that touch each.
+
+use super::diff::{block_touches, FileDiff};
+use super::types::{DiffBlock, Story};
+
+/// How a symbol changed in the PR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl SymbolChange {
+    pub fn sign(self) -> &'static str {
+        match self {
+            SymbolChange::Added => "+",
+            SymbolChange::Removed => "-",
+            SymbolChange::Modified => "~",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedSymbol {
+    pub name: String,
+    /// `fn` or `type`
+    pub kind: &'static str,
+    pub path: String,
+    pub change: SymbolChange,
+    /// (feature_idx, diff_idx) of blocks that change or use the symbol
+    pub blocks: Vec<(usize, usize)>,
+}
+
+/// Keywords that introduce a declaration, and what they declare, by file extension
+fn keywords(path: &str) -> &'static [(&'static str, &'static str)] {
+    let ext = path.rsplit_once('.').map_or("", |(_, ext)| ext);
+    match ext {
+        "rs" => &[
+            ("fn", "fn"),
+            ("struct", "type"),
+            ("enum", "type"),
+            ("trait", "type"),
+            ("type", "type"),
+            ("union", "type"),
+        ],
+        "go" => &[("func", "fn"), ("type", "type")],
+        "py" => &[("def", "fn"), ("class", "type")],
+        "rb" => &[("def", "fn"), ("class", "type"), ("module", "type")],
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => &[
+            ("function", "fn"),
+            ("class", "type"),
+            ("interface", "type"),
+            ("enum", "type"),
+            ("type", "type"),
+        ],
+        "java" | "kt" | "kts" | "scala" | "cs" | "swift" => &[
+            ("fun", "fn"),
+            ("func", "fn"),
+            ("def", "fn"),
+            ("class", "type"),
+            ("interface", "type"),
+            ("enum", "type"),
+            ("struct", "type"),
+            ("protocol", "type"),
+            ("object", "type"),
+            ("record", "type"),
+        ],
+        _ => &[],
+    }
+}
+
+/// The symbol a line of code declares, if any
+fn declaration(path: &str, code: &str) -> Option<(&'static str, String)> {
+    let keywords = keywords(path);
+    let code = code.trim_start();
+    // Comments and strings mention keywords without declaring anything
+    if ["//", "#", "*", "/*", "\"", "'"].iter().any(|prefix| code.starts_with(prefix)) {
+        return None;
+    }
+    let mut tokens = code
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '(' || c == ')'))
+        .filter(|token| !token.is_empty());
+    while let Some(token) = tokens.next() {
+        let Some(&(_, kind)) = keywords.iter().find(|(keyword, _)| *keyword == token) else {
+            continue;
+        };
+        let mut name = tokens.next()?;
+        // Go methods put the receiver first: func (s *Server) Handle(
+        if name.starts_with('(') {
+            if !name.contains(')') {
+                tokens.find(|token| token.contains(')'))?;
+            }
+            name = tokens.next()?;
+        }
+        let name = name.split('(').next()?;
+        let is_ident = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_');
+        return is_ident.then(|| (kind, name.to_string()));
+    }
+    None
+}
+
+/// Index the symbols each file's hunks declare or change, in diff order, with the
+/// story blocks that use each
+pub fn index_symbols(files: &[FileDiff], story: &Story) -> Vec<ChangedSymbol> {
+    let mut symbols = changed_symbols(files);
+    for symbol in &mut symbols {
+        let file = files.iter().find(|file| file.path == symbol.path);
+        symbol.blocks = story
+            .narrative
+            .iter()
+            .enumerate()
+            .flat_map(|(i, feature)| {
+                feature.diff_blocks.iter().enumerate().map(move |(j, block)| ((i, j), block))
+            })
+            .filter(|(_, block)| {
+                block_uses(block, &symbol.name)
+                    || (file.is_some_and(|file| block_touches(files, file, block))
+                        && mentions(block, &symbol.name, false))
+            })
+            .map(|(position, _)| position)
+            .collect();
+    }
+    symbols
+}
+
+/// The symbols each file's hunks declare or change, in diff order, without blocks
+pub fn changed_symbols(files: &[FileDiff]) -> Vec<ChangedSymbol> {
+    let mut symbols: Vec<ChangedSymbol> = Vec::new();
+    for file in files {
+        let mut note = |kind: &'static str, name: String, change: SymbolChange| {
+            match symbols.iter_mut().find(|s| s.path == file.path && s.name == name) {
+                Some(symbol) if symbol.change != change => symbol.change = SymbolChange::Modified,
+                Some(_) => {}
+                None => symbols.push(ChangedSymbol {
+                    name,
+                    kind,
+                    path: file.path.clone(),
+                    change,
+                    blocks: Vec::new(),
+                }),
+            }
+        };
+        for hunk in &file.hunks {
+            // Changes below a declaration (even an unchanged one) modify it
+            let mut enclosing: Option<(&'static str, String)> = None;
+            for line in &hunk.lines {
+                let (sign, code) = line.split_at(line.len().min(1));
+                let declared = declaration(&file.path, code);
+                match (sign, declared) {
+                    ("+", Some((kind, name))) => {
+                        note(kind, name, SymbolChange::Added);
+                        enclosing = None;
+                    }
+                    ("-", Some((kind, name))) => {
+                        note(kind, name, SymbolChange::Removed);
+                        enclosing = None;
+                    }
+                    ("+" | "-", None) => {
+                        if let Some((kind, name)) = enclosing.take() {
+                            note(kind, name, SymbolChange::Modified);
+                        }
+                    }
+                    (_, Some(declared)) => enclosing = Some(declared),
+                    _ => {}
+                }
+            }
+        }
+    }
+    symbols
+}
+
+/// Whether the block's changed lines use `name` as a word
+pub fn block_uses(block: &DiffBlock, name: &str) -> bool {
+    mentions(block, name, true)
+}
+
+/// Whether the block's lines (only changed ones if `changed_only`) use `name` as a word
+fn mentions(block: &DiffBlock, name: &str, changed_only: bool) -> bool {
+    block
+        .hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.lines())
+        .filter(|line| !changed_only || line.starts_with('+') || line.starts_with('-'))
+        .any(|line| {
+            line.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .any(|word| word == name)
+        })
+}
@@ -0,0 +1,29 @@
+//! Reusable review snippets from `config.toml`, e.g.
+//! `template."LGTM with nits" = "LGTM once the nits below are fixed, @{{author}}:\n"`,
+//! inserted into a draft with their placeholders filled in from the PR.
+
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+
+use super::types::PrContext;
+
+fn placeholder() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\{\{\s*(\w+)\s*\}\}").expect("valid pattern"))
+}
+
+/// `template` with each placeholder replaced by `pr`'s value; unknown ones are
+/// left as written so a typo shows up in the draft
+pub fn expand(template: &str, pr: &PrContext) -> String {
+    placeholder()
+        .replace_all(template, |found: &Captures| match &found[1] {
+            "pr_number" => pr.number.to_string(),
+            "author" => pr.author.clone(),
+            "title" => pr.title.clone(),
+            "repo" => format!("{}/{}", pr.owner, pr.repo),
+            "base_branch" => pr.base_branch.clone(),
+            _ => found[0].to_string(),
+        })
+        .into_owned()
+}
@@ -0,0 +1,462 @@
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::action::Action;
+use crate::domain::anonymize::Pseudonyms;
+use crate::domain::bitbucket::{self, BbActivity, BbPage, BbPullRequest};
+use crate::domain::cache::{self, CacheEntry, CachePolicy, PruneReason};
+use crate::domain::diff::parse_diff;
+use crate::domain::export;
+use crate::domain::github;
+use crate::domain::keychain;
+use crate::domain::listings::{self, now_secs};
+use crate::domain::privacy::Redaction;
+use crate::domain::prompt::build_user_prompt;
+use crate::domain::types::{
+    InlineComment, PrContext, PrState, QueueItem, Story, SymbolCallers,
+};
+use crate::domain::user_config::UserConfig;
+use crate::test_support::*;
+
+#[test]
+fn crash_reports_name_keys_and_results_but_not_their_contents() {
+    let key = Action::Input {
+        code: KeyCode::Char('z'),
+        modifiers: KeyModifiers::CONTROL,
+    };
+    assert_eq!(key.summary(), "Input Char('z') KeyModifiers(CONTROL)");
+    assert_eq!(Action::PrLoaded(Ok(pr_context())).summary(), "PrLoaded");
+    assert_eq!(Action::FocusChanged(true).summary(), "FocusChanged");
+    let generated = Action::StoryGenerated {
+        pr: PR_KEY.to_string(),
+        model: MODEL.to_string(),
+        result: Ok(story()),
+    };
+    assert_eq!(generated.summary(), "StoryGenerated acme/widgets#42 test-model");
+    let delta = Action::ReviewDeltaLoaded {
+        pr: PR_KEY.to_string(),
+        reviewed_head: "head000".to_string(),
+        result: Ok(DIFF.to_string()),
+    };
+    assert_eq!(delta.summary(), "ReviewDeltaLoaded acme/widgets#42");
+}
+
+#[test]
+fn viewed_files_are_read_past_the_first_page() {
+    // 130 files, every third one viewed, served 100 then 30
+    let page = |range: std::ops::Range<usize>, next: Option<&str>| {
+        let nodes: Vec<_> = range
+            .map(|i| {
+                let state = if i % 3 == 0 { "VIEWED" } else { "UNVIEWED" };
+                serde_json::json!({ "path": format!("src/f{}.rs", i), "viewerViewedState": state })
+            })
+            .collect();
+        serde_json::json!({
+            "id": "PR_kwDO42",
+            "files": {
+                "totalCount": 130,
+                "pageInfo": { "hasNextPage": next.is_some(), "endCursor": next },
+                "nodes": nodes,
+            }
+        })
+    };
+    let mut cursors = Vec::new();
+
+    let viewed = github::collect_viewed_files(|after| {
+        cursors.push(after.clone());
+        let json = match after.as_deref() {
+            None => page(0..100, Some("cursor-100")),
+            _ => page(100..130, None),
+        };
+        Ok(serde_json::from_value(json)?)
+    })
+    .unwrap();
+
+    assert_eq!(cursors, [None, Some("cursor-100".to_string())]);
+    assert_eq!(viewed.pr_id, "PR_kwDO42");
+    assert_eq!(viewed.paths.len(), 44);
+    assert!(viewed.paths.contains(&"src/f129.rs".to_string()));
+    assert!(!viewed.paths.contains(&"src/f128.rs".to_string()));
+}
+
+#[test]
+fn the_review_queue_groups_repos_by_their_most_pressing_pr_and_dates_each_wait() {
+    let item = |repo: &str, number: u32| QueueItem {
+        owner: OWNER.to_string(),
+        repo: repo.to_string(),
+        pr: pr_list_item(number, "Change"),
+    };
+    // Already ranked most pressing first
+    let mut queue =
+        vec![item("api", 3), item("web", 9), item("api", 5), item("web", 1), item("cli", 2)];
+    listings::group_by_repo(&mut queue);
+    let order: Vec<_> = queue.iter().map(|item| (item.repo.as_str(), item.pr.number)).collect();
+    assert_eq!(order, [("api", 3), ("api", 5), ("web", 9), ("web", 1), ("cli", 2)]);
+
+    assert_eq!(listings::parse_timestamp("2024-05-01T12:00:00Z"), Some(1_714_564_800));
+    assert_eq!(listings::format_timestamp(1_714_564_800), "2024-05-01T12:00:00Z");
+    assert_eq!(listings::parse_timestamp("yesterday"), None);
+    let opened = listings::parse_timestamp(&days_ago(3)).unwrap();
+    assert_eq!(listings::format_age(opened), "3d ago");
+}
+
+#[test]
+fn queue_repos_are_listed_side_by_side_in_bounded_batches() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let (running, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+    let repos: Vec<u32> = (0..20).collect();
+    let results = github::in_parallel(&repos, |&repo| {
+        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+        peak.fetch_max(now, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(20));
+        running.fetch_sub(1, Ordering::SeqCst);
+        anyhow::ensure!(repo != 5, "repo {} is private", repo);
+        Ok(repo * 10)
+    });
+
+    // In the order asked, each failure in its own place
+    assert_eq!(results.len(), 20);
+    assert_eq!(results[5].as_ref().unwrap_err().to_string(), "repo 5 is private");
+    let listed: Vec<u32> = results.into_iter().filter_map(Result::ok).collect();
+    let expected: Vec<u32> =
+        repos.iter().filter(|&&repo| repo != 5).map(|repo| repo * 10).collect();
+    assert_eq!(listed, expected);
+    let peak = peak.load(Ordering::SeqCst);
+    assert!(peak > 1 && peak <= 8, "{} gh calls ran at once", peak);
+}
+
+#[test]
+fn bitbucket_pr_urls_name_project_repo_and_number() {
+    let parse = bitbucket::parse_pr_url;
+    let url = "https://bitbucket.example.com/projects/PAY/repos/api/pull-requests/12/overview";
+    assert_eq!(parse(url), Some(("PAY".to_string(), "api".to_string(), 12)));
+    let under_context = "https://example.com/bitbucket/projects/PAY/repos/api/pull-requests/12/";
+    assert_eq!(parse(under_context), Some(("PAY".to_string(), "api".to_string(), 12)));
+
+    assert_eq!(parse("https://bitbucket.example.com/projects/PAY/repos/api/browse"), None);
+    assert_eq!(parse("https://bitbucket.example.com/projects/PAY/repos/api/pull-requests/x"), None);
+    assert_eq!(parse("https://github.com/acme/widgets/pull/42"), None);
+}
+
+#[test]
+fn bitbucket_pages_count_what_has_been_seen_and_stop_at_the_last() {
+    let page = |json: serde_json::Value| {
+        let page: BbPage<u32> = serde_json::from_value(json).unwrap();
+        page.into_page(|n| n * 10)
+    };
+
+    let first = page(serde_json::json!({
+        "values": [1, 2], "start": 50, "isLastPage": false, "nextPageStart": 52
+    }));
+    assert_eq!(first.items, [10, 20]);
+    assert_eq!(first.total, 52);
+    assert_eq!(first.next_cursor.as_deref(), Some("52"));
+
+    // Some servers still send nextPageStart on the last page
+    let last = page(serde_json::json!({
+        "values": [3], "start": 52, "isLastPage": true, "nextPageStart": 53
+    }));
+    assert_eq!((last.total, last.next_cursor), (53, None));
+}
+
+#[test]
+fn bitbucket_diffs_and_prs_read_like_githubs() {
+    let raw = "diff --git src://src/api.rs dst://src/api.rs\n\
+               --- src://src/api.rs\n\
+               +++ dst://src/api.rs\n\
+               @@ -1,1 +1,2 @@\n \
+               fn handler() {}\n\
+               +fn limit() {}\n";
+    let diff = bitbucket::normalize_diff(raw);
+    assert!(diff.starts_with("diff --git a/src/api.rs b/src/api.rs\n--- a/src/api.rs\n"));
+    assert!(diff.contains("+++ b/src/api.rs\n"));
+    let files = parse_diff(&diff);
+    assert_eq!(files.len(), 1);
+    assert_eq!((files[0].path.as_str(), files[0].additions), ("src/api.rs", 1));
+
+    let pr: BbPullRequest = serde_json::from_value(serde_json::json!({
+        "id": 12,
+        "title": "Limit requests",
+        "state": "DECLINED",
+        "createdDate": 1_767_225_600_000u64,
+        "updatedDate": 1_767_312_000_000u64,
+        "fromRef": { "displayId": "limit", "latestCommit": "head000" },
+        "toRef": { "displayId": "main", "latestCommit": "base000" },
+        "author": { "user": { "name": "octocat", "slug": "octocat" } },
+        "reviewers": [{ "user": { "name": "ana", "slug": "ana" }, "status": "UNAPPROVED" }],
+        "properties": { "commentCount": 3 }
+    }))
+    .unwrap();
+    let item = pr.into_list_item("ana");
+    assert_eq!((item.number, item.author.as_str()), (12, "octocat"));
+    assert_eq!((item.head_branch.as_str(), item.base_branch.as_str()), ("limit", "main"));
+    assert!(item.review_requested);
+    assert_eq!(item.state, PrState::Closed);
+    assert_eq!(item.created_at, "2026-01-01T00:00:00Z");
+    assert_eq!(item.comments, 3);
+
+    assert_eq!(bitbucket::encode_path("docs/read me#1?.md"), "docs/read%20me%231%3F.md");
+}
+
+#[test]
+fn bitbucket_review_retries_post_only_what_is_missing() {
+    let comment = |path: &str, line: u32, body: &str| InlineComment {
+        path: path.to_string(),
+        line,
+        body: body.to_string(),
+    };
+    let payload = bitbucket::inline_comment_payload(&comment("src/api.rs", 11, "Why not 429?"));
+    assert_eq!(payload["text"], "Why not 429?");
+    assert_eq!(payload["anchor"]["path"], "src/api.rs");
+    assert_eq!(payload["anchor"]["line"], 11);
+    assert_eq!(payload["anchor"]["lineType"], "ADDED");
+
+    let comments = [
+        comment("src/api.rs", 11, "Why not 429?"),
+        comment("src/limit.rs", 1, "Limit is per process"),
+    ];
+    let activity = |author: &str, text: &str, anchor: Option<(&str, u32)>| {
+        serde_json::json!({
+            "action": "COMMENTED",
+            "comment": { "id": 1, "text": text, "author": { "name": author, "slug": author } },
+            "commentAnchor": anchor.map(|(path, line)| serde_json::json!({
+                "path": path, "line": line
+            })),
+        })
+    };
+    let activities: Vec<BbActivity> = serde_json::from_value(serde_json::json!([
+        activity("ana", "Needs a test", None),
+        activity("ana", "Why not 429?", Some(("src/api.rs", 11))),
+        // Someone else's identical comment doesn't count as ours
+        activity("octocat", "Limit is per process", Some(("src/limit.rs", 1))),
+        { "action": "APPROVED" },
+    ]))
+    .unwrap();
+
+    // The first attempt failed after the body and one comment went out
+    let (body, remaining) = bitbucket::unposted(&activities, "ana", "Needs a test", &comments);
+    assert_eq!(body, None);
+    assert_eq!(remaining, [comments[1].clone()]);
+
+    let (body, remaining) = bitbucket::unposted(&[], "ana", "Needs a test", &comments);
+    assert_eq!(body, Some("Needs a test"));
+    assert_eq!(remaining, comments);
+}
+
+#[test]
+fn bot_reruns_update_the_marked_comment_rather_than_posting_a_second() {
+    let posted = export::bot_comment(&pr_context(), &story());
+    let comments = vec![
+        (1u64, "LGTM".to_string()),
+        (2, posted.clone()),
+        (3, format!("Quoting the bot: {}", posted)),
+    ];
+    assert_eq!(export::marked_comment(comments, export::BOT_MARKER), Some(2));
+    let unmarked = vec![(1u64, "LGTM".to_string())];
+    assert_eq!(export::marked_comment(unmarked, export::BOT_MARKER), None);
+
+    // On Bitbucket the edit has to name the version it replaces
+    let activities: Vec<BbActivity> = serde_json::from_value(serde_json::json!([
+        { "action": "APPROVED" },
+        {
+            "action": "COMMENTED",
+            "comment": {
+                "id": 7, "version": 3, "text": posted, "author": { "name": "ci", "slug": "ci" }
+            }
+        },
+    ]))
+    .unwrap();
+    assert_eq!(bitbucket::marked_comment(&activities, export::BOT_MARKER), Some((7, 3)));
+    assert_eq!(bitbucket::marked_comment(&activities[..1], export::BOT_MARKER), None);
+}
+
+#[test]
+fn callers_of_modified_functions_reach_the_prompt() {
+    let pr = PrContext {
+        callers: vec![SymbolCallers {
+            symbol: "handle".to_string(),
+            path: "src/api.rs".to_string(),
+            total: 12,
+            files: vec!["src/server.rs".to_string(), "src/routes.rs".to_string()],
+        }],
+        ..pr_context()
+    };
+
+    let prompt = build_user_prompt(&pr);
+    assert!(prompt.contains("## Impact"));
+    assert!(prompt.contains(
+        "- `handle` (changed in `src/api.rs`): referenced from 12 other files: \
+         `src/server.rs`, `src/routes.rs`, ..."
+    ));
+    assert!(!build_user_prompt(&pr_context()).contains("## Impact"));
+}
+
+#[test]
+fn config_lines_that_cannot_be_read_are_errors_naming_the_line() {
+    let error = |contents: &str| format!("{:#}", UserConfig::parse(contents).unwrap_err());
+
+    assert_eq!(
+        error("model = \"gpt-5\"\nredact.path = \"*.pem\"\n"),
+        "Line 2: Unknown setting `redact.path`"
+    );
+    assert_eq!(error("# keys\napi_key: \"sk\"\n"), "Line 2: Expected `key = \"value\"`");
+    assert_eq!(error("model = gpt-5\n"), "Line 1: Expected a quoted value");
+    assert_eq!(error("model = \"gpt-5\n"), "Line 1: Missing closing quote");
+    assert_eq!(error("model = \"a\" \"b\"\n"), "Line 1: Unexpected `\"b\"` after the value");
+
+    let config = UserConfig::parse(
+        "# comment\n\ntemperature = 0.2  # warmer\nmax_output_tokens = 4000\n\
+         template.\"Ask = why\" = \"Why \\\"this\\\"?\"\n",
+    )
+    .unwrap();
+    assert_eq!(config.temperature.as_deref(), Some("0.2"));
+    assert_eq!(config.max_output_tokens.as_deref(), Some("4000"));
+    assert_eq!(config.templates["Ask = why"], "Why \"this\"?");
+    assert_eq!(UserConfig::parse(&config.to_toml()).unwrap(), config);
+}
+
+#[test]
+fn redaction_rules_are_read_whole_or_rejected() {
+    // A pattern containing `=`, and the same rules written as tables
+    let dotted = UserConfig::parse(
+        "redact.paths = \"*.pem, internal/**\"\nredact.replace.\"token=\\\\w+\" = \"token=X\"\n",
+    )
+    .unwrap();
+    let tables = UserConfig::parse(
+        "[redact]\npaths = \"*.pem, internal/**\"\n\n\
+         [redact.replace]\n\"token=\\\\w+\" = \"token=X\"\n",
+    )
+    .unwrap();
+    assert_eq!(tables, dotted);
+    assert_eq!(dotted.redact_paths, ["*.pem", "internal/**"]);
+    let redaction = Redaction::new(&dotted.redact_paths, &dotted.redact_replace).unwrap();
+    assert_eq!(redaction.replace("auth token=abc123 sent"), "auth token=X sent");
+    assert!(redaction.withholds("internal/billing.rs"));
+
+    let error = |contents: &str| format!("{:#}", UserConfig::parse(contents).unwrap_err());
+    assert_eq!(
+        error("[redact]\nreplace.\"acme-(\" = \"CLIENT\"\n").lines().next().unwrap(),
+        "Line 2: Invalid redact.replace pattern \"acme-(\": regex parse error:"
+    );
+    assert_eq!(error("[redact\npaths = \"*.pem\"\n"), "Line 1: Expected `]`");
+    assert_eq!(error("[redact]\npath = \"*.pem\"\n"), "Line 2: Unknown setting `redact.path`");
+}
+
+#[test]
+fn keys_reach_the_keyring_on_stdin_never_the_command_line() {
+    let key = "sk-proj-\"quoted\\key";
+    for macos in [true, false] {
+        let command = keychain::store_command("openai", key, macos);
+        assert!(command.args.iter().all(|arg| !arg.contains("sk-proj")), "{:?}", command);
+        assert!(command.stdin.contains("sk-proj"));
+    }
+
+    let security = keychain::store_command("openai", key, true);
+    assert_eq!(security.args, ["-i"]);
+    assert_eq!(
+        security.stdin,
+        "add-generic-password -U -s \"dstl\" -a \"openai\" -w \"sk-proj-\\\"quoted\\\\key\"\n"
+    );
+    let secret_tool = keychain::store_command("openai", key, false);
+    assert_eq!(secret_tool.stdin, key);
+    assert_eq!(secret_tool.keyring, "the Secret Service keyring");
+}
+
+#[test]
+fn cache_prune_drops_expired_and_moved_entries() {
+    let cache_file = scratch_cache_file("prune");
+    let policy = CachePolicy {
+        ttl: Some(Duration::from_secs(24 * 60 * 60)),
+        max_bytes: u64::MAX,
+    };
+    let entry = |number: u32, head: &str, age_hours: u64| CacheEntry {
+        number,
+        created_at: now_secs() - age_hours * 60 * 60,
+        ..cache_entry(head)
+    };
+    write_cache(
+        &cache_file,
+        &[entry(1, "head000", 48), entry(2, "0ld5ha1abc", 1), entry(3, "head000", 1)],
+    );
+
+    let dropped = cache::prune(&cache_file, &policy, |_| Some("head000".to_string())).unwrap();
+    let dropped: Vec<_> = dropped.iter().map(|(entry, reason)| (entry.number, *reason)).collect();
+    assert_eq!(dropped, [(1, PruneReason::Expired), (2, PruneReason::HeadMoved)]);
+    let kept: Vec<u32> = cache::list(&cache_file).iter().map(|(_, entry)| entry.number).collect();
+    assert_eq!(kept, [3]);
+
+    // Found while fresh, but a story from another head or diff is stale
+    let found = cache::find(&cache_file, OWNER, REPO, 3, &policy).unwrap();
+    let mut pr = PrContext {
+        number: 3,
+        ..pr_context()
+    };
+    assert!(!found.is_stale(&pr));
+    pr.head_sha = "head111".to_string();
+    assert!(found.is_stale(&pr));
+    pr.head_sha = "head000".to_string();
+    pr.diff.push_str("+// rebased\n");
+    assert!(found.is_stale(&pr));
+    assert!(cache::find(&cache_file, OWNER, REPO, 2, &policy).is_none());
+
+    // An expired entry isn't found even before a prune removes it
+    write_cache(&cache_file, &[entry(PR_NUMBER, "head000", 48)]);
+    assert!(cache::find(&cache_file, OWNER, REPO, PR_NUMBER, &policy).is_none());
+    std::fs::remove_dir_all(std::path::Path::new(&cache_file).parent().unwrap()).unwrap();
+}
+
+#[test]
+fn cache_over_its_size_limit_evicts_the_oldest_story_across_model_files() {
+    let cache_file = scratch_cache_file("evict");
+    let sibling = cache_file.replace(".json", ".model-b.json");
+    let entry = |number: u32, age_hours: u64| CacheEntry {
+        number,
+        created_at: now_secs() - age_hours * 60 * 60,
+        ..cache_entry("head000")
+    };
+    write_cache(&cache_file, &[entry(1, 2), entry(2, 1)]);
+    write_cache(&sibling, &[entry(3, 3)]);
+    let size: u64 = cache::files(&cache_file)
+        .iter()
+        .map(|path| std::fs::metadata(path).unwrap().len())
+        .sum();
+
+    // One byte over: only the oldest story, in the compared model's file, goes
+    let policy = CachePolicy {
+        ttl: None,
+        max_bytes: size - 1,
+    };
+    let dropped = cache::prune(&cache_file, &policy, |_| None).unwrap();
+    let dropped: Vec<_> = dropped.iter().map(|(entry, reason)| (entry.number, *reason)).collect();
+    assert_eq!(dropped, [(3, PruneReason::Evicted)]);
+    assert!(!std::path::Path::new(&sibling).exists());
+    let kept: Vec<u32> = cache::list(&cache_file).iter().map(|(_, entry)| entry.number).collect();
+    assert_eq!(kept, [2, 1]);
+    std::fs::remove_dir_all(std::path::Path::new(&cache_file).parent().unwrap()).unwrap();
+}
+
+#[test]
+fn pseudonyms_hide_short_stems_only_where_they_stand_alone() {
+    let pr = PrContext {
+        diff: "diff --git a/app.ts b/app.ts\n--- a/app.ts\n+++ b/app.ts\n\
+               @@ -1 +1 @@\n-let mode;\n+let mode = 1;\n"
+            .to_string(),
+        ..pr_context()
+    };
+    let pseudonyms = Pseudonyms::for_pr(&pr);
+
+    let hidden = pseudonyms.hide("the application in app.ts, see app/, not my_app or app_id");
+    assert_eq!(
+        hidden,
+        "the application in ANON_PATH_1.ts, see ANON_PATH_1/, not my_app or app_id"
+    );
+
+    let story = Story {
+        summary: hidden,
+        ..story()
+    };
+    let revealed = pseudonyms.reveal(story).unwrap();
+    assert_eq!(revealed.summary, "the application in app.ts, see app/, not my_app or app_id");
+}
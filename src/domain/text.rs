@@ -0,0 +1,22 @@
+//! Cursor steps over what reads as one character (a grapheme cluster), so an
+//! accented letter, an emoji or a CJK character moves and deletes as a whole.
+//! Positions are byte indices into the text, always on a cluster boundary.
+
+use unicode_segmentation::GraphemeCursor;
+
+/// The boundary before `pos`, or 0 at the start
+pub fn prev_boundary(text: &str, pos: usize) -> usize {
+    let mut cursor = GraphemeCursor::new(pos.min(text.len()), text.len(), true);
+    cursor.prev_boundary(text, 0).ok().flatten().unwrap_or(0)
+}
+
+/// The boundary after `pos`, or the end of the text
+pub fn next_boundary(text: &str, pos: usize) -> usize {
+    let mut cursor = GraphemeCursor::new(pos.min(text.len()), text.len(), true);
+    cursor.next_boundary(text, 0).ok().flatten().unwrap_or(text.len())
+}
+
+/// Remove the last cluster, as Backspace at the end of the text does
+pub fn pop_grapheme(text: &mut String) {
+    text.truncate(prev_boundary(text, text.len()));
+}
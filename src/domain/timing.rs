@@ -0,0 +1,188 @@
+//! How long the reviewer spends on each diff block, and the compact review report
+//! built from it at submit time.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::storage;
+use super::types::{Significance, Story};
+
+const REPORTS_FILE: &str = "review_reports.jsonl";
+
+/// Longest gap between inputs credited to a block; anything beyond is time away
+const IDLE_LIMIT: Duration = Duration::from_secs(5 * 60);
+
+/// Dwell time per diff block: (feature_idx, diff_idx)
+#[derive(Debug, Clone, Default)]
+pub struct ReviewClock {
+    spent: HashMap<(usize, usize), Duration>,
+    current: Option<((usize, usize), Instant)>,
+}
+
+impl ReviewClock {
+    /// Credit the time since the last tick to the block selected then, and start
+    /// timing `block` (None pauses the clock)
+    pub fn tick(&mut self, block: Option<(usize, usize)>, now: Instant) {
+        if let Some((previous, since)) = self.current.take() {
+            let elapsed = now.saturating_duration_since(since).min(IDLE_LIMIT);
+            *self.spent.entry(previous).or_default() += elapsed;
+        }
+        self.current = block.map(|block| (block, now));
+    }
+
+    pub fn spent_on(&self, block: (usize, usize)) -> Duration {
+        self.spent.get(&block).copied().unwrap_or_default()
+    }
+
+    pub fn feature_total(&self, feature_idx: usize) -> Duration {
+        self.spent
+            .iter()
+            .filter(|((feature, _), _)| *feature == feature_idx)
+            .map(|(_, spent)| *spent)
+            .sum()
+    }
+
+    pub fn total(&self) -> Duration {
+        self.spent.values().sum()
+    }
+}
+
+/// What happens to the review report when requested changes are posted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportMode {
+    #[default]
+    Off,
+    /// Appended to the review body
+    Append,
+    /// Kept locally in `review_reports.jsonl`
+    Save,
+}
+
+impl ReportMode {
+    pub fn next(self) -> Self {
+        match self {
+            ReportMode::Off => ReportMode::Append,
+            ReportMode::Append => ReportMode::Save,
+            ReportMode::Save => ReportMode::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReportMode::Off => "off",
+            ReportMode::Append => "append to review",
+            ReportMode::Save => "save locally",
+        }
+    }
+}
+
+/// What went into a review, appended to its body or kept locally
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewReport {
+    /// `owner/repo#123`
+    pub pr: String,
+    pub submitted_at: u64,
+    pub seconds: u64,
+    pub viewed: usize,
+    pub blocks: usize,
+    /// Noise blocks left unviewed
+    pub noise_skipped: usize,
+    pub features: Vec<FeatureReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureReport {
+    pub title: String,
+    pub seconds: u64,
+    pub viewed: usize,
+    pub blocks: usize,
+}
+
+impl ReviewReport {
+    pub fn new(
+        pr: String,
+        submitted_at: u64,
+        story: &Story,
+        clock: &ReviewClock,
+        viewed: &HashSet<(usize, usize)>,
+    ) -> Self {
+        let features: Vec<FeatureReport> = story
+            .narrative
+            .iter()
+            .enumerate()
+            .map(|(feature_idx, feature)| FeatureReport {
+                title: feature.title.clone(),
+                seconds: clock.feature_total(feature_idx).as_secs(),
+                viewed: (0..feature.diff_blocks.len())
+                    .filter(|&diff_idx| viewed.contains(&(feature_idx, diff_idx)))
+                    .count(),
+                blocks: feature.diff_blocks.len(),
+            })
+            .collect();
+        let noise_skipped = story
+            .narrative
+            .iter()
+            .enumerate()
+            .flat_map(|(feature_idx, feature)| {
+                feature.diff_blocks.iter().enumerate().filter(move |(diff_idx, block)| {
+                    block.significance == Significance::Noise
+                        && !viewed.contains(&(feature_idx, *diff_idx))
+                })
+            })
+            .count();
+        Self {
+            pr,
+            submitted_at,
+            seconds: clock.total().as_secs(),
+            viewed: features.iter().map(|feature| feature.viewed).sum(),
+            blocks: features.iter().map(|feature| feature.blocks).sum(),
+            noise_skipped,
+            features,
+        }
+    }
+
+    /// Percentage of blocks viewed, rounded down
+    pub fn percent_viewed(&self) -> usize {
+        (self.viewed * 100).checked_div(self.blocks).unwrap_or(0)
+    }
+
+    /// One summary line plus a row per feature, for the end of a review body
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "**Review report:** {} spent, {}% of blocks viewed ({}/{}), {} noise skipped\n",
+            format_duration(self.seconds),
+            self.percent_viewed(),
+            self.viewed,
+            self.blocks,
+            self.noise_skipped,
+        );
+        for feature in &self.features {
+            out.push_str(&format!(
+                "- {}: {}, {}/{} viewed\n",
+                feature.title,
+                format_duration(feature.seconds),
+                feature.viewed,
+                feature.blocks,
+            ));
+        }
+        out
+    }
+
+    /// Append this report as a line of `review_reports.jsonl` in the data directory
+    pub fn save(&self) -> Result<()> {
+        let json = serde_json::to_string(self).context("Failed to serialize review report")?;
+        storage::append_line(REPORTS_FILE, &json)
+    }
+}
+
+/// Compact duration, e.g. "45s", "12m", "1h 05m"
+pub fn format_duration(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        _ => format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
@@ -1,5 +1,11 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use super::codeowners::OwnerRule;
+use super::diff::{block_file, block_head_range, FileDiff};
+use super::stack::{ParentPr, StackEntry};
+
 /// The complete story generated by the LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Story {
@@ -8,18 +14,87 @@ pub struct Story {
     pub narrative: Vec<Feature>,
     pub data: PrStats,
     pub open_questions: Vec<String>,
+    /// Changed code that looks untested, as the model sees it
+    #[serde(default)]
+    pub coverage_gaps: Vec<CoverageGap>,
+    /// What changed since the reviewer's last review, when the prompt had a delta
+    #[serde(default)]
+    pub whats_new: Vec<String>,
+    /// Changes to the public API that can break its users, as the model sees them
+    #[serde(default)]
+    pub breaking_changes: Vec<BreakingChange>,
     pub suggested_changes: String,
     pub clarification_questions: String,
     pub next_pr: String,
 }
 
+impl Story {
+    /// Every diff block as (feature, block) in the order to read them: key changes
+    /// before standard ones before noise, roots before what depends on them, and
+    /// story order otherwise
+    pub fn reading_order(&self) -> Vec<(usize, usize)> {
+        let mut order: Vec<(usize, usize)> = self
+            .narrative
+            .iter()
+            .enumerate()
+            .flat_map(|(i, feature)| (0..feature.diff_blocks.len()).map(move |j| (i, j)))
+            .collect();
+        order.sort_by_key(|&(i, j)| {
+            let block = &self.narrative[i].diff_blocks[j];
+            let significance = match block.significance {
+                Significance::Key => 0,
+                Significance::Standard => 1,
+                Significance::Noise => 2,
+            };
+            let role = match block.role {
+                DiffRole::Root => 0,
+                DiffRole::Downstream => 1,
+                DiffRole::Supporting => 2,
+            };
+            (significance, role)
+        });
+        order
+    }
+}
+
+impl Story {
+    /// Every feature's risks as (feature index, risk), most severe first
+    pub fn risks_by_severity(&self) -> Vec<(usize, &Risk)> {
+        let mut risks: Vec<(usize, &Risk)> = self
+            .narrative
+            .iter()
+            .enumerate()
+            .flat_map(|(i, feature)| feature.risks.iter().map(move |risk| (i, risk)))
+            .collect();
+        // Unrated risks (None) go last
+        risks.sort_by_key(|(_, risk)| risk.severity.map_or(3, |severity| severity as u8));
+        risks
+    }
+}
+
+/// Changed code the PR doesn't appear to test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageGap {
+    /// `path` or `path:function`
+    pub target: String,
+    /// What's left untested
+    pub reason: String,
+}
+
+impl CoverageGap {
+    /// The file part of the target
+    pub fn path(&self) -> &str {
+        self.target.split(':').next().unwrap_or(&self.target).trim()
+    }
+}
+
 /// A logical feature/concern grouping changes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Feature {
     pub title: String,
     pub why: String,
     pub changes: Vec<String>,
-    pub risks: Vec<String>,
+    pub risks: Vec<Risk>,
     pub tests: Vec<String>,
     pub diff_blocks: Vec<DiffBlock>,
 }
@@ -32,6 +107,119 @@ pub struct DiffBlock {
     pub significance: Significance,
     pub context: String,
     pub hunks: Vec<Hunk>,
+    /// Other blocks this one defines, consumes or mirrors
+    #[serde(default)]
+    pub related_blocks: Vec<BlockRef>,
+}
+
+/// A pointer from one diff block to another, e.g. a call site to the type it uses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockRef {
+    /// `feature.block`, both 1-based as in "2.1"
+    pub block: String,
+    /// How the two blocks relate
+    pub note: String,
+}
+
+impl BlockRef {
+    /// The referenced block as 0-based (feature, block) indices
+    pub fn position(&self) -> Option<(usize, usize)> {
+        block_position(&self.block)
+    }
+}
+
+/// Parse a 1-based `feature.block` number such as "2.1" into 0-based indices
+fn block_position(number: &str) -> Option<(usize, usize)> {
+    let (feature, block) = number.trim().split_once('.')?;
+    let feature: usize = feature.parse().ok()?;
+    let block: usize = block.parse().ok()?;
+    Some((feature.checked_sub(1)?, block.checked_sub(1)?))
+}
+
+/// Something that could go wrong with a feature, as the story rates it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "RiskEntry")]
+pub struct Risk {
+    pub text: String,
+    /// None for stories cached before risks were rated
+    pub severity: Option<Severity>,
+    /// `feature.block` numbers of the blocks that mitigate it
+    pub addressed_by: Vec<String>,
+}
+
+impl Risk {
+    /// Mitigating blocks as 0-based (feature, block) indices
+    pub fn addressing_blocks(&self) -> Vec<(usize, usize)> {
+        self.addressed_by
+            .iter()
+            .filter_map(|number| block_position(number))
+            .collect()
+    }
+}
+
+/// A risk as stored: rated, or a plain string from older stories
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RiskEntry {
+    Plain(String),
+    Rated {
+        text: String,
+        severity: Option<Severity>,
+        #[serde(default)]
+        addressed_by: Vec<String>,
+    },
+}
+
+impl From<RiskEntry> for Risk {
+    fn from(entry: RiskEntry) -> Self {
+        match entry {
+            RiskEntry::Plain(text) => Risk {
+                text,
+                severity: None,
+                addressed_by: Vec::new(),
+            },
+            RiskEntry::Rated {
+                text,
+                severity,
+                addressed_by,
+            } => Risk {
+                text,
+                severity,
+                addressed_by,
+            },
+        }
+    }
+}
+
+/// A change to the public API that can break code depending on it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakingChange {
+    /// The item affected, e.g. `parse in src/lib.rs`
+    pub item: String,
+    pub description: String,
+    pub severity: Severity,
+    /// Found by scanning the diff rather than flagged by the model
+    #[serde(default)]
+    pub detected: bool,
+}
+
+/// How badly a risk would hurt if it came true
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    High,
+    Medium,
+    Low,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::High => "HIGH",
+            Severity::Medium => "MED",
+            Severity::Low => "LOW",
+        }
+    }
 }
 
 /// The role of a diff block in the dependency chain
@@ -77,6 +265,17 @@ pub struct Hunk {
     pub lines: String,
 }
 
+/// A closer look at one feature, generated on request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeepDive {
+    /// How the feature works and where it could go wrong, in more depth
+    pub analysis: String,
+    /// What must hold before and after the change
+    pub invariants: Vec<String>,
+    pub edge_cases: Vec<String>,
+    pub suggested_tests: Vec<String>,
+}
+
 /// Statistics about the PR
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrStats {
@@ -85,8 +284,25 @@ pub struct PrStats {
     pub deletions: u32,
 }
 
+impl PrStats {
+    /// The counts as the diff itself has them
+    pub fn from_files<'a>(files: impl IntoIterator<Item = &'a ChangedFile>) -> Self {
+        let mut stats = PrStats {
+            files_touched: 0,
+            additions: 0,
+            deletions: 0,
+        };
+        for file in files {
+            stats.files_touched += 1;
+            stats.additions += file.additions;
+            stats.deletions += file.deletions;
+        }
+        stats
+    }
+}
+
 /// PR metadata fetched from GitHub
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrContext {
     pub owner: String,
     pub repo: String,
@@ -97,6 +313,527 @@ pub struct PrContext {
     pub author: String,
     pub base_branch: String,
     pub head_branch: String,
+    /// Commit SHA at the head of the PR branch
+    pub head_sha: String,
+    /// Commit SHA of the base branch the PR targets
+    pub base_sha: String,
+    /// Review comments left by bots (Copilot, Danger, linters)
+    pub bot_comments: Vec<BotComment>,
+    /// Issues referenced from the PR body
+    pub linked_issues: Vec<LinkedIssue>,
+    /// Contents at the head commit of the most heavily changed files
+    pub head_files: HashMap<String, String>,
+    /// Code surrounding the hunks of those files, included in the prompt
+    pub surrounding_code: Vec<CodeWindow>,
+    /// Blame at the base commit for the most heavily changed files (opt-in)
+    pub base_blame: HashMap<String, Vec<BlameRange>>,
+    /// Callers elsewhere in the repo of functions the PR modifies or removes (opt-in)
+    #[serde(default)]
+    pub callers: Vec<SymbolCallers>,
+    /// Per-file summaries sent in place of a diff too large to send whole
+    #[serde(default)]
+    pub file_summaries: Vec<FileSummary>,
+    /// Review instructions the repo keeps in `.dstl/prompt.md` at the base commit
+    pub repo_prompt: Option<CustomPrompt>,
+    /// The reviewer's past feedback on this repo, from their review memory
+    #[serde(default)]
+    pub review_memory: Option<CustomPrompt>,
+    /// The repo's `.dstl/styleguide.md` at the base commit, cut to the prompt budget
+    #[serde(default)]
+    pub style_guide: Option<String>,
+    /// Path patterns the review is limited to (`--paths`); empty means the whole PR
+    #[serde(default)]
+    pub scope: Vec<String>,
+    /// Changed files outside `scope`, left out of the prompt entirely
+    #[serde(default)]
+    pub out_of_scope: Vec<String>,
+    /// CODEOWNERS rules at the base commit, in file order
+    #[serde(default)]
+    pub code_owners: Vec<OwnerRule>,
+    /// The stack of PRs this one is part of, bottom first; empty when it isn't stacked
+    #[serde(default)]
+    pub stack: Vec<StackEntry>,
+    /// The PR this one is stacked on
+    #[serde(default)]
+    pub parent: Option<ParentPr>,
+    /// The commits `diff` is limited to, when it doesn't cover the whole PR
+    #[serde(default)]
+    pub commit_range: Option<CommitRange>,
+    /// What changed since the head the reviewer last reviewed, when it has moved
+    #[serde(default)]
+    pub review_delta: Option<ReviewDelta>,
+    /// Changed files left out of the prompt
+    pub excluded_files: Vec<ExcludedFile>,
+}
+
+impl PrContext {
+    pub fn pr_label(&self) -> String {
+        format!("{}/{}#{}", self.owner, self.repo, self.number)
+    }
+
+    pub fn is_out_of_scope(&self, path: &str) -> bool {
+        self.out_of_scope.iter().any(|other| other == path)
+    }
+
+    /// Files left out of the prompt that are still within the review scope
+    pub fn excluded_in_scope(&self) -> Vec<&ExcludedFile> {
+        self.excluded_files
+            .iter()
+            .filter(|file| !self.is_out_of_scope(&file.path))
+            .collect()
+    }
+}
+
+/// How a file changed in the PR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeType {
+    Added,
+    Deleted,
+    Renamed,
+    Modified,
+}
+
+impl ChangeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeType::Added => "added",
+            ChangeType::Deleted => "deleted",
+            ChangeType::Renamed => "renamed",
+            ChangeType::Modified => "modified",
+        }
+    }
+}
+
+/// A changed file whose diff isn't sent to the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExcludedFile {
+    pub path: String,
+    pub additions: u32,
+    pub deletions: u32,
+    pub change: ChangeType,
+    pub reason: ExclusionReason,
+}
+
+/// Why a changed file was left out of the prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExclusionReason {
+    /// Matched an ignore pattern (lockfiles, generated code, vendored directories)
+    Ignored { pattern: String },
+    /// Git shows no text diff; the new size when the patch states it
+    Binary { size: Option<u64> },
+    /// Its section of the diff is too big to send
+    TooLarge { bytes: usize },
+}
+
+impl ExclusionReason {
+    pub fn describe(&self) -> String {
+        match self {
+            ExclusionReason::Ignored { pattern } => format!("matches {}", pattern),
+            ExclusionReason::Binary { size: Some(size) } => {
+                format!("binary, {}", format_size(*size as usize))
+            }
+            ExclusionReason::Binary { size: None } => "binary".to_string(),
+            ExclusionReason::TooLarge { bytes } => {
+                format!("diff too large ({})", format_size(*bytes))
+            }
+        }
+    }
+}
+
+/// Human-readable byte count
+fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..1_000 => format!("{} B", bytes),
+        1_000..1_000_000 => format!("{:.1} KB", bytes as f64 / 1_000.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_000_000.0),
+    }
+}
+
+/// A reviewer perspective that shifts what the story emphasizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Persona {
+    Security,
+    Performance,
+    ApiDesign,
+    TestCoverage,
+}
+
+impl Persona {
+    pub const ALL: [Persona; 4] = [
+        Persona::Security,
+        Persona::Performance,
+        Persona::ApiDesign,
+        Persona::TestCoverage,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Persona::Security => "Security",
+            Persona::Performance => "Performance",
+            Persona::ApiDesign => "API design",
+            Persona::TestCoverage => "Test coverage",
+        }
+    }
+
+    /// Prompt section telling the model what to look for and how to rate significance
+    pub fn instructions(&self) -> &'static str {
+        match self {
+            Persona::Security => {
+                "Review as a security engineer. Look for injection, missing authorization or authentication checks, unsafe deserialization, secrets in code or logs, weakened crypto or TLS settings, and untrusted input reaching files, shells, queries or templates.\n\nRate as **key** any block that handles untrusted input, changes a trust boundary, or touches auth, sessions, permissions, crypto or secrets, even if small. Rate feature logic that stays inside trusted code as **standard**. Focus risks on exploitability and suggested changes on concrete mitigations."
+            }
+            Persona::Performance => {
+                "Review as a performance engineer. Look for work added to hot paths, N+1 queries, unbounded loops or allocations, blocking calls in async code, lock contention, missing caching or pagination, and algorithmic complexity changes.\n\nRate as **key** blocks that change what runs per request, per item or in a loop, and data structure or query changes. Rate one-off setup and configuration as **standard** or **noise**. Focus risks on latency, memory and load, and tests on benchmarks or load checks."
+            }
+            Persona::ApiDesign => {
+                "Review as an API designer. Look for breaking changes to public functions, types, endpoints, CLI flags, config keys, wire formats or database schemas; inconsistent naming; leaky abstractions; and missing versioning, deprecation or documentation.\n\nRate as **key** blocks that change a public surface or a contract other code relies on, and mark their definitions as **root**. Rate internal implementation behind an unchanged interface as **standard**. Focus risks on compatibility for existing callers."
+            }
+            Persona::TestCoverage => {
+                "Review as a test engineer. For each behavior the PR changes, check whether a test exercises it, including error paths and edge cases. Look for tests that only assert happy paths, mock away the change, or were weakened or deleted.\n\nRate as **key** changed logic that has no accompanying test, and test changes that loosen or remove assertions. Rate well-tested mechanical changes as **noise**. List the missing tests under each feature's tests and in suggested changes."
+            }
+        }
+    }
+}
+
+/// How long a reasoning model thinks before it answers
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Minimal,
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub const ALL: [ReasoningEffort; 4] = [
+        ReasoningEffort::Minimal,
+        ReasoningEffort::Low,
+        ReasoningEffort::Medium,
+        ReasoningEffort::High,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReasoningEffort::Minimal => "minimal",
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        }
+    }
+}
+
+/// Parameters sent with each story and deep dive; None leaves the model's default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ModelParams {
+    pub reasoning_effort: Option<ReasoningEffort>,
+    pub max_output_tokens: Option<u32>,
+    /// Only sent to models that aren't reasoning models, which reject it
+    pub temperature: Option<f32>,
+}
+
+impl ModelParams {
+    /// Steps the settings overlay offers after the default
+    pub const MAX_OUTPUT_TOKENS_STEPS: [u32; 4] = [4_000, 8_000, 16_000, 32_000];
+    pub const TEMPERATURE_STEPS: [f32; 5] = [0.0, 0.2, 0.5, 0.8, 1.0];
+
+    pub fn is_default(&self) -> bool {
+        *self == ModelParams::default()
+    }
+
+    /// What's been changed from the defaults, e.g. `high effort, 8000 tokens`
+    pub fn describe(&self) -> Option<String> {
+        let parts: Vec<String> = [
+            self.reasoning_effort.map(|effort| format!("{} effort", effort.label())),
+            self.max_output_tokens.map(|tokens| format!("{} tokens", tokens)),
+            self.temperature.map(|temperature| format!("temperature {}", temperature)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Team- or user-specific review instructions merged into the built-in system prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomPrompt {
+    /// Where the instructions came from, shown in the UI
+    pub source: String,
+    pub text: String,
+}
+
+/// Who last changed a range of lines at the PR base, from `git blame`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlameRange {
+    pub start_line: u32,
+    pub end_line: u32,
+    pub commit: String,
+    pub author: String,
+    /// ISO-8601 commit date
+    pub date: String,
+    pub summary: String,
+    /// PR that introduced the commit, if any
+    pub pr_number: Option<u32>,
+}
+
+/// Where the rest of the repo references a function the PR modifies or removes,
+/// from GitHub code search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolCallers {
+    pub symbol: String,
+    /// File the PR changes the symbol in
+    pub path: String,
+    /// Other files that mention it, as counted by the search
+    pub total: u32,
+    /// Up to the first page of those files
+    pub files: Vec<String>,
+}
+
+/// What a cheap model call made of one changed file, for building the story of a
+/// PR too large to send whole
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSummary {
+    pub path: String,
+    pub summary: String,
+    /// `@@ -<old start> +<new start> @@ <path>` for each hunk, as the story cites them
+    pub hunks: Vec<String>,
+}
+
+/// A run of lines from a file at the PR head
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeWindow {
+    pub path: String,
+    /// 1-based line number of the first line
+    pub start_line: u32,
+    pub lines: Vec<String>,
+}
+
+/// An issue referenced from the PR body (e.g. "Fixes #42")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedIssue {
+    pub owner: String,
+    pub repo: String,
+    pub number: u32,
+    pub title: String,
+    pub body: String,
+}
+
+/// An automated review comment anchored to a line of the diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotComment {
+    pub author: String,
+    pub path: String,
+    pub line: Option<u32>,
+    pub body: String,
+    /// Diff context GitHub stores with the comment; its last line is the commented line
+    pub diff_hunk: String,
+}
+
+impl BotComment {
+    /// Whether this comment belongs to the given diff block: the block must show the
+    /// commented file, and the commented line must fall in the lines it shows. A
+    /// comment without a line matches on the commented diff line instead.
+    pub fn anchors_in(&self, files: &[FileDiff], block: &DiffBlock) -> bool {
+        let Some(file) = block_file(files, block).filter(|file| file.path == self.path) else {
+            return false;
+        };
+        if let Some(line) = self.line {
+            return block_head_range(file, block)
+                .is_some_and(|(start, end)| (start..=end).contains(&line));
+        }
+        let anchor = self
+            .diff_hunk
+            .lines()
+            .rev()
+            .map(str::trim_end)
+            .find(|line| !line.is_empty() && !line.starts_with("@@"));
+        let mut shown = block.hunks.iter().flat_map(|hunk| hunk.lines.lines());
+        anchor.is_some_and(|anchor| shown.any(|line| line.trim_end() == anchor))
+    }
+}
+
+/// A file changed by the PR, with the story diff blocks that touch it
+#[derive(Debug, Clone)]
+pub struct ChangedFile {
+    pub path: String,
+    pub additions: u32,
+    pub deletions: u32,
+    /// (feature_idx, diff_idx) of blocks showing this file's changes
+    pub blocks: Vec<(usize, usize)>,
+}
+
+/// A line in a changed file (at the PR head) matching a workspace search
+#[derive(Debug, Clone)]
+pub struct WorkspaceMatch {
+    pub path: String,
+    pub line_number: usize,
+    pub text: String,
+}
+
+/// Commits and discussion on a PR, polled while it's being read
+#[derive(Debug, Clone, Default)]
+pub struct PrActivity {
+    /// Commit SHAs on the PR branch, oldest first
+    pub commits: Vec<String>,
+    /// Conversation comments and review summaries, oldest first
+    pub comments: Vec<ActivityComment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityComment {
+    pub id: String,
+    pub author: String,
+    pub body: String,
+    /// ISO 8601 timestamp
+    pub created_at: String,
+}
+
+/// A commit on the PR branch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrCommit {
+    pub sha: String,
+    /// First line of the message
+    pub headline: String,
+    pub author: String,
+}
+
+impl PrCommit {
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+}
+
+/// Consecutive commits of a PR reviewed on their own
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommitRange {
+    /// Commit the diff starts from: the one before the range, or the PR's base
+    pub base: String,
+    /// The range's commits, oldest first
+    pub commits: Vec<PrCommit>,
+    /// How many of the PR's commits come before the range
+    pub before: usize,
+    /// How many commits the PR has in all
+    pub total: usize,
+}
+
+impl CommitRange {
+    /// "commits 2-3 of 3 (abc1234..def5678)"
+    pub fn describe(&self) -> String {
+        let (Some(first), Some(last)) = (self.commits.first(), self.commits.last()) else {
+            return "no commits".to_string();
+        };
+        format!(
+            "commits {}-{} of {} ({}..{})",
+            self.before + 1,
+            self.before + self.commits.len(),
+            self.total,
+            first.short_sha(),
+            last.short_sha()
+        )
+    }
+}
+
+/// The diff from the head the reviewer last reviewed to the current one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewDelta {
+    pub reviewed_head: String,
+    pub diff: String,
+}
+
+impl ReviewDelta {
+    pub fn short_reviewed_head(&self) -> &str {
+        &self.reviewed_head[..self.reviewed_head.len().min(7)]
+    }
+}
+
+/// Viewed state of a PR's files in GitHub's review UI
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ViewedFiles {
+    /// GraphQL node id of the PR, needed to change the state
+    pub pr_id: String,
+    /// Files marked viewed
+    pub paths: Vec<String>,
+}
+
+/// An inline review comment on a line of the PR's head revision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InlineComment {
+    pub path: String,
+    pub line: u32,
+    pub body: String,
+}
+
+/// A step reached while fetching a PR or generating its story
+#[derive(Debug, Clone)]
+pub enum ProgressEvent {
+    FetchingPr { number: u32 },
+    FetchedMetadata,
+    FetchedDiff { bytes: usize },
+    FetchedContext { bot_comments: usize, linked_issues: usize },
+    FetchedFiles { count: usize },
+    FetchedBlame { count: usize },
+    FetchedCallers { count: usize },
+    PromptBuilt { tokens: usize },
+    /// The provider in the chain about to be asked, and the model asked for
+    UsingProvider { provider: String, model: String },
+    AwaitingModel { model: String },
+    /// An identical prompt was answered before, so the model wasn't called
+    ReusedResponse,
+    /// Running total of streamed output tokens
+    ReceivedTokens { tokens: usize },
+    /// Files summarized so far of those a large diff is split into
+    SummarizedFile { done: usize, total: usize },
+    /// Tokens the model call used, as the provider billed them
+    TokensUsed { input: u64, output: u64 },
+}
+
+impl ProgressEvent {
+    pub fn describe(&self) -> String {
+        match self {
+            ProgressEvent::FetchingPr { number } => format!("Fetching PR #{}", number),
+            ProgressEvent::FetchedMetadata => "Fetched PR metadata".to_string(),
+            ProgressEvent::FetchedDiff { bytes } => {
+                format!("Fetched diff ({} KB)", bytes.div_ceil(1024))
+            }
+            ProgressEvent::FetchedContext {
+                bot_comments,
+                linked_issues,
+            } => format!(
+                "Fetched {} bot comments and {} linked issues",
+                bot_comments, linked_issues
+            ),
+            ProgressEvent::FetchedFiles { count } => {
+                format!("Fetched {} files for surrounding code", count)
+            }
+            ProgressEvent::FetchedBlame { count } => {
+                format!("Fetched change history for {} files", count)
+            }
+            ProgressEvent::FetchedCallers { count } => {
+                format!("Searched the repo for callers of {} functions", count)
+            }
+            ProgressEvent::PromptBuilt { tokens } => {
+                format!("Built prompt (~{} tokens)", tokens)
+            }
+            ProgressEvent::UsingProvider { provider, model } => {
+                format!("Sending to {} ({})", provider, model)
+            }
+            ProgressEvent::AwaitingModel { model } => format!("Waiting for {}", model),
+            ProgressEvent::SummarizedFile { done, total } => {
+                format!("Summarized {} of {} files", done, total)
+            }
+            ProgressEvent::ReusedResponse => {
+                "Reused the answer to an identical prompt".to_string()
+            }
+            ProgressEvent::ReceivedTokens { tokens } => {
+                format!("Receiving story ({} tokens)", tokens)
+            }
+            ProgressEvent::TokensUsed { input, output } => {
+                format!("Used {} prompt and {} output tokens", input, output)
+            }
+        }
+    }
 }
 
 /// The three review actions
@@ -107,22 +844,222 @@ pub enum ReviewAction {
     NextPr,
 }
 
+/// The verdict a review is submitted with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewEvent {
+    #[default]
+    RequestChanges,
+    Comment,
+    Approve,
+}
+
 /// A PR in the picker list
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrListItem {
     pub number: u32,
     pub title: String,
     pub author: String,
     pub head_branch: String,
+    /// Branch the PR targets; another PR's head when it's stacked
+    #[serde(default)]
+    pub base_branch: String,
     pub is_draft: bool,
     pub review_requested: bool,
     pub ci_status: CiStatus,
     pub additions: u32,
     pub deletions: u32,
+    /// ISO-8601 creation timestamp
+    pub created_at: String,
+    #[serde(default)]
+    pub state: PrState,
+    /// ISO-8601 timestamp of the last change (empty when unknown)
+    #[serde(default)]
+    pub updated_at: String,
+    #[serde(default)]
+    pub labels: Vec<PrLabel>,
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// Conversation comments, not counting review threads
+    #[serde(default)]
+    pub comments: u32,
 }
 
-/// A repository in the repo selector
+/// A label on a PR, with GitHub's hex color (no `#`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrLabel {
+    pub name: String,
+    pub color: String,
+}
+
+impl PrListItem {
+    /// Where the PR sorts: review requested from the user first, then other ready
+    /// PRs, then drafts, then closed and merged PRs
+    pub fn priority(&self) -> u8 {
+        if self.state != PrState::Open {
+            3
+        } else if self.is_draft {
+            2
+        } else if self.review_requested {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+/// One page of a list GitHub serves in pages
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Items across every page
+    pub total: u32,
+    /// Cursor to fetch the next page from; None on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// A fetched list, possibly served from the offline cache
 #[derive(Debug, Clone)]
+pub struct Listing<T> {
+    pub items: Vec<T>,
+    /// Unix time the cached copy was fetched, set when the live fetch failed
+    pub cached_at: Option<u64>,
+    /// Items across every page (None for a cached copy)
+    pub total: Option<u32>,
+    /// Cursor to fetch the next page from; None on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Where a PR is in its lifecycle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrState {
+    #[default]
+    Open,
+    Closed,
+    Merged,
+}
+
+impl PrState {
+    /// From GitHub's `OPEN`/`CLOSED`/`MERGED`; anything else counts as open
+    pub fn from_github(state: &str) -> Self {
+        match state {
+            "CLOSED" => PrState::Closed,
+            "MERGED" => PrState::Merged,
+            _ => PrState::Open,
+        }
+    }
+}
+
+/// Which PRs the picker lists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrStateFilter {
+    #[default]
+    Open,
+    Merged,
+    Closed,
+    All,
+}
+
+impl PrStateFilter {
+    /// The filter `s` switches to next
+    pub fn next(self) -> Self {
+        match self {
+            PrStateFilter::Open => PrStateFilter::Merged,
+            PrStateFilter::Merged => PrStateFilter::Closed,
+            PrStateFilter::Closed => PrStateFilter::All,
+            PrStateFilter::All => PrStateFilter::Open,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PrStateFilter::Open => "open",
+            PrStateFilter::Merged => "merged",
+            PrStateFilter::Closed => "closed",
+            PrStateFilter::All => "all",
+        }
+    }
+
+    /// GitHub's `PullRequestState`s the filter matches
+    pub fn github_states(self) -> &'static [&'static str] {
+        match self {
+            PrStateFilter::Open => &["OPEN"],
+            PrStateFilter::Merged => &["MERGED"],
+            PrStateFilter::Closed => &["CLOSED"],
+            PrStateFilter::All => &["OPEN", "MERGED", "CLOSED"],
+        }
+    }
+}
+
+/// How the picker orders PRs (favorites and recents stay on top)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrSort {
+    /// Review requested first, then ready, drafts, and closed PRs
+    #[default]
+    Priority,
+    Newest,
+    Oldest,
+    RecentlyUpdated,
+    Largest,
+    Ci,
+}
+
+impl PrSort {
+    pub const ALL: [PrSort; 6] = [
+        PrSort::Priority,
+        PrSort::Newest,
+        PrSort::Oldest,
+        PrSort::RecentlyUpdated,
+        PrSort::Largest,
+        PrSort::Ci,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PrSort::Priority => "Priority",
+            PrSort::Newest => "Newest",
+            PrSort::Oldest => "Oldest",
+            PrSort::RecentlyUpdated => "Recently updated",
+            PrSort::Largest => "Largest",
+            PrSort::Ci => "CI status",
+        }
+    }
+
+    pub fn compare(self, a: &PrListItem, b: &PrListItem) -> std::cmp::Ordering {
+        // Passing first: those are ready for review
+        let ci_rank = |status: &CiStatus| match status {
+            CiStatus::Success => 0,
+            CiStatus::Pending => 1,
+            CiStatus::Unknown => 2,
+            CiStatus::Failure => 3,
+        };
+        match self {
+            PrSort::Priority => a.priority().cmp(&b.priority()),
+            // ISO-8601 timestamps sort as strings
+            PrSort::Newest => b.created_at.cmp(&a.created_at),
+            PrSort::Oldest => a.created_at.cmp(&b.created_at),
+            PrSort::RecentlyUpdated => b.updated_at.cmp(&a.updated_at),
+            PrSort::Largest => (b.additions + b.deletions).cmp(&(a.additions + a.deletions)),
+            PrSort::Ci => ci_rank(&a.ci_status).cmp(&ci_rank(&b.ci_status)),
+        }
+    }
+}
+
+/// A review-requested PR in the cross-repo review queue
+#[derive(Debug, Clone)]
+pub struct QueueItem {
+    pub owner: String,
+    pub repo: String,
+    pub pr: PrListItem,
+}
+
+impl QueueItem {
+    pub fn repo_label(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+}
+
+/// A repository in the repo selector
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoListItem {
     pub owner: String,
     pub name: String,
@@ -131,8 +1068,15 @@ pub struct RepoListItem {
     pub is_private: bool,
 }
 
+impl RepoListItem {
+    /// `owner/name` form used as the repo's identity
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
 /// CI/build status for a PR
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CiStatus {
     Pending,
     Success,
@@ -140,18 +1084,13 @@ pub enum CiStatus {
     Unknown,
 }
 
-impl CiStatus {
-    pub fn symbol(&self) -> &'static str {
-        match self {
-            CiStatus::Pending => "○",
-            CiStatus::Success => "✓",
-            CiStatus::Failure => "✗",
-            CiStatus::Unknown => "?",
-        }
-    }
-}
-
 impl ReviewAction {
+    pub const ALL: [ReviewAction; 3] = [
+        ReviewAction::RequestChanges,
+        ReviewAction::ClarificationQuestions,
+        ReviewAction::NextPr,
+    ];
+
     pub fn title(&self) -> &'static str {
         match self {
             ReviewAction::RequestChanges => "Request Changes",
@@ -160,3 +1099,30 @@ impl ReviewAction {
         }
     }
 }
+
+impl ReviewEvent {
+    pub fn next(self) -> Self {
+        match self {
+            ReviewEvent::RequestChanges => ReviewEvent::Comment,
+            ReviewEvent::Comment => ReviewEvent::Approve,
+            ReviewEvent::Approve => ReviewEvent::RequestChanges,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReviewEvent::RequestChanges => "request changes",
+            ReviewEvent::Comment => "comment",
+            ReviewEvent::Approve => "approve",
+        }
+    }
+
+    /// The `event` GitHub's reviews API takes
+    pub fn api_name(self) -> &'static str {
+        match self {
+            ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+            ReviewEvent::Comment => "COMMENT",
+            ReviewEvent::Approve => "APPROVE",
+        }
+    }
+}
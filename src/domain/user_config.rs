@@ -0,0 +1,350 @@
+//! `~/.config/dstl/config.toml`: the provider, default model and (optionally)
+//! API key chosen at first launch, and any webhooks, redaction rules or provider
+//! chain added by hand. Each line is a `key = "value"` pair with a dotted key,
+//! which is all dstl writes, or a `[table]` header for the keys after it. A line
+//! that's neither, or a setting dstl doesn't know, is an error naming the line
+//! rather than skipped, since these settings decide what leaves the machine.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use regex::Regex;
+
+use super::filter::parse_patterns;
+use super::storage::config_dir;
+use super::types::{ModelParams, ReasoningEffort};
+
+pub const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserConfig {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// Only set when the user chose to keep the key in this file
+    pub api_key: Option<String>,
+    /// Webhook URLs notified after a submission, keyed by `owner/repo` or `*` for
+    /// every repo: `webhooks."acme/payments" = "https://hooks.slack.com/..."`
+    pub webhooks: BTreeMap<String, String>,
+    /// Changed files never sent to the model, comma-separated:
+    /// `redact.paths = "internal/**, *.pem"`
+    pub redact_paths: Vec<String>,
+    /// Regex replacements applied to the prompt, keyed by pattern:
+    /// `redact.replace."acme-[0-9]+" = "CLIENT"`
+    pub redact_replace: BTreeMap<String, String>,
+    /// Providers to try in order, comma-separated: `providers = "proxy, openai"`
+    pub providers: Vec<String>,
+    /// Settings per provider, keyed by name then field:
+    /// `provider.proxy.url = "https://llm.example.com/v1"`
+    pub provider_settings: BTreeMap<String, BTreeMap<String, String>>,
+    /// Review snippets inserted with Ctrl+T, keyed by name:
+    /// `template."LGTM with nits" = "LGTM once these are fixed:\n"`
+    pub templates: BTreeMap<String, String>,
+    /// `minimal`, `low`, `medium` or `high`
+    pub reasoning_effort: Option<String>,
+    pub max_output_tokens: Option<String>,
+    pub temperature: Option<String>,
+    /// Language the story's prose is written in: `language = "de"`
+    pub language: Option<String>,
+    /// Bitbucket Server or Data Center to use instead of GitHub:
+    /// `bitbucket.url = "https://bitbucket.example.com"`
+    pub bitbucket_url: Option<String>,
+    /// HTTP access token for it; `BITBUCKET_TOKEN` takes precedence
+    pub bitbucket_token: Option<String>,
+}
+
+impl UserConfig {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    /// The saved configuration, or None before first-run setup
+    pub fn load() -> Result<Option<Self>> {
+        let Some(path) = Self::path() else {
+            return Ok(None);
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        Self::parse(&contents).with_context(|| format!("Invalid {}", path.display())).map(Some)
+    }
+
+    /// Every setting in `contents`, or an error naming the first line that can't be read
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut config = UserConfig::default();
+        // Keys under a `[redact]`-style table header are read as `redact.<key>`
+        let mut table = Vec::new();
+        for (i, line) in contents.lines().enumerate() {
+            config.parse_line(line, &mut table).with_context(|| format!("Line {}", i + 1))?;
+        }
+        Ok(config)
+    }
+
+    fn parse_line(&mut self, line: &str, table: &mut Vec<String>) -> Result<()> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let (key, rest) = parse_key(header)?;
+            let rest = rest.trim_start().strip_prefix(']').context("Expected `]`")?;
+            end_of_line(rest)?;
+            *table = key;
+            return Ok(());
+        }
+        let (key, rest) = parse_key(line)?;
+        let key: Vec<String> = table.iter().cloned().chain(key).collect();
+        let rest = rest
+            .trim_start()
+            .strip_prefix('=')
+            .context("Expected `key = \"value\"`")?;
+        let (value, rest) = parse_value(rest.trim_start())?;
+        end_of_line(rest)?;
+        self.set(&key, value)
+    }
+
+    fn set(&mut self, key: &[String], value: String) -> Result<()> {
+        let parts: Vec<&str> = key.iter().map(String::as_str).collect();
+        match parts.as_slice() {
+            ["provider"] => self.provider = Some(value),
+            ["model"] => self.model = Some(value),
+            ["api_key"] => self.api_key = Some(value),
+            ["redact", "paths"] => self.redact_paths = parse_patterns(&value),
+            ["providers"] => self.providers = parse_patterns(&value),
+            ["reasoning_effort"] => self.reasoning_effort = Some(value),
+            ["max_output_tokens"] => self.max_output_tokens = Some(value),
+            ["temperature"] => self.temperature = Some(value),
+            ["language"] => self.language = Some(value),
+            ["bitbucket", "url"] => self.bitbucket_url = Some(value),
+            ["bitbucket", "token"] => self.bitbucket_token = Some(value),
+            ["webhooks", repo] => {
+                self.webhooks.insert(repo.to_string(), value);
+            }
+            ["template", name] => {
+                self.templates.insert(name.to_string(), value);
+            }
+            ["redact", "replace", pattern] => {
+                Regex::new(pattern)
+                    .with_context(|| format!("Invalid redact.replace pattern {:?}", pattern))?;
+                self.redact_replace.insert(pattern.to_string(), value);
+            }
+            ["provider", name, field] => {
+                self.provider_settings
+                    .entry(name.to_string())
+                    .or_default()
+                    .insert(field.to_string(), value);
+            }
+            _ => anyhow::bail!("Unknown setting `{}`", parts.join(".")),
+        }
+        Ok(())
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("# Written by dstl's first-run setup\n");
+        for (key, value) in [
+            ("provider", &self.provider),
+            ("model", &self.model),
+            ("api_key", &self.api_key),
+            ("reasoning_effort", &self.reasoning_effort),
+            ("max_output_tokens", &self.max_output_tokens),
+            ("temperature", &self.temperature),
+            ("language", &self.language),
+            ("bitbucket.url", &self.bitbucket_url),
+            ("bitbucket.token", &self.bitbucket_token),
+        ] {
+            if let Some(value) = value {
+                out.push_str(&format!("{} = \"{}\"\n", key, escape(value)));
+            }
+        }
+        for (repo, url) in &self.webhooks {
+            out.push_str(&format!("webhooks.\"{}\" = \"{}\"\n", escape(repo), escape(url)));
+        }
+        for (name, text) in &self.templates {
+            out.push_str(&format!("template.\"{}\" = \"{}\"\n", escape(name), escape(text)));
+        }
+        if !self.redact_paths.is_empty() {
+            out.push_str(&format!(
+                "redact.paths = \"{}\"\n",
+                escape(&self.redact_paths.join(", "))
+            ));
+        }
+        for (pattern, replacement) in &self.redact_replace {
+            out.push_str(&format!(
+                "redact.replace.\"{}\" = \"{}\"\n",
+                escape(pattern),
+                escape(replacement)
+            ));
+        }
+        if !self.providers.is_empty() {
+            out.push_str(&format!("providers = \"{}\"\n", escape(&self.providers.join(", "))));
+        }
+        for (name, settings) in &self.provider_settings {
+            for (field, value) in settings {
+                out.push_str(&format!(
+                    "provider.{}.{} = \"{}\"\n",
+                    key_part(name),
+                    key_part(field),
+                    escape(value)
+                ));
+            }
+        }
+        out
+    }
+
+    /// The model parameters set here, checked
+    pub fn model_params(&self) -> Result<ModelParams> {
+        let reasoning_effort = self
+            .reasoning_effort
+            .as_deref()
+            .map(|effort| {
+                ReasoningEffort::from_str(effort, true)
+                    .map_err(|_| anyhow::anyhow!("Unknown reasoning_effort {:?}", effort))
+            })
+            .transpose()?;
+        let max_output_tokens = self
+            .max_output_tokens
+            .as_deref()
+            .map(|tokens| {
+                tokens.parse().with_context(|| format!("Invalid max_output_tokens {:?}", tokens))
+            })
+            .transpose()?;
+        let temperature = self
+            .temperature
+            .as_deref()
+            .map(|temperature| {
+                temperature
+                    .parse()
+                    .with_context(|| format!("Invalid temperature {:?}", temperature))
+            })
+            .transpose()?;
+        Ok(ModelParams {
+            reasoning_effort,
+            max_output_tokens,
+            temperature,
+        })
+    }
+
+    /// Write the file, readable only by the user since it may hold a key
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = Self::path().context("Could not determine config directory (HOME not set)")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        write_private(&path, &self.to_toml())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` only applies to new files; tighten one that already existed
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+/// A dotted key, e.g. `redact.replace."a=b"`, and what follows it
+fn parse_key(line: &str) -> Result<(Vec<String>, &str)> {
+    let mut parts = Vec::new();
+    let mut rest = line;
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with('"') {
+            let (part, after) = parse_string(rest)?;
+            parts.push(part);
+            rest = after;
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(rest.len());
+            anyhow::ensure!(end > 0, "Expected a setting name");
+            parts.push(rest[..end].to_string());
+            rest = &rest[end..];
+        }
+        match rest.trim_start().strip_prefix('.') {
+            Some(after) => rest = after,
+            None => return Ok((parts, rest)),
+        }
+    }
+}
+
+/// A quoted string, or a bare number or boolean as written, and what follows it
+fn parse_value(text: &str) -> Result<(String, &str)> {
+    if text.starts_with('"') {
+        return parse_string(text);
+    }
+    let end = text.find(|c: char| c.is_whitespace() || c == '#').unwrap_or(text.len());
+    let value = &text[..end];
+    anyhow::ensure!(
+        value == "true" || value == "false" || value.parse::<f64>().is_ok(),
+        "Expected a quoted value"
+    );
+    Ok((value.to_string(), &text[end..]))
+}
+
+/// A basic TOML string at the start of `text`, unescaped, and what follows it
+fn parse_string(text: &str) -> Result<(String, &str)> {
+    let inner = text.strip_prefix('"').context("Expected a quoted value")?;
+    let mut out = String::new();
+    let mut chars = inner.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((out, &inner[i + 1..])),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('"') => out.push('"'),
+                Some('\\') => out.push('\\'),
+                other => anyhow::bail!("Unknown escape `\\{}`", other.unwrap_or(' ')),
+            },
+            c => out.push(c),
+        }
+    }
+    anyhow::bail!("Missing closing quote")
+}
+
+/// Only a comment may follow a value
+fn end_of_line(rest: &str) -> Result<()> {
+    let rest = rest.trim_start();
+    anyhow::ensure!(
+        rest.is_empty() || rest.starts_with('#'),
+        "Unexpected `{}` after the value",
+        rest
+    );
+    Ok(())
+}
+
+/// A key part as written back: bare when it can be, quoted otherwise
+fn key_part(part: &str) -> String {
+    let bare = !part.is_empty()
+        && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare { part.to_string() } else { format!("\"{}\"", escape(part)) }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use super::types::WorkspaceMatch;
+
+/// Most matches to return from a workspace search
+const MAX_WORKSPACE_MATCHES: usize = 500;
+
+/// Case-insensitive substring search across file contents, ordered by path then line
+pub fn search_files(files: &HashMap<String, String>, query: &str) -> Vec<WorkspaceMatch> {
+    let query = query.to_lowercase();
+    let mut paths: Vec<&String> = files.keys().collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .flat_map(|path| {
+            let query = &query;
+            files[path]
+                .lines()
+                .enumerate()
+                .filter(move |(_, line)| line.to_lowercase().contains(query))
+                .map(move |(idx, line)| WorkspaceMatch {
+                    path: path.clone(),
+                    line_number: idx + 1,
+                    text: line.trim().to_string(),
+                })
+        })
+        .take(MAX_WORKSPACE_MATCHES)
+        .collect()
+}
@@ -5,50 +5,338 @@ mod app;
 mod command;
 mod config;
 mod domain;
+mod onboarding;
+mod subcommands;
+mod suspend;
+#[cfg(test)]
+mod test_support;
+#[cfg(test)]
+mod tests;
 mod ui;
 mod update;
 
 use std::collections::VecDeque;
-use std::io;
-use std::time::Duration;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
+use tokio::sync::mpsc;
 
 use action::Action;
 use app::{App, AppState};
 use command::{execute_command, Command};
-use config::AppConfig;
+use config::{AppConfig, Forge};
+use domain::{bitbucket, filter, github, keychain, llm};
+use domain::bitbucket::BitbucketServer;
+use domain::cache::CachePolicy;
+use domain::history::History;
+use domain::memory::ReviewMemory;
+use domain::reviewed::ReviewedPrs;
+use domain::outbox::Outbox;
+use domain::preferences::Preferences;
+use domain::session::Session;
+use domain::{preflight, prompt, providers};
+use domain::types::{ModelParams, Persona, ReasoningEffort};
+use domain::privacy::Redaction;
+use domain::user_config::UserConfig;
+use subcommands::ExportFormat;
 use update::update;
+use ui::glyphs;
+
+/// Model used when neither --model nor config.toml names one
+const DEFAULT_MODEL: &str = "gpt-5.2";
 
 #[derive(Parser)]
 #[command(name = "dstl")]
 #[command(about = "Distillery - Distill PR diffs into reviewable narratives")]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+
     /// PR reference: owner/repo#123 or GitHub URL (optional - starts repo selector if omitted)
     pr_ref: Option<String>,
 
     /// Repo for PR picker (owner/repo format)
-    #[arg(short = 'R', long)]
+    #[arg(short = 'R', long, global = true)]
     repo: Option<String>,
 
-    /// OpenAI model to use
-    #[arg(short, long, default_value = "gpt-5.2")]
-    model: String,
+    /// OpenAI model to use [default: the one in config.toml, else gpt-5.2]
+    #[arg(short, long, global = true)]
+    model: Option<String>,
+
+    /// Faster model to retry with when generation exceeds its time budget
+    #[arg(long, default_value = "gpt-5-mini", global = true)]
+    fallback_model: String,
+
+    /// Seconds to wait for generation before retrying with the fallback model
+    #[arg(long, value_name = "SECONDS", global = true)]
+    generation_budget: Option<u64>,
+
+    /// Generate stories with several models to compare side by side (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "MODELS", global = true)]
+    compare: Vec<String>,
+
+    /// Review persona that shifts what the story emphasizes
+    #[arg(long, value_enum, global = true)]
+    persona: Option<Persona>,
+
+    /// Write the story's prose in this language, e.g. `de` [default: the one in config.toml, else English]
+    #[arg(long, value_name = "LANGUAGE", global = true)]
+    language: Option<String>,
+
+    /// How long reasoning models think before answering [default: the one in config.toml, else the model's]
+    #[arg(long, value_enum, value_name = "EFFORT", global = true)]
+    reasoning_effort: Option<ReasoningEffort>,
+
+    /// Most tokens the model may write, reasoning included [default: the model's limit]
+    #[arg(long, value_name = "N", global = true)]
+    max_output_tokens: Option<u32>,
+
+    /// Sampling temperature, for models that aren't reasoning models [default: the model's]
+    #[arg(long, value_name = "T", global = true)]
+    temperature: Option<f32>,
 
     /// Use cached response (skip LLM call)
-    #[arg(long)]
+    #[arg(long, global = true)]
     cache: bool,
 
+    /// Always call the model, even for a prompt it has answered before
+    #[arg(long, global = true)]
+    no_llm_cache: bool,
+
     /// Path to cache file
-    #[arg(long, default_value = ".dstl-cache.json")]
+    #[arg(long, default_value = ".dstl-cache.json", global = true)]
     cache_file: String,
+
+    /// Days a cached story stays usable, 0 to keep it until evicted
+    #[arg(long, value_name = "DAYS", default_value_t = 7, global = true)]
+    cache_ttl: u64,
+
+    /// Megabytes the cache files may take before the oldest stories are evicted
+    #[arg(long, value_name = "MB", default_value_t = 50, global = true)]
+    cache_max_size: u64,
+
+    /// Organization whose repos the repo selector lists
+    #[arg(long, global = true)]
+    org: Option<String>,
+
+    /// Start in the cross-repo review queue
+    #[arg(long, global = true)]
+    queue: bool,
+
+    /// Repos to include in the review queue (comma-separated owner/repo; default: all)
+    #[arg(long, value_delimiter = ',', global = true)]
+    queue_repos: Vec<String>,
+
+    /// Times to retry failed gh listing calls before falling back to cached data
+    #[arg(long, default_value_t = 2, global = true)]
+    gh_retries: u32,
+
+    /// Seconds a gh call may run before it is killed
+    #[arg(long, default_value_t = 60, value_name = "SECONDS", global = true)]
+    gh_timeout: u64,
+
+    /// Seconds to wait for OpenAI to connect or send more data before giving up
+    #[arg(long, default_value_t = 120, value_name = "SECONDS", global = true)]
+    llm_timeout: u64,
+
+    /// Lines of surrounding code to include around changes (0 to disable)
+    #[arg(long, default_value_t = 10, value_name = "LINES", global = true)]
+    context_lines: usize,
+
+    /// Include who last changed the modified lines (git blame at the base) in the prompt
+    #[arg(long, global = true)]
+    blame_in_prompt: bool,
+
+    /// Search the repo for callers of functions the PR modifies or removes and show the blast radius
+    #[arg(long, global = true)]
+    impact: bool,
+
+    /// Send added lines that look like secrets (keys, tokens) to the model instead of redacting them
+    #[arg(long, global = true)]
+    allow_secrets: bool,
+
+    /// Show exactly what will be sent to the model and wait for Enter before each generation
+    #[arg(long, global = true)]
+    preview_prompt: bool,
+
+    /// Summarize each file first, with --map-model, when the diff is larger than this many KB (0 never)
+    #[arg(long, value_name = "KB", default_value_t = 256, global = true)]
+    map_threshold: usize,
+
+    /// Cheap model that summarizes each file of a large diff before the story is written
+    #[arg(long, value_name = "MODEL", default_value = "gpt-5-mini", global = true)]
+    map_model: String,
+
+    /// File summaries to request at once for a large diff
+    #[arg(long, value_name = "N", default_value_t = 4, global = true)]
+    map_concurrency: usize,
+
+    /// Replace file paths and string literals in the prompt with placeholders, restored locally in the story
+    #[arg(long, global = true)]
+    anonymize: bool,
+
+    /// Leave matching changed files out of the prompt (gitattributes-style patterns, comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "PATTERNS", global = true)]
+    ignore: Vec<String>,
+
+    /// Analyze matching changed files even if an ignore pattern matches them (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "PATTERNS", global = true)]
+    include: Vec<String>,
+
+    /// Only review changed files matching these patterns, e.g. services/payments/** (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "PATTERNS", global = true)]
+    paths: Vec<String>,
+
+    /// Skip noise-significance diff blocks when navigating
+    #[arg(long, global = true)]
+    skip_noise: bool,
+
+    /// Post clarification questions as inline comments on the blocks they concern
+    #[arg(long, global = true)]
+    thread_questions: bool,
+
+    /// Seconds between checks of the open PR for new commits and comments (0 to disable)
+    #[arg(long, default_value_t = 60, value_name = "SECONDS", global = true)]
+    poll: u64,
+
+    /// Write submissions to .dstl/outbox/ instead of posting them (toggle with D)
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Don't point out likely typos in the draft preview (Ctrl+P while editing)
+    #[arg(long, global = true)]
+    no_spellcheck: bool,
+
+    /// No desktop notification (or bell) when a story is ready or a submission finishes
+    #[arg(long, global = true)]
+    no_notify: bool,
+
+    /// Draw with plain ASCII instead of Unicode symbols and emoji
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Lay the UI out for screen readers: one column, words instead of symbols, no colours
+    #[arg(long, global = true)]
+    screen_reader: bool,
+
+    /// Print what dstl is doing as plain lines on the terminal's main screen, where screen
+    /// readers can follow it
+    #[arg(long, global = true)]
+    announce: bool,
+
+    /// Serve canned repos, PRs and a story instead of calling GitHub and OpenAI
+    #[arg(long, global = true)]
+    mock: bool,
+
+    /// Start without checking gh, its login, and the API key and models first
+    #[arg(long, global = true)]
+    skip_preflight: bool,
+}
+
+impl Cli {
+    /// The PR or repo to open: a subcommand's, else bare `dstl <ref>`'s. A bare
+    /// ref before a subcommand (`dstl <ref> bot <ref>`) is an error, not ignored
+    fn requested_ref(&self) -> Result<Option<&String>> {
+        if let (Some(pr_ref), Some(_)) = (&self.pr_ref, &self.command) {
+            anyhow::bail!(
+                "`{}` comes before a subcommand; give the PR to the subcommand, or run \
+                 `dstl {}` on its own",
+                pr_ref,
+                pr_ref
+            );
+        }
+        Ok(match &self.command {
+            Some(
+                CliCommand::Review { pr_ref }
+                | CliCommand::Export { pr_ref, .. }
+                | CliCommand::Bot { pr_ref },
+            ) => Some(pr_ref),
+            _ => self.pr_ref.as_ref(),
+        })
+    }
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Review a PR, or pick one from a repo (what `dstl <ref>` does)
+    Review {
+        /// PR reference: owner/repo#123, a GitHub URL, or owner/repo to pick a PR
+        pr_ref: String,
+    },
+    /// Start in the cross-repo review queue
+    Queue,
+    /// Print a PR's story as Markdown (or JSON) without opening the TUI
+    Export {
+        /// PR reference: owner/repo#123 or a GitHub URL
+        pr_ref: String,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// markdown for reading, json for a machine-readable review session record
+        #[arg(long, value_enum, default_value_t = ExportFormat::Markdown)]
+        format: ExportFormat,
+    },
+    /// Post a PR's story summary as a comment, for CI; re-runs edit the same comment
+    Bot {
+        /// PR reference: owner/repo#123 or a GitHub URL
+        pr_ref: String,
+    },
+    /// Manage cached stories
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+    /// Manage ~/.config/dstl/config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Manage the API key dstl uses
+    Auth {
+        #[command(subcommand)]
+        action: AuthCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommand {
+    /// Save an API key in the OS keychain (OPENAI_API_KEY still overrides it)
+    SetKey {
+        /// Provider the key is for
+        #[arg(long, default_value = "openai")]
+        provider: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// List cached stories: PR, model, head commit and age
+    List,
+    /// Delete every cached story (the --cache-file, its per-model siblings and cached model responses)
+    Clear,
+    /// Drop expired stories and those whose PR has new commits, then evict down to
+    /// --cache-max-size
+    Prune,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Open config.toml in $VISUAL or $EDITOR, creating it if needed
+    Edit,
 }
 
 /// Startup mode determined from CLI args
@@ -59,6 +347,8 @@ enum StartupMode {
     PrPicker { owner: String, repo: String },
     /// Load a specific PR directly
     DirectPr { owner: String, repo: String, number: u32 },
+    /// Start with the cross-repo review queue
+    ReviewQueue,
 }
 
 #[tokio::main]
@@ -67,11 +357,33 @@ async fn main() -> Result<()> {
     let _ = dotenvy::dotenv();
 
     let cli = Cli::parse();
+    let pr_ref = cli.requested_ref()?;
+    let cache = cache_policy(&cli);
+    match &cli.command {
+        Some(CliCommand::Auth {
+            action: AuthCommand::SetKey { provider },
+        }) => return onboarding::set_key(provider),
+        Some(CliCommand::Cache { action }) => {
+            return match action {
+                CacheCommand::List => subcommands::list_cache(&cli.cache_file),
+                CacheCommand::Clear => subcommands::clear_cache(&cli.cache_file),
+                CacheCommand::Prune => subcommands::prune_cache(&cli.cache_file, &cache),
+            };
+        }
+        Some(CliCommand::Config {
+            action: ConfigCommand::Edit,
+        }) => return subcommands::edit_config(cli.model.as_deref().unwrap_or(DEFAULT_MODEL)),
+        _ => {}
+    }
+    // Held until exit so buffered log lines are flushed
+    let logging = domain::logging::init();
 
     // Determine startup mode
-    let mode = if let Some(pr_ref) = &cli.pr_ref {
+    let mode = if let Some(pr_ref) = pr_ref {
         // Have a PR reference - could be owner/repo#num or just owner/repo
-        if pr_ref.contains('#') || pr_ref.contains("github.com") {
+        if let Some((owner, repo, number)) = bitbucket::parse_pr_url(pr_ref) {
+            StartupMode::DirectPr { owner, repo, number }
+        } else if pr_ref.contains('#') || pr_ref.contains("github.com") {
             // Full PR reference
             let (owner, repo, number) = domain::github::parse_pr_reference(pr_ref)
                 .context("Invalid PR reference")?;
@@ -95,51 +407,245 @@ async fn main() -> Result<()> {
             owner: owner.to_string(),
             repo: repo.to_string(),
         }
+    } else if cli.queue || matches!(cli.command, Some(CliCommand::Queue)) {
+        StartupMode::ReviewQueue
     } else {
         // No args - start with repo selector
         StartupMode::RepoSelector
     };
 
-    // Get API key
-    let api_key = std::env::var("OPENAI_API_KEY")
-        .context("OPENAI_API_KEY environment variable not set")?;
+    // The environment overrides the keychain, which overrides config.toml
+    let env_key = std::env::var("OPENAI_API_KEY").ok().filter(|key| !key.trim().is_empty());
+    let saved_config = UserConfig::load()?;
+    let provider = saved_config
+        .as_ref()
+        .and_then(|config| config.provider.clone())
+        .unwrap_or_else(|| llm::PROVIDER.to_string());
+    let keychain_key = if cli.mock || env_key.is_some() {
+        None
+    } else {
+        keychain::get(&provider)
+    };
+    // First launch: set up instead of failing on the missing key
+    let user_config = match saved_config {
+        Some(user_config) => user_config,
+        None if !cli.mock
+            && env_key.is_none()
+            && keychain_key.is_none()
+            && io::stdin().is_terminal() =>
+        {
+            onboarding::run(cli.model.as_deref().unwrap_or(DEFAULT_MODEL)).await?
+        }
+        None => UserConfig::default(),
+    };
+    let api_key = env_key.or(keychain_key).or(user_config.api_key.clone());
+    let providers = providers::chain(&user_config, api_key.as_deref().unwrap_or_default())
+        .context("Invalid provider chain in config.toml")?;
+    let saved_params = user_config.model_params().context("Invalid model settings in config.toml")?;
+    let model = cli.model.or(user_config.model).unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    let model_params = ModelParams {
+        reasoning_effort: cli.reasoning_effort.or(saved_params.reasoning_effort),
+        max_output_tokens: cli.max_output_tokens.or(saved_params.max_output_tokens),
+        temperature: cli.temperature.or(saved_params.temperature),
+    };
+
+    let redaction = Redaction::new(&user_config.redact_paths, &user_config.redact_replace)
+        .context("Invalid redaction rules in config.toml")?;
+    // The environment overrides config.toml here too
+    let forge = match user_config.bitbucket_url.clone() {
+        Some(url) => {
+            let token = std::env::var("BITBUCKET_TOKEN")
+                .ok()
+                .filter(|token| !token.trim().is_empty())
+                .or(user_config.bitbucket_token.clone())
+                .context("bitbucket.url is set in config.toml but there's no token: \
+                          set BITBUCKET_TOKEN or bitbucket.token")?;
+            Forge::Bitbucket(BitbucketServer::new(url, token)?)
+        }
+        None => Forge::GitHub,
+    };
+    let (mut ignore_patterns, mut include_patterns) = filter::load_user_patterns();
+    ignore_patterns.extend(cli.ignore);
+    include_patterns.extend(cli.include);
 
     let config = AppConfig {
-        api_key,
-        model: cli.model,
+        providers,
+        model,
+        fallback_model: cli.fallback_model,
+        generation_budget: cli.generation_budget.map(Duration::from_secs),
         use_cache: cli.cache,
+        llm_cache: !cli.no_llm_cache,
+        cache_ttl: cache.ttl,
+        cache_max_bytes: cache.max_bytes,
         cache_file: cli.cache_file,
+        skip_noise: cli.skip_noise,
+        thread_questions: cli.thread_questions,
+        dry_run: cli.dry_run,
+        spellcheck: !cli.no_spellcheck,
+        notify: !cli.no_notify,
+        announce: cli.announce,
+        poll_interval: (cli.poll > 0).then(|| Duration::from_secs(cli.poll)),
+        queue_repos: cli.queue_repos,
+        gh_retries: cli.gh_retries,
+        llm_timeout: Duration::from_secs(cli.llm_timeout),
+        context_lines: cli.context_lines,
+        blame_in_prompt: cli.blame_in_prompt,
+        impact: cli.impact,
+        allow_secrets: cli.allow_secrets,
+        redaction,
+        anonymize: cli.anonymize,
+        preview_prompt: cli.preview_prompt,
+        map_model: cli.map_model,
+        map_threshold: cli.map_threshold.saturating_mul(1024),
+        map_concurrency: cli.map_concurrency,
+        compare_models: cli.compare,
+        user_prompt: prompt::load_user_prompt(),
+        persona: cli.persona,
+        language: cli.language.or(user_config.language.clone()),
+        model_params,
+        ignore_patterns,
+        include_patterns,
+        webhooks: user_config.webhooks,
+        templates: user_config.templates,
+        mock: cli.mock,
+        forge,
     };
+    github::set_gh_timeout(Duration::from_secs(cli.gh_timeout));
+
+    // Only OpenAI can be probed; other providers are found out on first use
+    let openai_first = config.providers.first().is_some_and(|first| first.name == llm::PROVIDER);
+    let needs_key = config.providers.iter().any(|p| p.name == llm::PROVIDER);
+    // Mock mode never calls gh or OpenAI
+    if !config.mock && !cli.skip_preflight {
+        let mut checks = match &config.forge {
+            Forge::Bitbucket(server) => vec![preflight::bitbucket_check(server).await],
+            Forge::GitHub => preflight::gh_checks(),
+        };
+        if openai_first {
+            let models = config.story_models();
+            checks.extend(preflight::openai_checks(api_key.as_deref(), &models).await);
+        }
+        if checks.iter().any(|check| check.outcome.is_err()) {
+            let g = if cli.screen_reader {
+                &glyphs::SCREEN_READER
+            } else if cli.ascii {
+                &glyphs::ASCII
+            } else {
+                &glyphs::UNICODE
+            };
+            eprintln!(
+                "dstl can't start yet:\n\n{}",
+                preflight::report(&checks, g.ci_success, g.ci_failure)
+            );
+            anyhow::bail!("Fix the items above, or pass --skip-preflight to start anyway");
+        }
+    }
+    if !config.mock && needs_key && api_key.is_none() {
+        anyhow::bail!("No API key: set OPENAI_API_KEY or api_key in ~/.config/dstl/config.toml");
+    }
+
+    let mut app = App::new();
+    app.skip_noise = config.skip_noise;
+    app.thread_questions = config.thread_questions;
+    app.dry_run = config.dry_run;
+    app.spellcheck = config.spellcheck;
+    app.context_lines = config.context_lines;
+    app.persona = config.persona;
+    app.language = config.language.clone();
+    app.model_params = config.model_params;
+    app.repo_org = cli.org;
+    app.scope = cli.paths;
+    if cli.screen_reader {
+        app.glyphs = &glyphs::SCREEN_READER;
+        app.screen_reader = true;
+    } else if cli.ascii {
+        app.glyphs = &glyphs::ASCII;
+    }
+    app.recent_log = logging.recent.clone();
+    app.history = History::load();
+    app.preferences = Preferences::load();
+    if !config.mock {
+        // Whatever failed to post last time is offered for retry straight away
+        app.outbox = Outbox::load();
+        app.memory = ReviewMemory::load();
+        app.reviewed = ReviewedPrs::load();
+        app.show_outbox = !app.outbox.items.is_empty();
+        if matches!(mode, StartupMode::RepoSelector) {
+            app.resume = Session::load();
+            app.resume_selected = app.resume.is_some();
+        }
+    }
+    let initial_commands = bootstrap(&mut app, &mode, &config);
+
+    if let Some(CliCommand::Export { output, format, .. }) = &cli.command {
+        anyhow::ensure!(
+            matches!(mode, StartupMode::DirectPr { .. }),
+            "Export needs a PR: owner/repo#123 or a GitHub URL"
+        );
+        return subcommands::export(&mut app, &config, initial_commands, output.as_deref(), *format)
+            .await;
+    }
+    if let Some(CliCommand::Bot { .. }) = &cli.command {
+        anyhow::ensure!(
+            matches!(mode, StartupMode::DirectPr { .. }),
+            "Bot needs a PR: owner/repo#123 or a GitHub URL"
+        );
+        return subcommands::bot(&mut app, &config, initial_commands).await;
+    }
 
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableFocusChange)?;
+    suspend::register()?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
-    let initial_commands = bootstrap(&mut app, &mode, &config);
-
+    // While the UI owns the terminal, a panic has to hand it back before it
+    // can report anything
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(report_crash));
     let result = run_event_loop(&mut terminal, &mut app, &config, initial_commands).await;
+    std::panic::set_hook(default_hook);
+
+    // Remember where the review left off for the next launch without arguments
+    if let Some(session) = app.session().filter(|_| !config.mock)
+        && let Err(err) = session.save()
+    {
+        tracing::warn!(error = %err, "Could not save session");
+    }
 
     // Restore terminal
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
     terminal.show_cursor()?;
 
     result
 }
 
+fn cache_policy(cli: &Cli) -> CachePolicy {
+    CachePolicy {
+        ttl: (cli.cache_ttl > 0).then(|| Duration::from_secs(cli.cache_ttl * 24 * 60 * 60)),
+        max_bytes: cli.cache_max_size.saturating_mul(1024 * 1024),
+    }
+}
+
 fn bootstrap(app: &mut App, mode: &StartupMode, config: &AppConfig) -> Vec<Command> {
     match mode {
         StartupMode::RepoSelector => {
             app.state = AppState::LoadingRepoList;
-            vec![Command::FetchRepoList]
+            vec![Command::FetchStartup {
+                org: app.repo_org.clone(),
+            }]
+        }
+        StartupMode::ReviewQueue => {
+            app.state = AppState::LoadingReviewQueue;
+            vec![Command::FetchReviewQueue]
         }
         StartupMode::PrPicker { owner, repo } => {
             app.state = AppState::LoadingPrList;
@@ -147,6 +653,7 @@ fn bootstrap(app: &mut App, mode: &StartupMode, config: &AppConfig) -> Vec<Comma
             vec![Command::FetchPrList {
                 owner: owner.clone(),
                 repo: repo.clone(),
+                states: app.pr_filter,
             }]
         }
         StartupMode::DirectPr {
@@ -158,8 +665,19 @@ fn bootstrap(app: &mut App, mode: &StartupMode, config: &AppConfig) -> Vec<Comma
             app.current_repo = Some((owner.clone(), repo.clone()));
             app.current_pr_number = Some(*number);
             if config.use_cache {
+                let entries = config
+                    .story_models()
+                    .into_iter()
+                    .map(|model| {
+                        let path = config.cache_path(&model);
+                        (model, path)
+                    })
+                    .collect();
                 vec![Command::LoadCache {
-                    path: config.cache_file.clone(),
+                    owner: owner.clone(),
+                    repo: repo.clone(),
+                    number: *number,
+                    entries,
                 }]
             } else {
                 vec![Command::FetchPr {
@@ -182,21 +700,26 @@ async fn run_event_loop(
 
     run_commands(terminal, app, config, initial_commands, &mut actions).await?;
     process_actions(terminal, app, config, &mut actions).await?;
+    let mut last_poll = Instant::now();
 
     loop {
-        terminal.draw(|f| ui::render(f, app))?;
+        draw(terminal, app, config)?;
 
         if app.should_quit {
             break;
         }
+        suspend::handle_signals(terminal, std::mem::take(&mut app.suspend_requested))?;
+
+        if config.poll_interval.is_some_and(|interval| last_poll.elapsed() >= interval) {
+            last_poll = Instant::now();
+            actions.push_back(Action::PollDue);
+            process_actions(terminal, app, config, &mut actions).await?;
+        }
 
         if event::poll(Duration::from_millis(100))?
-            && let Event::Key(key) = event::read()?
+            && let Some(action) = event_action(terminal, app, config, event::read()?)?
         {
-            actions.push_back(Action::Input {
-                code: key.code,
-                modifiers: key.modifiers,
-            });
+            actions.push_back(action);
             process_actions(terminal, app, config, &mut actions).await?;
         }
     }
@@ -204,6 +727,82 @@ async fn run_event_loop(
     Ok(())
 }
 
+/// Panic hook for the event loop: restore the terminal, write a crash report,
+/// and say where it is
+fn report_crash(info: &std::panic::PanicHookInfo) {
+    let _ = suspend::release();
+    eprintln!("dstl crashed: {}", domain::crash::describe(info));
+    match domain::crash::save(&domain::crash::report(info)) {
+        Ok(path) => eprintln!(
+            "A crash report with the last actions is in {}; please attach it to a bug report.",
+            path.display()
+        ),
+        Err(err) => eprintln!("Could not write a crash report: {:#}", err),
+    }
+}
+
+/// Print announcements that weren't already on the main screen there, one per
+/// line, then come back and redraw the UI from scratch
+fn announce(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> Result<()> {
+    let lines = ui::announcements(app);
+    let new: Vec<&String> = lines.iter().filter(|line| !app.announced.contains(line)).collect();
+    if !new.is_empty() {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        for line in new {
+            // Raw mode doesn't return the carriage on a newline
+            write!(terminal.backend_mut(), "{}\r\n", line)?;
+        }
+        execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+        terminal.clear()?;
+    }
+    app.announced = lines;
+    Ok(())
+}
+
+/// The action a terminal event stands for, if any. A resize is laid out at
+/// the new size first, so the update sees the document as it now wraps.
+fn event_action(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    config: &AppConfig,
+    event: Event,
+) -> Result<Option<Action>> {
+    Ok(match event {
+        Event::Key(key) => Some(Action::Input {
+            code: key.code,
+            modifiers: key.modifiers,
+        }),
+        Event::FocusGained => Some(Action::FocusChanged(true)),
+        Event::FocusLost => Some(Action::FocusChanged(false)),
+        Event::Resize(..) => {
+            // Some terminals reflow the old contents; start from a blank screen
+            terminal.autoresize()?;
+            terminal.clear()?;
+            draw(terminal, app, config)?;
+            Some(Action::Resized)
+        }
+        _ => None,
+    })
+}
+
+/// Draw the UI, and retitle the terminal window when what it shows has changed
+fn draw(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    config: &AppConfig,
+) -> Result<()> {
+    if config.announce {
+        announce(terminal, app)?;
+    }
+    terminal.draw(|f| ui::render(f, app))?;
+    let title = ui::terminal_title(app);
+    if title != app.terminal_title {
+        execute!(terminal.backend_mut(), SetTitle(&title))?;
+        app.terminal_title = title;
+    }
+    Ok(())
+}
+
 async fn process_actions(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
@@ -221,16 +820,64 @@ async fn process_actions(
     Ok(())
 }
 
+/// Run commands one at a time, redrawing while each is in flight so
+/// progress events and the spinner stay live. Keys pressed meanwhile are
+/// handled immediately, so an in-flight command can be cancelled.
 async fn run_commands(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    app: &App,
+    app: &mut App,
     config: &AppConfig,
     commands: Vec<Command>,
     actions: &mut VecDeque<Action>,
 ) -> Result<()> {
-    for command in commands {
-        terminal.draw(|f| ui::render(f, app))?;
-        if let Some(action) = execute_command(command, config).await {
+    let mut commands: VecDeque<Command> = commands.into();
+    'commands: while let Some(command) = commands.pop_front() {
+        draw(terminal, app, config)?;
+
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+        let task_config = config.clone();
+        let mut task = tokio::spawn(async move {
+            execute_command(command, &task_config, &progress_tx).await
+        });
+        let mut redraw = tokio::time::interval(Duration::from_millis(100));
+
+        let result = loop {
+            tokio::select! {
+                result = &mut task => break result?,
+                Some(progress) = progress_rx.recv() => {
+                    // Progress updates only touch display state and never issue commands
+                    update(app, progress, config);
+                }
+                _ = redraw.tick() => {
+                    let mut issued = Vec::new();
+                    while event::poll(Duration::ZERO)? {
+                        if let Some(action) = event_action(terminal, app, config, event::read()?)? {
+                            issued.extend(update(app, action, config));
+                        }
+                    }
+                    // Dropping the task's future aborts any HTTP request in progress
+                    if app.should_quit {
+                        task.abort();
+                        return Ok(());
+                    }
+                    if std::mem::take(&mut app.cancel_in_flight) {
+                        // Queued work (e.g. other compared models) belongs to the cancelled load
+                        task.abort();
+                        commands = issued.into();
+                        continue 'commands;
+                    }
+                    commands.extend(issued);
+                    suspend::handle_signals(terminal, std::mem::take(&mut app.suspend_requested))?;
+                    draw(terminal, app, config)?;
+                }
+            }
+        };
+
+        // Apply progress sent just before the command finished
+        while let Ok(progress) = progress_rx.try_recv() {
+            update(app, progress, config);
+        }
+        if let Some(action) = result {
             actions.push_back(action);
         }
     }
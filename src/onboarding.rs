@@ -0,0 +1,217 @@
+//! First-run setup, before the TUI starts: pick a provider and model, enter an
+//! API key, check gh is logged in, and write `~/.config/dstl/config.toml`.
+
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use reqwest::StatusCode;
+
+use crate::domain::user_config::UserConfig;
+use crate::domain::{github, keychain, llm};
+
+/// Providers dstl can generate stories with: (id, name, where to get a key)
+const PROVIDERS: &[(&str, &str, &str)] =
+    &[("openai", "OpenAI", "https://platform.openai.com/api-keys")];
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Walk through setup and save the result
+pub async fn run(default_model: &str) -> Result<UserConfig> {
+    println!("Welcome to dstl. Let's get you set up; this only happens once.\n");
+
+    let (provider, provider_name, key_url) = choose_provider()?;
+    println!();
+
+    let mut api_key = read_secret(&format!(
+        "{} API key (input hidden; create one at {}): ",
+        provider_name, key_url
+    ))?;
+    let mut model = ask(&format!("Default model [{}]: ", default_model))?;
+    if model.is_empty() {
+        model = default_model.to_string();
+    }
+    loop {
+        print!("Checking the key can use {}... ", model);
+        io::stdout().flush()?;
+        match llm::probe_model(&api_key, &model, PROBE_TIMEOUT).await {
+            Ok(status) if status.is_success() => {
+                println!("ok");
+                break;
+            }
+            Ok(StatusCode::UNAUTHORIZED) => {
+                println!("the key was rejected");
+                api_key = read_secret("API key: ")?;
+            }
+            Ok(StatusCode::NOT_FOUND) => {
+                println!("{} isn't available to this key", model);
+                model = ask("Model: ")?;
+            }
+            Ok(status) => {
+                println!("{} returned {}; keeping these and carrying on", provider_name, status);
+                break;
+            }
+            Err(err) => {
+                println!("couldn't check ({:#}); keeping these and carrying on", err);
+                break;
+            }
+        }
+    }
+
+    println!();
+    let stored_in_file = store_key(provider, &api_key)?;
+    println!();
+    check_gh()?;
+
+    let config = UserConfig {
+        provider: Some(provider.to_string()),
+        model: Some(model),
+        api_key: stored_in_file.then_some(api_key),
+        ..UserConfig::default()
+    };
+    let path = config.save()?;
+    println!(
+        "\nSaved {}. Edit it any time; flags and OPENAI_API_KEY override it.\n",
+        path.display()
+    );
+    Ok(config)
+}
+
+/// Keep the key where the user chooses. True when it belongs in config.toml.
+fn store_key(provider: &str, api_key: &str) -> Result<bool> {
+    println!("Where should dstl keep the key?");
+    println!("  1) The OS keychain");
+    println!("  2) config.toml, readable only by you");
+    println!("  3) Nowhere; I'll set OPENAI_API_KEY myself");
+    loop {
+        match ask("Key storage [1]: ")?.as_str() {
+            "" | "1" => match keychain::set(provider, api_key) {
+                Ok(keyring) => {
+                    println!("Saved the key in {}", keyring);
+                    return Ok(false);
+                }
+                Err(err) => println!("Couldn't use the keychain: {:#}", err),
+            },
+            "2" => return Ok(true),
+            "3" => return Ok(false),
+            _ => println!("Enter 1, 2 or 3"),
+        }
+    }
+}
+
+/// `dstl auth set-key`: save a key in the OS keychain
+pub fn set_key(provider: &str) -> Result<()> {
+    let Some((_, name, key_url)) = PROVIDERS.iter().find(|(id, _, _)| *id == provider) else {
+        let known: Vec<&str> = PROVIDERS.iter().map(|(id, _, _)| *id).collect();
+        anyhow::bail!("Unknown provider {}; choose from {}", provider, known.join(", "));
+    };
+    let key = read_secret(&format!("{} API key (input hidden; from {}): ", name, key_url))?;
+    let keyring = keychain::set(provider, &key)?;
+    println!("Saved the {} key in {}. OPENAI_API_KEY still takes precedence.", name, keyring);
+    Ok(())
+}
+
+fn choose_provider() -> Result<(&'static str, &'static str, &'static str)> {
+    println!("Which provider should write the stories?");
+    for (i, (_, name, _)) in PROVIDERS.iter().enumerate() {
+        println!("  {}) {}", i + 1, name);
+    }
+    loop {
+        let answer = ask("Provider [1]: ")?;
+        if answer.is_empty() {
+            return Ok(PROVIDERS[0]);
+        }
+        match answer.parse::<usize>().ok().and_then(|n| PROVIDERS.get(n.wrapping_sub(1))) {
+            Some(provider) => return Ok(*provider),
+            None => println!("Enter a number from 1 to {}", PROVIDERS.len()),
+        }
+    }
+}
+
+/// Make sure gh is installed and logged in, offering to log in now
+fn check_gh() -> Result<()> {
+    match github::gh_version() {
+        Ok(version) => println!("Found {}", version),
+        Err(_) => {
+            println!(
+                "The GitHub CLI (gh) wasn't found. Install it from https://cli.github.com/ \
+                 and run `gh auth login` before starting dstl."
+            );
+            return Ok(());
+        }
+    }
+    loop {
+        match github::auth_status() {
+            Ok(account) => {
+                println!("gh is logged in to {}", account);
+                return Ok(());
+            }
+            Err(err) => {
+                println!("gh isn't logged in: {}", err);
+                if !confirm("Run `gh auth login` now? [Y/n]: ")? {
+                    return Ok(());
+                }
+                std::process::Command::new("gh")
+                    .args(["auth", "login"])
+                    .status()
+                    .context("Failed to run gh auth login")?;
+            }
+        }
+    }
+}
+
+fn ask(prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        anyhow::bail!("Setup cancelled");
+    }
+    Ok(line.trim().to_string())
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    let answer = ask(prompt)?.to_lowercase();
+    Ok(answer.is_empty() || answer.starts_with('y'))
+}
+
+/// Read a line without echoing it
+fn read_secret(prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    enable_raw_mode()?;
+    let secret = read_hidden();
+    disable_raw_mode()?;
+    println!();
+    let secret = secret?;
+    if secret.is_empty() {
+        anyhow::bail!("Setup cancelled: no key entered");
+    }
+    Ok(secret)
+}
+
+fn read_hidden() -> Result<String> {
+    let mut secret = String::new();
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => return Ok(secret.trim().to_string()),
+            KeyCode::Backspace => {
+                secret.pop();
+            }
+            KeyCode::Esc => anyhow::bail!("Setup cancelled"),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                anyhow::bail!("Setup cancelled")
+            }
+            KeyCode::Char(c) => secret.push(c),
+            _ => {}
+        }
+    }
+}
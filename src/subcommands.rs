@@ -0,0 +1,203 @@
+//! Subcommands that do their job and exit without opening the TUI.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tokio::sync::mpsc;
+
+use crate::action::Action;
+use crate::app::{App, AppState};
+use crate::command::{execute_command, Command};
+use crate::config::{AppConfig, Forge};
+use crate::domain::cache::CachePolicy;
+use crate::domain::listings::{format_age, now_secs};
+use crate::domain::secrets::SecretsChoice;
+use crate::domain::user_config::UserConfig;
+use crate::domain::{cache, export, github, llm_cache};
+use crate::update::update;
+
+/// Run a PR's load through the same reducer as the TUI until its story is ready.
+/// Likely secrets are redacted, since there's no one to ask
+async fn generate(app: &mut App, config: &AppConfig, commands: Vec<Command>) -> Result<()> {
+    app.secrets_choice = Some(SecretsChoice::Redact);
+    let mut commands: VecDeque<Command> = commands.into();
+    while app.story.is_none()
+        && let Some(command) = commands.pop_front()
+    {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+        let result = execute_command(command, config, &progress_tx).await;
+        while let Ok(progress) = progress_rx.try_recv() {
+            if let Action::Progress(event) = &progress {
+                eprintln!("{}", event.describe());
+            }
+            update(app, progress, config);
+        }
+        if let Some(action) = result {
+            commands.extend(update(app, action, config));
+        }
+        if let AppState::Error(err) = &app.state {
+            anyhow::bail!("{}", err);
+        }
+    }
+    if !app.secret_findings.is_empty() && !config.allow_secrets {
+        eprintln!(
+            "Redacted {} added lines that look like secrets (--allow-secrets to send them)",
+            app.secret_findings.len()
+        );
+    }
+    Ok(())
+}
+
+/// What `dstl export` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Markdown,
+    /// The review session record the TUI exports with `E`
+    Json,
+}
+
+/// `dstl export`: write the story to `output` (stdout if None)
+pub async fn export(
+    app: &mut App,
+    config: &AppConfig,
+    commands: Vec<Command>,
+    output: Option<&Path>,
+    format: ExportFormat,
+) -> Result<()> {
+    generate(app, config, commands).await?;
+    let story = app.story.as_ref().context("No story was generated")?;
+
+    let text = match format {
+        ExportFormat::Markdown => export::story_markdown(app.pr.as_ref(), story),
+        ExportFormat::Json => {
+            let record = app.session_record(now_secs()).context("No PR was loaded")?;
+            record.to_json()? + "\n"
+        }
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(path, text)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Wrote {}", path.display());
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// `dstl bot`: post the story's summary as a PR comment, editing the one an
+/// earlier run posted. With `--dry-run` or `--mock` the comment is printed instead
+pub async fn bot(app: &mut App, config: &AppConfig, commands: Vec<Command>) -> Result<()> {
+    generate(app, config, commands).await?;
+    let story = app.story.as_ref().context("No story was generated")?;
+    let pr = app.pr.as_ref().context("No PR was loaded")?;
+
+    let comment = export::bot_comment(pr, story);
+    if config.dry_run || config.mock {
+        print!("{}", comment);
+        return Ok(());
+    }
+    let updated = match &config.forge {
+        Forge::GitHub => github::upsert_marked_comment(
+            &pr.owner,
+            &pr.repo,
+            pr.number,
+            export::BOT_MARKER,
+            &comment,
+        )?,
+        Forge::Bitbucket(server) => {
+            server
+                .upsert_marked_comment(&pr.owner, &pr.repo, pr.number, export::BOT_MARKER, &comment)
+                .await?
+        }
+    };
+    let verb = if updated { "Updated" } else { "Posted" };
+    eprintln!("{} the story comment on {}/{}#{}", verb, pr.owner, pr.repo, pr.number);
+    Ok(())
+}
+
+/// `dstl cache list`
+pub fn list_cache(cache_file: &str) -> Result<()> {
+    let entries = cache::list(cache_file);
+    if entries.is_empty() {
+        println!("No cached stories for {}", cache_file);
+    }
+    for (path, entry) in entries {
+        let head = cache::short_sha(&entry.head_sha);
+        println!(
+            "{:<32} {:<16} {:<8} {:<10} {}",
+            entry.pr_label(),
+            entry.model,
+            if head.is_empty() { "-" } else { head },
+            format_age(entry.created_at),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// `dstl cache clear`
+pub fn clear_cache(cache_file: &str) -> Result<()> {
+    let mut removed = cache::clear(cache_file)?;
+    removed.extend(llm_cache::clear()?);
+    if removed.is_empty() {
+        println!("No cached stories for {}", cache_file);
+    }
+    for file in removed {
+        println!("Deleted {}", file.display());
+    }
+    Ok(())
+}
+
+/// `dstl config edit`: open config.toml in the user's editor, creating it if needed
+pub fn edit_config(default_model: &str) -> Result<()> {
+    let path = match UserConfig::path() {
+        Some(path) if path.exists() => path,
+        _ => UserConfig {
+            provider: Some(crate::domain::llm::PROVIDER.to_string()),
+            model: Some(default_model.to_string()),
+            api_key: None,
+            ..UserConfig::default()
+        }
+        .save()?,
+    };
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    // $EDITOR may carry arguments, e.g. `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = words.next().context("$EDITOR is empty")?;
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to run {}", editor))?;
+    anyhow::ensure!(status.success(), "{} exited with {}", editor, status);
+    Ok(())
+}
+
+/// `dstl cache prune`: asks GitHub for each cached PR's head, once per PR
+pub fn prune_cache(cache_file: &str, policy: &CachePolicy) -> Result<()> {
+    let mut heads: HashMap<String, Option<String>> = HashMap::new();
+    let dropped = cache::prune(cache_file, policy, |entry| {
+        heads
+            .entry(entry.pr_label())
+            .or_insert_with(|| {
+                github::fetch_head_sha(&entry.owner, &entry.repo, entry.number)
+                    .inspect_err(|err| {
+                        let pr = entry.pr_label();
+                        eprintln!("Keeping {}: couldn't check its head ({:#})", pr, err)
+                    })
+                    .ok()
+            })
+            .clone()
+    })?;
+    if dropped.is_empty() {
+        println!("Nothing to prune");
+    }
+    for (entry, reason) in dropped {
+        println!("Dropped {} ({}): {}", entry.pr_label(), entry.model, reason.label());
+    }
+    Ok(())
+}
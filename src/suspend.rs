@@ -0,0 +1,121 @@
+//! Job control: Ctrl+Z (or a `SIGTSTP` from elsewhere) hands the terminal back
+//! to the shell in its normal state, and `fg` picks the UI up again. Raw mode
+//! turns Ctrl+Z into a key press, so dstl stops itself.
+
+use std::io;
+
+use anyhow::Result;
+use crossterm::{
+    cursor::Show,
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+
+#[cfg(unix)]
+mod signals {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, OnceLock};
+
+    use signal_hook::consts::{SIGCONT, SIGTSTP};
+
+    /// Set by `SIGTSTP` and `SIGCONT` until the event loop sees them
+    static FLAGS: OnceLock<(Arc<AtomicBool>, Arc<AtomicBool>)> = OnceLock::new();
+
+    pub fn register() -> std::io::Result<()> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let resume = Arc::new(AtomicBool::new(false));
+        signal_hook::flag::register(SIGTSTP, Arc::clone(&stop))?;
+        signal_hook::flag::register(SIGCONT, Arc::clone(&resume))?;
+        let _ = FLAGS.set((stop, resume));
+        Ok(())
+    }
+
+    pub fn take_stop() -> bool {
+        FLAGS.get().is_some_and(|(stop, _)| stop.swap(false, Ordering::Relaxed))
+    }
+
+    pub fn take_resume() -> bool {
+        FLAGS.get().is_some_and(|(_, resume)| resume.swap(false, Ordering::Relaxed))
+    }
+
+    /// Stop the process until the shell continues it
+    pub fn stop() -> std::io::Result<()> {
+        signal_hook::low_level::raise(signal_hook::consts::SIGSTOP)
+    }
+}
+
+#[cfg(not(unix))]
+mod signals {
+    pub fn register() -> std::io::Result<()> {
+        Ok(())
+    }
+
+    pub fn take_stop() -> bool {
+        false
+    }
+
+    pub fn take_resume() -> bool {
+        false
+    }
+
+    pub fn stop() -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Catch `SIGTSTP` and `SIGCONT` so they go through [`suspend`] and [`resume`]
+pub fn register() -> Result<()> {
+    signals::register()?;
+    Ok(())
+}
+
+/// Suspend if Ctrl+Z was pressed or a stop signal arrived, and repair the
+/// terminal if something else stopped and continued the process
+pub fn handle_signals(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    requested: bool,
+) -> Result<()> {
+    if requested || signals::take_stop() {
+        suspend(terminal)?;
+    } else if signals::take_resume() {
+        resume(terminal)?;
+    }
+    Ok(())
+}
+
+/// Restore the terminal, stop until `fg`, then take the terminal back
+pub fn suspend(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    release()?;
+    signals::stop()?;
+    // The SIGCONT that woke us up is this resume
+    signals::take_resume();
+    resume(terminal)
+}
+
+/// Hand the terminal back in the state the shell expects: cooked mode, the
+/// main screen, and a visible cursor. Also used when dstl panics.
+pub fn release() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableFocusChange,
+        Show
+    )
+}
+
+/// Put the terminal back in the state the UI draws in and redraw it all
+pub fn resume(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
+    terminal.clear()?;
+    Ok(())
+}
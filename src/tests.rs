@@ -0,0 +1,27 @@
+use clap::Parser;
+
+use crate::{Cli, CliCommand};
+
+#[test]
+fn bare_refs_and_subcommand_names_parse_apart() {
+    let parse = |args: &[&str]| Cli::try_parse_from([&["dstl"], args].concat());
+    let requested = |args: &[&str]| parse(args).unwrap().requested_ref().unwrap().cloned();
+
+    assert_eq!(requested(&["acme/widgets#42"]).as_deref(), Some("acme/widgets#42"));
+    assert_eq!(requested(&["review", "acme/widgets#42"]).as_deref(), Some("acme/widgets#42"));
+    assert_eq!(requested(&["--mock", "acme/widgets"]).as_deref(), Some("acme/widgets"));
+    assert_eq!(requested(&[]), None);
+
+    // A subcommand's name is never taken for a ref
+    let cli = parse(&["--mock", "bot", "acme/widgets#42"]).unwrap();
+    assert!(matches!(cli.command, Some(CliCommand::Bot { .. })));
+    assert_eq!(cli.pr_ref, None);
+    let bot = requested(&["--mock", "bot", "acme/widgets#42"]);
+    assert_eq!(bot.as_deref(), Some("acme/widgets#42"));
+    assert!(matches!(parse(&["queue"]).unwrap().command, Some(CliCommand::Queue)));
+    assert!(parse(&["bot"]).is_err());
+
+    // A ref before a subcommand would be silently dropped, so it's refused
+    let cli = parse(&["acme/widgets#42", "bot", "acme/widgets#7"]).unwrap();
+    assert!(cli.requested_ref().is_err());
+}
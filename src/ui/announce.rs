@@ -0,0 +1,44 @@
+use crate::app::{App, AppState};
+use crate::domain::types::ProgressEvent;
+
+/// What dstl is doing, as plain sentences for `--announce`: the screen, each
+/// step of a load or generation so far, and the status message
+pub fn announcements(app: &App) -> Vec<String> {
+    let target = match (&app.pr, &app.current_repo, app.current_pr_number) {
+        (Some(pr), _, _) => format!("{}#{}", pr.repo, pr.number),
+        (None, Some((_, repo)), Some(number)) => format!("{}#{}", repo, number),
+        _ => "the PR".to_string(),
+    };
+    let screen = match &app.state {
+        AppState::LoadingRepoList => Some("Loading repositories".to_string()),
+        AppState::RepoSelector => Some("Choose a repository".to_string()),
+        AppState::LoadingPrList => Some("Loading pull requests".to_string()),
+        AppState::PrPicker => Some("Choose a pull request".to_string()),
+        AppState::LoadingReviewQueue => Some("Loading the review queue".to_string()),
+        AppState::ReviewQueue => Some("Choose a pull request from the review queue".to_string()),
+        AppState::LoadingPr => Some(format!("Loading {}", target)),
+        AppState::GeneratingStory => Some(format!("Generating the story for {}", target)),
+        AppState::Viewing => app
+            .pr
+            .as_ref()
+            .map(|pr| format!("Reading the story for {}: {}", target, pr.title)),
+        AppState::Submitting(action) => {
+            Some(format!("Submitting {} to {}", action.title(), target))
+        }
+        AppState::Error(message) => Some(format!("Error: {}", message)),
+        _ => None,
+    };
+
+    let mut lines: Vec<String> = screen.into_iter().collect();
+    if matches!(app.state, AppState::LoadingPr | AppState::GeneratingStory) {
+        // The running token count would be a new line every few words
+        lines.extend(
+            app.progress
+                .iter()
+                .filter(|event| !matches!(event, ProgressEvent::ReceivedTokens { .. }))
+                .map(ProgressEvent::describe),
+        );
+    }
+    lines.extend(app.status.clone());
+    lines
+}
@@ -0,0 +1,72 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::markdown::render_markdown;
+use super::util::centered_rect;
+
+/// One-line notice of commits and comments that arrived while reading, if any
+pub fn activity_banner(app: &App) -> Option<Line<'static>> {
+    let commits = app.new_commits();
+    let comments = app.new_comments().len();
+    if commits == 0 && comments == 0 {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    if commits > 0 {
+        parts.push(format!("{} new commit{}", commits, if commits == 1 { "" } else { "s" }));
+    }
+    if comments > 0 {
+        parts.push(format!("{} new comment{}", comments, if comments == 1 { "" } else { "s" }));
+    }
+    let mut keys = vec!["U to update"];
+    if comments > 0 {
+        keys.push("A to read");
+    }
+    Some(Line::from(vec![
+        Span::styled(
+            format!("{} {}", app.glyphs.bullet, parts.join(", ")),
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!(" ({})", keys.join(", ")), Style::default().fg(Color::DarkGray)),
+    ]))
+}
+
+/// Overlay of the comments and reviews posted since reading began
+pub fn render_activity_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(80, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = Vec::new();
+    for comment in app.new_comments() {
+        lines.push(Line::from(vec![
+            Span::styled(
+                comment.author.clone(),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("  {}", comment.created_at), Style::default().fg(Color::DarkGray)),
+        ]));
+        lines.extend(render_markdown(&comment.body, g));
+        lines.push(Line::from(""));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Magenta))
+        .title(" New comments ");
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((app.activity_scroll, 0));
+    frame.render_widget(paragraph, popup_area);
+}
@@ -0,0 +1,87 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::{App, BatchStatus};
+
+use super::util::{centered_rect, truncate};
+
+/// Overlay of the three drafted actions: which go out together, and how each went
+pub fn render_batch_submit(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(70, 50, area);
+    frame.render_widget(Clear, popup_area);
+
+    let target = app
+        .pr
+        .as_ref()
+        .map(|pr| format!("{}/{}#{}", pr.owner, pr.repo, pr.number))
+        .unwrap_or_default();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("SUBMIT TO {}", target),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    let width = popup_area.width.saturating_sub(40) as usize;
+    for (i, item) in app.batch.iter().enumerate() {
+        let selected = i == app.batch_selected;
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let text = app.action_text(item.action);
+        let first_line = text.lines().find(|line| !line.trim().is_empty());
+        let (status, status_style) = match &item.status {
+            BatchStatus::Draft if first_line.is_none() => {
+                ("(no draft)".to_string(), Style::default().fg(Color::DarkGray))
+            }
+            BatchStatus::Draft => (String::new(), Style::default()),
+            BatchStatus::Queued => {
+                (format!("{} sending", g.waiting), Style::default().fg(Color::Yellow))
+            }
+            BatchStatus::Sent => {
+                let sent = if app.dry_run { "written" } else { "sent" };
+                (format!("{}{}", g.done, sent), Style::default().fg(Color::Green))
+            }
+            BatchStatus::Failed(_) => {
+                (format!("{} failed", g.ci_failure), Style::default().fg(Color::Red))
+            }
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(if selected { g.selected } else { "  " }, style),
+            Span::styled(format!("[{}] ", if item.checked { "x" } else { " " }), style),
+            Span::styled(format!("{}. {:<24}", i + 1, item.action.title()), style),
+            Span::styled(status, status_style),
+        ]));
+        if let Some(first_line) = first_line {
+            lines.push(Line::from(Span::styled(
+                format!("         {}", truncate(first_line.trim(), width.max(10), g)),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        if let BatchStatus::Failed(err) = &item.status {
+            lines.push(Line::from(Span::styled(
+                format!("         {}", err),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Submit drafts ");
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
@@ -0,0 +1,87 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::centered_rect;
+
+/// Overlay listing who last changed the base-side lines the selected diff block rewrites
+pub fn render_blame_view(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(80, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some((path, start, end)) = &app.blame_view else {
+        return;
+    };
+    let mut lines: Vec<Line> = vec![
+        Line::from(vec![
+            Span::styled(
+                "LAST CHANGED",
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{} lines {}-{} at base", path, start, end),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    match app.blame.get(path) {
+        None => lines.push(Line::from(Span::styled(
+            format!("{} Fetching blame...", g.waiting),
+            Style::default().fg(Color::Yellow),
+        ))),
+        Some(ranges) => {
+            let touching: Vec<_> = ranges
+                .iter()
+                .filter(|range| range.start_line <= *end && range.end_line >= *start)
+                .collect();
+            if touching.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No existing lines are rewritten (pure addition)",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            for range in touching {
+                let pr_ref = range
+                    .pr_number
+                    .map(|n| format!(" #{}", n))
+                    .unwrap_or_default();
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("L{}-{} ", range.start_line, range.end_line),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(format!("{} ", range.commit), Style::default().fg(Color::Yellow)),
+                    Span::styled(format!("{} ", range.author), Style::default().fg(Color::Cyan)),
+                    Span::styled(
+                        range.date.get(..10).unwrap_or(&range.date).to_string(),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(pr_ref, Style::default().fg(Color::Blue)),
+                ]));
+                lines.push(Line::from(vec![
+                    Span::styled("    ", Style::default()),
+                    Span::styled(range.summary.clone(), Style::default().fg(Color::White)),
+                ]));
+            }
+        }
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Blame ");
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
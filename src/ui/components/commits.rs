@@ -0,0 +1,59 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::{centered_rect, truncate};
+
+/// Overlay listing the PR's commits, checked ones to be reviewed on their own
+pub fn render_commit_range(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(70, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "COMMITS TO REVIEW",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            "The story covers the first to the last checked commit",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+    ];
+
+    let width = popup_area.width.saturating_sub(26) as usize;
+    for (i, commit) in app.commits.iter().enumerate() {
+        let selected = i == app.commit_selected;
+        let checked = app.commit_checked.get(i).copied().unwrap_or(false);
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else if checked {
+            Style::default().fg(Color::White)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let marker = if selected { g.selected } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Cyan)),
+            Span::styled(if checked { "[x] " } else { "[ ] " }, style),
+            Span::styled(format!("{} ", commit.short_sha()), Style::default().fg(Color::Magenta)),
+            Span::styled(truncate(&commit.headline, width, g), style),
+            Span::styled(format!("  {}", commit.author), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Commit range ")
+        .title_bottom(" Space Toggle   f From here   Enter Regenerate   Esc Cancel ");
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
@@ -0,0 +1,131 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::types::{Significance, Story};
+use crate::ui::glyphs::Glyphs;
+
+use super::util::centered_rect;
+
+/// Overlay putting each compared model's story side by side: summary, focus,
+/// how it grouped and rated the changes, and what it flagged
+pub fn render_compare_view(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(90, 80, area);
+    frame.render_widget(Clear, popup_area);
+
+    let columns = app.story_variants.len() + app.pending_models.len();
+    if columns == 0 {
+        return;
+    }
+    let outer = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Compare models ");
+    let inner = outer.inner(popup_area);
+    frame.render_widget(outer, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+        .split(inner);
+
+    for (i, variant) in app.story_variants.iter().enumerate() {
+        let active = i == app.active_variant;
+        let lines = story_column(&variant.story, g);
+        render_column(frame, chunks[i], &variant.model, active, lines, g);
+    }
+    for (i, model) in app.pending_models.iter().enumerate() {
+        let lines = vec![Line::from(Span::styled(
+            format!("{} Generating...", g.waiting),
+            Style::default().fg(Color::Yellow),
+        ))];
+        render_column(frame, chunks[app.story_variants.len() + i], model, false, lines, g);
+    }
+}
+
+fn render_column(
+    frame: &mut Frame,
+    area: Rect,
+    model: &str,
+    active: bool,
+    lines: Vec<Line<'static>>,
+    g: &Glyphs,
+) {
+    let (title, border) = if active {
+        (format!(" {}{} ", g.selected, model), Color::Yellow)
+    } else {
+        (format!(" {} ", model), Color::DarkGray)
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(border))
+        .title(title);
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+fn story_column(story: &Story, g: &Glyphs) -> Vec<Line<'static>> {
+    let heading = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let blocks: Vec<_> = story
+        .narrative
+        .iter()
+        .flat_map(|feature| &feature.diff_blocks)
+        .collect();
+    let key = blocks
+        .iter()
+        .filter(|block| block.significance == Significance::Key)
+        .count();
+    let noise = blocks
+        .iter()
+        .filter(|block| block.significance == Significance::Noise)
+        .count();
+    let risks: usize = story.narrative.iter().map(|feature| feature.risks.len()).sum();
+
+    let mut lines = vec![
+        Line::from(Span::styled("FOCUS", heading)),
+        Line::from(Span::styled(
+            format!("{} {}", g.focus, story.focus.key_change),
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("SUMMARY", heading)),
+        Line::from(story.summary.clone()),
+        Line::from(""),
+        Line::from(Span::styled("SHAPE", heading)),
+        Line::from(format!(
+            "{} features, {} blocks ({} {}, {} {})",
+            story.narrative.len(),
+            blocks.len(),
+            key,
+            g.key,
+            noise,
+            g.noise
+        )),
+        Line::from(format!(
+            "{} risks, {} open questions",
+            risks,
+            story.open_questions.len()
+        )),
+        Line::from(""),
+        Line::from(Span::styled("FEATURES", heading)),
+    ];
+    lines.extend(story.narrative.iter().enumerate().map(|(i, feature)| {
+        Line::from(vec![
+            Span::styled(format!("{}. ", i + 1), Style::default().fg(Color::DarkGray)),
+            Span::raw(feature.title.clone()),
+            Span::styled(
+                format!(" ({})", feature.diff_blocks.len()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])
+    }));
+    lines
+}
@@ -0,0 +1,152 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::dry_run::OUTBOX_DIR;
+use crate::domain::listings::now_secs;
+use crate::domain::timing::ReportMode;
+use crate::domain::types::ReviewAction;
+
+use super::markdown::render_markdown;
+use super::util::centered_rect;
+
+/// Overlay previewing what each pending action will post, and where
+pub fn render_confirm_submit(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(80, 80, area);
+    frame.render_widget(Clear, popup_area);
+
+    let target = app
+        .pr
+        .as_ref()
+        .map(|pr| format!("{}/{}#{}", pr.owner, pr.repo, pr.number))
+        .unwrap_or_default();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("POST TO {}", target),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    for &action in &app.confirm_actions {
+        let text = app.action_text(action);
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", action.title()),
+                Style::default().fg(action_color(action)).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("({})", delivery(app, action)),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        match action {
+            ReviewAction::NextPr => {
+                let mut text_lines = text.lines();
+                let title = text_lines.next().unwrap_or("Follow-up work");
+                lines.push(Line::from(vec![
+                    Span::styled("Title: ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(title.to_string(), Style::default().add_modifier(Modifier::BOLD)),
+                ]));
+                lines.extend(render_markdown(&text_lines.collect::<Vec<_>>().join("\n"), g));
+            }
+            ReviewAction::RequestChanges => {
+                lines.extend(render_markdown(text, g));
+                lines.extend(comment_lines(app));
+                lines.extend(report_lines(app));
+            }
+            _ => lines.extend(render_markdown(text, g)),
+        }
+        lines.push(Line::from(""));
+    }
+
+    let (title, verb) = if app.dry_run {
+        (format!(" Dry run: written to {}, nothing posted ", OUTBOX_DIR), "Write")
+    } else {
+        (" Confirm: this can't be undone ".to_string(), "Post")
+    };
+    let report_key = if app.confirm_actions.contains(&ReviewAction::RequestChanges) {
+        "e Verdict   m Report   "
+    } else {
+        ""
+    };
+    let keys = format!(" y {}   {}n Cancel ", verb, report_key);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Yellow))
+        .title(title)
+        .title_bottom(keys);
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((app.confirm_scroll, 0));
+    frame.render_widget(paragraph, popup_area);
+}
+
+/// The pending review's inline comments, each under where it goes
+fn comment_lines(app: &App) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for comment in &app.pending_comments {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("{}:{}", comment.path, comment.line),
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.extend(render_markdown(&comment.body, app.glyphs));
+    }
+    if !lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
+/// Where the review report goes, previewed when it's appended to the body
+fn report_lines(app: &App) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(vec![
+        Span::styled("Review report: ", Style::default().fg(Color::DarkGray)),
+        Span::styled(app.report_mode.label(), Style::default().fg(Color::Cyan)),
+        Span::styled(" (m to change)", Style::default().fg(Color::DarkGray)),
+    ])];
+    if app.report_mode == ReportMode::Append
+        && let Some(report) = app.review_report(now_secs())
+    {
+        lines.push(Line::from(""));
+        lines.extend(render_markdown(&report.to_markdown(), app.glyphs));
+    }
+    lines
+}
+
+/// How the action reaches GitHub
+fn delivery(app: &App, action: ReviewAction) -> String {
+    match action {
+        ReviewAction::RequestChanges => {
+            let comments = match app.pending_comments.len() {
+                0 => String::new(),
+                1 => " with 1 inline comment".to_string(),
+                n => format!(" with {} inline comments", n),
+            };
+            format!("review{}, submitted as: {}", comments, app.review_event.label())
+        }
+        ReviewAction::ClarificationQuestions if app.thread_questions => {
+            "inline threads on the blocks each question concerns".to_string()
+        }
+        ReviewAction::ClarificationQuestions => "PR comment".to_string(),
+        ReviewAction::NextPr => "new issue, linked from the PR in a comment".to_string(),
+    }
+}
+
+fn action_color(action: ReviewAction) -> Color {
+    match action {
+        ReviewAction::RequestChanges => Color::Red,
+        ReviewAction::ClarificationQuestions => Color::Blue,
+        ReviewAction::NextPr => Color::Green,
+    }
+}
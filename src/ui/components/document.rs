@@ -6,14 +6,48 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::App;
-use crate::domain::types::{DiffRole, ReviewAction, Significance};
+use std::collections::HashSet;
+
+use crate::app::{App, AppState};
+use crate::domain::diff::{block_window, locate_block, FileDiff};
+use crate::domain::symbols::block_uses;
+use crate::domain::types::{
+    BotComment, DiffBlock, DiffRole, ExcludedFile, ExclusionReason, ReviewAction, Significance,
+    SymbolCallers,
+};
+use crate::ui::glyphs::Glyphs;
+
+use super::util::{cited_spans, severity_color};
 
 pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
     // Build the full document as lines
     let mut lines: Vec<Line> = Vec::new();
+    // Index in `lines` of each feature's heading
+    let mut feature_lines: Vec<usize> = Vec::new();
 
     if let Some(story) = &app.story {
+        let bot_comments: &[BotComment] = app.pr.as_ref().map_or(&[], |pr| &pr.bot_comments);
+        let mut attached_comments: HashSet<usize> = HashSet::new();
+
+        // Warn before anything is read from a story that looks unreliable
+        if let Some(lint) = app.story_lint().filter(|lint| lint.is_low()) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} Low-quality story ({}/100)", g.flag, lint.score),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(" Ctrl+R to regenerate", Style::default().fg(Color::DarkGray)),
+            ]));
+            for finding in lint.findings {
+                lines.push(Line::from(Span::styled(
+                    format!("  {} {}", g.bullet, finding),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+
         // Summary
         lines.push(Line::from(vec![Span::styled(
             "SUMMARY",
@@ -24,23 +58,141 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(Color::White),
         )));
         lines.push(Line::from(""));
-        lines.push(Line::from(vec![Span::styled(
+        // The diff's own counts; the model's are only shown when they're off
+        let (stats, disagrees) = app
+            .diff_stats()
+            .unwrap_or_else(|| (story.data.clone(), false));
+        let mut counts = vec![Span::styled(
             format!(
-                "Files: {} │ +{} -{}",
-                story.data.files_touched, story.data.additions, story.data.deletions
+                "Files: {}{}+{} -{}",
+                stats.files_touched, g.separator, stats.additions, stats.deletions
             ),
             Style::default().fg(Color::DarkGray),
-        )]));
+        )];
+        if disagrees {
+            counts.push(Span::styled(
+                format!(
+                    "  (story says {} files, +{} -{})",
+                    story.data.files_touched, story.data.additions, story.data.deletions
+                ),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        lines.push(Line::from(counts));
         lines.push(Line::from(""));
 
+        // Public API changes that break users, before anything else is read
+        let api = &app.api_report;
+        if !api.breaking.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} BREAKING CHANGES ({})", g.flag, api.breaking.len()),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  recommended bump: {}", api.bump.label()),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]));
+            for change in &api.breaking {
+                let mut row = vec![
+                    Span::styled(
+                        format!("{} {:<4} ", g.bullet, change.severity.label()),
+                        Style::default().fg(severity_color(change.severity)),
+                    ),
+                    Span::styled(
+                        &change.item,
+                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(": {}", change.description),
+                        Style::default().fg(Color::White),
+                    ),
+                ];
+                if change.detected {
+                    row.push(Span::styled(
+                        " (from the diff)",
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                lines.push(Line::from(row));
+            }
+            lines.push(Line::from(""));
+        }
+
+        // What's new since the version the reviewer already reviewed
+        if let Some(delta) = app.pr.as_ref().and_then(|pr| pr.review_delta.as_ref())
+            && !story.whats_new.is_empty()
+        {
+            lines.push(Line::from(vec![Span::styled(
+                format!("WHAT'S NEW SINCE YOUR LAST REVIEW ({})", delta.short_reviewed_head()),
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            )]));
+            for item in &story.whats_new {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", g.bullet), Style::default().fg(Color::Magenta)),
+                    Span::styled(item, Style::default().fg(Color::White)),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+
+        // Linked issues referenced from the PR body
+        let linked_issues = app.pr.as_ref().map_or(&[][..], |pr| &pr.linked_issues);
+        if !linked_issues.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                "LINKED ISSUES",
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            )]));
+            for issue in linked_issues {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", g.bullet), Style::default().fg(Color::Magenta)),
+                    Span::styled(
+                        format!("{}/{}#{} ", issue.owner, issue.repo, issue.number),
+                        Style::default().fg(Color::Blue),
+                    ),
+                    Span::styled(&issue.title, Style::default().fg(Color::White)),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+
+        // Screenshots from the PR body, which the story can't describe
+        let attachments = app.attachments();
+        if !attachments.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("ATTACHMENTS ({})", attachments.len()),
+                    Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled("  i to open in the browser", Style::default().fg(Color::DarkGray)),
+            ]));
+            for (i, attachment) in attachments.into_iter().enumerate() {
+                let marker = if app.attachment_opened == Some(i) {
+                    g.selected.trim_end()
+                } else {
+                    g.bullet
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", marker), Style::default().fg(Color::Magenta)),
+                    Span::styled(
+                        format!("{} ", attachment.label),
+                        Style::default().fg(Color::White),
+                    ),
+                    Span::styled(attachment.url, Style::default().fg(Color::Blue)),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+
         // Focus section
         lines.push(Line::from(Span::styled(
-            "━".repeat(70),
+            g.heavy_rule.repeat(70),
             Style::default().fg(Color::Yellow),
         )));
         lines.push(Line::from(vec![
             Span::styled(
-                "⚡ FOCUS: ",
+                format!("{} FOCUS: ", g.focus),
                 Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
             ),
             Span::styled(
@@ -52,9 +204,9 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
         // Review these
         if !story.focus.review_these.is_empty() {
             lines.push(Line::from(vec![
-                Span::styled("👁 Review: ", Style::default().fg(Color::Cyan)),
+                Span::styled(format!("{} Review: ", g.review), Style::default().fg(Color::Cyan)),
                 Span::styled(
-                    story.focus.review_these.join(" │ "),
+                    story.focus.review_these.join(g.separator),
                     Style::default().fg(Color::White),
                 ),
             ]));
@@ -63,21 +215,21 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
         // Skim these
         if !story.focus.skim_these.is_empty() {
             lines.push(Line::from(vec![
-                Span::styled("⏭ Skim: ", Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("{} Skim: ", g.skim), Style::default().fg(Color::DarkGray)),
                 Span::styled(
-                    story.focus.skim_these.join(" │ "),
+                    story.focus.skim_these.join(g.separator),
                     Style::default().fg(Color::DarkGray),
                 ),
             ]));
         }
         lines.push(Line::from(Span::styled(
-            "━".repeat(70),
+            g.heavy_rule.repeat(70),
             Style::default().fg(Color::Yellow),
         )));
         lines.push(Line::from(""));
 
         lines.push(Line::from(Span::styled(
-            "─".repeat(70),
+            g.rule.repeat(70),
             Style::default().fg(Color::DarkGray),
         )));
         lines.push(Line::from(""));
@@ -85,7 +237,8 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
         // Each feature
         for (i, feature) in story.narrative.iter().enumerate() {
             let is_selected = i == app.selected_feature;
-            let marker = if is_selected { "▶ " } else { "  " };
+            let marker = if is_selected { g.selected } else { "  " };
+            feature_lines.push(lines.len());
 
             // Feature title
             lines.push(Line::from(vec![
@@ -109,6 +262,13 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
                 Span::styled("   ", Style::default()),
                 Span::styled(&feature.why, Style::default().fg(Color::DarkGray)),
             ]));
+            let owners = app.feature_owners(i);
+            if !owners.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::styled("   Owners: ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(owners.join(", "), Style::default().fg(Color::Blue)),
+                ]));
+            }
             lines.push(Line::from(""));
 
             // Changes
@@ -118,10 +278,10 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
                     Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
                 )]));
                 for change in &feature.changes {
-                    lines.push(Line::from(vec![
-                        Span::styled("   • ", Style::default().fg(Color::Green)),
-                        Span::styled(change, Style::default().fg(Color::White)),
-                    ]));
+                    let mut spans =
+                        vec![Span::styled(format!("   {} ", g.bullet), Style::default().fg(Color::Green))];
+                    spans.extend(cited_spans(change, Style::default().fg(Color::White)));
+                    lines.push(Line::from(spans));
                 }
             }
 
@@ -132,10 +292,16 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 )]));
                 for risk in &feature.risks {
-                    lines.push(Line::from(vec![
-                        Span::styled("   • ", Style::default().fg(Color::Red)),
-                        Span::styled(risk, Style::default().fg(Color::White)),
-                    ]));
+                    let mut spans =
+                        vec![Span::styled(format!("   {} ", g.bullet), Style::default().fg(Color::Red))];
+                    if let Some(severity) = risk.severity {
+                        spans.push(Span::styled(
+                            format!("{} ", severity.label()),
+                            Style::default().fg(severity_color(severity)),
+                        ));
+                    }
+                    spans.extend(cited_spans(&risk.text, Style::default().fg(Color::White)));
+                    lines.push(Line::from(spans));
                 }
             }
 
@@ -147,12 +313,54 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
                 )]));
                 for test in &feature.tests {
                     lines.push(Line::from(vec![
-                        Span::styled("   • ", Style::default().fg(Color::Blue)),
+                        Span::styled(format!("   {} ", g.bullet), Style::default().fg(Color::Blue)),
                         Span::styled(test, Style::default().fg(Color::White)),
                     ]));
                 }
             }
 
+            // Deep dive, once asked for
+            if let Some(deep_dive) = app.deep_dives.get(&i) {
+                lines.push(Line::from(vec![Span::styled(
+                    "   Deep dive: ",
+                    Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                )]));
+                for paragraph in deep_dive.analysis.lines().filter(|line| !line.trim().is_empty()) {
+                    lines.push(Line::from(vec![
+                        Span::styled("   ", Style::default()),
+                        Span::styled(paragraph, Style::default().fg(Color::White)),
+                    ]));
+                }
+                let sections = [
+                    ("Invariants", &deep_dive.invariants),
+                    ("Edge cases", &deep_dive.edge_cases),
+                    ("Suggested tests", &deep_dive.suggested_tests),
+                ];
+                for (heading, items) in sections {
+                    if items.is_empty() {
+                        continue;
+                    }
+                    lines.push(Line::from(Span::styled(
+                        format!("   {}:", heading),
+                        Style::default().fg(Color::Magenta),
+                    )));
+                    for item in items {
+                        lines.push(Line::from(vec![
+                            Span::styled(
+                                format!("   {} ", g.bullet),
+                                Style::default().fg(Color::Magenta),
+                            ),
+                            Span::styled(item, Style::default().fg(Color::White)),
+                        ]));
+                    }
+                }
+            } else if app.deep_dive_pending == Some(i) {
+                lines.push(Line::from(Span::styled(
+                    "   Deep dive in progress...",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+
             lines.push(Line::from(""));
 
             // Diff blocks
@@ -170,17 +378,26 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
                 // Significance badge
                 let significance_badge = match block.significance {
                     Significance::Key => Span::styled(
-                        "★ KEY ",
+                        format!("{} KEY ", g.key),
                         Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
                     ),
                     Significance::Standard => Span::styled("", Style::default()),
                     Significance::Noise => {
-                        Span::styled("· noise ", Style::default().fg(Color::DarkGray))
+                        Span::styled(format!("{} noise ", g.noise), Style::default().fg(Color::DarkGray))
                     }
                 };
 
                 // Diff header with viewed status
-                let viewed_marker = if is_viewed { " ✓" } else { "" };
+                let viewed_marker = if is_viewed {
+                    format!(" {}", g.viewed)
+                } else {
+                    String::new()
+                };
+                let flag_marker = if app.is_diff_flagged(i, j) {
+                    format!(" {} follow-up", g.flag)
+                } else {
+                    String::new()
+                };
                 let selection_marker = if is_diff_selected { ">> " } else { "   " };
 
                 // Apply dimming for noise blocks
@@ -197,27 +414,52 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
 
                 lines.push(Line::from(vec![
                     Span::styled(selection_marker, Style::default().fg(Color::Yellow)),
-                    Span::styled("┌─ ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(g.block_open, Style::default().fg(Color::DarkGray)),
                     significance_badge,
                     Span::styled(&block.label, label_style),
                     Span::styled(format!(" [{}]", block.role.as_str()), role_style),
                     Span::styled(viewed_marker, Style::default().fg(Color::Green)),
+                    Span::styled(flag_marker, Style::default().fg(Color::LightRed)),
                 ]));
 
                 // Context (why) - on the right conceptually, but we show it inline
                 let context_color = if is_noise { Color::DarkGray } else { Color::White };
                 let why_color = if is_noise { Color::DarkGray } else { Color::Yellow };
                 lines.push(Line::from(vec![
-                    Span::styled("   │ ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(g.block_gutter, Style::default().fg(Color::DarkGray)),
                     Span::styled("WHY: ", Style::default().fg(why_color)),
                     Span::styled(&block.context, Style::default().fg(context_color)),
                 ]));
 
+                // Links to related blocks, followed with r
+                for (link, related) in block.related_blocks.iter().enumerate() {
+                    let Some((fi, bi)) = app.related_target((i, j), link) else {
+                        continue;
+                    };
+                    let target = &story.narrative[fi].diff_blocks[bi];
+                    let mut spans = vec![
+                        Span::styled(g.block_gutter, Style::default().fg(Color::DarkGray)),
+                        Span::styled("SEE: ", Style::default().fg(why_color)),
+                        Span::styled(
+                            &target.label,
+                            Style::default().fg(Color::Cyan).add_modifier(Modifier::UNDERLINED),
+                        ),
+                        Span::styled(
+                            format!(" - {}", related.note),
+                            Style::default().fg(context_color),
+                        ),
+                    ];
+                    if is_diff_selected && link == 0 {
+                        spans.push(Span::styled(" (r)", Style::default().fg(Color::DarkGray)));
+                    }
+                    lines.push(Line::from(spans));
+                }
+
                 // Hunks
                 for hunk in &block.hunks {
                     let header_color = if is_noise { Color::DarkGray } else { Color::Cyan };
                     lines.push(Line::from(vec![
-                        Span::styled("   │ ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(g.block_gutter, Style::default().fg(Color::DarkGray)),
                         Span::styled(&hunk.header, Style::default().fg(header_color)),
                     ]));
 
@@ -236,21 +478,163 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
                         };
 
                         lines.push(Line::from(vec![
-                            Span::styled("   │ ", Style::default().fg(Color::DarkGray)),
+                            Span::styled(g.block_gutter, Style::default().fg(Color::DarkGray)),
                             Span::styled(line_text, style),
                         ]));
                     }
                 }
 
+                if app.expanded_context.contains(&(i, j)) {
+                    push_surrounding_code(&mut lines, app, &app.diff_files, block);
+                }
+
+                if block.significance == Significance::Key {
+                    push_impact(&mut lines, app, block);
+                }
+
+                // Automated review comments anchored in this block
+                for (idx, comment) in bot_comments.iter().enumerate() {
+                    if comment.anchors_in(&app.diff_files, block) {
+                        attached_comments.insert(idx);
+                        push_bot_comment(&mut lines, comment, g.block_gutter, g);
+                    }
+                }
+
                 lines.push(Line::from(vec![Span::styled(
-                    "   └─",
+                    g.block_close,
                     Style::default().fg(Color::DarkGray),
                 )]));
                 lines.push(Line::from(""));
             }
 
             lines.push(Line::from(Span::styled(
-                "─".repeat(70),
+                g.rule.repeat(70),
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::from(""));
+        }
+
+        // Changed code the model found no tests for
+        let gaps = app.coverage_gaps();
+        if !gaps.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                format!("COVERAGE GAPS ({})", gaps.len()),
+                Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            )]));
+            for (gap, tests) in gaps {
+                // A changed test named after the file suggests the model missed it
+                let color = if tests.is_empty() { Color::White } else { Color::DarkGray };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", g.bullet), Style::default().fg(Color::LightRed)),
+                    Span::styled(gap.target.as_str(), Style::default().fg(Color::Cyan)),
+                    Span::styled(format!("  {}", gap.reason), Style::default().fg(color)),
+                ]));
+                if !tests.is_empty() {
+                    lines.push(Line::from(Span::styled(
+                        format!("  but the PR changes {}", tests.join(", ")),
+                        Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                    )));
+                }
+            }
+            lines.push(Line::from(Span::styled(
+                "Press c to ask about these in Clarification Questions",
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                g.rule.repeat(70),
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::from(""));
+        }
+
+        // Files outside the review scope are only counted
+        if let Some(pr) = app.pr.as_ref().filter(|pr| !pr.out_of_scope.is_empty()) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("OUT OF SCOPE ({} files)", pr.out_of_scope.len()),
+                    Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  only {} is reviewed (s to change)", pr.scope.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            lines.push(Line::from(""));
+        }
+
+        // Changed files left out of the prompt
+        let excluded: Vec<&ExcludedFile> =
+            app.pr.as_ref().map_or(Vec::new(), |pr| pr.excluded_in_scope());
+        if !excluded.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                format!("NOT ANALYZED ({} files)", excluded.len()),
+                Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD),
+            )]));
+            for file in &excluded {
+                // Noise fades out; binary and oversized files stand out since nobody reviewed them
+                let (badge, color) = match file.reason {
+                    ExclusionReason::Ignored { .. } => (g.noise, Color::DarkGray),
+                    ExclusionReason::Binary { .. } => ("BIN", Color::Magenta),
+                    ExclusionReason::TooLarge { .. } => ("BIG", Color::Yellow),
+                };
+                let stats = match file.reason {
+                    ExclusionReason::Binary { .. } => format!("  {}", file.change.as_str()),
+                    _ => format!(
+                        "  {} +{} -{}",
+                        file.change.as_str(),
+                        file.additions,
+                        file.deletions
+                    ),
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", badge),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(file.path.as_str(), Style::default().fg(color)),
+                    Span::styled(stats, Style::default().fg(Color::DarkGray)),
+                    Span::styled(
+                        format!("  {}", file.reason.describe()),
+                        Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                    ),
+                ]));
+            }
+            if excluded
+                .iter()
+                .any(|file| !matches!(file.reason, ExclusionReason::Binary { .. }))
+            {
+                lines.push(Line::from(Span::styled(
+                    "Run with --include <pattern> to analyze ignored or oversized files",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                g.rule.repeat(70),
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::from(""));
+        }
+
+        // Bot comments that couldn't be matched to a diff block
+        let unattached: Vec<&BotComment> = bot_comments
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !attached_comments.contains(idx))
+            .map(|(_, comment)| comment)
+            .collect();
+        if !unattached.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                "AUTOMATED FEEDBACK",
+                Style::default().fg(Color::LightMagenta).add_modifier(Modifier::BOLD),
+            )]));
+            for comment in unattached {
+                push_bot_comment(&mut lines, comment, "", g);
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                g.rule.repeat(70),
                 Style::default().fg(Color::DarkGray),
             )));
             lines.push(Line::from(""));
@@ -264,13 +648,50 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
             )]));
             for q in &story.open_questions {
                 lines.push(Line::from(vec![
-                    Span::styled("• ", Style::default().fg(Color::Yellow)),
+                    Span::styled(format!("{} ", g.bullet), Style::default().fg(Color::Yellow)),
                     Span::styled(q, Style::default().fg(Color::White)),
                 ]));
             }
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
-                "─".repeat(70),
+                g.rule.repeat(70),
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::from(""));
+        }
+
+        // Follow-up summary: everything flagged during the review
+        let flagged = app.flagged_diffs();
+        if !flagged.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                "NEEDS FOLLOW-UP",
+                Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            )]));
+            for (feature_idx, diff_idx) in flagged {
+                let Some(feature) = story.narrative.get(feature_idx) else {
+                    continue;
+                };
+                let Some(block) = feature.diff_blocks.get(diff_idx) else {
+                    continue;
+                };
+                let viewed_marker = if app.is_diff_viewed(feature_idx, diff_idx) {
+                    format!(" {}", g.viewed)
+                } else {
+                    String::new()
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", g.flag), Style::default().fg(Color::LightRed)),
+                    Span::styled(
+                        format!("Feature {}: ", feature_idx + 1),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(&block.label, Style::default().fg(Color::White)),
+                    Span::styled(viewed_marker, Style::default().fg(Color::Green)),
+                ]));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                g.rule.repeat(70),
                 Style::default().fg(Color::DarkGray),
             )));
             lines.push(Line::from(""));
@@ -305,21 +726,33 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
         };
 
         lines.push(Line::from(vec![
-            Span::styled("▶ ", Style::default().fg(action_color)),
+            Span::styled(g.selected, Style::default().fg(action_color)),
             Span::styled(
                 action_title,
                 Style::default().fg(action_color).add_modifier(Modifier::BOLD),
             ),
         ]));
 
-        for text_line in action_text.lines().take(5) {
-            lines.push(Line::from(vec![
-                Span::styled("  ", Style::default()),
-                Span::styled(text_line, Style::default().fg(Color::White)),
-            ]));
+        let editing = matches!(app.state, AppState::EditingAction(_));
+        if editing {
+            // The whole draft, with the cursor drawn where the next character goes
+            let (before, after) = action_text.split_at(app.cursor_pos.min(action_text.len()));
+            let text = format!("{}{}{}", before, g.text_cursor, after);
+            for text_line in text.split('\n') {
+                lines.push(Line::from(vec![
+                    Span::styled("  ", Style::default()),
+                    Span::styled(text_line.to_string(), Style::default().fg(Color::White)),
+                ]));
+            }
+        } else {
+            for text_line in action_text.lines().take(5) {
+                let mut spans = vec![Span::styled("  ", Style::default())];
+                spans.extend(cited_spans(text_line, Style::default().fg(Color::White)));
+                lines.push(Line::from(spans));
+            }
         }
 
-        if action_text.lines().count() > 5 {
+        if !editing && action_text.lines().count() > 5 {
             lines.push(Line::from(vec![Span::styled(
                 "  ... (press Enter to edit full text)",
                 Style::default().fg(Color::DarkGray),
@@ -336,10 +769,199 @@ pub fn render_document(frame: &mut Frame, app: &App, area: Rect) {
         )));
     }
 
-    // Render with scroll
-    let paragraph = Paragraph::new(lines)
-        .scroll((app.scroll_offset, 0))
-        .wrap(Wrap { trim: false });
+    // Highlight search matches, and record the rows they and the feature
+    // headings render on
+    let query = app.search_query.to_ascii_lowercase();
+    let mut match_rows = Vec::new();
+    let mut feature_rows = Vec::new();
+    let mut row: u16 = 0;
+    for (idx, line) in lines.iter_mut().enumerate() {
+        if feature_lines.contains(&idx) {
+            feature_rows.push(row);
+        }
+        if !query.is_empty() && line_matches(line, &query) {
+            match_rows.push(row);
+            *line = highlight_line(line, &query);
+        }
+        let height = Paragraph::new(line.clone())
+            .wrap(Wrap { trim: false })
+            .line_count(area.width);
+        row = row.saturating_add(u16::try_from(height).unwrap_or(u16::MAX));
+    }
+    if !query.is_empty() {
+        *app.search_matches.borrow_mut() = match_rows;
+    }
+    *app.feature_rows.borrow_mut() = feature_rows;
+
+    // Measure the wrapped document so the update layer can clamp scrolling
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    let document_height = u16::try_from(paragraph.line_count(area.width)).unwrap_or(u16::MAX);
+    app.document_height.set(document_height);
+    app.viewport_height.set(area.height);
+
+    // Render with scroll (clamped in case the terminal grew since the last keypress)
+    let paragraph = paragraph.scroll((app.scroll_offset.min(app.max_scroll()), 0));
 
     frame.render_widget(paragraph, area);
 }
+
+/// Render the head-side code around a diff block
+fn push_surrounding_code<'a>(
+    lines: &mut Vec<Line<'a>>,
+    app: &'a App,
+    files: &[FileDiff],
+    block: &DiffBlock,
+) {
+    let g = app.glyphs;
+    let gutter = Span::styled(g.block_gutter, Style::default().fg(Color::DarkGray));
+    let note = |text: String| {
+        Line::from(vec![
+            gutter.clone(),
+            Span::styled(text, Style::default().fg(Color::DarkGray)),
+        ])
+    };
+
+    let Some((path, _)) = locate_block(files, block) else {
+        lines.push(note("(couldn't find this block in the PR diff)".to_string()));
+        return;
+    };
+    let Some(contents) = app.head_files.get(&path) else {
+        lines.push(note(format!("Loading {} at PR head...", path)));
+        return;
+    };
+    let Some(window) = block_window(files, block, contents, app.context_lines) else {
+        return;
+    };
+
+    let end_line = window.start_line as usize + window.lines.len() - 1;
+    lines.push(note(format!(
+        "{rule}{rule} {} lines {}-{} at head {rule}{rule}",
+        window.path,
+        window.start_line,
+        end_line,
+        rule = g.rule
+    )));
+    for (offset, code) in window.lines.into_iter().enumerate() {
+        lines.push(Line::from(vec![
+            gutter.clone(),
+            Span::styled(
+                format!("{:>5} ", window.start_line as usize + offset),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(code, Style::default().fg(Color::Gray)),
+        ]));
+    }
+}
+
+/// Render how many other files use the modified functions this block changes
+fn push_impact<'a>(lines: &mut Vec<Line<'a>>, app: &'a App, block: &DiffBlock) {
+    let g = app.glyphs;
+    let callers: Vec<&SymbolCallers> = app
+        .pr
+        .iter()
+        .flat_map(|pr| &pr.callers)
+        .filter(|entry| block_uses(block, &entry.symbol))
+        .collect();
+    if callers.is_empty() {
+        return;
+    }
+
+    lines.push(Line::from(vec![
+        Span::styled(g.block_gutter, Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "Impact",
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        ),
+    ]));
+    for entry in callers {
+        let mut files = entry.files.iter().take(3).cloned().collect::<Vec<_>>().join(", ");
+        if entry.total as usize > entry.files.len().min(3) {
+            files.push_str(", ...");
+        }
+        lines.push(Line::from(vec![
+            Span::styled(g.block_gutter, Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{} ", g.bullet), Style::default().fg(Color::Magenta)),
+            Span::styled(&entry.symbol, Style::default().fg(Color::White)),
+            Span::styled(
+                format!(
+                    " used in {} other file{}",
+                    entry.total,
+                    if entry.total == 1 { "" } else { "s" }
+                ),
+                Style::default().fg(Color::Magenta),
+            ),
+            Span::styled(
+                if files.is_empty() { String::new() } else { format!("  {}", files) },
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+}
+
+/// Render a bot review comment with a distinct style so it reads apart from the story
+fn push_bot_comment<'a>(
+    lines: &mut Vec<Line<'a>>,
+    comment: &'a BotComment,
+    prefix: &'a str,
+    g: &Glyphs,
+) {
+    let location = match comment.line {
+        Some(line) => format!("{}:{}", comment.path, line),
+        None => comment.path.clone(),
+    };
+    let style = Style::default()
+        .fg(Color::LightMagenta)
+        .add_modifier(Modifier::ITALIC);
+
+    lines.push(Line::from(vec![
+        Span::styled(prefix, Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            format!("{} {} ", g.bot, comment.author),
+            Style::default().fg(Color::LightMagenta).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(location, Style::default().fg(Color::DarkGray)),
+    ]));
+    for body_line in comment.body.lines() {
+        lines.push(Line::from(vec![
+            Span::styled(prefix, Style::default().fg(Color::DarkGray)),
+            Span::styled("   ", Style::default()),
+            Span::styled(body_line, style),
+        ]));
+    }
+}
+
+/// Whether any span in the line contains the (lowercased) query
+fn line_matches(line: &Line, query: &str) -> bool {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    text.to_ascii_lowercase().contains(query)
+}
+
+/// Rebuild a line with each occurrence of the (lowercased) query highlighted
+fn highlight_line<'a>(line: &Line<'a>, query: &str) -> Line<'a> {
+    let highlight = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+
+    for span in &line.spans {
+        let content = span.content.as_ref();
+        // ASCII lowercasing keeps byte offsets aligned with the original text
+        let lowered = content.to_ascii_lowercase();
+        let mut start = 0;
+        while let Some(pos) = lowered[start..].find(query) {
+            let match_start = start + pos;
+            let match_end = match_start + query.len();
+            if match_start > start {
+                spans.push(Span::styled(content[start..match_start].to_string(), span.style));
+            }
+            spans.push(Span::styled(content[match_start..match_end].to_string(), highlight));
+            start = match_end;
+        }
+        if start < content.len() {
+            spans.push(Span::styled(content[start..].to_string(), span.style));
+        }
+    }
+
+    Line::from(spans)
+}
@@ -0,0 +1,57 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::spelling::find_typos;
+
+use super::markdown::{render_markdown, render_markdown_checked};
+
+/// The draft being edited as GitHub will render it, beside the editor, with
+/// likely typos underlined and listed
+pub fn render_draft_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let [_, pane] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+    frame.render_widget(Clear, pane);
+
+    let text = app.current_action_text();
+    let mut lines = if app.spellcheck {
+        render_markdown_checked(text, g)
+    } else {
+        render_markdown(text, g)
+    };
+    let typos = if app.spellcheck { find_typos(text) } else { Vec::new() };
+    if !typos.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "POSSIBLE TYPOS",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
+        for typo in typos {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{} ", g.bullet), Style::default().fg(Color::DarkGray)),
+                Span::styled(typo.word, Style::default().fg(Color::Red)),
+                Span::styled(": did you mean ", Style::default().fg(Color::DarkGray)),
+                Span::styled(typo.suggestion, Style::default().fg(Color::Green)),
+                Span::styled("?", Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" Preview: {} ", app.selected_action.title()))
+        .title_bottom(" Ctrl+P Hide ");
+
+    frame.render_widget(
+        Paragraph::new(lines).block(block).wrap(Wrap { trim: false }),
+        pane,
+    );
+}
@@ -0,0 +1,64 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::diff::added_lines;
+
+use super::util::centered_rect;
+
+/// Overlay showing a whole file at the PR head, with the PR's added lines highlighted
+pub fn render_file_view(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(90, 90, area);
+    frame.render_widget(Clear, popup_area);
+
+    let path = app.file_view_path.as_deref().unwrap_or_default();
+    let mut lines: Vec<Line> = Vec::new();
+
+    match app.head_files.get(path) {
+        Some(contents) => {
+            let added = added_lines(&app.diff_files, path);
+            for (idx, code) in contents.lines().enumerate() {
+                let line_number = idx as u32 + 1;
+                let is_added = added.contains(&line_number);
+                let (marker, code_style) = if is_added {
+                    (
+                        "+",
+                        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    (" ", Style::default().fg(Color::White))
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:>5} ", line_number),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(marker, Style::default().fg(Color::Green)),
+                    Span::styled(" ", Style::default()),
+                    Span::styled(code, code_style),
+                ]));
+            }
+        }
+        None => lines.push(Line::from(Span::styled(
+            format!("{} Fetching {} at PR head...", g.waiting, path),
+            Style::default().fg(Color::Yellow),
+        ))),
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" {} (head) ", path));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((app.file_view_scroll, 0));
+    frame.render_widget(paragraph, popup_area);
+}
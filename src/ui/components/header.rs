@@ -7,34 +7,172 @@ use ratatui::{
 };
 
 use crate::app::App;
+use crate::domain::prompt::language_name;
+use crate::domain::stats::{compact_count, StorySource};
+
+use super::activity::activity_banner;
 
 /// Render the fixed header with PR info
 pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
     let mut lines: Vec<Line> = Vec::new();
 
     if let Some(pr) = &app.pr {
-        lines.push(Line::from(vec![
+        let mut title = vec![
             Span::styled(
                 "Distillery",
                 Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
+            Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
             Span::styled(
                 format!("{}/{}#{}", pr.owner, pr.repo, pr.number),
                 Style::default().fg(Color::White),
             ),
-        ]));
-        lines.push(Line::from(vec![Span::styled(
-            &pr.title,
-            Style::default().fg(Color::Yellow),
-        )]));
+        ];
+        // Which compared model's story is shown, and which are still generating
+        let compared = app.story_variants.len() + app.pending_models.len();
+        if compared > 1
+            && let Some(model) = app.active_model()
+        {
+            title.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            title.push(Span::styled(
+                format!("{} ({}/{})", model, app.active_variant + 1, app.story_variants.len()),
+                Style::default().fg(Color::Magenta),
+            ));
+            if !app.pending_models.is_empty() {
+                title.push(Span::styled(
+                    format!(" {} {}", g.waiting, app.pending_models.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+        }
+        if let Some(range) = &pr.commit_range {
+            title.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            title.push(Span::styled(range.describe(), Style::default().fg(Color::Magenta)));
+        }
+        if let Some(persona) = app.persona {
+            title.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            title.push(Span::styled(
+                format!("{} review", persona.label()),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        if let Some(language) = &app.language {
+            title.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            title.push(Span::styled(
+                format!("in {}", language_name(language)),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+        if let Some(params) = app.model_params.describe() {
+            title.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            title.push(Span::styled(params, Style::default().fg(Color::Magenta)));
+        }
+        if !app.custom_prompt_sources.is_empty() {
+            title.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            title.push(Span::styled(
+                format!("custom prompt: {}", app.custom_prompt_sources.join(" + ")),
+                Style::default().fg(Color::Green),
+            ));
+        }
+        if let Some(pending) = app.pending_review_label() {
+            title.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            title.push(Span::styled(pending, Style::default().fg(Color::Yellow)));
+        }
+        if app.dry_run {
+            title.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            title.push(Span::styled(
+                "DRY RUN",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ));
+        }
+        let used: usize = title.iter().map(Span::width).sum();
+        let stats = stats_segment(app, usize::from(area.width).saturating_sub(used + 1));
+        if !stats.is_empty() {
+            let width: usize = stats.iter().map(Span::width).sum();
+            title.push(Span::raw(" ".repeat(usize::from(area.width) - used - width)));
+            title.extend(stats);
+        }
+        lines.push(Line::from(title));
+        let mut subtitle = vec![Span::styled(&pr.title, Style::default().fg(Color::Yellow))];
+        if !pr.stack.is_empty() {
+            subtitle.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            subtitle.push(Span::styled("Stack: ", Style::default().fg(Color::DarkGray)));
+            for (i, entry) in pr.stack.iter().enumerate() {
+                if i > 0 {
+                    subtitle.push(Span::styled(g.stack_step, Style::default().fg(Color::DarkGray)));
+                }
+                let style = if entry.number == pr.number {
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Gray)
+                };
+                subtitle.push(Span::styled(format!("#{}", entry.number), style));
+            }
+        }
+        lines.push(Line::from(subtitle));
+        if let Some(banner) = activity_banner(app) {
+            lines.push(banner);
+        }
     }
 
     let header = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::BOTTOM)
+            .border_set(g.border)
             .border_style(Style::default().fg(Color::DarkGray)),
     );
 
     frame.render_widget(header, area);
 }
+
+/// Model and provider, cache state, last call's tokens and GitHub's rate limit,
+/// dropping from the left until it fits in `room`
+fn stats_segment(app: &App, room: usize) -> Vec<Span<'static>> {
+    let g = app.glyphs;
+    let stats = &app.stats;
+    let mut parts: Vec<Span<'static>> = Vec::new();
+    if let (Some(provider), Some(model)) = (&stats.provider, &stats.model) {
+        parts.push(Span::styled(
+            format!("{}/{}", provider, model),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+    if let Some(source) = stats.source {
+        let color = match source {
+            StorySource::Cached => Color::Yellow,
+            StorySource::Generated => Color::Green,
+        };
+        parts.push(Span::styled(source.label(), Style::default().fg(color)));
+    }
+    if let Some(usage) = stats.last_usage {
+        parts.push(Span::styled(
+            format!("{} in / {} out", compact_count(usage.input), compact_count(usage.output)),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if let Some(limit) = stats.rate_limit {
+        let color = if limit.is_low() { Color::Red } else { Color::DarkGray };
+        parts.push(Span::styled(
+            format!("GH {}/{}", limit.remaining, limit.limit),
+            Style::default().fg(color),
+        ));
+    }
+
+    let separator = Span::styled(g.separator, Style::default().fg(Color::DarkGray));
+    loop {
+        let width: usize = parts.iter().map(|part| part.width() + separator.width()).sum();
+        if width <= room + separator.width() || parts.is_empty() {
+            break;
+        }
+        parts.remove(0);
+    }
+    let mut spans = Vec::new();
+    for (i, part) in parts.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(separator.clone());
+        }
+        spans.push(part);
+    }
+    spans
+}
@@ -0,0 +1,192 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::centered_rect;
+
+/// How a review goes, shown above the key reference
+const WORKFLOW: &[&str] = &[
+    "Pick a repo and a PR. The model groups its diff into features, ordered so",
+    "the changes others depend on come first. Step through features with n/p",
+    "and their blocks with h/l, marking each viewed with v (u finds the next",
+    "unviewed one). Write up what you find in the three drafts (1-3, Enter to",
+    "edit) and post them with Ctrl+S.",
+];
+
+/// Every key, grouped by the screen it works on
+const SECTIONS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Anywhere",
+        &[
+            ("?", "Show or hide this help"),
+            ("~", "Warnings and errors logged this session"),
+            ("R", "Outbox of submissions that failed to post"),
+            ("Ctrl+Z", "Suspend to the shell; fg comes back"),
+            ("q", "Quit"),
+        ],
+    ),
+    (
+        "Repo selector, PR picker and review queue",
+        &[
+            ("j/k", "Move down / up"),
+            ("Enter", "Open the selected repo or PR"),
+            ("*", "Pin or unpin as a favorite"),
+            ("Tab/Shift+Tab", "Switch between your repos and each org"),
+            ("t", "Open the PR in a new tab (picker over a story)"),
+            ("s", "List open, merged, closed or all PRs (picker)"),
+            ("d", "Hide or show drafts (picker)"),
+            ("o", "Sort by priority, age, size or CI (picker)"),
+            ("r", "Refresh"),
+            ("Q", "Review queue: PRs waiting on you across repos"),
+            ("Esc", "Back, or cancel loading a PR"),
+        ],
+    ),
+    (
+        "Reading a story",
+        &[
+            ("j/k", "Scroll down / up"),
+            ("Space/b", "Page down / up"),
+            ("n/p", "Next / previous feature (guided step with gr on)"),
+            ("h/l", "Previous / next diff block"),
+            ("{/}", "Previous / next feature heading"),
+            ("gg/G", "Top / bottom of the document"),
+            ("N j", "Repeat a motion N times: 10j, 5n, 3}"),
+            ("gf/gd N", "Jump to feature N / diff N of this feature"),
+            ("v", "Mark the block viewed (syncs files viewed on GitHub)"),
+            ("u", "Next unviewed block"),
+            ("f", "Flag the block for follow-up"),
+            ("m", "Write a private note on the block"),
+            ("Ctrl+N", "Your notes; c compiles them into Request Changes"),
+            ("a", "Comment on the block in the pending review"),
+            ("N", "Skip noise blocks"),
+            ("gr", "Guided reading: key changes and roots first (was G)"),
+            ("r", "Follow the block's related blocks"),
+            ("Ctrl+O", "Back to where you followed a related block from"),
+            ("x", "Surrounding code"),
+            ("w", "Whole file at the PR head"),
+            ("B", "Blame for the lines the block rewrites"),
+            ("F", "Changed-file tree, then symbols"),
+            ("[/]", "Narrow / widen the sidebar"),
+            ("\\", "Hide or show the sidebar"),
+            ("J/K", "Down / up a PR stack (was {/})"),
+            ("Alt+1-9", "Switch tab"),
+            ("X", "Close the tab"),
+            ("o", "PR picker"),
+            ("O", "Repo selector"),
+        ],
+    ),
+    (
+        "Searching and copying",
+        &[
+            ("/", "Search the story; n/N for the next / previous match"),
+            ("S", "Search every file the PR touches"),
+            ("y", "Copy the block as Markdown"),
+            ("Y", "Copy the whole story as Markdown"),
+            ("E", "Export the review session as JSON"),
+            ("i", "Open the PR description's next image in the browser"),
+        ],
+    ),
+    (
+        "Shaping the story",
+        &[
+            ("Ctrl+R", "Regenerate"),
+            ("U", "Reload the PR and regenerate"),
+            ("s", "Review scope: which paths the story covers"),
+            ("t", "Review a range of commits on their own"),
+            ("P", "Regenerate under a review persona"),
+            ("L", "Model settings: effort, output tokens, temperature, language"),
+            ("M", "Switch between compared models' stories"),
+            ("C", "Compare the models' stories side by side"),
+        ],
+    ),
+    (
+        "Reviewing",
+        &[
+            ("1/2/3", "Request Changes / Clarification Questions / Next PR draft"),
+            ("Enter", "Edit the selected draft"),
+            ("Ctrl+S", "Preview and submit; outside a draft, submit several"),
+            ("c", "Ask about the coverage gaps"),
+            ("e", "Deep dive into the selected feature"),
+            ("!", "Risks and open questions: add to a draft, or > to quote"),
+            ("T", "Post questions as inline threads"),
+            ("H", "Review memory for this repo"),
+            ("W", "Request reviews from code owners"),
+            ("A", "Comments and reviews since you opened the PR"),
+            ("D", "Dry run: write submissions to the outbox instead"),
+        ],
+    ),
+    (
+        "Editing a draft",
+        &[
+            ("Type", "Edit; Enter adds a line"),
+            ("Ctrl+S", "Preview and submit"),
+            ("Ctrl+Shift+S", "Submit several drafts"),
+            ("Ctrl+Y", "Copy the draft"),
+            ("Ctrl+T", "Insert a review template"),
+            ("Ctrl+P", "Preview the draft as rendered, with likely typos"),
+            ("Esc", "Done"),
+        ],
+    ),
+    (
+        "Panels and lists",
+        &[
+            ("j/k", "Select"),
+            ("Space", "Check or uncheck"),
+            ("Enter", "Apply, jump or retry"),
+            ("Esc", "Close"),
+        ],
+    ),
+];
+
+/// Widest key, so descriptions line up
+const KEY_WIDTH: usize = 14;
+
+/// The help text, one entry per line
+fn help_lines() -> Vec<Line<'static>> {
+    let heading = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::from(Span::styled("Reviewing a PR", heading))];
+    lines.extend(WORKFLOW.iter().map(|line| Line::from(*line)));
+    for (title, keys) in SECTIONS {
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(*title, heading)));
+        for (key, description) in *keys {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<width$}", key, width = KEY_WIDTH),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(*description),
+            ]));
+        }
+    }
+    lines
+}
+
+/// Overlay listing every key, over whatever screen is open
+pub fn render_help_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(80, 90, area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Help ")
+        .title_bottom(" j/k scroll, Esc or ? to close ");
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let lines = help_lines();
+    // Stop once the last line is in view
+    let max_scroll = lines.len().saturating_sub(usize::from(inner.height));
+    let scroll = usize::from(app.help_scroll).min(max_scroll) as u16;
+    let help = Paragraph::new(lines).scroll((scroll, 0));
+    frame.render_widget(help, inner);
+}
@@ -6,22 +6,83 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::{App, AppState};
+use crate::app::{App, AppState, GoTo, SidebarMode};
+use crate::domain::types::ReviewAction;
 
 /// Render the keybindings bar at the bottom
 pub fn render_keybindings(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
     let keys: Vec<(&str, &str)> = match &app.state {
+        _ if app.show_help => vec![("j/k", "Scroll"), ("Esc", "Close")],
+        _ if app.show_log => vec![("j/k", "Scroll"), ("G", "Newest"), ("Esc", "Close")],
+        _ if app.show_activity => vec![("j/k", "Scroll"), ("Esc", "Done")],
+        _ if app.show_risks => {
+            vec![
+                ("j/k", "Select"),
+                ("Enter", "Add to draft"),
+                (">", "Quote into draft"),
+                ("Esc", "Close"),
+            ]
+        }
+        _ if app.show_memory && app.memory_cursor.is_some() => {
+            vec![("Enter", "Newline"), ("Esc", "Done")]
+        }
+        _ if app.show_memory => {
+            vec![("j/k", "Select"), ("e", "Edit themes"), ("d", "Forget"), ("Esc", "Close")]
+        }
+        _ if app.note_editing.is_some() || app.comment_editing.is_some() => {
+            vec![("Enter", "Save"), ("Esc", "Cancel")]
+        }
+        _ if app.show_notes => {
+            vec![
+                ("j/k", "Select"),
+                ("Enter", "Go to block"),
+                ("c", "Compile into Request Changes"),
+                ("d", "Delete"),
+                ("Esc", "Close"),
+            ]
+        }
+        _ if app.show_templates => vec![("j/k", "Select"), ("Enter", "Insert"), ("Esc", "Close")],
+        _ if app.show_owners => {
+            vec![("j/k", "Select"), ("Space", "Toggle"), ("Enter", "Request"), ("Esc", "Close")]
+        }
+        _ if app.show_outbox => {
+            vec![
+                ("j/k", "Select"),
+                ("Enter", "Retry"),
+                ("a", "Retry all"),
+                ("d", "Discard"),
+                ("Esc", "Close"),
+            ]
+        }
         AppState::LoadingRepoList
         | AppState::LoadingPrList
-        | AppState::LoadingPr
-        | AppState::GeneratingStory => {
+        | AppState::LoadingReviewQueue
+        | AppState::LoadingWorkspaceFiles => {
             vec![("q", "Quit")]
         }
+        AppState::LoadingPr | AppState::GeneratingStory => {
+            vec![("Esc", "Cancel"), ("q", "Quit")]
+        }
         AppState::RepoSelector => {
             vec![
                 ("j/↓", "Down"),
                 ("k/↑", "Up"),
                 ("Enter", "Select"),
+                ("*", "Favorite"),
+                ("Tab", "Org"),
+                ("r", "Refresh"),
+                ("Q", "Queue"),
+                ("?", "Help"),
+                ("q", "Quit"),
+            ]
+        }
+        AppState::ReviewQueue => {
+            vec![
+                ("j/↓", "Down"),
+                ("k/↑", "Up"),
+                ("Enter", "Review"),
+                ("Esc", "Back"),
                 ("r", "Refresh"),
                 ("q", "Quit"),
             ]
@@ -32,32 +93,210 @@ pub fn render_keybindings(frame: &mut Frame, app: &App, area: Rect) {
                     ("j/↓", "Down"),
                     ("k/↑", "Up"),
                     ("Enter", "Select"),
+                    ("*", "Favorite"),
+                    ("s", "State"),
+                    ("d", "Drafts"),
                     ("Esc", "Back"),
                     ("r", "Refresh"),
                     ("q", "Quit"),
                 ]
             } else {
-                vec![
+                let mut keys = vec![
                     ("j/↓", "Down"),
                     ("k/↑", "Up"),
                     ("Enter", "Select"),
+                    ("*", "Favorite"),
+                    ("s", "State"),
+                    ("d", "Drafts"),
                     ("r", "Refresh"),
                     ("Esc", "Cancel"),
-                ]
+                ];
+                if app.story.is_some() {
+                    keys.insert(3, ("t", "New tab"));
+                }
+                keys
             }
         }
-        AppState::Viewing => {
+        AppState::Searching => {
+            vec![
+                ("/", app.search_query.as_str()),
+                ("Enter", "Search"),
+                ("Esc", "Cancel"),
+            ]
+        }
+        AppState::WorkspaceSearch => {
+            vec![
+                ("S", app.workspace_query.as_str()),
+                ("Enter", "Search files"),
+                ("Esc", "Cancel"),
+            ]
+        }
+        AppState::BlameView => vec![("Esc", "Close")],
+        AppState::EditingScope => {
+            vec![("Enter", "Regenerate"), ("Esc", "Cancel")]
+        }
+        AppState::SelectingCommits => {
+            vec![
+                ("j/k", "Select"),
+                ("Space", "Toggle"),
+                ("f", "From here"),
+                ("Enter", "Regenerate"),
+                ("Esc", "Cancel"),
+            ]
+        }
+        AppState::ChoosingPrSort => {
+            vec![("j/k", "Select"), ("Enter", "Sort"), ("Esc", "Cancel")]
+        }
+        AppState::ChoosingPersona => {
+            vec![("j/k", "Select"), ("Enter", "Regenerate"), ("Esc", "Cancel")]
+        }
+        AppState::ModelSettings => {
+            vec![("j/k", "Select"), ("h/l", "Change"), ("Enter", "Apply"), ("Esc", "Cancel")]
+        }
+        AppState::BatchSubmit if app.batch_running() => vec![("Esc", "Hide")],
+        AppState::BatchSubmit => {
+            vec![
+                ("j/k", "Select"),
+                ("Space/1-3", "Check"),
+                ("e", "Edit"),
+                ("Enter", "Submit checked"),
+                ("Esc", "Close"),
+            ]
+        }
+        AppState::ConfirmingSubmit => {
+            let post = if app.dry_run { "Write to outbox" } else { "Post" };
+            let mut keys = vec![("y", post), ("n/Esc", "Cancel"), ("j/k", "Scroll")];
+            if app.confirm_actions.contains(&ReviewAction::RequestChanges) {
+                keys.insert(1, ("m", "Review report"));
+                keys.insert(1, ("e", "Verdict"));
+            }
+            keys
+        }
+        AppState::StaleCache => vec![("v", "View stale story"), ("r", "Regenerate")],
+        AppState::PreviewingPrompt => vec![
+            ("Enter", "Send"),
+            ("j/k", "Scroll"),
+            ("Space/b", "Page"),
+            ("Esc", "Cancel"),
+        ],
+        AppState::SecretsFound => {
+            vec![("r", "Redact and generate"), ("S", "Send as is"), ("Esc", "Cancel")]
+        }
+        AppState::ComparingStories => vec![("M/Tab", "Switch story"), ("Esc", "Close")],
+        AppState::FileView => {
             vec![
+                ("j/k", "Scroll"),
+                ("Space/b", "Page"),
+                ("Home/End", "Top/bottom"),
+                ("Esc", "Close"),
+            ]
+        }
+        AppState::WorkspaceResults => {
+            vec![
+                ("j/k", "Select"),
+                ("Enter", "Jump to diff"),
+                ("/", "New query"),
+                ("Esc", "Close"),
+            ]
+        }
+        AppState::Viewing if app.go_to == Some(GoTo::Prefix) => {
+            vec![
+                ("g", "Top"),
+                ("f", "Feature"),
+                ("d", "Diff"),
+                ("r", "Guided"),
+                ("Esc", "Cancel"),
+            ]
+        }
+        AppState::Viewing if app.go_to.is_some() => {
+            let (keys, digits) = match &app.go_to {
+                Some(GoTo::Feature(digits)) => ("gf", digits.as_str()),
+                Some(GoTo::Diff(digits)) => ("gd", digits.as_str()),
+                _ => ("g", ""),
+            };
+            vec![(keys, digits), ("Enter", "Go"), ("Esc", "Cancel")]
+        }
+        AppState::Viewing if app.has_active_search() => {
+            vec![
+                ("n/N", "Next/prev match"),
+                ("Esc", "Clear search"),
+                ("j/k", "Scroll"),
+                ("/", "New search"),
+                ("q", "Quit"),
+            ]
+        }
+        AppState::Viewing if app.sidebar_mode == SidebarMode::Files => {
+            vec![
+                ("j/k", "File"),
+                ("Enter", "Jump to diff"),
+                ("Space/b", "Page"),
+                ("v", "Viewed"),
+                ("F", "Symbols"),
+                ("Esc", "Features"),
+                ("q", "Quit"),
+            ]
+        }
+        AppState::Viewing if app.sidebar_mode == SidebarMode::Symbols => {
+            vec![
+                ("j/k", "Symbol"),
+                ("Enter", "Next block using it"),
+                ("Space/b", "Page"),
+                ("v", "Viewed"),
+                ("F/Esc", "Features"),
+                ("q", "Quit"),
+            ]
+        }
+        AppState::Viewing => {
+            let mut keys = vec![
+                ("?", "Help"),
                 ("j/k", "Scroll"),
                 ("Space/b", "Page"),
                 ("h/l", "Diff"),
-                ("n/p", "Feature"),
+                ("n/p", if app.guided { "Guided step" } else { "Feature" }),
                 ("v", "Viewed"),
+                ("f", "Follow-up"),
+                ("m", "Note"),
+                ("a", "Comment"),
+                ("u", "Unviewed"),
+                ("N", "Skip noise"),
+                ("gr", "Guided"),
+                ("r", "Related"),
+                ("!", "Risks"),
+                ("H", "Memory"),
+                ("W", "Request owners"),
+                ("s", "Scope"),
+                ("t", "Commits"),
+                ("Ctrl+R", "Regenerate"),
+                ("c", "Ask about test gaps"),
+                ("e", "Deep dive"),
+                ("x", "Context"),
+                ("w", "Whole file"),
+                ("B", "Blame"),
+                ("y/Y", "Copy block/story"),
+                ("M", "Model"),
+                ("C", "Compare"),
+                ("P", "Persona"),
+                ("L", "Model settings"),
+                ("F", "Files"),
+                ("[/]", "Sidebar width"),
+                ("\\", "Hide sidebar"),
+                ("/", "Search"),
+                ("S", "Search files"),
                 ("1-3", "Actions"),
+                ("Ctrl+S", "Submit drafts"),
                 ("o", "PRs"),
                 ("O", "Repos"),
+                ("Q", "Queue"),
+                ("~", "Log"),
                 ("q", "Quit"),
-            ]
+            ];
+            if app.pr.as_ref().is_some_and(|pr| !pr.stack.is_empty()) {
+                keys.insert(0, ("J/K", "Stack"));
+            }
+            if !app.tabs.is_empty() {
+                keys.splice(0..0, [("Alt+1-9", "Tab"), ("X", "Close tab")]);
+            }
+            keys
         }
         AppState::EditingAction(action) => {
             vec![
@@ -65,10 +304,14 @@ pub fn render_keybindings(frame: &mut Frame, app: &App, area: Rect) {
                 ("Type", "Edit text"),
                 ("Ctrl+S", "Submit"),
                 ("Esc", "Done"),
+                ("Ctrl+Y", "Copy"),
+                ("Ctrl+T", "Template"),
+                ("Ctrl+P", if app.draft_preview { "Hide preview" } else { "Preview" }),
+                ("Ctrl+Shift+S", "Submit drafts"),
             ]
         }
         AppState::Submitting(action) => vec![("Submitting", action.title())],
-        AppState::Error(_) => vec![("q", "Quit"), ("r", "Retry")],
+        AppState::Error(_) => vec![("q", "Quit"), ("r", "Retry"), ("~", "Log")],
     };
 
     // Build the line with key highlights
@@ -76,10 +319,12 @@ pub fn render_keybindings(frame: &mut Frame, app: &App, area: Rect) {
 
     for (i, (key, desc)) in keys.iter().enumerate() {
         if i > 0 {
-            spans.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+            spans.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
         }
+        // Arrow hints are written inline in the tables above
+        let key = key.replace('↓', g.arrow_down).replace('↑', g.arrow_up);
         spans.push(Span::styled(
-            *key,
+            key,
             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         ));
         spans.push(Span::styled(
@@ -91,6 +336,7 @@ pub fn render_keybindings(frame: &mut Frame, app: &App, area: Rect) {
     let paragraph = Paragraph::new(Line::from(spans)).block(
         Block::default()
             .borders(Borders::TOP)
+            .border_set(g.border)
             .border_style(Style::default().fg(Color::DarkGray)),
     );
 
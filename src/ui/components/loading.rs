@@ -6,11 +6,13 @@ use ratatui::{
     Frame,
 };
 
-pub fn render_loading(frame: &mut Frame, area: Rect, message: &str) {
+use crate::ui::glyphs::Glyphs;
+
+pub fn render_loading(frame: &mut Frame, area: Rect, message: &str, g: &Glyphs) {
     let loading = Paragraph::new(vec![
         Line::from(""),
         Line::from(vec![Span::styled(
-            format!("⏳ {}", message),
+            format!("{} {}", g.waiting, message),
             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         )]),
     ]);
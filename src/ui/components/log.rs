@@ -0,0 +1,58 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use tracing::Level;
+
+use crate::app::App;
+
+use super::util::centered_rect;
+
+/// Overlay tailing the warnings and errors logged this session, newest at the bottom
+pub fn render_log_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(90, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Log: warnings and errors ");
+    if let Some(dir) = &app.recent_log.dir {
+        block = block.title_bottom(format!(" Full log in {} ", dir.display()));
+    }
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let entries = app.recent_log.entries();
+    if entries.is_empty() {
+        let empty = Paragraph::new("No warnings or errors this session")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(empty, inner);
+        return;
+    }
+
+    let end = entries.len().saturating_sub(app.log_scroll);
+    let start = end.saturating_sub(inner.height as usize);
+    let lines: Vec<Line> = entries[start..end]
+        .iter()
+        .map(|entry| {
+            let (level, color) = match entry.level {
+                Level::ERROR => ("ERROR", Color::Red),
+                _ => ("WARN ", Color::Yellow),
+            };
+            let target = entry.target.strip_prefix("dstl::").unwrap_or(&entry.target);
+            Line::from(vec![
+                Span::styled(format!("{} ", entry.time), Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("{} ", level), Style::default().fg(color)),
+                Span::styled(format!("{}: ", target), Style::default().fg(Color::DarkGray)),
+                Span::raw(entry.message.clone()),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
@@ -0,0 +1,123 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::domain::spelling::{suggest, words};
+use crate::ui::glyphs::Glyphs;
+
+/// Markdown styled roughly as GitHub will show it: headings, list markers,
+/// quotes, fenced code, and inline bold and code
+pub fn render_markdown(text: &str, g: &Glyphs) -> Vec<Line<'static>> {
+    render(text, g, false)
+}
+
+/// [`render_markdown`] with likely typos outside code underlined in red
+pub fn render_markdown_checked(text: &str, g: &Glyphs) -> Vec<Line<'static>> {
+    render(text, g, true)
+}
+
+fn render(text: &str, g: &Glyphs, check: bool) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", line),
+                Style::default().fg(Color::Cyan),
+            )));
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let indent = " ".repeat(line.len() - trimmed.len());
+        if let Some(heading) = heading_text(trimmed) {
+            let style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+            lines.push(Line::from(prose_spans(heading, style, check)));
+        } else if let Some(item) =
+            trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* "))
+        {
+            let mut spans = vec![Span::raw(format!("{}{} ", indent, g.bullet))];
+            spans.extend(inline_spans(item, check));
+            lines.push(Line::from(spans));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let style = Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC);
+            let mut spans = vec![Span::styled(g.block_gutter.trim_start().to_string(), style)];
+            spans.extend(prose_spans(quote.trim_start(), style, check));
+            lines.push(Line::from(spans));
+        } else {
+            let mut spans = vec![Span::raw(indent)];
+            spans.extend(inline_spans(trimmed, check));
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
+
+/// Heading text without its `#` markers
+fn heading_text(line: &str) -> Option<&str> {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&hashes) {
+        line[hashes..].strip_prefix(' ')
+    } else {
+        None
+    }
+}
+
+/// Spans for `**bold**` and `` `code` `` within a line; unmatched markers stay literal
+fn inline_spans(text: &str, check: bool) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let next_bold = rest.find("**");
+        let next_code = rest.find('`');
+        let (start, marker, style) = match (next_bold, next_code) {
+            (Some(b), Some(c)) if c < b => (c, "`", Style::default().fg(Color::Cyan)),
+            (None, Some(c)) => (c, "`", Style::default().fg(Color::Cyan)),
+            (Some(b), _) => (b, "**", Style::default().add_modifier(Modifier::BOLD)),
+            (None, None) => break,
+        };
+        let after = &rest[start + marker.len()..];
+        let Some(end) = after.find(marker) else {
+            break;
+        };
+        if start > 0 {
+            spans.extend(prose_spans(&rest[..start], Style::default(), check));
+        }
+        if marker == "`" {
+            spans.push(Span::styled(after[..end].to_string(), style));
+        } else {
+            spans.extend(prose_spans(&after[..end], style, check));
+        }
+        rest = &after[end + marker.len()..];
+    }
+    if !rest.is_empty() {
+        spans.extend(prose_spans(rest, Style::default(), check));
+    }
+    spans
+}
+
+/// `text` in `style`, split around likely typos when checking spelling
+fn prose_spans(text: &str, style: Style, check: bool) -> Vec<Span<'static>> {
+    if !check {
+        return vec![Span::styled(text.to_string(), style)];
+    }
+    let typo = style.fg(Color::Red).add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut done = 0;
+    for (start, word) in words(text).filter(|&(_, word)| suggest(word).is_some()) {
+        if start > done {
+            spans.push(Span::styled(text[done..start].to_string(), style));
+        }
+        spans.push(Span::styled(word.to_string(), typo));
+        done = start + word.len();
+    }
+    if done < text.len() || spans.is_empty() {
+        spans.push(Span::styled(text[done..].to_string(), style));
+    }
+    spans
+}
@@ -0,0 +1,86 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::{centered_rect, truncate};
+
+/// The reviewer's themes and past suggestions for the open repo, as fed to the prompt
+pub fn render_memory_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(80, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(repo) = app.memory_repo() else {
+        return;
+    };
+    let memory = app.memory.repo(&repo);
+    let themes = memory.map_or("", |memory| memory.themes.as_str());
+    let suggestions = memory.map_or(&[][..], |memory| memory.suggestions.as_slice());
+    let heading = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let marker = |row: usize| if row == app.memory_selected { g.selected } else { "  " };
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(marker(0), Style::default().fg(Color::Cyan)),
+        Span::styled("THEMES", heading),
+    ])];
+    if let Some(cursor) = app.memory_cursor {
+        let cursor = cursor.min(themes.len());
+        let (before, after) = themes.split_at(cursor);
+        let text = format!("{}{}{}", before, g.text_cursor, after);
+        lines.extend(text.lines().map(|line| Line::from(format!("  {}", line))));
+    } else if themes.trim().is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  None yet; press e to note what you often flag here",
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else {
+        lines.extend(themes.lines().map(|line| Line::from(format!("  {}", line))));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("  PAST SUGGESTIONS ({})", suggestions.len()),
+        heading,
+    )));
+    if suggestions.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  Items from Request Changes reviews you post are remembered here",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    let width = popup_area.width.saturating_sub(6) as usize;
+    // Newest first, matching what the prompt includes
+    for (i, suggestion) in suggestions.iter().rev().enumerate() {
+        let row = i + 1;
+        let style = if row == app.memory_selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let first_line = suggestion.lines().next().unwrap_or("");
+        lines.push(Line::from(vec![
+            Span::styled(marker(row), style),
+            Span::styled(truncate(first_line, width, g), style),
+        ]));
+    }
+
+    let hints = if app.memory_cursor.is_some() {
+        " Type to edit   Enter Newline   Esc Done "
+    } else {
+        " e Edit themes   d Forget suggestion   Esc Close "
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" Review memory: {} ", repo))
+        .title_bottom(hints);
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
@@ -1,9 +1,37 @@
+pub mod activity;
+pub mod batch;
+pub mod blame;
+pub mod commits;
+pub mod compare;
+pub mod confirm;
 pub mod document;
+pub mod draft_preview;
 pub mod error;
+pub mod file_view;
 pub mod header;
+pub mod help;
 pub mod keybindings;
 pub mod loading;
+pub mod log;
+pub mod memory;
+pub mod model_settings;
+pub mod notes;
+pub mod outbox;
+pub mod owners;
+pub mod pending_review;
+pub mod markdown;
+pub mod persona;
 pub mod picker;
+pub mod progress;
+pub mod prompt_preview;
+pub mod queue;
 pub mod repo_selector;
+pub mod risks;
+pub mod scope;
+pub mod secrets;
 pub mod sidebar;
+pub mod stale;
+pub mod tabs;
+pub mod templates;
 pub mod util;
+pub mod workspace_search;
@@ -0,0 +1,97 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::llm::is_reasoning_model;
+use crate::domain::prompt::language_name;
+
+use super::util::centered_rect;
+
+/// Overlay for the parameters sent with the next story and deep dive
+pub fn render_model_settings(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(60, 40, area);
+    frame.render_widget(Clear, popup_area);
+
+    let draft = &app.settings_draft;
+    // Parameters the story's model won't take are sent, but only to models that do
+    let reasoning = app.stats.model.as_deref().map(is_reasoning_model);
+    let rows = [
+        (
+            "Reasoning effort",
+            draft.reasoning_effort.map(|effort| effort.label().to_string()),
+            "model default",
+            reasoning == Some(false),
+        ),
+        (
+            "Max output tokens",
+            draft.max_output_tokens.map(|tokens| tokens.to_string()),
+            "model default",
+            false,
+        ),
+        (
+            "Temperature",
+            draft.temperature.map(|temperature| temperature.to_string()),
+            "model default",
+            reasoning == Some(true),
+        ),
+        (
+            "Language",
+            app.settings_language
+                .as_deref()
+                .map(|language| language_name(language).to_string()),
+            "English",
+            false,
+        ),
+    ];
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "MODEL SETTINGS",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for (i, (label, value, default, ignored)) in rows.into_iter().enumerate() {
+        let selected = i == app.settings_selected;
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let marker = if selected { g.selected } else { "  " };
+        let mut spans = vec![
+            Span::styled(marker, style),
+            Span::styled(format!("{:<20}", label), style),
+            match value {
+                Some(value) => Span::styled(value, Style::default().fg(Color::Green)),
+                None => Span::styled(default, Style::default().fg(Color::DarkGray)),
+            },
+        ];
+        if ignored {
+            spans.push(Span::styled(
+                " (not used by this model)",
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Less effort and fewer tokens are quicker and cheaper",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Model ");
+
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
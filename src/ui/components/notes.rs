@@ -0,0 +1,107 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::{centered_rect, truncate};
+
+/// Your notes on the story's blocks, in diff order
+pub fn render_notes_panel(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(70, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "NOTES",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            "Private until compiled into the Request Changes draft",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+    ];
+
+    let labels = |(feature_idx, diff_idx): (usize, usize)| {
+        app.story
+            .as_ref()
+            .and_then(|story| story.narrative.get(feature_idx))
+            .and_then(|feature| feature.diff_blocks.get(diff_idx))
+            .map_or_else(String::new, |block| block.label.clone())
+    };
+    let width = popup_area.width.saturating_sub(8) as usize;
+    for (row, (key, location, note)) in app.notes_in_diff_order().into_iter().enumerate() {
+        let selected = row == app.note_selected;
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let marker = if selected { g.selected } else { "  " };
+        let place = location.map_or_else(|| labels(key), |(path, line)| {
+            format!("{}:{} {}", path, line, labels(key))
+        });
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Cyan)),
+            Span::styled(truncate(&place, width, g), Style::default().fg(Color::DarkGray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  ", Style::default()),
+            Span::styled(note.to_string(), style),
+        ]));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Notes ");
+
+    frame.render_widget(
+        Paragraph::new(lines).block(block).wrap(Wrap { trim: false }),
+        popup_area,
+    );
+}
+
+/// One-line editor for the selected block's note
+pub fn render_note_editor(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(60, 20, area);
+    frame.render_widget(Clear, popup_area);
+
+    let label = app
+        .note_editing
+        .and_then(|(feature_idx, diff_idx)| {
+            let feature = app.story.as_ref()?.narrative.get(feature_idx)?;
+            Some(feature.diff_blocks.get(diff_idx)?.label.clone())
+        })
+        .unwrap_or_default();
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("NOTE ON {}", label),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(app.note_draft.as_str(), Style::default().fg(Color::White)),
+            Span::styled(g.text_cursor, Style::default().fg(Color::Yellow)),
+        ]),
+    ];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Note ");
+
+    frame.render_widget(
+        Paragraph::new(lines).block(block).wrap(Wrap { trim: false }),
+        popup_area,
+    );
+}
@@ -0,0 +1,80 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::listings::format_age;
+
+use super::util::{centered_rect, truncate};
+
+/// Overlay of submissions that failed to post, kept until they're retried or discarded
+pub fn render_outbox_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(80, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = Vec::new();
+    if app.dry_run {
+        lines.push(Line::from(Span::styled(
+            "Dry run is on, so nothing is retried",
+            Style::default().fg(Color::Yellow),
+        )));
+        lines.push(Line::from(""));
+    }
+    if app.outbox.items.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Everything has been posted",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let width = popup_area.width.saturating_sub(12) as usize;
+    for (i, item) in app.outbox.items.iter().enumerate() {
+        let selected = i == app.outbox_selected;
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let (status, status_style) = if item.retrying {
+            (format!("{} retrying", g.waiting), Style::default().fg(Color::Yellow))
+        } else {
+            (
+                format!("failed {}", format_age(item.failed_at)),
+                Style::default().fg(Color::DarkGray),
+            )
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(if selected { g.selected } else { "  " }, style),
+            Span::styled(
+                format!("{:<24} {:<24} ", item.submission.target(), item.submission.action().title()),
+                style,
+            ),
+            Span::styled(status, status_style),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!("    {}", truncate(item.submission.summary(), width.max(10), g)),
+            Style::default().fg(Color::DarkGray),
+        )));
+        if !item.retrying {
+            lines.push(Line::from(Span::styled(
+                format!("    {}", item.error),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" Outbox: {} not posted ", app.outbox.items.len()));
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
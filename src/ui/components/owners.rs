@@ -0,0 +1,74 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::{centered_rect, truncate};
+
+/// CODEOWNERS owners of the PR's features, checked ones to be asked for a review
+pub fn render_owners_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(70, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "SUGGESTED REVIEWERS",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            "From CODEOWNERS; checked owners are asked to review",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+    ];
+
+    let suggestions = app.suggested_reviewers();
+    let titles = |features: &[usize]| -> Vec<String> {
+        let Some(story) = &app.story else {
+            return Vec::new();
+        };
+        features
+            .iter()
+            .filter_map(|&i| story.narrative.get(i))
+            .map(|feature| feature.title.clone())
+            .collect()
+    };
+    let width = popup_area.width.saturating_sub(8) as usize;
+    for (row, (owner, checked)) in app.reviewer_choices.iter().enumerate() {
+        let selected = row == app.reviewer_selected;
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let marker = if selected { g.selected } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Cyan)),
+            Span::styled(if *checked { "[x] " } else { "[ ] " }, style),
+            Span::styled(owner.as_str(), style),
+        ]));
+        let owned = suggestions
+            .iter()
+            .find(|(suggested, _)| suggested == owner)
+            .map(|(_, features)| titles(features))
+            .unwrap_or_default();
+        lines.push(Line::from(Span::styled(
+            format!("      {}", truncate(&owned.join(", "), width, g)),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Code owners ")
+        .title_bottom(" Space Toggle   Enter Request reviews   Esc Close ");
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
@@ -0,0 +1,50 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::centered_rect;
+
+/// One-line editor for the pending review's comment on the selected block
+pub fn render_comment_editor(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(60, 20, area);
+    frame.render_widget(Clear, popup_area);
+
+    let place = app
+        .comment_editing
+        .as_ref()
+        .map(|(path, line)| format!("{}:{}", path, line))
+        .unwrap_or_default();
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("COMMENT ON {}", place),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            "Held in the pending review until it's submitted",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(app.comment_draft.as_str(), Style::default().fg(Color::White)),
+            Span::styled(g.text_cursor, Style::default().fg(Color::Yellow)),
+        ]),
+    ];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Review comment ");
+
+    frame.render_widget(
+        Paragraph::new(lines).block(block).wrap(Wrap { trim: false }),
+        popup_area,
+    );
+}
@@ -0,0 +1,53 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::types::Persona;
+
+use super::util::centered_rect;
+
+/// Overlay listing review personas to regenerate the story under
+pub fn render_persona_chooser(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(50, 40, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "REGENERATE AS",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    let options = std::iter::once(None).chain(Persona::ALL.into_iter().map(Some));
+    for (i, persona) in options.enumerate() {
+        let selected = i == app.persona_selected;
+        let label = persona.map_or("General review", |p| p.label());
+        let current = if persona == app.persona { " (current)" } else { "" };
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let marker = if selected { g.selected } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(marker, style),
+            Span::styled(label, style),
+            Span::styled(current, Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Persona ");
+
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
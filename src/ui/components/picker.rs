@@ -1,15 +1,17 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
 use crate::app::App;
-use crate::domain::types::CiStatus;
+use crate::domain::listings::{format_age, parse_timestamp};
+use crate::domain::stats::{compact_count, Workload};
+use crate::domain::types::{CiStatus, PrSort, PrState, PrStateFilter};
 
-use super::util::truncate;
+use super::util::{centered_rect, follow_selection, label_chip, loading_more_line, truncate};
 
 pub fn render_picker(frame: &mut Frame, app: &App, area: Rect) {
     render_picker_content(frame, app, area, false);
@@ -23,7 +25,8 @@ pub fn render_picker_overlay(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_picker_content(frame: &mut Frame, app: &App, area: Rect, is_overlay: bool) {
-    let mut lines: Vec<Line> = Vec::new();
+    let g = app.glyphs;
+    let mut header: Vec<Line> = Vec::new();
 
     // Header with repo name
     let repo_name = app
@@ -32,19 +35,48 @@ fn render_picker_content(frame: &mut Frame, app: &App, area: Rect, is_overlay: b
         .map(|(o, r)| format!("{}/{}", o, r))
         .unwrap_or_else(|| "Unknown".to_string());
 
-    lines.push(Line::from(vec![
+    header.push(Line::from(vec![
         Span::styled(
             "SELECT PR",
             Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
+        Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
         Span::styled(repo_name, Style::default().fg(Color::White)),
+        Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            format!(
+                "{}{}{}",
+                app.pr_filter.label(),
+                if app.hide_drafts { ", no drafts" } else { "" },
+                match app.pr_sort {
+                    PrSort::Priority => String::new(),
+                    sort => format!(", {} first", sort.label().to_lowercase()),
+                }
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
     ]));
-    lines.push(Line::from(""));
+    if !app.pr_list.is_empty() {
+        header.push(workload_line(&Workload::from_prs(&app.pr_list), app));
+    }
+    if let Some(banner) = &app.listing_banner {
+        header.push(Line::from(Span::styled(
+            banner,
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    header.push(Line::from(""));
+
+    // Rows of the selected PR, from its section header when it opens one
+    let mut lines: Vec<Line> = Vec::new();
+    let mut selected_rows = 0..0;
 
     if app.pr_list.is_empty() {
         lines.push(Line::from(Span::styled(
-            "No open PRs found",
+            match app.pr_filter {
+                PrStateFilter::All => "No PRs found".to_string(),
+                filter => format!("No {} PRs found", filter.label()),
+            },
             Style::default().fg(Color::DarkGray),
         )));
     } else {
@@ -52,8 +84,20 @@ fn render_picker_content(frame: &mut Frame, app: &App, area: Rect, is_overlay: b
         let mut last_section: Option<&str> = None;
 
         for (i, pr) in app.pr_list.iter().enumerate() {
-            // Determine section
-            let section = if pr.is_draft {
+            // Determine section (favorites and recents are sorted to the top)
+            let pr_key = app.pr_key(pr.number).unwrap_or_default();
+            let section = if app.history.is_favorite_pr(&pr_key) {
+                "FAVORITES"
+            } else if app.history.is_recent_pr(&pr_key) {
+                "RECENT"
+            } else if app.pr_sort != PrSort::Priority {
+                // Other orders mix states, so the rows carry them instead
+                "ALL"
+            } else if pr.state == PrState::Merged {
+                "MERGED"
+            } else if pr.state == PrState::Closed {
+                "CLOSED"
+            } else if pr.is_draft {
                 "DRAFTS"
             } else if pr.review_requested {
                 "REVIEW REQUESTED"
@@ -61,25 +105,28 @@ fn render_picker_content(frame: &mut Frame, app: &App, area: Rect, is_overlay: b
                 "OPEN"
             };
 
+            let is_selected = i == app.picker_selected;
+
             // Section header if changed
+            let mut first_row = lines.len();
             if last_section != Some(section) {
                 if last_section.is_some() {
                     lines.push(Line::from(""));
+                    first_row += 1;
                 }
                 let section_color = match section {
-                    "REVIEW REQUESTED" => Color::Yellow,
-                    "DRAFTS" => Color::DarkGray,
+                    "FAVORITES" | "REVIEW REQUESTED" => Color::Yellow,
+                    "DRAFTS" | "CLOSED" => Color::DarkGray,
+                    "MERGED" => Color::Magenta,
                     _ => Color::White,
                 };
                 lines.push(Line::from(Span::styled(
-                    format!("── {} ──", section),
+                    format!("{rule}{rule} {} {rule}{rule}", section, rule = g.rule),
                     Style::default().fg(section_color),
                 )));
                 last_section = Some(section);
             }
 
-            let is_selected = i == app.picker_selected;
-
             // CI status indicator
             let ci_color = match pr.ci_status {
                 CiStatus::Success => Color::Green,
@@ -89,66 +136,171 @@ fn render_picker_content(frame: &mut Frame, app: &App, area: Rect, is_overlay: b
             };
 
             // Build the line
-            let marker = if is_selected { "▶ " } else { "  " };
-            let title = truncate(&pr.title, 50);
+            let marker = if is_selected { g.selected } else { "  " };
+            let title = truncate(&pr.title, 50, g);
 
             let line_style = if is_selected {
                 Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
-            } else if pr.is_draft {
+            } else if pr.is_draft || pr.state != PrState::Open {
                 Style::default().fg(Color::DarkGray)
             } else {
                 Style::default().fg(Color::White)
             };
 
-            lines.push(Line::from(vec![
+            let mut row = vec![
                 Span::styled(marker, Style::default().fg(Color::Cyan)),
-                Span::styled(pr.ci_status.symbol(), Style::default().fg(ci_color)),
+                Span::styled(g.ci(&pr.ci_status), Style::default().fg(ci_color)),
                 Span::styled(" ", Style::default()),
                 Span::styled(format!("#{:<5}", pr.number), Style::default().fg(Color::Blue)),
                 Span::styled(title, line_style),
-            ]));
+            ];
+            // State badge, where the section doesn't already say it
+            let badge = match pr.state {
+                PrState::Merged => Some(("merged", Color::Magenta)),
+                PrState::Closed => Some(("closed", Color::Red)),
+                PrState::Open if pr.is_draft => Some(("draft", Color::DarkGray)),
+                PrState::Open => None,
+            };
+            let badged = !matches!(section, "MERGED" | "CLOSED" | "DRAFTS");
+            if let Some((badge, color)) = badge.filter(|_| badged) {
+                row.push(Span::styled(format!(" [{}]", badge), Style::default().fg(color)));
+            }
+            for label in &pr.labels {
+                row.push(Span::raw(" "));
+                row.push(label_chip(label));
+            }
+            lines.push(Line::from(row));
 
-            // Second line with author and stats
-            lines.push(Line::from(vec![
+            // Second line with author, stats and activity
+            let dim = Style::default().fg(Color::DarkGray);
+            let mut details = vec![
                 Span::styled("     ", Style::default()),
-                Span::styled(pr.author.clone(), Style::default().fg(Color::DarkGray)),
-                Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
+                Span::styled(pr.author.clone(), dim),
+                Span::styled(g.separator, dim),
                 Span::styled(format!("+{}", pr.additions), Style::default().fg(Color::Green)),
-                Span::styled("/", Style::default().fg(Color::DarkGray)),
+                Span::styled("/", dim),
                 Span::styled(format!("-{}", pr.deletions), Style::default().fg(Color::Red)),
-                Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
+                Span::styled(g.separator, dim),
                 Span::styled(pr.head_branch.clone(), Style::default().fg(Color::Magenta)),
-            ]));
+            ];
+            if let Some(opened) = parse_timestamp(&pr.created_at) {
+                details.push(Span::styled(g.separator, dim));
+                details.push(Span::styled(format!("opened {}", format_age(opened)), dim));
+            }
+            if let Some(updated) = parse_timestamp(&pr.updated_at) {
+                details.push(Span::styled(format!(", updated {}", format_age(updated)), dim));
+            }
+            if pr.comments > 0 {
+                let noun = if pr.comments == 1 { "comment" } else { "comments" };
+                details.push(Span::styled(g.separator, dim));
+                details.push(Span::styled(format!("{} {}", pr.comments, noun), dim));
+            }
+            if let Some(milestone) = &pr.milestone {
+                details.push(Span::styled(g.separator, dim));
+                details.push(Span::styled(milestone.clone(), Style::default().fg(Color::Cyan)));
+            }
+            lines.push(Line::from(details));
+            if is_selected {
+                selected_rows = first_row..lines.len();
+            }
+        }
+        if app.pr_paging.loading {
+            lines.push(loading_more_line(g));
         }
     }
 
+    let name = if is_overlay { "PR Picker" } else { "Pull Requests" };
+    let title = match app.pr_paging.count_label(app.pr_list.len()) {
+        Some(count) => format!(" {} ({}) ", name, count),
+        None => format!(" {} ", name),
+    };
     let block = Block::default()
         .borders(Borders::ALL)
+        .border_set(g.border)
         .border_style(Style::default().fg(if is_overlay { Color::Cyan } else { Color::DarkGray }))
-        .title(if is_overlay { " PR Picker " } else { " Pull Requests " });
+        .title(title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
 
-    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    // The header stays put while the list scrolls beneath it
+    let [header_area, list_area] = Layout::vertical([
+        Constraint::Length(u16::try_from(header.len()).unwrap_or(u16::MAX)),
+        Constraint::Min(0),
+    ])
+    .areas(inner);
+    frame.render_widget(Paragraph::new(header), header_area);
+    let scroll = follow_selection(&app.picker_scroll, selected_rows, lines.len(), list_area.height);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), list_area);
+}
 
-    frame.render_widget(paragraph, area);
+/// One line on what's waiting: review requests and their size, failing CI, drafts
+fn workload_line(workload: &Workload, app: &App) -> Line<'static> {
+    let g = app.glyphs;
+    let dim = Style::default().fg(Color::DarkGray);
+    let count = |n: usize, one: &str, many: &str| {
+        format!("{} {}", n, if n == 1 { one } else { many })
+    };
+    let mut spans = vec![if workload.review_requested > 0 {
+        Span::styled(
+            format!(
+                "{} awaiting your review (+{}/-{})",
+                count(workload.review_requested, "PR", "PRs"),
+                compact_count(workload.additions),
+                compact_count(workload.deletions)
+            ),
+            Style::default().fg(Color::Yellow),
+        )
+    } else {
+        Span::styled("Nothing awaiting your review", dim)
+    }];
+    if workload.failing_ci > 0 {
+        spans.push(Span::styled(g.separator, dim));
+        spans.push(Span::styled(
+            format!("{} failing CI", workload.failing_ci),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if workload.drafts > 0 {
+        spans.push(Span::styled(g.separator, dim));
+        spans.push(Span::styled(count(workload.drafts, "draft", "drafts"), dim));
+    }
+    Line::from(spans)
 }
 
-/// Create a centered rectangle
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
-
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1]
+/// Menu of orders for the PR list, over the picker
+pub fn render_sort_menu(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(40, 40, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "SORT PRS BY",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for (i, sort) in PrSort::ALL.into_iter().enumerate() {
+        let selected = i == app.pr_sort_selected;
+        let current = if sort == app.pr_sort { " (current)" } else { "" };
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let marker = if selected { g.selected } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(marker, style),
+            Span::styled(sort.label(), style),
+            Span::styled(current, Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Sort ");
+
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
 }
@@ -0,0 +1,51 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+/// Live progress panel for a PR fetch or story generation: spinner, elapsed
+/// time, and the steps completed so far
+pub fn render_progress(frame: &mut Frame, app: &App, area: Rect, message: &str) {
+    let g = app.glyphs;
+    let elapsed = app
+        .progress_started
+        .map(|started| started.elapsed())
+        .unwrap_or_default();
+    let frame_idx = (elapsed.as_millis() / 100) as usize % g.spinner.len();
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("{} {}", g.spinner[frame_idx], message),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {}s", elapsed.as_secs()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    let last = app.progress.len().saturating_sub(1);
+    for (i, event) in app.progress.iter().enumerate() {
+        let (marker, style) = if i == last {
+            (g.current, Style::default().fg(Color::White))
+        } else {
+            (g.done, Style::default().fg(Color::DarkGray))
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {}", marker), Style::default().fg(Color::Green)),
+            Span::styled(event.describe(), style),
+        ]));
+    }
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
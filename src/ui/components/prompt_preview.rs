@@ -0,0 +1,38 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+/// The exact prompts about to go to the model, scrollable, before they're sent
+pub fn render_prompt_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    frame.render_widget(Clear, area);
+
+    let lines: Vec<Line> = app
+        .prompt_preview
+        .lines()
+        .map(|line| match line {
+            "SYSTEM" | "USER" => Line::from(Span::styled(line, Style::default().fg(Color::Cyan))),
+            _ => Line::from(line),
+        })
+        .collect();
+    let title = format!(
+        " Prompt to send: {} KB, Enter to send, Esc to cancel ",
+        app.prompt_preview.len().div_ceil(1024)
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Yellow))
+        .title(title);
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((app.prompt_preview_scroll, 0));
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,148 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::listings::{format_age, now_secs, parse_timestamp};
+use crate::domain::types::CiStatus;
+
+use super::util::{follow_selection, truncate};
+
+/// Color for how long a PR has been waiting: fresh, getting old, overdue
+fn age_color(opened_at: u64) -> Color {
+    match now_secs().saturating_sub(opened_at) / 86_400 {
+        0..2 => Color::Green,
+        2..7 => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+pub fn render_queue(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    // Queue items arrive grouped by repo
+    let mut repos: Vec<String> = app.queue.iter().map(|item| item.repo_label()).collect();
+    repos.dedup();
+    let header = vec![
+        Line::from(vec![
+            Span::styled(
+                "REVIEW QUEUE",
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(
+                    "{} PRs awaiting your review in {} repos",
+                    app.queue.len(),
+                    repos.len()
+                ),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut selected_rows = 0..0;
+    if app.queue.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No PRs awaiting your review",
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else {
+        let mut last_repo: Option<String> = None;
+        for (i, item) in app.queue.iter().enumerate() {
+            let pr = &item.pr;
+            let is_selected = i == app.queue_selected;
+
+            // Repo header when the group changes
+            let mut first_row = lines.len();
+            let repo = item.repo_label();
+            if last_repo.as_ref() != Some(&repo) {
+                if last_repo.is_some() {
+                    lines.push(Line::from(""));
+                    first_row += 1;
+                }
+                let count = app.queue.iter().filter(|other| other.repo_label() == repo).count();
+                lines.push(Line::from(Span::styled(
+                    format!("{rule}{rule} {} ({}) {rule}{rule}", repo, count, rule = g.rule),
+                    Style::default().fg(Color::Blue),
+                )));
+                last_repo = Some(repo);
+            }
+
+            let ci_color = match pr.ci_status {
+                CiStatus::Success => Color::Green,
+                CiStatus::Failure => Color::Red,
+                CiStatus::Pending => Color::Yellow,
+                CiStatus::Unknown => Color::DarkGray,
+            };
+
+            let marker = if is_selected { g.selected } else { "  " };
+            let title = truncate(&pr.title, 50, g);
+
+            let line_style = if is_selected {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            } else if pr.is_draft {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(marker, Style::default().fg(Color::Cyan)),
+                Span::styled(g.ci(&pr.ci_status), Style::default().fg(ci_color)),
+                Span::styled(" ", Style::default()),
+                Span::styled(format!("#{:<5}", pr.number), Style::default().fg(Color::Blue)),
+                Span::styled(title, line_style),
+            ]));
+
+            // Second line with author, stats, and how long it's been waiting
+            let opened = match parse_timestamp(&pr.created_at) {
+                Some(opened_at) => Span::styled(
+                    format!("opened {}", format_age(opened_at)),
+                    Style::default().fg(age_color(opened_at)),
+                ),
+                None => Span::styled(
+                    format!("opened {}", pr.created_at.get(..10).unwrap_or(&pr.created_at)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            };
+            lines.push(Line::from(vec![
+                Span::styled("     ", Style::default()),
+                Span::styled(pr.author.clone(), Style::default().fg(Color::DarkGray)),
+                Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("+{}", pr.additions), Style::default().fg(Color::Green)),
+                Span::styled("/", Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("-{}", pr.deletions), Style::default().fg(Color::Red)),
+                Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
+                opened,
+                if pr.is_draft {
+                    Span::styled(format!("{}draft", g.separator), Style::default().fg(Color::DarkGray))
+                } else {
+                    Span::styled(String::new(), Style::default())
+                },
+            ]));
+            if is_selected {
+                selected_rows = first_row..lines.len();
+            }
+        }
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(" Review Queue ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [header_area, list_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+    frame.render_widget(Paragraph::new(header), header_area);
+    let scroll = follow_selection(&app.queue_scroll, selected_rows, lines.len(), list_area.height);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), list_area);
+}
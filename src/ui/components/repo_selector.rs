@@ -1,24 +1,83 @@
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
 use crate::app::App;
 
-use super::util::truncate;
+use super::util::{follow_selection, loading_more_line, truncate};
 
 pub fn render_repo_selector(frame: &mut Frame, app: &App, area: Rect) {
-    let mut lines: Vec<Line> = Vec::new();
+    let g = app.glyphs;
+    let mut header: Vec<Line> = Vec::new();
 
     // Header
-    lines.push(Line::from(vec![Span::styled(
+    header.push(Line::from(vec![Span::styled(
         "SELECT REPOSITORY",
         Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
     )]));
-    lines.push(Line::from(""));
+
+    if let Some(banner) = &app.listing_banner {
+        header.push(Line::from(Span::styled(
+            banner,
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+
+    // Scope switcher: the user's own repos followed by each org
+    if let Some(orgs) = app.orgs.as_ref().filter(|orgs| !orgs.is_empty()) {
+        let mut spans = vec![Span::styled("Scope: ", Style::default().fg(Color::DarkGray))];
+        let scopes = std::iter::once(None).chain(orgs.iter().map(Some));
+        for (i, scope) in scopes.enumerate() {
+            if i > 0 {
+                spans.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            }
+            let is_current = scope == app.repo_org.as_ref();
+            let style = if is_current {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            spans.push(Span::styled(
+                scope.map_or("Mine", String::as_str).to_string(),
+                style,
+            ));
+        }
+        header.push(Line::from(spans));
+    }
+    header.push(Line::from(""));
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut selected_rows = 0..0;
+    if let Some(session) = &app.resume {
+        let marker = if app.resume_selected { g.selected } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Cyan)),
+            Span::styled(
+                "  Resume: ",
+                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(session.target(), Style::default().fg(Color::White)),
+            Span::styled(
+                format!(" ({}% reviewed)", session.percent_reviewed()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("     ", Style::default()),
+            Span::styled(
+                truncate(&session.pr.title, 60, g),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        if app.resume_selected {
+            selected_rows = 0..lines.len();
+        }
+        lines.push(Line::from(""));
+    }
 
     if app.repo_list.is_empty() {
         lines.push(Line::from(Span::styled(
@@ -27,14 +86,24 @@ pub fn render_repo_selector(frame: &mut Frame, app: &App, area: Rect) {
         )));
     } else {
         for (i, repo) in app.repo_list.iter().enumerate() {
-            let is_selected = i == app.repo_selected;
+            let is_selected = !app.resume_selected && i == app.repo_selected;
+            let first_row = lines.len();
 
             // Build the line
-            let marker = if is_selected { "▶ " } else { "  " };
+            let marker = if is_selected { g.selected } else { "  " };
 
             // Repo name with owner
-            let repo_name = format!("{}/{}", repo.owner, repo.name);
-            let repo_display = truncate(&repo_name, 40);
+            let repo_name = repo.full_name();
+            let repo_display = truncate(&repo_name, 40, g);
+
+            // Favorite / recent indicator
+            let history_marker = if app.history.is_favorite_repo(&repo_name) {
+                Span::styled(format!("{} ", g.favorite), Style::default().fg(Color::Yellow))
+            } else if app.history.is_recent_repo(&repo_name) {
+                Span::styled(format!("{} ", g.recent), Style::default().fg(Color::DarkGray))
+            } else {
+                Span::styled("  ".to_string(), Style::default())
+            };
 
             let line_style = if is_selected {
                 Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
@@ -44,20 +113,21 @@ pub fn render_repo_selector(frame: &mut Frame, app: &App, area: Rect) {
 
             // Visibility indicator
             let visibility = if repo.is_private {
-                Span::styled(" 🔒", Style::default().fg(Color::Yellow))
+                Span::styled(format!(" {}", g.private), Style::default().fg(Color::Yellow))
             } else {
-                Span::styled("", Style::default())
+                Span::styled(String::new(), Style::default())
             };
 
             // Fork indicator
             let fork_indicator = if repo.is_fork {
-                Span::styled(" ⑂", Style::default().fg(Color::DarkGray))
+                Span::styled(format!(" {}", g.fork), Style::default().fg(Color::DarkGray))
             } else {
-                Span::styled("", Style::default())
+                Span::styled(String::new(), Style::default())
             };
 
             lines.push(Line::from(vec![
                 Span::styled(marker, Style::default().fg(Color::Cyan)),
+                history_marker,
                 Span::styled(repo_display, line_style),
                 visibility,
                 fork_indicator,
@@ -65,22 +135,41 @@ pub fn render_repo_selector(frame: &mut Frame, app: &App, area: Rect) {
 
             // Description on second line (if present and selected or short list)
             if !repo.description.is_empty() {
-                let desc = truncate(&repo.description, 60);
+                let desc = truncate(&repo.description, 60, g);
                 let desc_style = Style::default().fg(Color::DarkGray);
                 lines.push(Line::from(vec![
-                    Span::styled("   ", Style::default()),
+                    Span::styled("     ", Style::default()),
                     Span::styled(desc, desc_style),
                 ]));
             }
+            if is_selected {
+                selected_rows = first_row..lines.len();
+            }
+        }
+        if app.repo_paging.loading {
+            lines.push(loading_more_line(g));
         }
     }
 
+    let title = match app.repo_paging.count_label(app.repo_list.len()) {
+        Some(count) => format!(" Repositories ({}) ", count),
+        None => " Repositories ".to_string(),
+    };
     let block = Block::default()
         .borders(Borders::ALL)
+        .border_set(g.border)
         .border_style(Style::default().fg(Color::DarkGray))
-        .title(" Repositories ");
-
-    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+        .title(title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
 
-    frame.render_widget(paragraph, area);
+    // The header stays put while the list scrolls beneath it
+    let [header_area, list_area] = Layout::vertical([
+        Constraint::Length(u16::try_from(header.len()).unwrap_or(u16::MAX)),
+        Constraint::Min(0),
+    ])
+    .areas(inner);
+    frame.render_widget(Paragraph::new(header), header_area);
+    let scroll = follow_selection(&app.repo_scroll, selected_rows, lines.len(), list_area.height);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), list_area);
 }
@@ -0,0 +1,131 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::types::ReviewAction;
+
+use super::util::{centered_rect, cited_spans, severity_color};
+
+/// Every feature's risks in one list, most severe first, with what covers each,
+/// then the story's open questions
+pub fn render_risk_panel(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(80, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(story) = &app.story else {
+        return;
+    };
+    let in_draft = |text: &str| {
+        ReviewAction::ALL.into_iter().any(|action| app.action_text(action).contains(text))
+    };
+    let mut lines = Vec::new();
+    let mut selected_row = 0;
+    let risks = story.risks_by_severity();
+    for (idx, &(feature_idx, risk)) in risks.iter().enumerate() {
+        let is_selected = idx == app.risk_selected;
+        if is_selected {
+            selected_row = lines.len();
+        }
+        let marker = if is_selected { g.selected } else { "  " };
+        let severity = match risk.severity {
+            Some(severity) => Span::styled(
+                format!("{:<5}", severity.label()),
+                Style::default().fg(severity_color(severity)).add_modifier(Modifier::BOLD),
+            ),
+            None => Span::styled("?    ", Style::default().fg(Color::DarkGray)),
+        };
+        let text_style = if is_selected {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let mut spans = vec![Span::styled(marker, Style::default().fg(Color::Cyan)), severity];
+        spans.extend(cited_spans(&risk.text, text_style));
+        lines.push(Line::from(spans));
+
+        let blocks: Vec<&str> = risk
+            .addressing_blocks()
+            .into_iter()
+            .filter_map(|(i, j)| story.narrative.get(i)?.diff_blocks.get(j))
+            .map(|block| block.label.as_str())
+            .collect();
+        let coverage = if blocks.is_empty() {
+            Span::styled("not addressed", Style::default().fg(Color::Red))
+        } else {
+            Span::styled(
+                format!("{}addressed by {}", g.done, blocks.join(", ")),
+                Style::default().fg(Color::Green),
+            )
+        };
+        let mut detail = vec![
+            Span::raw("       "),
+            Span::styled(
+                story.narrative[feature_idx].title.as_str(),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
+            coverage,
+        ];
+        if in_draft(&risk.text) {
+            detail.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            detail.push(Span::styled("in draft", Style::default().fg(Color::Cyan)));
+        }
+        lines.push(Line::from(detail));
+    }
+
+    if !story.open_questions.is_empty() {
+        if !risks.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            "OPEN QUESTIONS",
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )));
+    }
+    for (idx, question) in story.open_questions.iter().enumerate() {
+        let is_selected = risks.len() + idx == app.risk_selected;
+        if is_selected {
+            selected_row = lines.len();
+        }
+        let marker = if is_selected { g.selected } else { "  " };
+        let text_style = if is_selected {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let mut spans = vec![
+            Span::styled(marker, Style::default().fg(Color::Cyan)),
+            Span::styled(question.as_str(), text_style),
+        ];
+        if in_draft(question) {
+            spans.push(Span::styled(g.separator, Style::default().fg(Color::DarkGray)));
+            spans.push(Span::styled("in draft", Style::default().fg(Color::Cyan)));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Red))
+        .title(" Risks and open questions ")
+        .title_bottom(format!(
+            " Enter Add to draft   > Quote into {}   Esc Close ",
+            app.selected_action.title()
+        ));
+
+    // Keep the selected row and the one under it in view
+    let visible = popup_area.height.saturating_sub(2);
+    let selected_row = u16::try_from(selected_row + 2).unwrap_or(u16::MAX);
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((selected_row.saturating_sub(visible), 0));
+    frame.render_widget(paragraph, popup_area);
+}
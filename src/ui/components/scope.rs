@@ -0,0 +1,71 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::diff::parse_diff;
+use crate::domain::filter::{in_scope, parse_patterns};
+
+use super::util::{centered_rect, truncate};
+
+/// Overlay for typing the path patterns to limit the review to, with the files they keep
+pub fn render_scope_editor(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(70, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                "REVIEW SCOPE",
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
+            Span::styled(&app.scope_input, Style::default().fg(Color::Yellow)),
+            Span::styled(g.text_cursor, Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(Span::styled(
+            "Comma-separated, e.g. services/payments/**; empty for every file",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+    ];
+
+    let scope = parse_patterns(&app.scope_input);
+    let paths: Vec<String> = app
+        .pr
+        .as_ref()
+        .map(|pr| parse_diff(&pr.diff).into_iter().map(|file| file.path).collect())
+        .unwrap_or_default();
+    let (kept, left_out): (Vec<&String>, Vec<&String>) =
+        paths.iter().partition(|path| in_scope(&scope, path));
+    lines.push(Line::from(Span::styled(
+        format!("{} of {} changed files in scope", kept.len(), paths.len()),
+        Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+    )));
+    let width = popup_area.width.saturating_sub(6) as usize;
+    for path in &kept {
+        lines.push(Line::from(Span::styled(
+            format!("{} {}", g.bullet, truncate(path, width, g)),
+            Style::default().fg(Color::Green),
+        )));
+    }
+    for path in &left_out {
+        lines.push(Line::from(Span::styled(
+            format!("{} {}", g.noise, truncate(path, width, g)),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Scope ")
+        .title_bottom(" Enter Regenerate   Esc Cancel ");
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
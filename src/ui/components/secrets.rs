@@ -0,0 +1,75 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::centered_rect;
+
+/// Most findings listed before the rest are summarized
+const MAX_LISTED: usize = 8;
+
+/// Warning that holds back generation while the diff adds likely secrets
+pub fn render_secrets_warning(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(70, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let findings = &app.secret_findings;
+    let key = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} POSSIBLE SECRETS IN THE DIFF ({})", g.flag, findings.len()),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(
+            "These added lines look like credentials. Nothing has been sent to the model yet.",
+        ),
+        Line::from(""),
+    ];
+    for finding in findings.iter().take(MAX_LISTED) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", g.bullet), Style::default().fg(Color::Red)),
+            Span::styled(
+                format!("{}:{} ", finding.path, finding.line),
+                Style::default().fg(Color::Blue),
+            ),
+            Span::styled(finding.rule, Style::default().fg(Color::White)),
+            Span::styled(
+                format!("  {}", finding.masked()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+    if findings.len() > MAX_LISTED {
+        lines.push(Line::from(Span::styled(
+            format!("  ...and {} more", findings.len() - MAX_LISTED),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.extend([
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  r  ", key),
+            Span::raw("Redact these lines from the prompt and generate"),
+        ]),
+        Line::from(vec![
+            Span::styled("  S  ", key),
+            Span::raw("Send them to the model as they are"),
+        ]),
+        Line::from(vec![Span::styled("  Esc", key), Span::raw("  Cancel")]),
+    ]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Red))
+        .title(" Secrets ");
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
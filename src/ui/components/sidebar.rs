@@ -6,21 +6,21 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::App;
-use crate::domain::types::Significance;
+use crate::app::{App, SidebarMode};
+use crate::domain::symbols::SymbolChange;
+use crate::domain::types::{ExclusionReason, Significance};
 
 use super::util::truncate;
 
 pub fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    // Inner width, less the right border
+    let width = usize::from(area.width.saturating_sub(2)).max(10);
     let mut lines: Vec<Line> = Vec::new();
 
     // Progress header
     let (viewed, total) = app.total_progress();
-    let progress_pct = if total > 0 {
-        (viewed * 100) / total
-    } else {
-        0
-    };
+    let progress_pct = (viewed * 100).checked_div(total).unwrap_or(0);
 
     lines.push(Line::from(vec![
         Span::styled(
@@ -35,22 +35,34 @@ pub fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::from(""));
 
     // Progress bar
-    let bar_width = 28;
-    let filled = if total > 0 { (viewed * bar_width) / total } else { 0 };
+    let bar_width = width.saturating_sub(2);
+    let filled = (viewed * bar_width).checked_div(total).unwrap_or(0);
     let empty = bar_width - filled;
     lines.push(Line::from(vec![
-        Span::styled("█".repeat(filled), Style::default().fg(Color::Green)),
-        Span::styled("░".repeat(empty), Style::default().fg(Color::DarkGray)),
+        Span::styled(g.bar_filled.repeat(filled), Style::default().fg(Color::Green)),
+        Span::styled(g.bar_empty.repeat(empty), Style::default().fg(Color::DarkGray)),
     ]));
+    let flagged = app.follow_up_diffs.len();
+    if flagged > 0 {
+        lines.push(Line::from(Span::styled(
+            format!("{} {} flagged for follow-up", g.flag, flagged),
+            Style::default().fg(Color::LightRed),
+        )));
+    }
+    push_guided_breadcrumb(&mut lines, app, width);
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
-        "─".repeat(30),
+        g.rule.repeat(width),
         Style::default().fg(Color::DarkGray),
     )));
     lines.push(Line::from(""));
 
-    // Feature list
-    if let Some(story) = &app.story {
+    // Feature list (or changed-file tree, or symbol index)
+    if app.sidebar_mode == SidebarMode::Files {
+        push_file_tree(&mut lines, app, width);
+    } else if app.sidebar_mode == SidebarMode::Symbols {
+        push_symbol_list(&mut lines, app, width);
+    } else if let Some(story) = &app.story {
         for (i, feature) in story.narrative.iter().enumerate() {
             let is_selected = i == app.selected_feature;
             let (feat_viewed, feat_total) = app.feature_progress(i);
@@ -58,9 +70,9 @@ pub fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
 
             // Feature marker
             let marker = if is_selected {
-                "▶ "
+                g.selected
             } else if all_viewed {
-                "✓ "
+                g.done
             } else {
                 "  "
             };
@@ -74,7 +86,7 @@ pub fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
             };
 
             // Feature title (truncated)
-            let title = truncate(&feature.title, 20);
+            let title = truncate(&feature.title, width.saturating_sub(4), g);
             let title_style = if is_selected {
                 Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
             } else if all_viewed {
@@ -106,9 +118,9 @@ pub fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
                     let is_viewed = app.is_diff_viewed(i, j);
 
                     let diff_marker = if is_diff_selected {
-                        "→ "
+                        g.current
                     } else if is_viewed {
-                        "✓ "
+                        g.done
                     } else {
                         "  "
                     };
@@ -123,12 +135,12 @@ pub fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
 
                     // Significance marker
                     let (sig_marker, sig_color) = match block.significance {
-                        Significance::Key => ("★", Color::Yellow),
+                        Significance::Key => (g.key, Color::Yellow),
                         Significance::Standard => (" ", Color::DarkGray),
-                        Significance::Noise => ("·", Color::DarkGray),
+                        Significance::Noise => (g.noise, Color::DarkGray),
                     };
 
-                    let label = truncate(&block.label, 20);
+                    let label = truncate(&block.label, width.saturating_sub(8), g);
                     let label_style = if block.significance == Significance::Noise {
                         Style::default().fg(Color::DarkGray)
                     } else if is_diff_selected {
@@ -139,13 +151,27 @@ pub fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
                         Style::default().fg(Color::White)
                     };
 
+                    let flag_marker = if app.is_diff_flagged(i, j) {
+                        format!(" {}", g.flag)
+                    } else {
+                        String::new()
+                    };
+
                     lines.push(Line::from(vec![
                         Span::styled("  ", Style::default()),
                         Span::styled(diff_marker, Style::default().fg(diff_marker_color)),
                         Span::styled(sig_marker, Style::default().fg(sig_color)),
                         Span::styled(" ", Style::default()),
                         Span::styled(label, label_style),
+                        Span::styled(flag_marker, Style::default().fg(Color::LightRed)),
                     ]));
+                    if let Some(note) = app.block_notes.get(&(i, j)) {
+                        let style = Style::default().fg(Color::Cyan);
+                        lines.push(Line::from(vec![
+                            Span::styled(format!("      {} ", g.note), style),
+                            Span::styled(truncate(note, width.saturating_sub(10), g), style),
+                        ]));
+                    }
                 }
             }
 
@@ -157,9 +183,198 @@ pub fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::RIGHT)
+                .border_set(g.border)
                 .border_style(Style::default().fg(Color::DarkGray)),
         )
         .wrap(Wrap { trim: true });
 
     frame.render_widget(sidebar, area);
 }
+
+/// Changed-file tree with per-file stats and viewed markers
+/// Where the selected block sits on the guided reading path, and what comes next
+fn push_guided_breadcrumb(lines: &mut Vec<Line>, app: &App, width: usize) {
+    let g = app.glyphs;
+    if !app.guided {
+        return;
+    }
+    let (Some(story), Some((step, total))) = (&app.story, app.guided_step()) else {
+        return;
+    };
+    let block_name = |(i, j): (usize, usize)| {
+        let feature = &story.narrative[i];
+        format!("{}: {}", feature.title, feature.diff_blocks[j].label)
+    };
+
+    lines.push(Line::from(vec![
+        Span::styled(
+            "GUIDED ",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("{}/{}", step, total), Style::default().fg(Color::White)),
+    ]));
+    lines.push(Line::from(Span::styled(
+        truncate(&block_name((app.selected_feature, app.selected_diff)), width, g),
+        Style::default().fg(Color::Yellow),
+    )));
+    let next = match app.guided_path().get(step) {
+        Some(&next) => format!("Next: {}", block_name(next)),
+        None => "End of the path".to_string(),
+    };
+    lines.push(Line::from(Span::styled(
+        truncate(&next, width, g),
+        Style::default().fg(Color::DarkGray),
+    )));
+}
+
+fn push_file_tree<'a>(lines: &mut Vec<Line<'a>>, app: &'a App, width: usize) {
+    let g = app.glyphs;
+    lines.push(Line::from(vec![
+        Span::styled(
+            "FILES ",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("({})", app.changed_files.len()),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+    lines.push(Line::from(""));
+
+    let mut prev_dirs: Vec<&str> = Vec::new();
+    for (idx, file) in app.changed_files.iter().enumerate() {
+        let parts: Vec<&str> = file.path.split('/').collect();
+        let (dirs, name) = parts.split_at(parts.len() - 1);
+
+        // Only print directories not already opened by the previous file
+        let shared = prev_dirs
+            .iter()
+            .zip(dirs)
+            .take_while(|(a, b)| a == b)
+            .count();
+        for (depth, dir) in dirs.iter().enumerate().skip(shared) {
+            lines.push(Line::from(Span::styled(
+                format!("{}{}/", "  ".repeat(depth), truncate(dir, width.saturating_sub(2 * depth + 2), g)),
+                Style::default().fg(Color::Blue),
+            )));
+        }
+        prev_dirs = dirs.to_vec();
+
+        let is_selected = idx == app.file_selected;
+        let is_viewed = app.is_file_viewed(file);
+        let marker = if is_selected {
+            g.current
+        } else if is_viewed {
+            g.done
+        } else {
+            "  "
+        };
+        let marker_color = if is_viewed {
+            Color::Green
+        } else if is_selected {
+            Color::Yellow
+        } else {
+            Color::DarkGray
+        };
+        let name_style = if is_selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else if is_viewed {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().fg(Color::White)
+        };
+
+        let indent = "  ".repeat(dirs.len());
+        let name_width = width.saturating_sub(indent.len() + 12).max(8);
+        let mut row = vec![
+            Span::styled(indent, Style::default()),
+            Span::styled(marker, Style::default().fg(marker_color)),
+            Span::styled(truncate(name[0], name_width, g), name_style),
+        ];
+        // Files the model never saw are tagged instead of showing line stats
+        match app.excluded_file(&file.path).map(|excluded| &excluded.reason) {
+            Some(ExclusionReason::Binary { .. }) => {
+                row.push(Span::styled(" bin", Style::default().fg(Color::Magenta)))
+            }
+            Some(ExclusionReason::TooLarge { .. }) => {
+                row.push(Span::styled(" big", Style::default().fg(Color::Yellow)))
+            }
+            Some(ExclusionReason::Ignored { .. }) => {
+                row.push(Span::styled(" skip", Style::default().fg(Color::DarkGray)))
+            }
+            None => {
+                row.push(Span::styled(
+                    format!(" +{}", file.additions),
+                    Style::default().fg(Color::Green),
+                ));
+                row.push(Span::styled(
+                    format!(" -{}", file.deletions),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+        }
+        lines.push(Line::from(row));
+    }
+}
+
+fn push_symbol_list<'a>(lines: &mut Vec<Line<'a>>, app: &'a App, width: usize) {
+    let g = app.glyphs;
+    lines.push(Line::from(vec![
+        Span::styled(
+            "SYMBOLS ",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("({})", app.symbols.len()),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+    lines.push(Line::from(""));
+    if app.symbols.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No functions or types changed",
+            Style::default().fg(Color::DarkGray),
+        )));
+        return;
+    }
+
+    let mut prev_path = "";
+    for (idx, symbol) in app.symbols.iter().enumerate() {
+        if symbol.path != prev_path {
+            lines.push(Line::from(Span::styled(
+                truncate(&symbol.path, width, g),
+                Style::default().fg(Color::Blue),
+            )));
+            prev_path = &symbol.path;
+        }
+
+        let is_selected = idx == app.symbol_selected;
+        let marker = if is_selected { g.current } else { "  " };
+        let sign_color = match symbol.change {
+            SymbolChange::Added => Color::Green,
+            SymbolChange::Removed => Color::Red,
+            SymbolChange::Modified => Color::Yellow,
+        };
+        let name_style = if is_selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else if symbol.blocks.is_empty() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let name_width = width.saturating_sub(14).max(8);
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Yellow)),
+            Span::styled(symbol.change.sign(), Style::default().fg(sign_color)),
+            Span::styled(
+                format!(" {:<4} ", symbol.kind),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(truncate(&symbol.name, name_width, g), name_style),
+            Span::styled(
+                format!(" {}", symbol.blocks.len()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+}
@@ -0,0 +1,57 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::domain::cache::short_sha;
+use crate::domain::listings::format_age;
+
+use super::util::centered_rect;
+
+/// Prompt over a cached story whose PR has changed since it was generated
+pub fn render_stale_prompt(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(70, 40, area);
+    frame.render_widget(Clear, popup_area);
+
+    let (cached_head, created_at) = app.stale_cache.clone().unwrap_or_default();
+    let live_head = app.pr.as_ref().map_or("", |pr| pr.head_sha.as_str());
+    let what_changed = if cached_head.is_empty() || cached_head == live_head {
+        format!("The PR's diff has changed since this story was cached {}.", format_age(created_at))
+    } else {
+        format!(
+            "This story was cached {} at {}; the PR is now at {}.",
+            format_age(created_at),
+            short_sha(&cached_head),
+            short_sha(live_head)
+        )
+    };
+    let key = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(Span::styled(
+            "CACHED STORY IS OUT OF DATE",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(what_changed),
+        Line::from("It may miss or misdescribe the newer changes."),
+        Line::from(""),
+        Line::from(vec![Span::styled("  v  ", key), Span::raw("View the stale story")]),
+        Line::from(vec![
+            Span::styled("  r  ", key),
+            Span::raw("Regenerate it for the current diff"),
+        ]),
+    ];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Yellow))
+        .title(" Stale cache ");
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
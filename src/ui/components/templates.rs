@@ -0,0 +1,56 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+use super::util::centered_rect;
+
+/// Review templates from config.toml, with the selected one's text as it'll be inserted
+pub fn render_template_picker(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(60, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "INSERT TEMPLATE",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for (i, (name, _)) in app.template_choices.iter().enumerate() {
+        let selected = i == app.template_selected;
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let marker = if selected { g.selected } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(marker, style),
+            Span::styled(name.as_str(), style),
+        ]));
+    }
+    if let Some((_, text)) = app.template_choices.get(app.template_selected) {
+        lines.push(Line::from(""));
+        lines.extend(text.lines().map(|line| {
+            Line::from(Span::styled(line, Style::default().fg(Color::DarkGray)))
+        }));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Templates ");
+
+    frame.render_widget(
+        Paragraph::new(lines).block(block).wrap(Wrap { trim: false }),
+        popup_area,
+    );
+}
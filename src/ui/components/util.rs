@@ -1,7 +1,107 @@
-pub fn truncate(s: &str, max_len: usize) -> String {
+use std::cell::Cell;
+use std::ops::Range;
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use crate::domain::style_guide::split_citations;
+use crate::domain::types::{PrLabel, Severity};
+use crate::ui::glyphs::Glyphs;
+
+pub fn truncate(s: &str, max_len: usize, g: &Glyphs) -> String {
     if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}…", s.chars().take(max_len - 1).collect::<String>())
+        format!("{}{}", s.chars().take(max_len - 1).collect::<String>(), g.ellipsis)
     }
 }
+
+/// Last row of a list while its next page is being fetched
+pub fn loading_more_line(g: &Glyphs) -> Line<'static> {
+    Line::from(Span::styled(
+        format!("  Loading more{}", g.ellipsis),
+        Style::default().fg(Color::DarkGray),
+    ))
+}
+
+/// A PR label as a chip in its GitHub color, with text that stays readable on it
+pub fn label_chip(label: &PrLabel) -> Span<'static> {
+    let channel = |i: usize| {
+        label
+            .color
+            .get(i..i + 2)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+    };
+    let style = match (channel(0), channel(2), channel(4)) {
+        (Some(r), Some(g), Some(b)) => {
+            let luma = 299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b);
+            let text = if luma > 150_000 { Color::Black } else { Color::White };
+            Style::default().bg(Color::Rgb(r, g, b)).fg(text)
+        }
+        _ => Style::default().bg(Color::DarkGray).fg(Color::White),
+    };
+    Span::styled(format!(" {} ", label.name), style)
+}
+
+/// Scroll offset for a `height`-row window onto `rows` rows that keeps the
+/// `selected` rows in view, moving `scroll` only as far as needed
+pub fn follow_selection(
+    scroll: &Cell<u16>,
+    selected: Range<usize>,
+    rows: usize,
+    height: u16,
+) -> u16 {
+    let height = usize::from(height);
+    let mut offset = usize::from(scroll.get()).min(rows.saturating_sub(height));
+    if selected.end > offset + height {
+        offset = selected.end.saturating_sub(height);
+    }
+    if selected.start < offset {
+        offset = selected.start;
+    }
+    let offset = u16::try_from(offset).unwrap_or(u16::MAX);
+    scroll.set(offset);
+    offset
+}
+
+/// Create a centered rectangle
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+/// Color a risk's severity is shown in
+pub fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::High => Color::Red,
+        Severity::Medium => Color::Yellow,
+        Severity::Low => Color::DarkGray,
+    }
+}
+
+/// Spans for text in `style`, with style guide citations set apart
+pub fn cited_spans(text: &str, style: Style) -> Vec<Span<'_>> {
+    split_citations(text)
+        .into_iter()
+        .map(|(part, is_citation)| {
+            let citation = Style::default().fg(Color::Magenta).add_modifier(Modifier::ITALIC);
+            Span::styled(part, if is_citation { citation } else { style })
+        })
+        .collect()
+}
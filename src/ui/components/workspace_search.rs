@@ -0,0 +1,98 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::{App, AppState};
+
+use super::util::{centered_rect, truncate};
+
+/// Overlay listing matches for a search across all changed files at the PR head
+pub fn render_workspace_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let g = app.glyphs;
+    let popup_area = centered_rect(80, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    lines.push(Line::from(vec![
+        Span::styled(
+            "SEARCH CHANGED FILES",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(g.separator, Style::default().fg(Color::DarkGray)),
+        Span::styled(&app.workspace_query, Style::default().fg(Color::Yellow)),
+        if matches!(app.state, AppState::WorkspaceSearch) {
+            Span::styled(g.text_cursor, Style::default().fg(Color::Yellow))
+        } else {
+            Span::styled("", Style::default())
+        },
+    ]));
+    lines.push(Line::from(""));
+
+    match app.state {
+        AppState::WorkspaceSearch => {
+            lines.push(Line::from(Span::styled(
+                "Type a query and press Enter to search every file this PR touches",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        AppState::LoadingWorkspaceFiles => {
+            lines.push(Line::from(Span::styled(
+                format!("{} Fetching {} changed files at head...", g.waiting, app.changed_files.len()),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        _ if app.workspace_results.is_empty() => {
+            lines.push(Line::from(Span::styled(
+                "No matches",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        _ => {
+            lines.push(Line::from(Span::styled(
+                format!("{} matches", app.workspace_results.len()),
+                Style::default().fg(Color::DarkGray),
+            )));
+
+            // Keep the selection visible within the popup
+            let visible = usize::from(popup_area.height.saturating_sub(5)).max(1);
+            let start = app.workspace_selected.saturating_sub(visible - 1);
+            let width = usize::from(popup_area.width.saturating_sub(4));
+
+            for (i, m) in app
+                .workspace_results
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(visible)
+            {
+                let is_selected = i == app.workspace_selected;
+                let marker = if is_selected { g.selected } else { "  " };
+                let location = format!("{}:{} ", m.path, m.line_number);
+                let text_width = width.saturating_sub(location.chars().count() + 2).max(10);
+                let text_style = if is_selected {
+                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+
+                lines.push(Line::from(vec![
+                    Span::styled(marker, Style::default().fg(Color::Cyan)),
+                    Span::styled(location, Style::default().fg(Color::Blue)),
+                    Span::styled(truncate(&m.text, text_width, g), text_style),
+                ]));
+            }
+        }
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Workspace Search ");
+
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
@@ -0,0 +1,189 @@
+use ratatui::symbols::border;
+
+use crate::domain::types::CiStatus;
+
+/// Every symbol the UI draws, so terminals without good Unicode/emoji
+/// support can swap in plain ASCII (`--ascii`)
+#[derive(Debug)]
+pub struct Glyphs {
+    /// Cursor on the selected row
+    pub selected: &'static str,
+    /// Current position in a tree
+    pub current: &'static str,
+    /// Viewed/completed row marker
+    pub done: &'static str,
+    pub viewed: &'static str,
+    pub key: &'static str,
+    pub noise: &'static str,
+    pub flag: &'static str,
+    pub note: &'static str,
+    pub bullet: &'static str,
+    pub favorite: &'static str,
+    pub recent: &'static str,
+    pub private: &'static str,
+    pub fork: &'static str,
+    pub bot: &'static str,
+    pub focus: &'static str,
+    pub review: &'static str,
+    pub skim: &'static str,
+    pub waiting: &'static str,
+    /// Animation frames for in-flight work
+    pub spinner: &'static [&'static str],
+    pub ellipsis: &'static str,
+    pub text_cursor: &'static str,
+    pub arrow_up: &'static str,
+    pub arrow_down: &'static str,
+    /// Between PRs in a stack, bottom first
+    pub stack_step: &'static str,
+    /// Inline separator between fields, including padding
+    pub separator: &'static str,
+    pub rule: &'static str,
+    pub heavy_rule: &'static str,
+    pub bar_filled: &'static str,
+    pub bar_empty: &'static str,
+    pub block_open: &'static str,
+    pub block_gutter: &'static str,
+    pub block_close: &'static str,
+    pub ci_pending: &'static str,
+    pub ci_success: &'static str,
+    pub ci_failure: &'static str,
+    pub ci_unknown: &'static str,
+    pub border: border::Set<'static>,
+}
+
+pub const UNICODE: Glyphs = Glyphs {
+    selected: "▶ ",
+    current: "→ ",
+    done: "✓ ",
+    viewed: "✓",
+    key: "★",
+    noise: "·",
+    flag: "⚑",
+    note: "✎",
+    bullet: "•",
+    favorite: "★",
+    recent: "↺",
+    private: "🔒",
+    fork: "⑂",
+    bot: "🤖",
+    focus: "⚡",
+    review: "👁",
+    skim: "⏭",
+    waiting: "⏳",
+    spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+    ellipsis: "…",
+    text_cursor: "▏",
+    arrow_up: "↑",
+    arrow_down: "↓",
+    stack_step: " › ",
+    separator: " │ ",
+    rule: "─",
+    heavy_rule: "━",
+    bar_filled: "█",
+    bar_empty: "░",
+    block_open: "┌─ ",
+    block_gutter: "   │ ",
+    block_close: "   └─",
+    ci_pending: "○",
+    ci_success: "✓",
+    ci_failure: "✗",
+    ci_unknown: "?",
+    border: border::PLAIN,
+};
+
+pub const ASCII: Glyphs = Glyphs {
+    selected: "> ",
+    current: "> ",
+    done: "v ",
+    viewed: "v",
+    key: "*",
+    noise: ".",
+    flag: "!",
+    note: "[n]",
+    bullet: "-",
+    favorite: "*",
+    recent: "r",
+    private: "[private]",
+    fork: "[fork]",
+    bot: "[bot]",
+    focus: ">>",
+    review: "[review]",
+    skim: "[skim]",
+    waiting: "...",
+    spinner: &["|", "/", "-", "\\"],
+    ellipsis: "~",
+    text_cursor: "_",
+    arrow_up: "Up",
+    arrow_down: "Dn",
+    stack_step: " > ",
+    separator: " | ",
+    rule: "-",
+    heavy_rule: "=",
+    bar_filled: "#",
+    bar_empty: ".",
+    block_open: "+- ",
+    block_gutter: "   | ",
+    block_close: "   +-",
+    ci_pending: "o",
+    ci_success: "v",
+    ci_failure: "x",
+    ci_unknown: "?",
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+};
+
+/// ASCII that reads well aloud (`--screen-reader`): words for states, and
+/// nothing where the other sets draw lines, borders and bars
+pub const SCREEN_READER: Glyphs = Glyphs {
+    done: "done ",
+    viewed: "viewed",
+    // Already followed by a label saying the same
+    key: "",
+    focus: "",
+    review: "",
+    skim: "",
+    spinner: &["working"],
+    ellipsis: "...",
+    separator: ", ",
+    rule: "",
+    heavy_rule: "",
+    bar_filled: "",
+    bar_empty: "",
+    block_open: "",
+    block_gutter: "  ",
+    block_close: "",
+    ci_pending: "pending",
+    ci_success: "passing",
+    ci_failure: "failing",
+    ci_unknown: "unknown",
+    border: border::Set {
+        top_left: " ",
+        top_right: " ",
+        bottom_left: " ",
+        bottom_right: " ",
+        vertical_left: " ",
+        vertical_right: " ",
+        horizontal_top: " ",
+        horizontal_bottom: " ",
+    },
+    ..ASCII
+};
+
+impl Glyphs {
+    pub fn ci(&self, status: &CiStatus) -> &'static str {
+        match status {
+            CiStatus::Pending => self.ci_pending,
+            CiStatus::Success => self.ci_success,
+            CiStatus::Failure => self.ci_failure,
+            CiStatus::Unknown => self.ci_unknown,
+        }
+    }
+}
@@ -1,13 +1,22 @@
 use ratatui::{
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier},
     Frame,
 };
 
 use crate::app::{App, AppState};
+use crate::domain::preferences::MIN_SIDEBAR_WIDTH;
 use crate::ui::components::{
-    document, error, header, keybindings, loading, picker, repo_selector, sidebar,
+    activity, batch, blame, commits, compare, confirm, document, draft_preview, error, file_view,
+    header, help, keybindings, loading, log, memory, model_settings, notes, outbox, owners,
+    pending_review, persona, picker, progress, prompt_preview, queue, repo_selector, risks, scope,
+    secrets, sidebar, stale, tabs, templates, workspace_search,
 };
 
+/// Columns the document keeps beside the sidebar; narrower and the sidebar goes
+const MIN_DOCUMENT_WIDTH: u16 = 50;
+
 /// Main render function
 pub fn render(frame: &mut Frame, app: &App) {
     let size = frame.area();
@@ -27,51 +36,193 @@ pub fn render(frame: &mut Frame, app: &App) {
     // Render main content based on state
     match &app.state {
         AppState::LoadingRepoList => {
-            loading::render_loading(frame, main_area, "Fetching repositories...")
+            loading::render_loading(frame, main_area, "Fetching repositories...", app.glyphs)
         }
         AppState::RepoSelector => repo_selector::render_repo_selector(frame, app, main_area),
         AppState::LoadingPrList => {
-            loading::render_loading(frame, main_area, "Fetching PR list...")
+            loading::render_loading(frame, main_area, "Fetching PR list...", app.glyphs)
+        }
+        AppState::LoadingReviewQueue => {
+            loading::render_loading(frame, main_area, "Fetching review queue...", app.glyphs)
+        }
+        AppState::ReviewQueue => queue::render_queue(frame, app, main_area),
+        AppState::LoadingPr => {
+            progress::render_progress(frame, app, main_area, "Fetching PR from GitHub...")
         }
-        AppState::LoadingPr => loading::render_loading(frame, main_area, "Fetching PR from GitHub..."),
         AppState::GeneratingStory => {
-            loading::render_loading(frame, main_area, "Generating story with AI...")
+            let model = app.generation_model.as_deref().unwrap_or("AI");
+            let message = match app.persona {
+                Some(persona) => format!("Generating {} review with {}...", persona.label(), model),
+                None => format!("Generating story with {}...", model),
+            };
+            progress::render_progress(frame, app, main_area, &message)
         }
         AppState::Error(msg) => error::render_error(frame, main_area, msg),
         AppState::PrPicker => picker::render_picker(frame, app, main_area),
-        AppState::Viewing | AppState::EditingAction(_) | AppState::Submitting(_) => {
+        AppState::ChoosingPrSort => {
+            picker::render_picker(frame, app, main_area);
+            picker::render_sort_menu(frame, app, main_area);
+        }
+        AppState::Viewing
+        | AppState::Searching
+        | AppState::EditingAction(_)
+        | AppState::Submitting(_) => {
             render_main(frame, app, main_area);
+            if matches!(app.state, AppState::EditingAction(_)) && app.draft_preview {
+                draft_preview::render_draft_preview(frame, app, main_area);
+            }
             // Show picker as overlay if open
             if app.show_picker {
                 picker::render_picker_overlay(frame, app, main_area);
             }
         }
+        AppState::BlameView => {
+            render_main(frame, app, main_area);
+            blame::render_blame_view(frame, app, main_area);
+        }
+        AppState::ChoosingPersona => {
+            render_main(frame, app, main_area);
+            persona::render_persona_chooser(frame, app, main_area);
+        }
+        AppState::ModelSettings => {
+            render_main(frame, app, main_area);
+            model_settings::render_model_settings(frame, app, main_area);
+        }
+        AppState::EditingScope => {
+            render_main(frame, app, main_area);
+            scope::render_scope_editor(frame, app, main_area);
+        }
+        AppState::SelectingCommits => {
+            render_main(frame, app, main_area);
+            commits::render_commit_range(frame, app, main_area);
+        }
+        AppState::BatchSubmit => {
+            render_main(frame, app, main_area);
+            batch::render_batch_submit(frame, app, main_area);
+        }
+        AppState::ConfirmingSubmit => {
+            render_main(frame, app, main_area);
+            if app.confirm_batch {
+                batch::render_batch_submit(frame, app, main_area);
+            }
+            confirm::render_confirm_submit(frame, app, main_area);
+        }
+        AppState::PreviewingPrompt => prompt_preview::render_prompt_preview(frame, app, main_area),
+        AppState::SecretsFound => {
+            progress::render_progress(frame, app, main_area, "Waiting to send the diff...");
+            secrets::render_secrets_warning(frame, app, main_area);
+        }
+        AppState::StaleCache => {
+            render_main(frame, app, main_area);
+            stale::render_stale_prompt(frame, app, main_area);
+        }
+        AppState::ComparingStories => {
+            render_main(frame, app, main_area);
+            compare::render_compare_view(frame, app, main_area);
+        }
+        AppState::FileView => {
+            render_main(frame, app, main_area);
+            file_view::render_file_view(frame, app, main_area);
+        }
+        AppState::WorkspaceSearch
+        | AppState::LoadingWorkspaceFiles
+        | AppState::WorkspaceResults => {
+            render_main(frame, app, main_area);
+            workspace_search::render_workspace_overlay(frame, app, main_area);
+        }
+    }
+
+    if app.show_risks {
+        risks::render_risk_panel(frame, app, main_area);
+    }
+    if app.show_memory {
+        memory::render_memory_overlay(frame, app, main_area);
+    }
+    if app.show_owners {
+        owners::render_owners_overlay(frame, app, main_area);
+    }
+    if app.show_templates {
+        templates::render_template_picker(frame, app, main_area);
+    }
+    if app.show_notes {
+        notes::render_notes_panel(frame, app, main_area);
+    }
+    if app.note_editing.is_some() {
+        notes::render_note_editor(frame, app, main_area);
+    }
+    if app.comment_editing.is_some() {
+        pending_review::render_comment_editor(frame, app, main_area);
+    }
+    if app.show_activity {
+        activity::render_activity_overlay(frame, app, main_area);
+    }
+    if app.show_outbox {
+        outbox::render_outbox_overlay(frame, app, main_area);
+    }
+    if app.show_log {
+        log::render_log_overlay(frame, app, main_area);
+    }
+    if app.show_help {
+        help::render_help_overlay(frame, app, main_area);
     }
 
     // Always render keybindings bar at bottom
     keybindings::render_keybindings(frame, app, keys_area);
+
+    if app.screen_reader {
+        without_colour(frame.buffer_mut());
+    }
+}
+
+/// Drop colours so nothing is told by colour alone. Cells picked out by
+/// their background, like a highlighted row, are shown reversed instead.
+fn without_colour(buffer: &mut Buffer) {
+    for cell in &mut buffer.content {
+        if cell.bg != Color::Reset {
+            cell.modifier.insert(Modifier::REVERSED);
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
 }
 
 fn render_main(frame: &mut Frame, app: &App, area: Rect) {
-    // Split into header and content area
+    // Split into header, tab bar (with several PRs open) and content area
+    let tab_bar_height = if app.tabs.is_empty() { 0 } else { 1 };
     let vertical_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(4), // Header (app name, repo, title)
-            Constraint::Min(10),   // Content area
+            Constraint::Length(4),              // Header (app name, repo, title)
+            Constraint::Length(tab_bar_height), // Tab bar
+            Constraint::Min(10),                // Content area
         ])
         .split(area);
 
     header::render_header(frame, app, vertical_chunks[0]);
+    if !app.tabs.is_empty() {
+        tabs::render_tab_bar(frame, app, vertical_chunks[1]);
+    }
+
+    // A narrow terminal gives the document its columns before the sidebar
+    let sidebar_width = app
+        .preferences
+        .sidebar_width
+        .min(area.width.saturating_sub(MIN_DOCUMENT_WIDTH));
+    // A screen reader reads across the sidebar and document line by line, so
+    // it gets the document alone
+    if app.preferences.sidebar_hidden || app.screen_reader || sidebar_width < MIN_SIDEBAR_WIDTH {
+        document::render_document(frame, app, vertical_chunks[2]);
+        return;
+    }
 
     // Split content into sidebar and main document
     let horizontal_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Length(32), // Sidebar
-            Constraint::Min(50),    // Main content
+            Constraint::Length(sidebar_width),   // Sidebar
+            Constraint::Min(MIN_DOCUMENT_WIDTH), // Main content
         ])
-        .split(vertical_chunks[1]);
+        .split(vertical_chunks[2]);
 
     sidebar::render_sidebar(frame, app, horizontal_chunks[0]);
     document::render_document(frame, app, horizontal_chunks[1]);
@@ -1,4 +1,39 @@
+mod announce;
 mod components;
+pub mod glyphs;
 mod layout;
+#[cfg(test)]
+mod tests;
+mod title;
 
+pub use announce::announcements;
 pub use layout::render;
+pub use title::terminal_title;
+
+#[cfg(test)]
+use crate::app::App;
+
+/// Render `app` into an in-memory `width`x`height` buffer and return the text
+/// grid, one line per row with trailing spaces trimmed. Lets tests see the
+/// screen without a terminal
+#[cfg(test)]
+pub fn render_to_text(app: &App, width: u16, height: u16) -> String {
+    use ratatui::{backend::TestBackend, Terminal};
+
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("test backend");
+    terminal
+        .draw(|frame| render(frame, app))
+        .expect("render into test backend");
+
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            let row: String = (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
@@ -0,0 +1,779 @@
+//! Golden-file snapshots of every screen. After an intended layout change,
+//! regenerate them with `UPDATE_SNAPSHOTS=1 cargo test` and review the diff.
+
+use std::path::PathBuf;
+
+use tracing::Level;
+
+use crate::action::Action;
+use crate::app::{App, AppState, BatchStatus, Paging, SidebarMode};
+use crate::config::AppConfig;
+use crate::domain::codeowners;
+use crate::domain::listings::now_secs;
+use crate::domain::logging::LogEntry;
+use crate::domain::llm;
+use crate::domain::mock;
+use crate::domain::stats::{RateLimit, StorySource};
+use crate::domain::outbox::{OutboxItem, Submission, SubmissionKind};
+use crate::domain::types::{
+    ActivityComment, BlameRange, CiStatus, PrActivity, PrContext, PrLabel, PrListItem, PrSort,
+    PrState, PrStateFilter, ProgressEvent, QueueItem, RepoListItem, ReviewAction, ReviewDelta,
+    ReviewEvent, SymbolCallers, WorkspaceMatch,
+};
+use crate::test_support::*;
+
+use super::render_to_text;
+
+const WIDTH: u16 = 100;
+const HEIGHT: u16 = 32;
+
+fn assert_snapshot(name: &str, app: &App) {
+    let actual = render_to_text(app, WIDTH, HEIGHT);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/ui/snapshots")
+        .join(format!("{}.txt", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}; run UPDATE_SNAPSHOTS=1 cargo test",
+            path.display()
+        )
+    });
+    assert!(
+        actual == expected,
+        "{} doesn't match its snapshot; rerun with UPDATE_SNAPSHOTS=1 if the change is intended\n\n{}",
+        name,
+        actual
+    );
+}
+
+fn app_in(state: AppState) -> App {
+    let mut app = App::new();
+    app.state = state;
+    app
+}
+
+fn repo(owner: &str, name: &str, description: &str) -> RepoListItem {
+    RepoListItem {
+        owner: owner.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        is_fork: false,
+        is_private: false,
+    }
+}
+
+#[test]
+fn repo_selector() {
+    let mut app = app_in(AppState::RepoSelector);
+    app.repo_list = vec![
+        repo(OWNER, REPO, "Widget service"),
+        repo(OWNER, "gadgets", "Gadget library"),
+    ];
+    assert_snapshot("repo_selector", &app);
+
+    let mut viewed = app_viewing();
+    viewed.viewed_diffs.insert((0, 0));
+    app.resume = viewed.session();
+    app.resume_selected = true;
+    assert_snapshot("repo_selector_resume", &app);
+}
+
+#[test]
+fn loading_lists() {
+    assert_snapshot("loading_repo_list", &app_in(AppState::LoadingRepoList));
+    assert_snapshot("loading_pr_list", &app_in(AppState::LoadingPrList));
+    assert_snapshot("loading_review_queue", &app_in(AppState::LoadingReviewQueue));
+}
+
+#[test]
+fn pr_picker() {
+    assert_snapshot("pr_picker", &app_in_picker());
+}
+
+#[test]
+fn pr_picker_loading_more() {
+    let mut app = app_in_picker();
+    app.pr_paging = Paging {
+        total: Some(57),
+        next_cursor: Some("cursor".to_string()),
+        loading: true,
+    };
+    assert_snapshot("pr_picker_loading_more", &app);
+}
+
+#[test]
+fn pr_picker_keeps_selection_in_view() {
+    let mut app = app_in_picker();
+    app.pr_list = (1..=40)
+        .map(|number| pr_list_item(number, &format!("Change {}", number)))
+        .collect();
+    app.picker_selected = 30;
+    assert_snapshot("pr_picker_scrolled", &app);
+}
+
+#[test]
+fn pr_picker_with_closed_and_merged() {
+    let mut app = app_in_picker();
+    app.pr_filter = PrStateFilter::All;
+    app.pr_list[1].is_draft = true;
+    let closed = |number, title, state| PrListItem {
+        state,
+        ..pr_list_item(number, title)
+    };
+    app.pr_list.push(closed(5, "Drop the old client", PrState::Merged));
+    app.pr_list.push(closed(3, "Try a new parser", PrState::Closed));
+    assert_snapshot("pr_picker_all_states", &app);
+}
+
+#[test]
+fn pr_sort_menu_over_labelled_prs() {
+    let mut app = app_in_picker();
+    app.state = AppState::ChoosingPrSort;
+    app.pr_sort = PrSort::Largest;
+    app.pr_sort_selected = 4;
+    let pr = &mut app.pr_list[0];
+    pr.updated_at = days_ago(1);
+    pr.comments = 3;
+    pr.milestone = Some("v2.0".to_string());
+    pr.labels = vec![PrLabel {
+        name: "security".to_string(),
+        color: "d73a4a".to_string(),
+    }];
+    assert_snapshot("pr_sort_menu", &app);
+}
+
+#[test]
+fn pr_picker_workload_summary() {
+    let mut app = app_in_picker();
+    app.pr_list[0].review_requested = true;
+    app.pr_list[0].additions = 1250;
+    app.pr_list[0].deletions = 40;
+    app.pr_list[1].ci_status = CiStatus::Failure;
+    let mut draft = pr_list_item(3, "Try a new parser");
+    draft.is_draft = true;
+    app.pr_list.push(draft);
+    assert_snapshot("pr_picker_workload", &app);
+}
+
+#[test]
+fn pr_picker_over_story() {
+    let mut app = app_viewing();
+    app.pr_list = app_in_picker().pr_list;
+    app.show_picker = true;
+    assert_snapshot("pr_picker_over_story", &app);
+}
+
+#[test]
+fn review_queue() {
+    let mut app = app_in(AppState::ReviewQueue);
+    let item = |repo: &str, pr: PrListItem, days: u64| QueueItem {
+        owner: OWNER.to_string(),
+        repo: repo.to_string(),
+        pr: PrListItem {
+            created_at: days_ago(days),
+            ..pr
+        },
+    };
+    app.queue = vec![
+        item(REPO, pr_list_item(PR_NUMBER, "Add rate limiting"), 9),
+        item(REPO, pr_list_item(7, "Fix typo"), 3),
+        item("gadgets", pr_list_item(12, "Bump deps"), 0),
+    ];
+    assert_snapshot("review_queue", &app);
+}
+
+#[test]
+fn loading_pr() {
+    let mut app = app_in(AppState::LoadingPr);
+    // Set directly: record_progress would start the (wall-clock) timer
+    app.progress = vec![
+        ProgressEvent::FetchingPr { number: PR_NUMBER },
+        ProgressEvent::FetchedMetadata,
+    ];
+    assert_snapshot("loading_pr", &app);
+}
+
+#[test]
+fn generating_story() {
+    let mut app = app_in(AppState::GeneratingStory);
+    app.generation_model = Some(MODEL.to_string());
+    app.progress = vec![
+        ProgressEvent::PromptBuilt { tokens: 1200 },
+        ProgressEvent::AwaitingModel {
+            model: MODEL.to_string(),
+        },
+    ];
+    assert_snapshot("generating_story", &app);
+}
+
+#[test]
+fn summarizing_files() {
+    let mut app = app_in(AppState::GeneratingStory);
+    app.generation_model = Some(MODEL.to_string());
+    app.progress = vec![
+        ProgressEvent::FetchedDiff { bytes: 900_000 },
+        ProgressEvent::SummarizedFile { done: 7, total: 12 },
+    ];
+    assert_snapshot("summarizing_files", &app);
+}
+
+#[test]
+fn viewing() {
+    let mut app = app_viewing();
+    app.viewed_diffs.insert((0, 0));
+    assert_snapshot("viewing", &app);
+}
+
+#[test]
+fn viewing_for_a_screen_reader() {
+    let mut app = app_viewing();
+    app.viewed_diffs.insert((0, 0));
+    app.glyphs = &super::glyphs::SCREEN_READER;
+    app.screen_reader = true;
+    assert_snapshot("viewing_screen_reader", &app);
+}
+
+#[test]
+fn deep_dive() {
+    let mut app = app_viewing();
+    let deep_dive = mock::deep_dive(app.pr.as_ref().unwrap());
+    app.deep_dives.insert(0, deep_dive);
+    app.document_height.set(100);
+    app.scroll_offset = 14;
+    assert_snapshot("deep_dive", &app);
+}
+
+#[test]
+fn template_picker() {
+    let mut config = config();
+    config.templates.insert(
+        "LGTM with nits".to_string(),
+        "LGTM on #{{pr_number}}, @{{author}}. A few nits:\n".to_string(),
+    );
+    config.templates.insert("Security concern".to_string(), "Security: ".to_string());
+    let mut app = app_viewing();
+    press(&mut app, &config, "1<Enter><C-t>");
+    assert_snapshot("template_picker", &app);
+}
+
+#[test]
+fn notes_panel() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "nmWhy not 429?<Enter>pmNo test for the limit<Enter><C-n>");
+    assert_snapshot("notes_panel", &app);
+}
+
+#[test]
+fn model_settings() {
+    let config = config();
+    let mut app = app_viewing();
+    app.stats.model = Some(MODEL.to_string());
+    press(&mut app, &config, "Lljl");
+    assert_snapshot("model_settings", &app);
+}
+
+#[test]
+fn related_blocks() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "n");
+    app.document_height.set(100);
+    app.scroll_offset = 30;
+    assert_snapshot("related_blocks", &app);
+}
+
+#[test]
+fn go_to_prompt() {
+    let config = config();
+    let mut app = app_viewing();
+    let story = app.story.as_mut().unwrap();
+    let copies = vec![story.narrative[1].clone(); 10];
+    story.narrative.extend(copies);
+    press(&mut app, &config, "gf1");
+    assert_snapshot("go_to_prompt", &app);
+}
+
+#[test]
+fn risk_panel() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "!j<Enter>");
+    assert_snapshot("risk_panel", &app);
+}
+
+#[test]
+fn review_memory() {
+    let config = config();
+    let mut app = app_viewing();
+    let repo = format!("{}/{}", OWNER, REPO);
+    app.memory.record(&repo, "1. Return 429 rather than 500\n2. Add context to errors");
+    app.memory.repo_mut(&repo).themes = "Flags errors without context".to_string();
+    press(&mut app, &config, "Hj");
+    assert_snapshot("review_memory", &app);
+}
+
+#[test]
+fn low_quality_story() {
+    let mut app = app_viewing();
+    app.story = Some(sloppy_story());
+    assert_snapshot("low_quality_story", &app);
+}
+
+#[test]
+fn attachments() {
+    let config = config();
+    let mut app = app_viewing();
+    app.pr.as_mut().unwrap().body = "Limits requests.\n\n\
+        ![Before](https://example.com/before.png)\n\
+        <img alt=\"After\" src=\"https://github.com/user-attachments/assets/after\">"
+        .to_string();
+    press(&mut app, &config, "ii");
+    assert_snapshot("attachments", &app);
+}
+
+#[test]
+fn scope_editor() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "ssrc/limit.rs, src/api.rs");
+    assert_snapshot("scope_editor", &app);
+}
+
+#[test]
+fn owners_overlay() {
+    let config = config();
+    let mut app = app_viewing();
+    app.pr.as_mut().unwrap().code_owners =
+        codeowners::parse("* @acme/platform\n/src/api.rs @acme/api-team @octocat\n");
+    press(&mut app, &config, "W ");
+    assert_snapshot("owners_overlay", &app);
+}
+
+#[test]
+fn help_overlay() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "?jj");
+    assert_snapshot("help_overlay", &app);
+}
+
+#[test]
+fn secrets_warning() {
+    let config = config();
+    let mut app = app_in_picker();
+    press(&mut app, &config, "<Enter>");
+    let pr = PrContext {
+        diff: SECRET_DIFF.to_string(),
+        ..pr_context()
+    };
+    deliver(&mut app, &config, Action::PrLoaded(Ok(pr)));
+    assert_snapshot("secrets_warning", &app);
+}
+
+#[test]
+fn prompt_preview() {
+    let config = AppConfig {
+        preview_prompt: true,
+        ..config()
+    };
+    let mut app = app_in_picker();
+    press(&mut app, &config, "<Enter>");
+    deliver(&mut app, &config, Action::PrLoaded(Ok(pr_context())));
+    // Wrapped system prompt lines push the user prompt further down
+    let user = app.prompt_preview.lines().position(|line| line == "USER").unwrap();
+    app.prompt_preview_scroll = user as u16 + 20;
+    assert_snapshot("prompt_preview", &app);
+}
+
+#[test]
+fn stale_cache_prompt() {
+    let config = config();
+    let mut app = app_in_picker();
+    press(&mut app, &config, "<Enter>");
+    let entry = cache_entry("0ld5ha1abc");
+    let loaded = Action::CacheLoaded {
+        pr: PR_KEY.to_string(),
+        stories: vec![(MODEL.to_string(), entry)],
+    };
+    deliver(&mut app, &config, loaded);
+    deliver(&mut app, &config, Action::PrLoaded(Ok(pr_context())));
+    assert_snapshot("stale_cache", &app);
+}
+
+#[test]
+fn stacked_pr() {
+    let mut app = app_viewing();
+    app.pr.as_mut().unwrap().stack = vec![
+        stack_entry(41, "main"),
+        stack_entry(PR_NUMBER, "branch-41"),
+        stack_entry(43, "branch-42"),
+    ];
+    assert_snapshot("stacked_pr", &app);
+}
+
+#[test]
+fn session_stats_in_header() {
+    let config = config();
+    let mut app = app_viewing();
+    app.stats.record_story(llm::PROVIDER, MODEL, StorySource::Cached);
+    deliver(
+        &mut app,
+        &config,
+        Action::Progress(ProgressEvent::TokensUsed {
+            input: 12_345,
+            output: 2_100,
+        }),
+    );
+    deliver(
+        &mut app,
+        &config,
+        Action::RateLimitLoaded(Ok(RateLimit {
+            limit: 5000,
+            remaining: 312,
+            reset: 0,
+        })),
+    );
+    assert_snapshot("session_stats", &app);
+}
+
+#[test]
+fn commit_range() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "t");
+    let commits = Action::CommitsLoaded {
+        pr: PR_KEY.to_string(),
+        result: Ok(pr_commits()),
+    };
+    deliver(&mut app, &config, commits);
+    press(&mut app, &config, "j ");
+    assert_snapshot("commit_range", &app);
+}
+
+#[test]
+fn whats_new_since_last_review() {
+    let mut app = app_viewing();
+    app.pr.as_mut().unwrap().review_delta = Some(ReviewDelta {
+        reviewed_head: "f00dfeed".to_string(),
+        diff: String::new(),
+    });
+    app.story.as_mut().unwrap().whats_new = vec![
+        "The limit now allows the 100th request".to_string(),
+        "Handlers log rejected requests".to_string(),
+    ];
+    assert_snapshot("whats_new_since_last_review", &app);
+}
+
+#[test]
+fn coverage_gaps() {
+    let mut app = app_viewing();
+    app.scroll_offset = u16::MAX;
+    assert_snapshot("coverage_gaps", &app);
+}
+
+#[test]
+fn guided_reading() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "grn");
+    assert_snapshot("guided_reading", &app);
+}
+
+#[test]
+fn viewing_with_tabs() {
+    let config = config();
+    let mut app = app_viewing();
+    app.pr_list = app_in_picker().pr_list;
+    app.show_picker = true;
+    app.state = AppState::PrPicker;
+    press(&mut app, &config, "jt");
+    app.pr = Some(PrContext {
+        number: 7,
+        title: "Fix typo".to_string(),
+        ..pr_context()
+    });
+    app.add_story_variant(MODEL.to_string(), story());
+    app.state = AppState::Viewing;
+    assert_snapshot("viewing_with_tabs", &app);
+}
+
+#[test]
+fn viewing_file_tree() {
+    let mut app = app_viewing();
+    app.sidebar_mode = SidebarMode::Files;
+    assert_snapshot("viewing_file_tree", &app);
+}
+
+#[test]
+fn breaking_changes() {
+    let mut app = App::new();
+    app.pr = Some(PrContext {
+        diff: BREAKING_DIFF.to_string(),
+        ..pr_context()
+    });
+    app.add_story_variant(MODEL.to_string(), story());
+    app.state = AppState::Viewing;
+    assert_snapshot("breaking_changes", &app);
+}
+
+#[test]
+fn viewing_impact() {
+    let mut app = app_viewing();
+    app.pr.as_mut().unwrap().callers = vec![SymbolCallers {
+        symbol: "allow".to_string(),
+        path: "src/limit.rs".to_string(),
+        total: 4,
+        files: vec!["src/api.rs".to_string(), "src/jobs.rs".to_string()],
+    }];
+    app.scroll_offset = 16;
+    assert_snapshot("viewing_impact", &app);
+}
+
+#[test]
+fn viewing_symbols() {
+    let mut app = app_viewing();
+    app.sidebar_mode = SidebarMode::Symbols;
+    app.symbol_selected = 1;
+    assert_snapshot("viewing_symbols", &app);
+}
+
+#[test]
+fn searching() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "/limit");
+    assert_snapshot("searching", &app);
+}
+
+#[test]
+fn workspace_search() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "Sallow");
+    assert_snapshot("workspace_search", &app);
+
+    app.state = AppState::LoadingWorkspaceFiles;
+    assert_snapshot("loading_workspace_files", &app);
+
+    app.workspace_results = vec![WorkspaceMatch {
+        path: "src/limit.rs".to_string(),
+        line_number: 1,
+        text: "pub fn allow(count: u32) -> bool {".to_string(),
+    }];
+    app.state = AppState::WorkspaceResults;
+    assert_snapshot("workspace_results", &app);
+}
+
+#[test]
+fn file_view() {
+    let config = config();
+    let mut app = app_viewing();
+    app.head_files.insert(
+        "src/limit.rs".to_string(),
+        "pub fn allow(count: u32) -> bool {\n    count < 100\n}\n".to_string(),
+    );
+    press(&mut app, &config, "w");
+    assert_snapshot("file_view", &app);
+}
+
+#[test]
+fn blame_view() {
+    let mut app = app_viewing();
+    app.blame.insert(
+        "src/api.rs".to_string(),
+        vec![BlameRange {
+            start_line: 8,
+            end_line: 14,
+            commit: "abc1234".to_string(),
+            author: "octocat".to_string(),
+            date: "2026-01-01T00:00:00Z".to_string(),
+            summary: "Add handler".to_string(),
+            pr_number: Some(7),
+        }],
+    );
+    app.blame_view = Some(("src/api.rs".to_string(), 10, 12));
+    app.state = AppState::BlameView;
+    assert_snapshot("blame_view", &app);
+}
+
+#[test]
+fn comparing_stories() {
+    let mut app = app_viewing();
+    app.add_story_variant("other-model".to_string(), story());
+    app.pending_models = vec!["slow-model".to_string()];
+    app.state = AppState::ComparingStories;
+    assert_snapshot("comparing_stories", &app);
+}
+
+#[test]
+fn choosing_persona() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "Pj");
+    assert_snapshot("choosing_persona", &app);
+}
+
+#[test]
+fn editing_wide_characters() {
+    let config = config();
+    let mut app = app_viewing();
+    app.action_texts.clarification.clear();
+    app.document_height.set(100);
+    app.scroll_offset = 62;
+    press(&mut app, &config, "2<Enter>Naïve 日本語 check 👩\u{200d}💻?<Left><Left>");
+    assert_snapshot("editing_wide_characters", &app);
+}
+
+#[test]
+fn draft_preview() {
+    let config = config();
+    let mut app = app_viewing();
+    app.action_texts.clarification =
+        "## Questions\n\n- Do we recieve `recieve` twice?\n- Is teh retry safe?\n\n```\nteh()\n```"
+            .to_string();
+    press(&mut app, &config, "2<Enter><C-p>");
+    assert_snapshot("draft_preview", &app);
+}
+
+#[test]
+fn editing_and_submitting() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "2<Enter>");
+    assert_snapshot("editing_action", &app);
+
+    app.state = AppState::Submitting(ReviewAction::ClarificationQuestions);
+    assert_snapshot("submitting", &app);
+}
+
+#[test]
+fn batch_submit() {
+    let config = config();
+    let mut app = app_viewing();
+    press(&mut app, &config, "<C-s>");
+    app.batch[0].checked = false;
+    app.batch[1].checked = false;
+    app.batch[1].status = BatchStatus::Sent;
+    app.batch[2].status = BatchStatus::Failed("gh issue create failed: issues are disabled".to_string());
+    assert_snapshot("batch_submit", &app);
+}
+
+#[test]
+fn confirm_submit() {
+    let config = config();
+    let mut app = app_viewing();
+    app.action_texts.request_changes = "## Limits\n- Make **the limit** configurable\n\
+        - Return `429` rather than `500`\n> The handler is hot"
+        .to_string();
+    press(&mut app, &config, "1<Enter><C-s>");
+    assert_snapshot("confirm_submit", &app);
+}
+
+#[test]
+fn confirm_submit_with_report() {
+    let config = config();
+    let mut app = app_viewing();
+    app.action_texts.request_changes = "1. Make the limit configurable".to_string();
+    app.viewed_diffs.insert((0, 0));
+    press(&mut app, &config, "1<Enter><C-s>m");
+    assert_snapshot("confirm_submit_report", &app);
+}
+
+#[test]
+fn confirm_pending_review() {
+    let config = config();
+    let mut app = app_viewing();
+    app.action_texts.request_changes = "Looks good apart from the limit.".to_string();
+    press(&mut app, &config, "aShould this be configurable?<Enter>naWhy not 429?<Enter>");
+    press(&mut app, &config, "1<Enter><C-s>ee");
+    assert_snapshot("confirm_pending_review", &app);
+}
+
+#[test]
+fn outbox_overlay() {
+    let mut app = app_viewing();
+    let failed = |kind, error: &str| OutboxItem {
+        submission: Submission {
+            owner: OWNER.to_string(),
+            repo: REPO.to_string(),
+            number: PR_NUMBER,
+            kind,
+        },
+        error: error.to_string(),
+        failed_at: now_secs() - 7200,
+        retrying: false,
+    };
+    app.outbox.items = vec![
+        failed(
+            SubmissionKind::Review {
+                body: "1. Make the limit configurable".to_string(),
+                event: ReviewEvent::RequestChanges,
+                commit_id: String::new(),
+                comments: Vec::new(),
+            },
+            "gh pr review failed: HTTP 401: Bad credentials",
+        ),
+        failed(
+            SubmissionKind::NextPrIssue {
+                title: "Configurable limits".to_string(),
+                body: "- Read from config".to_string(),
+            },
+            "gh issue create failed: error connecting to api.github.com",
+        ),
+    ];
+    app.outbox.items[1].retrying = true;
+    app.show_outbox = true;
+    assert_snapshot("outbox_overlay", &app);
+}
+
+#[test]
+fn new_activity() {
+    let mut app = app_viewing();
+    app.seen_comments = Some(Default::default());
+    app.activity = Some(PrActivity {
+        commits: vec!["head000".to_string(), "head001".to_string(), "head002".to_string()],
+        comments: vec![ActivityComment {
+            id: "c1".to_string(),
+            author: "octocat".to_string(),
+            body: "Made the limit configurable in `Config`.\n- Defaults to **100**".to_string(),
+            created_at: "2026-01-02T09:30:00Z".to_string(),
+        }],
+    });
+    assert_snapshot("activity_banner", &app);
+
+    app.show_activity = true;
+    assert_snapshot("activity_overlay", &app);
+}
+
+#[test]
+fn log_overlay() {
+    let config = config();
+    let mut app = app_viewing();
+    app.recent_log.push(LogEntry {
+        time: "09:14:02".to_string(),
+        level: Level::WARN,
+        target: "dstl::domain::github".to_string(),
+        message: "gh failed command=gh api contents status=exit status: 1".to_string(),
+    });
+    app.recent_log.push(LogEntry {
+        time: "09:14:05".to_string(),
+        level: Level::ERROR,
+        target: "dstl::command".to_string(),
+        message: "Command failed command=fetch PR error=gh pr view failed: not found".to_string(),
+    });
+    press(&mut app, &config, "~");
+    assert_snapshot("log_overlay", &app);
+}
+
+#[test]
+fn error() {
+    let app = app_in(AppState::Error("gh pr view failed: not found".to_string()));
+    assert_snapshot("error", &app);
+}
@@ -0,0 +1,22 @@
+use crate::app::{App, AppState};
+
+/// The terminal window's title: what dstl is doing for which PR, so a long
+/// generation can be watched from another window
+pub fn terminal_title(app: &App) -> String {
+    let target = match (&app.pr, &app.current_repo, app.current_pr_number) {
+        (Some(pr), _, _) => Some(format!("{}#{}", pr.repo, pr.number)),
+        (None, Some((_, repo)), Some(number)) => Some(format!("{}#{}", repo, number)),
+        _ => None,
+    };
+    let Some(target) = target else {
+        return "dstl".to_string();
+    };
+    let ellipsis = app.glyphs.ellipsis;
+    match app.state {
+        AppState::LoadingPr => format!("dstl: loading {}{}", target, ellipsis),
+        AppState::GeneratingStory => format!("dstl: generating {}{}", target, ellipsis),
+        AppState::Submitting(_) => format!("dstl: submitting to {}{}", target, ellipsis),
+        _ if app.batch_running() => format!("dstl: submitting to {}{}", target, ellipsis),
+        _ => format!("dstl: {}", target),
+    }
+}
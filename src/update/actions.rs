@@ -1,18 +1,44 @@
-use crate::app::{App, AppState};
+use crate::app::{App, AppState, Paging};
 use crate::command::Command;
 use crate::config::AppConfig;
-use crate::domain::types::{PrContext, PrListItem, RepoListItem, ReviewAction, Story};
+use crate::domain::cache::CacheEntry;
+use crate::domain::filter::apply_scope;
+use crate::domain::listings::format_age;
+use crate::domain::{llm, notify};
+use crate::domain::stats::{RateLimit, StorySource, TokenUsage};
+use crate::domain::style_guide::STYLE_GUIDE_PATH;
+use crate::domain::types::{
+    BlameRange, Listing, PrContext, PrListItem, ProgressEvent, QueueItem, RepoListItem,
+    ReviewAction, ReviewEvent, Story, ViewedFiles,
+};
 
-use super::helpers;
+use super::{activity, batch, helpers, memory, outbox, reviewed, stale};
 
-pub fn handle_repo_list_loaded(app: &mut App, result: Result<Vec<RepoListItem>, String>) -> Vec<Command> {
+/// Describe a listing served from the offline cache
+fn listing_banner<T>(listing: &Listing<T>) -> Option<String> {
+    listing
+        .cached_at
+        .map(|fetched_at| format!("Offline: showing cached data from {}", format_age(fetched_at)))
+}
+
+pub fn handle_repo_list_loaded(
+    app: &mut App,
+    result: Result<Listing<RepoListItem>, String>,
+) -> Vec<Command> {
     match result {
-        Ok(repo_list) => {
-            app.repo_list = repo_list;
+        Ok(listing) => {
+            app.listing_banner = listing_banner(&listing);
+            app.repo_paging = Paging::from_listing(&listing);
+            app.repo_list = listing.items;
             app.repo_selected = 0;
+            app.sort_repo_list();
             app.state = AppState::RepoSelector;
             app.show_picker = false;
-            Vec::new()
+            if app.orgs.is_none() {
+                vec![Command::FetchOrgList]
+            } else {
+                Vec::new()
+            }
         }
         Err(err) => {
             app.state = AppState::Error(format!("Failed to fetch repo list: {}", err));
@@ -21,11 +47,33 @@ pub fn handle_repo_list_loaded(app: &mut App, result: Result<Vec<RepoListItem>,
     }
 }
 
-pub fn handle_pr_list_loaded(app: &mut App, result: Result<Vec<PrListItem>, String>) -> Vec<Command> {
+/// Repos and orgs arrive together, so there's no org fetch to chain
+pub fn handle_startup_loaded(
+    app: &mut App,
+    repos: Result<Listing<RepoListItem>, String>,
+    orgs: Vec<String>,
+) -> Vec<Command> {
+    app.orgs = Some(orgs);
+    handle_repo_list_loaded(app, repos)
+}
+
+pub fn handle_org_list_loaded(app: &mut App, orgs: Vec<String>) -> Vec<Command> {
+    app.orgs = Some(orgs);
+    Vec::new()
+}
+
+pub fn handle_pr_list_loaded(
+    app: &mut App,
+    result: Result<Listing<PrListItem>, String>,
+) -> Vec<Command> {
     match result {
-        Ok(pr_list) => {
-            app.pr_list = pr_list;
+        Ok(listing) => {
+            app.listing_banner = listing_banner(&listing);
+            app.pr_paging = Paging::from_listing(&listing);
+            app.pr_list = listing.items;
             app.picker_selected = 0;
+            app.drop_hidden_prs();
+            app.sort_pr_list();
             app.state = AppState::PrPicker;
             app.show_picker = app.story.is_some();
             Vec::new()
@@ -37,14 +85,71 @@ pub fn handle_pr_list_loaded(app: &mut App, result: Result<Vec<PrListItem>, Stri
     }
 }
 
-pub fn handle_pr_loaded(app: &mut App, result: Result<PrContext, String>) -> Vec<Command> {
+pub fn handle_review_queue_loaded(
+    app: &mut App,
+    result: Result<Vec<QueueItem>, String>,
+) -> Vec<Command> {
     match result {
-        Ok(pr) => {
+        Ok(queue) => {
+            app.queue = queue;
+            app.queue_selected = 0;
+            app.state = AppState::ReviewQueue;
+            app.show_picker = false;
+            Vec::new()
+        }
+        Err(err) => {
+            app.state = AppState::Error(format!("Failed to fetch review queue: {}", err));
+            Vec::new()
+        }
+    }
+}
+
+pub fn handle_pr_loaded(
+    app: &mut App,
+    result: Result<PrContext, String>,
+    config: &AppConfig,
+) -> Vec<Command> {
+    match result {
+        Ok(mut pr) => {
+            apply_scope(&mut pr, &app.scope);
+            pr.review_memory = app.memory.prompt(&format!("{}/{}", pr.owner, pr.repo));
             app.current_repo = Some((pr.owner.clone(), pr.repo.clone()));
             app.current_pr_number = Some(pr.number);
+            app.head_files = pr.head_files.clone();
+            app.blame = pr.base_blame.clone();
             app.pr = Some(pr.clone());
-            app.state = AppState::GeneratingStory;
-            vec![Command::GenerateStory { pr }]
+            app.custom_prompt_sources = config
+                .custom_prompts(&pr)
+                .into_iter()
+                .map(|custom| custom.source)
+                .chain(pr.style_guide.as_ref().map(|_| STYLE_GUIDE_PATH.to_string()))
+                .collect();
+            app.history.touch_repo(&format!("{}/{}", pr.owner, pr.repo));
+            app.history.touch_pr(&format!("{}/{}#{}", pr.owner, pr.repo, pr.number));
+            let mut commands = vec![
+                Command::SaveHistory {
+                    history: app.history.clone(),
+                },
+                Command::FetchRateLimit,
+            ];
+            if !app.pending_cache.is_empty() {
+                commands.extend(stale::check(app, &pr, config));
+                return commands;
+            }
+            match reviewed::fetch_delta(app, &pr) {
+                Some(fetch) => commands.push(fetch),
+                None => commands.extend(helpers::generate_stories(app, &pr, config)),
+            }
+            commands
+        }
+        Err(err) if !app.pending_cache.is_empty() => {
+            let stories = std::mem::take(&mut app.pending_cache);
+            let commands = show_cached(app, stories, config);
+            app.status = Some(format!(
+                "Loaded from cache, but couldn't check it's current: {}",
+                err
+            ));
+            commands
         }
         Err(err) => {
             app.state = AppState::Error(err);
@@ -55,63 +160,339 @@ pub fn handle_pr_loaded(app: &mut App, result: Result<PrContext, String>) -> Vec
 
 pub fn handle_story_generated(
     app: &mut App,
+    model: String,
     result: Result<Story, String>,
     config: &AppConfig,
 ) -> Vec<Command> {
+    let served_by = app.generation_provider.take();
+    let replaced = app
+        .fallback_for
+        .take_if(|_| model == config.fallback_model)
+        .map(|(slow, budget_secs)| {
+            format!("{} exceeded the {}s budget; used {} instead", slow, budget_secs, model)
+        });
     match result {
         Ok(story) => {
-            app.populate_from_story(&story);
-            app.story = Some(story.clone());
+            let save = helpers::save_to_cache(app, config, &model, story.clone());
+            if !app.add_story_variant(model.clone(), story) {
+                app.status = Some(replaced.unwrap_or_else(|| {
+                    format!("Story from {} ready (M to switch, C to compare)", model)
+                }));
+                return save.into_iter().collect();
+            }
+            let (provider, served) =
+                served_by.unwrap_or_else(|| (llm::PROVIDER.to_string(), model.clone()));
+            app.stats.record_story(&provider, &served, StorySource::Generated);
             app.state = AppState::Viewing;
             app.show_picker = false;
-            vec![Command::SaveCache {
-                path: config.cache_file.clone(),
-                story,
-            }]
+            reviewed::restore(app);
+            if replaced.is_some() {
+                app.status = replaced;
+            }
+            let mut commands: Vec<Command> = save.into_iter().collect();
+            let title = app.pr.as_ref().map_or("", |pr| pr.title.as_str());
+            commands.extend(helpers::notify_desktop(app, config, "Story ready", title));
+            commands.extend(helpers::fetch_viewed_files(app));
+            commands.extend(activity::fetch(app));
+            // Prefetch key blocks' files so their surrounding code expands instantly
+            let missing = app.missing_key_block_files();
+            if let Some(pr) = app.pr.as_ref().filter(|_| !missing.is_empty()) {
+                commands.push(Command::FetchHeadFiles {
+                    owner: pr.owner.clone(),
+                    repo: pr.repo.clone(),
+                    number: pr.number,
+                    sha: pr.head_sha.clone(),
+                    paths: missing,
+                });
+            }
+            commands
         }
         Err(err) => {
-            app.state = AppState::Error(err);
+            app.pending_models.retain(|pending| *pending != model);
+            if app.story.is_none() && app.pending_models.is_empty() {
+                let notify = helpers::notify_desktop(app, config, "Story generation failed", &err);
+                app.state = AppState::Error(err);
+                return notify.into_iter().collect();
+            }
+            app.status = Some(format!("{} failed: {}", model, err));
+            if app.story.is_none() {
+                app.generation_model = app.pending_models.first().cloned();
+            }
             Vec::new()
         }
     }
 }
 
-pub fn handle_cache_loaded(app: &mut App, story: Option<Story>) -> Vec<Command> {
-    match story {
-        Some(story) => {
-            app.populate_from_story(&story);
-            app.story = Some(story);
-            app.state = AppState::Viewing;
-            app.show_picker = false;
-            app.status = Some("Loaded from cache".to_string());
-            helpers::ensure_cached_pr_context(app);
-            Vec::new()
+pub fn handle_story_generation_timed_out(
+    app: &mut App,
+    pr: PrContext,
+    model: String,
+    config: &AppConfig,
+) -> Vec<Command> {
+    let budget_secs = config.generation_budget.map_or(0, |b| b.as_secs());
+
+    if model == config.fallback_model {
+        let err = format!("Generation with {} exceeded the {}s budget", model, budget_secs);
+        return handle_story_generated(app, model, Err(err), config);
+    }
+
+    // Until the fallback's story arrives, it's only being tried
+    app.status = Some(format!(
+        "{} exceeded the {}s budget; trying {}",
+        model, budget_secs, config.fallback_model
+    ));
+    app.fallback_for = Some((model.clone(), budget_secs));
+    if let Some(pending) = app.pending_models.iter_mut().find(|pending| **pending == model) {
+        *pending = config.fallback_model.clone();
+    }
+    if app.story.is_none() {
+        app.generation_model = Some(config.fallback_model.clone());
+    }
+    vec![Command::GenerateStory {
+        pr: Box::new(pr),
+        model: config.fallback_model.clone(),
+        persona: app.persona,
+        language: app.language.clone(),
+        params: app.model_params,
+    }]
+}
+
+pub fn handle_progress(app: &mut App, event: ProgressEvent) -> Vec<Command> {
+    match &event {
+        ProgressEvent::TokensUsed { input, output } => {
+            app.stats.last_usage = Some(TokenUsage {
+                input: *input,
+                output: *output,
+            });
         }
-        None => {
-            if let Some((owner, repo, number)) = helpers::current_pr_ref(app) {
-                app.state = AppState::LoadingPr;
-                vec![Command::FetchPr { owner, repo, number }]
-            } else {
-                app.state = AppState::Error("Missing PR context".to_string());
-                Vec::new()
+        ProgressEvent::ReusedResponse => {
+            app.status = Some(
+                "Same prompt as before, so the model's last answer was reused \
+                 (--no-llm-cache to call it again)"
+                    .to_string(),
+            );
+        }
+        ProgressEvent::UsingProvider { provider, model } => {
+            let next = (provider.clone(), model.clone());
+            if let Some((failed, _)) = app.generation_provider.replace(next) {
+                app.status = Some(format!("{} unavailable; trying {}", failed, provider));
+            }
+        }
+        _ => {}
+    }
+    app.record_progress(event);
+    Vec::new()
+}
+
+pub fn handle_head_files_loaded(
+    app: &mut App,
+    result: Result<Vec<(String, String)>, String>,
+) -> Vec<Command> {
+    let searching = matches!(app.state, AppState::LoadingWorkspaceFiles);
+    match result {
+        Ok(files) => {
+            app.head_files.extend(files);
+            if searching {
+                app.run_workspace_search();
+            }
+        }
+        // Search whatever was fetched earlier
+        Err(_) if searching && !app.head_files.is_empty() => app.run_workspace_search(),
+        Err(err) => {
+            app.status = Some(format!("Error: {}", err));
+            if searching || matches!(app.state, AppState::FileView) {
+                app.state = AppState::Viewing;
+            }
+        }
+    }
+    Vec::new()
+}
+
+pub fn handle_blame_loaded(
+    app: &mut App,
+    path: String,
+    result: Result<Vec<BlameRange>, String>,
+) -> Vec<Command> {
+    match result {
+        Ok(ranges) => {
+            app.blame.insert(path, ranges);
+        }
+        Err(err) => {
+            app.status = Some(format!("Error: {}", err));
+            if matches!(app.state, AppState::BlameView) {
+                app.state = AppState::Viewing;
             }
         }
     }
+    Vec::new()
+}
+
+/// Cached stories are checked against the live PR before they're shown
+pub fn handle_cache_loaded(app: &mut App, stories: Vec<(String, CacheEntry)>) -> Vec<Command> {
+    let Some((owner, repo, number)) = helpers::current_pr_ref(app) else {
+        app.state = AppState::Error("Missing PR context".to_string());
+        return Vec::new();
+    };
+    // A story cached in another language is regenerated in this one
+    app.pending_cache = stories
+        .into_iter()
+        .filter(|(_, entry)| entry.language == app.language)
+        .collect();
+    app.state = AppState::LoadingPr;
+    vec![Command::FetchPr { owner, repo, number }]
+}
+
+/// Show cached stories, generating any compared model's that wasn't cached
+pub fn show_cached(
+    app: &mut App,
+    stories: Vec<(String, CacheEntry)>,
+    config: &AppConfig,
+) -> Vec<Command> {
+    let uncached: Vec<String> = config
+        .story_models()
+        .into_iter()
+        .filter(|model| stories.iter().all(|(cached, _)| cached != model))
+        .collect();
+    if let Some((model, _)) = stories.first() {
+        app.stats.record_story(llm::PROVIDER, model, StorySource::Cached);
+    }
+    for (model, entry) in stories {
+        app.add_story_variant(model, entry.story);
+    }
+    app.state = AppState::Viewing;
+    app.show_picker = false;
+    app.status = Some(if uncached.is_empty() {
+        "Loaded from cache".to_string()
+    } else {
+        format!("Loaded from cache (nothing cached for {})", uncached.join(", "))
+    });
+    helpers::ensure_cached_pr_context(app);
+    let mut commands = helpers::fetch_viewed_files(app);
+    commands.extend(activity::fetch(app));
+    commands
+}
+
+pub fn handle_rate_limit_loaded(
+    app: &mut App,
+    result: Result<RateLimit, String>,
+) -> Vec<Command> {
+    // A failed check keeps the last known figure; the error is logged
+    if let Ok(limit) = result {
+        app.stats.rate_limit = Some(limit);
+    }
+    Vec::new()
 }
 
 pub fn handle_submission_result(
     app: &mut App,
     action: ReviewAction,
     result: Result<(), String>,
+    config: &AppConfig,
 ) -> Vec<Command> {
+    let (mut commands, retried) = outbox::record_result(app, action, &result);
+    if retried {
+        return commands;
+    }
+    if result.is_ok() {
+        if !app.submitted_actions.contains(&action) {
+            app.submitted_actions.push(action);
+        }
+        commands.extend(memory::record_submission(app, action));
+        commands.extend(notify_webhook(app, action, config));
+        // The pending review went out with them
+        if action == ReviewAction::RequestChanges {
+            app.pending_comments.clear();
+        }
+    }
+    // Batch results update their row and leave whatever screen is open alone
+    if batch::record_result(app, action, &result) {
+        if !app.batch_running() {
+            let status = app.status.as_deref().unwrap_or_default();
+            commands.extend(helpers::notify_desktop(app, config, "Submissions finished", status));
+        }
+        return commands;
+    }
+
+    let title = if result.is_ok() { "Submitted" } else { "Submission failed" };
     match result {
+        Ok(()) if action == ReviewAction::RequestChanges => {
+            app.status = Some(format!("Review submitted ({})", app.review_event.label()));
+        }
         Ok(()) => {
             app.status = Some(format!("{} submitted successfully!", action.title()));
         }
         Err(err) => {
-            app.status = Some(format!("Error: {}", err));
+            app.status = Some(format!("Error: {} (kept in the outbox; R to retry)", err));
         }
     }
+    let status = app.status.as_deref().unwrap_or_default();
+    commands.extend(helpers::notify_desktop(app, config, title, status));
+    app.state = AppState::Viewing;
+    app.show_picker = false;
+    commands
+}
+
+/// Tell the repo's team channel what was just posted, if it has a webhook
+fn notify_webhook(app: &App, action: ReviewAction, config: &AppConfig) -> Option<Command> {
+    let (owner, repo, number) = helpers::current_pr_ref(app)?;
+    let text = app.action_text(action);
+    webhook_command(config, (&owner, &repo, number), action, app.review_event, text)
+}
+
+/// The webhook notification for `action` posted on PR `owner/repo#number`, if the
+/// repo has a webhook
+pub fn webhook_command(
+    config: &AppConfig,
+    (owner, repo, number): (&str, &str, u32),
+    action: ReviewAction,
+    event: ReviewEvent,
+    text: &str,
+) -> Option<Command> {
+    let url = notify::webhook_for(&config.webhooks, owner, repo)?;
+    Some(Command::NotifyWebhook {
+        url: url.to_string(),
+        summary: notify::summary(action, event, repo, number, text),
+    })
+}
+
+pub fn handle_viewed_files_loaded(
+    app: &mut App,
+    result: Result<ViewedFiles, String>,
+) -> Vec<Command> {
+    // Without it the review just isn't synced; the failure is logged
+    if let Ok(viewed) = result {
+        app.pr_node_id = Some(viewed.pr_id);
+        app.mark_files_viewed(&viewed.paths);
+    }
+    Vec::new()
+}
+
+pub fn handle_file_viewed_synced(
+    app: &mut App,
+    path: String,
+    result: Result<(), String>,
+) -> Vec<Command> {
+    if let Err(err) = result {
+        app.status = Some(format!("Couldn't sync viewed state of {} to GitHub: {}", path, err));
+    }
+    Vec::new()
+}
+
+pub fn handle_dry_run_written(
+    app: &mut App,
+    action: ReviewAction,
+    result: Result<String, String>,
+) -> Vec<Command> {
+    // Nothing was posted, so the action stays submittable
+    let outcome = result.as_ref().map(|_| ()).map_err(Clone::clone);
+    if batch::record_result(app, action, &outcome) {
+        return Vec::new();
+    }
+
+    app.status = Some(match result {
+        Ok(path) => format!("Dry run: {} written to {}", action.title(), path),
+        Err(err) => format!("Error: {}", err),
+    });
     app.state = AppState::Viewing;
     app.show_picker = false;
     Vec::new()
@@ -0,0 +1,81 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::domain::types::PrActivity;
+
+use super::helpers;
+
+/// Check the open PR for new activity, unless something else is on screen
+pub fn handle_poll_due(app: &mut App) -> Vec<Command> {
+    if !matches!(app.state, AppState::Viewing) || app.pr.is_none() {
+        return Vec::new();
+    }
+    let mut commands = fetch(app);
+    commands.push(Command::FetchRateLimit);
+    commands
+}
+
+pub fn fetch(app: &App) -> Vec<Command> {
+    helpers::current_pr_ref(app)
+        .map(|(owner, repo, number)| Command::FetchActivity { owner, repo, number })
+        .into_iter()
+        .collect()
+}
+
+pub fn handle_loaded(app: &mut App, result: Result<PrActivity, String>) -> Vec<Command> {
+    // A failed poll just means no banner this time; the error is logged
+    let Ok(activity) = result else {
+        return Vec::new();
+    };
+    // The first fetch sets what counts as already read
+    if app.seen_comments.is_none() {
+        app.seen_comments = Some(activity.comments.iter().map(|c| c.id.clone()).collect());
+    }
+    app.activity = Some(activity);
+    Vec::new()
+}
+
+/// Reload the PR and regenerate its story to take in new commits
+pub fn refresh(app: &mut App) -> Vec<Command> {
+    let Some((owner, repo, number)) = helpers::current_pr_ref(app) else {
+        return Vec::new();
+    };
+    app.reset_for_new_pr();
+    app.current_pr_number = Some(number);
+    app.state = AppState::LoadingPr;
+    vec![Command::FetchPr {
+        owner,
+        repo,
+        number,
+    }]
+}
+
+pub fn open(app: &mut App) {
+    if app.new_comments().is_empty() {
+        app.status = Some("No new comments".to_string());
+        return;
+    }
+    app.show_activity = true;
+    app.activity_scroll = 0;
+}
+
+/// Input while the new-comments overlay is open; closing it marks them read
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('A') => {
+            app.show_activity = false;
+            if let (Some(seen), Some(activity)) = (&mut app.seen_comments, &app.activity) {
+                seen.extend(activity.comments.iter().map(|c| c.id.clone()));
+            }
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.activity_scroll = app.activity_scroll.saturating_add(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.activity_scroll = app.activity_scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+    Vec::new()
+}
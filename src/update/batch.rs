@@ -0,0 +1,128 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState, BatchStatus};
+use crate::command::Command;
+use crate::domain::types::ReviewAction;
+
+use super::helpers;
+
+/// Open the batch submit overlay over the story
+pub fn open(app: &mut App) {
+    if app.story.is_none() {
+        return;
+    }
+    app.open_batch();
+}
+
+/// Input while the batch submit overlay is open
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        // Results still land on their rows if the overlay is closed early
+        KeyCode::Esc | KeyCode::Char('q') => app.state = AppState::Viewing,
+        _ if app.batch_running() => {}
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.batch_selected = (app.batch_selected + 1).min(app.batch.len().saturating_sub(1));
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.batch_selected = app.batch_selected.saturating_sub(1);
+        }
+        KeyCode::Char(' ') => toggle(app, app.batch_selected),
+        KeyCode::Char(c @ '1'..='3') => toggle(app, c as usize - '1' as usize),
+        KeyCode::Char('e') => {
+            if let Some(item) = app.batch.get(app.batch_selected) {
+                app.selected_action = item.action;
+                app.start_editing();
+            }
+        }
+        KeyCode::Enter => return submit_checked(app),
+        _ => {}
+    }
+    Vec::new()
+}
+
+fn toggle(app: &mut App, index: usize) {
+    let Some(action) = app.batch.get(index).map(|item| item.action) else {
+        return;
+    };
+    if app.action_text(action).trim().is_empty() {
+        app.status = Some(format!("{} has no draft", action.title()));
+        return;
+    }
+    app.batch_selected = index;
+    app.batch[index].checked = !app.batch[index].checked;
+}
+
+/// Ask to confirm every checked action with a draft
+fn submit_checked(app: &mut App) -> Vec<Command> {
+    let actions: Vec<ReviewAction> = app
+        .batch
+        .iter()
+        .filter(|item| item.checked && !app.action_text(item.action).trim().is_empty())
+        .map(|item| item.action)
+        .collect();
+    if actions.is_empty() {
+        app.status = Some("Nothing checked to submit".to_string());
+    } else {
+        app.open_confirm(actions, true);
+    }
+    Vec::new()
+}
+
+/// Queue confirmed actions; they post one after another
+pub fn queue(app: &mut App, actions: &[ReviewAction]) -> Vec<Command> {
+    let Some((owner, repo, number)) = helpers::current_pr_ref(app) else {
+        app.status = Some("Missing PR context".to_string());
+        return Vec::new();
+    };
+
+    let mut commands = Vec::new();
+    for &action in actions {
+        let text = app.action_text(action).to_string();
+        commands.extend(helpers::submit_action(
+            app,
+            action,
+            owner.clone(),
+            repo.clone(),
+            number,
+            text,
+        ));
+        if let Some(item) = app.batch.iter_mut().find(|item| item.action == action) {
+            item.status = BatchStatus::Queued;
+        }
+    }
+    commands
+}
+
+/// Record a batch-submitted action's result on its row. Returns false if the
+/// action wasn't part of a running batch
+pub fn record_result(app: &mut App, action: ReviewAction, result: &Result<(), String>) -> bool {
+    let Some(item) = app
+        .batch
+        .iter_mut()
+        .find(|item| item.action == action && item.status == BatchStatus::Queued)
+    else {
+        return false;
+    };
+    match result {
+        Ok(()) => {
+            item.status = BatchStatus::Sent;
+            item.checked = false;
+        }
+        Err(err) => item.status = BatchStatus::Failed(err.clone()),
+    }
+
+    if !app.batch_running() {
+        let failed: Vec<&str> = app
+            .batch
+            .iter()
+            .filter(|item| matches!(item.status, BatchStatus::Failed(_)))
+            .map(|item| item.action.title())
+            .collect();
+        app.status = Some(if failed.is_empty() {
+            "All checked actions submitted".to_string()
+        } else {
+            format!("{} failed and stays checked; e edits it, Enter retries", failed.join(", "))
+        });
+    }
+    true
+}
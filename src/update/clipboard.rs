@@ -0,0 +1,74 @@
+use crate::app::App;
+use crate::command::Command;
+use crate::domain::export;
+use crate::domain::listings::now_secs;
+
+/// Copy the selected diff block as Markdown
+pub fn copy_block(app: &mut App) -> Vec<Command> {
+    let (feature_idx, block_idx) = (app.selected_feature, app.selected_diff);
+    let Some(block) = app
+        .story
+        .as_ref()
+        .and_then(|story| story.narrative.get(feature_idx))
+        .and_then(|feature| feature.diff_blocks.get(block_idx))
+    else {
+        app.status = Some("No diff block selected".to_string());
+        return Vec::new();
+    };
+    let path = app.block_location(feature_idx, block_idx).map(|(path, _)| path);
+    let text = export::block_markdown(feature_idx, block_idx, block, path.as_deref());
+    vec![Command::CopyToClipboard {
+        text,
+        what: format!("block {}.{}", feature_idx + 1, block_idx + 1),
+    }]
+}
+
+/// Copy the whole story as Markdown
+pub fn copy_story(app: &mut App) -> Vec<Command> {
+    let Some(story) = &app.story else {
+        return Vec::new();
+    };
+    vec![Command::CopyToClipboard {
+        text: export::story_markdown(app.pr.as_ref(), story),
+        what: "the story".to_string(),
+    }]
+}
+
+/// Write the review so far as JSON: story, drafts, viewed blocks and timings
+pub fn export_session(app: &mut App) -> Vec<Command> {
+    let Some(record) = app.session_record(now_secs()) else {
+        return Vec::new();
+    };
+    vec![Command::ExportSession {
+        record: Box::new(record),
+    }]
+}
+
+/// Copy the draft being edited
+pub fn copy_draft(app: &mut App) -> Vec<Command> {
+    let text = app.current_action_text().to_string();
+    if text.is_empty() {
+        app.status = Some("Nothing to copy yet".to_string());
+        return Vec::new();
+    }
+    vec![Command::CopyToClipboard {
+        text,
+        what: "the draft".to_string(),
+    }]
+}
+
+pub fn handle_copied(app: &mut App, what: String, result: Result<String, String>) -> Vec<Command> {
+    app.status = Some(match result {
+        Ok(via) => format!("Copied {} to the clipboard (via {})", what, via),
+        Err(err) => format!("Couldn't copy {}: {}", what, err),
+    });
+    Vec::new()
+}
+
+pub fn handle_session_exported(app: &mut App, result: Result<String, String>) -> Vec<Command> {
+    app.status = Some(match result {
+        Ok(path) => format!("Review session exported to {}", path),
+        Err(err) => format!("Couldn't export the review session: {}", err),
+    });
+    Vec::new()
+}
@@ -0,0 +1,151 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::filter::apply_scope;
+use crate::domain::types::{CommitRange, PrCommit};
+
+use super::{activity, helpers};
+
+/// Fetch the PR's commits to choose a range from
+pub fn open(app: &mut App) -> Vec<Command> {
+    let Some((owner, repo, number)) = helpers::current_pr_ref(app) else {
+        return Vec::new();
+    };
+    app.status = Some("Loading commits...".to_string());
+    vec![Command::FetchCommits {
+        owner,
+        repo,
+        number,
+    }]
+}
+
+/// Show the commit list, checked as the current range (every commit for the whole PR)
+pub fn handle_loaded(app: &mut App, result: Result<Vec<PrCommit>, String>) -> Vec<Command> {
+    // The reader may have moved on while the list loaded
+    if !matches!(app.state, AppState::Viewing) {
+        return Vec::new();
+    }
+    let commits = match result {
+        Ok(commits) if !commits.is_empty() => commits,
+        Ok(_) => {
+            app.status = Some("This PR has no commits".to_string());
+            return Vec::new();
+        }
+        Err(err) => {
+            app.status = Some(format!("Couldn't load commits: {}", err));
+            return Vec::new();
+        }
+    };
+    let range = app.pr.as_ref().and_then(|pr| pr.commit_range.as_ref());
+    app.commit_checked = commits
+        .iter()
+        .map(|commit| range.is_none_or(|range| range.commits.contains(commit)))
+        .collect();
+    app.commit_selected = app.commit_checked.iter().position(|&checked| checked).unwrap_or(0);
+    app.commits = commits;
+    app.status = None;
+    app.state = AppState::SelectingCommits;
+    Vec::new()
+}
+
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    let count = app.commits.len();
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.state = AppState::Viewing,
+        KeyCode::Char('j') | KeyCode::Down if app.commit_selected + 1 < count => {
+            app.commit_selected += 1;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.commit_selected = app.commit_selected.saturating_sub(1);
+        }
+        KeyCode::Char(' ') => {
+            if let Some(checked) = app.commit_checked.get_mut(app.commit_selected) {
+                *checked = !*checked;
+            }
+        }
+        // Everything from the highlighted commit on: what's new since it
+        KeyCode::Char('f') => {
+            let from = app.commit_selected;
+            for (i, checked) in app.commit_checked.iter_mut().enumerate() {
+                *checked = i >= from;
+            }
+        }
+        KeyCode::Enter => return choose(app),
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// Fetch the diff from the first to the last checked commit
+fn choose(app: &mut App) -> Vec<Command> {
+    let first = app.commit_checked.iter().position(|&checked| checked);
+    let last = app.commit_checked.iter().rposition(|&checked| checked);
+    let (Some(first), Some(last)) = (first, last) else {
+        app.status = Some("Check at least one commit with Space".to_string());
+        return Vec::new();
+    };
+    let Some(pr) = &app.pr else {
+        return Vec::new();
+    };
+    app.state = AppState::Viewing;
+
+    if first == 0 && last + 1 == app.commits.len() {
+        if pr.commit_range.is_none() {
+            return Vec::new();
+        }
+        let commands = activity::refresh(app);
+        app.status = Some("Reloading the whole PR".to_string());
+        return commands;
+    }
+
+    let range = CommitRange {
+        base: first.checked_sub(1).map_or(pr.base_sha.clone(), |i| app.commits[i].sha.clone()),
+        commits: app.commits[first..=last].to_vec(),
+        before: first,
+        total: app.commits.len(),
+    };
+    if pr.commit_range.as_ref() == Some(&range) {
+        return Vec::new();
+    }
+    app.status = Some(format!("Fetching the diff of {}...", range.describe()));
+    vec![Command::FetchRangeDiff {
+        owner: pr.owner.clone(),
+        repo: pr.repo.clone(),
+        number: pr.number,
+        range,
+    }]
+}
+
+/// Review just the range's diff, regenerating the story for it
+pub fn handle_range_diff(
+    app: &mut App,
+    range: CommitRange,
+    result: Result<String, String>,
+    config: &AppConfig,
+) -> Vec<Command> {
+    let diff = match result {
+        Ok(diff) => diff,
+        Err(err) => {
+            app.status = Some(format!("Couldn't fetch the commit range: {}", err));
+            return Vec::new();
+        }
+    };
+    let Some(pr) = &mut app.pr else {
+        return Vec::new();
+    };
+    let (_, excluded_files) = config.fetch_options().filter.apply(&diff);
+    pr.diff = diff;
+    pr.excluded_files = excluded_files;
+    apply_scope(pr, &app.scope);
+    let description = range.describe();
+    pr.commit_range = Some(range);
+    app.index_changed_files();
+    let Some(pr) = helpers::regenerable_pr(app) else {
+        return Vec::new();
+    };
+    let commands = helpers::regenerate_stories(app, &pr, config);
+    app.status = Some(format!("Regenerating for {}", description));
+    commands
+}
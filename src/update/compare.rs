@@ -0,0 +1,19 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+
+/// Input while the model comparison overlay is open
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        // Closing views whichever story is highlighted
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') | KeyCode::Char('C') => {
+            app.state = AppState::Viewing
+        }
+        KeyCode::Char('M') | KeyCode::Tab => {
+            app.cycle_story_variant();
+        }
+        _ => {}
+    }
+    Vec::new()
+}
@@ -0,0 +1,82 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::domain::types::{ReviewAction, ReviewEvent};
+
+use super::{batch, helpers};
+
+/// Input while the confirmation overlay is open; only `y` posts
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => return post(app),
+        KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') => cancel(app),
+        KeyCode::Char('m') if app.confirm_actions.contains(&ReviewAction::RequestChanges) => {
+            app.report_mode = app.report_mode.next();
+        }
+        KeyCode::Char('e') if app.confirm_actions.contains(&ReviewAction::RequestChanges) => {
+            app.review_event = app.review_event.next();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.confirm_scroll = app.confirm_scroll.saturating_add(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.confirm_scroll = app.confirm_scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// Go back to whatever asked for confirmation, nothing posted
+fn cancel(app: &mut App) {
+    let actions = std::mem::take(&mut app.confirm_actions);
+    app.state = match actions.first() {
+        _ if app.confirm_batch => AppState::BatchSubmit,
+        Some(&action) => AppState::EditingAction(action),
+        None => AppState::Viewing,
+    };
+    app.status = Some("Not posted".to_string());
+}
+
+fn post(app: &mut App) -> Vec<Command> {
+    if let Some(missing) = missing_review_body(app) {
+        app.status = Some(missing.to_string());
+        return Vec::new();
+    }
+    let actions = std::mem::take(&mut app.confirm_actions);
+    if app.confirm_batch {
+        app.state = AppState::BatchSubmit;
+        return batch::queue(app, &actions);
+    }
+
+    let Some(&action) = actions.first() else {
+        app.state = AppState::Viewing;
+        return Vec::new();
+    };
+    let Some((owner, repo, number)) = helpers::current_pr_ref(app) else {
+        app.status = Some("Missing PR context".to_string());
+        app.state = AppState::Viewing;
+        return Vec::new();
+    };
+    let text = app.action_text(action).to_string();
+    app.state = AppState::Submitting(action);
+    helpers::submit_action(app, action, owner, repo, number, text)
+}
+
+/// Why GitHub would turn the review down for want of a body: requesting
+/// changes always needs one, a comment needs one or some inline comments
+fn missing_review_body(app: &App) -> Option<&'static str> {
+    if !app.confirm_actions.contains(&ReviewAction::RequestChanges)
+        || !app.action_text(ReviewAction::RequestChanges).trim().is_empty()
+    {
+        return None;
+    }
+    match app.review_event {
+        ReviewEvent::RequestChanges => Some("Requesting changes needs a review body"),
+        ReviewEvent::Comment if app.pending_comments.is_empty() => {
+            Some("A comment review needs a body or inline comments")
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,34 @@
+use crate::app::App;
+use crate::domain::review::append_list_item;
+
+/// Ask the author about every coverage gap no changed test seems to cover,
+/// in the Clarification Questions draft
+pub fn ask_about_gaps(app: &mut App) {
+    let questions: Vec<(String, String)> = app
+        .coverage_gaps()
+        .into_iter()
+        .filter(|(_, tests)| tests.is_empty())
+        .map(|(gap, _)| {
+            let question = format!("Are there tests for {}? {}", gap.target, gap.reason);
+            (gap.target.clone(), question)
+        })
+        .collect();
+    if questions.is_empty() {
+        app.status = Some("No coverage gaps to ask about".to_string());
+        return;
+    }
+
+    let draft = &mut app.action_texts.clarification;
+    let mut added = 0;
+    for (target, question) in questions {
+        if !draft.contains(&target) {
+            append_list_item(draft, &question);
+            added += 1;
+        }
+    }
+    app.status = Some(match added {
+        0 => "Coverage gaps are already in the Clarification Questions draft".to_string(),
+        1 => "Added 1 coverage question to Clarification Questions".to_string(),
+        n => format!("Added {} coverage questions to Clarification Questions", n),
+    });
+}
@@ -0,0 +1,85 @@
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::diff::block_touches;
+use crate::domain::prompt::build_deep_dive_prompts;
+use crate::domain::secrets::{self, SecretsChoice};
+use crate::domain::types::{DeepDive, PrContext};
+
+/// Ask for a deep dive into the selected feature, with the whole head-side files
+/// its blocks touch as context. Likely secrets are redacted unless the reviewer
+/// chose to send them, and under `--preview-prompt` the prompt waits for approval
+pub fn request(app: &mut App, config: &AppConfig) -> Vec<Command> {
+    let (Some(pr), Some(story)) = (&app.pr, &app.story) else {
+        return Vec::new();
+    };
+    let i = app.selected_feature;
+    let Some(feature) = story.narrative.get(i) else {
+        return Vec::new();
+    };
+    if app.deep_dives.contains_key(&i) {
+        app.status = Some(format!("Feature {} already has a deep dive", i + 1));
+        return Vec::new();
+    }
+    if let Some(pending) = app.deep_dive_pending {
+        app.status = Some(format!("Still diving into feature {}", pending + 1));
+        return Vec::new();
+    }
+
+    let pr = PrContext {
+        head_files: app.head_files.clone(),
+        ..pr.clone()
+    };
+    let send_secrets = config.allow_secrets || app.secrets_choice == Some(SecretsChoice::Send);
+    let findings = if send_secrets { Vec::new() } else { secrets::scan(&pr.diff) };
+    let pr = if findings.is_empty() { pr } else { secrets::redact(&pr, &findings) };
+    let diff_files = &app.diff_files;
+    let files: Vec<(&str, &str)> = diff_files
+        .iter()
+        .filter(|file| !config.redaction.withholds(&file.path))
+        .filter(|file| {
+            feature.diff_blocks.iter().any(|block| block_touches(diff_files, file, block))
+        })
+        .filter_map(|file| {
+            let (path, contents) = pr.head_files.get_key_value(&file.path)?;
+            Some((path.as_str(), contents.as_str()))
+        })
+        .collect();
+    // The story's own copies of the diff lines hold the secrets too
+    let (system, user) = build_deep_dive_prompts(&pr, feature, &files, app.language.as_deref());
+    let prompts = (system, secrets::mask(&user, &findings));
+
+    if config.preview_prompt && !std::mem::take(&mut app.prompt_approved) {
+        let (system, user) = config.outbound_deep_dive_prompts(&pr, &prompts);
+        app.prompt_preview = format!("SYSTEM\n\n{}\n\nUSER\n\n{}", system, user);
+        app.prompt_preview_scroll = 0;
+        app.deep_dive_preview = Some(i);
+        app.state = AppState::PreviewingPrompt;
+        return Vec::new();
+    }
+
+    app.status = Some(format!("Diving into feature {} with {}...", i + 1, config.model));
+    app.deep_dive_pending = Some(i);
+    vec![Command::GenerateDeepDive {
+        pr: Box::new(pr),
+        feature: i,
+        model: config.model.clone(),
+        prompts,
+        params: app.model_params,
+    }]
+}
+
+pub fn handle_generated(app: &mut App, feature: usize, result: Result<DeepDive, String>) {
+    // The story it was asked for has since been replaced
+    if app.deep_dive_pending != Some(feature) {
+        return;
+    }
+    app.deep_dive_pending = None;
+    match result {
+        Ok(deep_dive) => {
+            app.deep_dives.insert(feature, deep_dive);
+            app.status = Some(format!("Deep dive added to feature {}", feature + 1));
+        }
+        Err(err) => app.status = Some(format!("Deep dive failed: {}", err)),
+    }
+}
@@ -2,11 +2,17 @@ use crossterm::event::{KeyCode, KeyModifiers};
 
 use crate::app::{App, AppState};
 use crate::command::Command;
+use crate::config::AppConfig;
 use crate::domain::types::ReviewAction;
 
-use super::helpers;
+use super::{batch, clipboard, helpers, templates};
 
-pub fn handle_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Vec<Command> {
+pub fn handle_input(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    config: &AppConfig,
+) -> Vec<Command> {
     match code {
         KeyCode::Esc => {
             app.stop_editing();
@@ -28,52 +34,43 @@ pub fn handle_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Ve
             app.cursor_right();
             Vec::new()
         }
+        KeyCode::Char('s' | 'S')
+            if modifiers.contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT) =>
+        {
+            batch::open(app);
+            Vec::new()
+        }
+        KeyCode::Char('y') if modifiers.contains(KeyModifiers::CONTROL) => {
+            clipboard::copy_draft(app)
+        }
+        KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
+            templates::open(app, config);
+            Vec::new()
+        }
+        KeyCode::Char('p') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.draft_preview = !app.draft_preview;
+            Vec::new()
+        }
         KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
             let action = app.selected_action;
             let text = app.current_action_text().to_string();
 
-            if text.is_empty() {
+            // A pending review can go out as just its inline comments
+            let comments_only =
+                action == ReviewAction::RequestChanges && !app.pending_comments.is_empty();
+            if text.is_empty() && !comments_only {
                 app.status = Some("Cannot submit empty text".to_string());
                 return Vec::new();
             }
 
-            let Some((owner, repo, number)) = helpers::current_pr_ref(app) else {
+            if helpers::current_pr_ref(app).is_none() {
                 app.status = Some("Missing PR context".to_string());
                 app.state = AppState::Viewing;
                 return Vec::new();
-            };
-
-            app.state = AppState::Submitting(action);
-
-            match action {
-                ReviewAction::RequestChanges => vec![Command::PostReview {
-                    owner,
-                    repo,
-                    number,
-                    body: text,
-                }],
-                ReviewAction::ClarificationQuestions => vec![Command::PostComment {
-                    owner,
-                    repo,
-                    number,
-                    body: text,
-                }],
-                ReviewAction::NextPr => {
-                    let mut iter = text.lines();
-                    let title = iter
-                        .next()
-                        .unwrap_or("Follow-up work")
-                        .to_string();
-                    let body = iter.collect::<Vec<&str>>().join("\n");
-                    vec![Command::CreateNextPrIssue {
-                        owner,
-                        repo,
-                        number,
-                        title,
-                        body,
-                    }]
-                }
             }
+
+            app.open_confirm(vec![action], false);
+            Vec::new()
         }
         KeyCode::Char(c) => {
             app.insert_char(c);
@@ -12,6 +12,10 @@ pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
             Vec::new()
         }
         KeyCode::Char('r') => retry_from_error(app),
+        KeyCode::Esc if !app.tabs.is_empty() => {
+            app.close_tab();
+            Vec::new()
+        }
         _ => Vec::new(),
     }
 }
@@ -28,9 +32,15 @@ fn retry_from_error(app: &mut App) -> Vec<Command> {
 
     if let Some((owner, repo)) = helpers::current_repo(app) {
         app.state = AppState::LoadingPrList;
-        return vec![Command::FetchPrList { owner, repo }];
+        return vec![Command::FetchPrList {
+            owner,
+            repo,
+            states: app.pr_filter,
+        }];
     }
 
     app.state = AppState::LoadingRepoList;
-    vec![Command::FetchRepoList]
+    vec![Command::FetchRepoList {
+        org: app.repo_org.clone(),
+    }]
 }
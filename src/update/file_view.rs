@@ -0,0 +1,35 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+
+/// Input while the full-file overlay is open
+pub fn handle_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Vec<Command> {
+    match (code, modifiers) {
+        (KeyCode::Esc, _) | (KeyCode::Char('q'), _) | (KeyCode::Char('w'), _) => {
+            app.file_view_path = None;
+            app.state = AppState::Viewing;
+        }
+        (KeyCode::Char('j'), _) | (KeyCode::Down, _) => app.file_view_down(1),
+        (KeyCode::Char('k'), _) | (KeyCode::Up, _) => app.file_view_up(1),
+        (KeyCode::Char('d'), KeyModifiers::CONTROL)
+        | (KeyCode::Char(' '), KeyModifiers::NONE)
+        | (KeyCode::PageDown, _) => app.file_view_down(20),
+        (KeyCode::Char('u'), KeyModifiers::CONTROL)
+        | (KeyCode::Char('b'), _)
+        | (KeyCode::PageUp, _) => app.file_view_up(20),
+        (KeyCode::Home, _) => app.file_view_scroll = 0,
+        (KeyCode::End, _) => app.file_view_down(u16::MAX),
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// Input while the blame overlay is open
+pub fn handle_blame_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    if matches!(code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('B')) {
+        app.blame_view = None;
+        app.state = AppState::Viewing;
+    }
+    Vec::new()
+}
@@ -0,0 +1,111 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, GoTo};
+use crate::command::Command;
+
+/// Start a `g` command: `gg` goes to the top, `gr` toggles guided reading, and
+/// `gf` or `gd` and a number jump to a feature or diff
+pub fn start(app: &mut App) {
+    prompt(app, GoTo::Prefix);
+}
+
+/// Input while a jump is being typed. It happens on `Enter`, or as soon as
+/// another digit couldn't make a number in range; any other key cancels it
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    let Some(mut go_to) = app.go_to.take() else {
+        return Vec::new();
+    };
+    app.status = None;
+    match code {
+        KeyCode::Char('g') if go_to == GoTo::Prefix => app.scroll_offset = 0,
+        KeyCode::Char('r') if go_to == GoTo::Prefix => {
+            app.status = Some(if app.toggle_guided() {
+                "Guided reading: n/p follow key changes and roots first".to_string()
+            } else {
+                "Guided reading off".to_string()
+            });
+        }
+        KeyCode::Char('f') if go_to == GoTo::Prefix => prompt(app, GoTo::Feature(String::new())),
+        KeyCode::Char('d') if go_to == GoTo::Prefix => prompt(app, GoTo::Diff(String::new())),
+        KeyCode::Enter => jump(app, &go_to),
+        KeyCode::Backspace => {
+            if let Some(digits) = digits_mut(&mut go_to) {
+                digits.pop();
+                prompt(app, go_to);
+            }
+        }
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            let Some(digits) = digits_mut(&mut go_to) else {
+                return Vec::new();
+            };
+            if !(digits.is_empty() && c == '0') {
+                digits.push(c);
+            }
+            let number: usize = digits.parse().unwrap_or(0);
+            if number * 10 > limit(app, &go_to) {
+                jump(app, &go_to);
+            } else {
+                prompt(app, go_to);
+            }
+        }
+        _ => {}
+    }
+    Vec::new()
+}
+
+fn digits_mut(go_to: &mut GoTo) -> Option<&mut String> {
+    match go_to {
+        GoTo::Prefix => None,
+        GoTo::Feature(digits) | GoTo::Diff(digits) => Some(digits),
+    }
+}
+
+/// The highest number the jump can go to
+fn limit(app: &App, go_to: &GoTo) -> usize {
+    let Some(story) = &app.story else {
+        return 0;
+    };
+    match go_to {
+        GoTo::Prefix => 0,
+        GoTo::Feature(_) => story.narrative.len(),
+        GoTo::Diff(_) => story
+            .narrative
+            .get(app.selected_feature)
+            .map_or(0, |feature| feature.diff_blocks.len()),
+    }
+}
+
+fn prompt(app: &mut App, go_to: GoTo) {
+    let limit = limit(app, &go_to);
+    app.status = Some(match &go_to {
+        GoTo::Prefix => {
+            "g: g top, f feature, d diff in this feature, r guided reading".to_string()
+        }
+        GoTo::Feature(digits) => format!("Go to feature (1-{}): {}", limit, digits),
+        GoTo::Diff(digits) => format!("Go to diff (1-{}): {}", limit, digits),
+    });
+    app.go_to = Some(go_to);
+}
+
+fn jump(app: &mut App, go_to: &GoTo) {
+    let (kind, digits) = match go_to {
+        GoTo::Prefix => return,
+        GoTo::Feature(digits) => ("feature", digits),
+        GoTo::Diff(digits) => ("diff", digits),
+    };
+    let Ok(number) = digits.parse() else {
+        return;
+    };
+    let found = match go_to {
+        GoTo::Feature(_) => app.go_to_feature(number),
+        _ => app.go_to_diff(number),
+    };
+    if !found {
+        app.status = Some(format!(
+            "There's no {} {}; this goes up to {}",
+            kind,
+            number,
+            limit(app, go_to)
+        ));
+    }
+}
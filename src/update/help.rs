@@ -0,0 +1,27 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+
+pub fn open(app: &mut App) {
+    app.show_help = true;
+    app.help_scroll = 0;
+}
+
+/// Input while the key reference is open, over whatever screen is underneath
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => app.show_help = false,
+        KeyCode::Char('j') | KeyCode::Down => app.help_scroll = app.help_scroll.saturating_add(1),
+        KeyCode::Char('k') | KeyCode::Up => app.help_scroll = app.help_scroll.saturating_sub(1),
+        KeyCode::Char(' ') | KeyCode::PageDown => {
+            app.help_scroll = app.help_scroll.saturating_add(10);
+        }
+        KeyCode::Char('b') | KeyCode::PageUp => {
+            app.help_scroll = app.help_scroll.saturating_sub(10);
+        }
+        KeyCode::Char('g') | KeyCode::Home => app.help_scroll = 0,
+        _ => {}
+    }
+    Vec::new()
+}
@@ -1,5 +1,16 @@
-use crate::app::App;
-use crate::domain::types::PrContext;
+use std::collections::HashMap;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::cache::{self, CacheEntry};
+use crate::domain::diff::parse_diff;
+use crate::domain::listings::now_secs;
+use crate::domain::review::{anchor_questions, split_list_items};
+use crate::domain::secrets::{self, SecretsChoice};
+use crate::domain::summarize;
+use crate::domain::timing::ReportMode;
+use crate::domain::types::{PrContext, ReviewAction, Story};
 
 pub fn current_repo(app: &App) -> Option<(String, String)> {
     if let Some((owner, repo)) = &app.current_repo {
@@ -22,6 +33,134 @@ pub fn current_pr_ref(app: &App) -> Option<(String, String, u32)> {
     }
 }
 
+/// `owner/repo#123` of the PR showing, even before it has loaded
+pub fn current_pr_key(app: &App) -> Option<String> {
+    current_pr_ref(app).map(|(owner, repo, number)| format!("{}/{}#{}", owner, repo, number))
+}
+
+/// A desktop notification about the open PR, unless the terminal says it has
+/// focus (the reviewer is already looking) or notifications are off
+pub fn notify_desktop(
+    app: &App,
+    config: &AppConfig,
+    title: &str,
+    detail: &str,
+) -> Option<Command> {
+    if !config.notify || app.terminal_focused == Some(true) {
+        return None;
+    }
+    let (_, repo, number) = current_pr_ref(app)?;
+    Some(Command::NotifyDesktop {
+        title: title.to_string(),
+        body: format!("{}#{}: {}", repo, number, detail),
+    })
+}
+
+/// Cache `model`'s story for the open PR, stamped with the head it was generated at
+pub fn save_to_cache(app: &App, config: &AppConfig, model: &str, story: Story) -> Option<Command> {
+    let pr = app.pr.as_ref()?;
+    Some(save_pr_to_cache(pr, app.language.clone(), config, model, story))
+}
+
+/// Cache `pr`'s story, whichever tab it's open in
+pub fn save_pr_to_cache(
+    pr: &PrContext,
+    language: Option<String>,
+    config: &AppConfig,
+    model: &str,
+    story: Story,
+) -> Command {
+    Command::SaveCache {
+        path: config.cache_path(model),
+        entry: Box::new(CacheEntry {
+            owner: pr.owner.clone(),
+            repo: pr.repo.clone(),
+            number: pr.number,
+            model: model.to_string(),
+            head_sha: pr.head_sha.clone(),
+            diff_hash: cache::diff_hash(&pr.diff),
+            language,
+            created_at: 0,
+            story,
+        }),
+    }
+}
+
+/// The open PR, if its story can be regenerated; otherwise says why and returns to the story
+pub fn regenerable_pr(app: &mut App) -> Option<PrContext> {
+    // A PR restored from the story cache has no diff to regenerate from
+    let pr = app.pr.clone().filter(|pr| !pr.diff.is_empty());
+    if pr.is_none() {
+        app.state = AppState::Viewing;
+        app.status = Some("Reload the PR without --cache to regenerate its story".to_string());
+    }
+    pr
+}
+
+/// Throw away the current stories and generate them again for `pr`
+pub fn regenerate_stories(app: &mut App, pr: &PrContext, config: &AppConfig) -> Vec<Command> {
+    app.reset_story();
+    app.progress.clear();
+    app.progress_started = None;
+    generate_stories(app, pr, config)
+}
+
+/// Generate the story for `pr` with every configured model under the current
+/// persona. Compared models run one after another; the first story is shown
+/// as soon as it lands. Holds off while the diff adds likely secrets the
+/// reviewer hasn't chosen to redact or send
+pub fn generate_stories(app: &mut App, pr: &PrContext, config: &AppConfig) -> Vec<Command> {
+    app.secret_findings = secrets::scan(&pr.diff);
+    let choice = if config.allow_secrets {
+        Some(SecretsChoice::Send)
+    } else {
+        app.secrets_choice
+    };
+    let pr = match choice {
+        _ if app.secret_findings.is_empty() => pr.clone(),
+        None => {
+            app.state = AppState::SecretsFound;
+            return Vec::new();
+        }
+        Some(SecretsChoice::Redact) => secrets::redact(pr, &app.secret_findings),
+        Some(SecretsChoice::Send) => pr.clone(),
+    };
+    if config.preview_prompt && !std::mem::take(&mut app.prompt_approved) {
+        let (_, (system, user)) =
+            config.outbound_prompts(&pr, app.persona, app.language.as_deref());
+        app.prompt_preview = format!("SYSTEM\n\n{}\n\nUSER\n\n{}", system, user);
+        if summarize::needs_map(&pr, config.map_threshold) {
+            let files = summarize::file_requests(&pr, |path| !config.redaction.withholds(path));
+            app.prompt_preview = format!(
+                "This diff is too large to send whole: each of its {} files goes to {} \
+                 separately first, and the story is written from their summaries in place \
+                 of the diff below.\n\n{}",
+                files.len(),
+                config.map_model,
+                app.prompt_preview
+            );
+        }
+        app.prompt_preview_scroll = 0;
+        app.state = AppState::PreviewingPrompt;
+        return Vec::new();
+    }
+
+    let models = config.story_models();
+    app.state = AppState::GeneratingStory;
+    app.generation_model = models.first().cloned();
+    app.pending_models = models.clone();
+    models
+        .into_iter()
+        .map(|model| Command::GenerateStory {
+            pr: Box::new(pr.clone()),
+            model,
+            persona: app.persona,
+            language: app.language.clone(),
+            params: app.model_params,
+        })
+        .collect()
+}
+
 pub fn ensure_cached_pr_context(app: &mut App) {
     if app.pr.is_some() {
         return;
@@ -41,5 +180,138 @@ pub fn ensure_cached_pr_context(app: &mut App) {
         author: String::new(),
         base_branch: String::new(),
         head_branch: String::new(),
+        head_sha: String::new(),
+        base_sha: String::new(),
+        bot_comments: Vec::new(),
+        linked_issues: Vec::new(),
+        head_files: HashMap::new(),
+        surrounding_code: Vec::new(),
+        base_blame: HashMap::new(),
+        callers: Vec::new(),
+        file_summaries: Vec::new(),
+        repo_prompt: None,
+        review_memory: None,
+        style_guide: None,
+        scope: Vec::new(),
+        out_of_scope: Vec::new(),
+        code_owners: Vec::new(),
+        stack: Vec::new(),
+        parent: None,
+        commit_range: None,
+        review_delta: None,
+        excluded_files: Vec::new(),
     });
 }
+
+/// Fetch which files are marked viewed on GitHub, to sync with the story's marks
+pub fn fetch_viewed_files(app: &App) -> Vec<Command> {
+    current_pr_ref(app)
+        .map(|(owner, repo, number)| Command::FetchViewedFiles { owner, repo, number })
+        .into_iter()
+        .collect()
+}
+
+/// The commands that post `action` with `text` to the PR
+pub fn submit_action(
+    app: &mut App,
+    action: ReviewAction,
+    owner: String,
+    repo: String,
+    number: u32,
+    text: String,
+) -> Vec<Command> {
+    let mut report = None;
+    let text = match (action, app.report_mode) {
+        (ReviewAction::RequestChanges, ReportMode::Append) => match app.review_report(now_secs()) {
+            Some(report) => format!("{}\n\n{}", text.trim_end(), report.to_markdown()),
+            None => text,
+        },
+        (ReviewAction::RequestChanges, ReportMode::Save) => {
+            report = app.review_report(now_secs());
+            text
+        }
+        _ => text,
+    };
+    let mut commands = match action {
+        ReviewAction::RequestChanges => vec![Command::PostReview {
+            owner,
+            repo,
+            number,
+            event: app.review_event,
+            body: text,
+            commit_id: app.pr.as_ref().map(|pr| pr.head_sha.clone()).unwrap_or_default(),
+            comments: app.pending_comments.clone(),
+        }],
+        ReviewAction::ClarificationQuestions if app.thread_questions => {
+            threaded_questions(app, owner, repo, number, &text)
+        }
+        ReviewAction::ClarificationQuestions => vec![Command::PostComment {
+            owner,
+            repo,
+            number,
+            body: text,
+        }],
+        ReviewAction::NextPr => {
+            let mut iter = text.lines();
+            let title = iter
+                .next()
+                .unwrap_or("Follow-up work")
+                .to_string();
+            let body = iter.collect::<Vec<&str>>().join("\n");
+            vec![Command::CreateNextPrIssue {
+                owner,
+                repo,
+                number,
+                title,
+                body,
+            }]
+        }
+    };
+    if app.dry_run {
+        return commands.into_iter().map(|command| Command::DryRun(Box::new(command))).collect();
+    }
+    // Remembered until the result arrives, so a failure can go to the outbox
+    app.in_flight.extend(commands.iter().filter_map(Command::submission));
+    commands.extend(report.map(|report| Command::SaveReviewReport { report }));
+    commands
+}
+
+/// Split clarification questions into inline threads on the blocks they concern,
+/// posting any that can't be anchored as a single regular comment
+pub fn threaded_questions(
+    app: &App,
+    owner: String,
+    repo: String,
+    number: u32,
+    text: &str,
+) -> Vec<Command> {
+    let questions = split_list_items(text);
+    let (Some(story), Some(pr)) = (&app.story, &app.pr) else {
+        return vec![Command::PostComment {
+            owner,
+            repo,
+            number,
+            body: text.to_string(),
+        }];
+    };
+
+    let files = parse_diff(&pr.diff);
+    let (comments, unanchored) = anchor_questions(questions, story, &files);
+    let fallback_body = (!unanchored.is_empty()).then(|| {
+        unanchored
+            .iter()
+            .enumerate()
+            .map(|(i, q)| format!("{}. {}", i + 1, q))
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+
+    vec![Command::PostInlineQuestions {
+        owner,
+        repo,
+        number,
+        commit_id: pr.head_sha.clone(),
+        comments,
+        fallback_body,
+    }]
+}
@@ -1,10 +1,13 @@
 use crossterm::event::KeyCode;
 
-use crate::app::App;
+use crate::app::{App, AppState};
 use crate::command::Command;
 
 pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
     match code {
+        KeyCode::Esc if matches!(app.state, AppState::LoadingPr | AppState::GeneratingStory) => {
+            cancel_pr_load(app)
+        }
         KeyCode::Char('q') | KeyCode::Esc => {
             app.should_quit = true;
             Vec::new()
@@ -12,3 +15,40 @@ pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
         _ => Vec::new(),
     }
 }
+
+/// Abort the in-flight PR fetch or story generation and go back to the picker
+pub fn cancel_pr_load(app: &mut App) -> Vec<Command> {
+    app.cancel_in_flight = true;
+    app.generation_model = None;
+    app.progress.clear();
+    app.progress_started = None;
+    app.reset_for_new_pr();
+
+    // A PR being opened in a new tab gives way to the one it was opened from
+    if app.close_tab() {
+        return Vec::new();
+    }
+
+    if !app.pr_list.is_empty() {
+        app.state = AppState::PrPicker;
+        return Vec::new();
+    }
+
+    // Opened directly (or from the queue), so there's no PR list to return to yet
+    match app.current_repo.clone() {
+        Some((owner, repo)) => {
+            app.state = AppState::LoadingPrList;
+            vec![Command::FetchPrList {
+                owner,
+                repo,
+                states: app.pr_filter,
+            }]
+        }
+        None => {
+            app.state = AppState::LoadingRepoList;
+            vec![Command::FetchRepoList {
+                org: app.repo_org.clone(),
+            }]
+        }
+    }
+}
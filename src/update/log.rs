@@ -0,0 +1,36 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+
+/// Whether `~` (or `?` for help) opens an overlay here instead of being typed
+/// into a text field
+pub fn can_open(state: &AppState) -> bool {
+    !matches!(
+        state,
+        AppState::Searching
+            | AppState::WorkspaceSearch
+            | AppState::EditingScope
+            | AppState::EditingAction(_)
+    )
+}
+
+pub fn open(app: &mut App) {
+    app.show_log = true;
+    app.log_scroll = 0;
+}
+
+/// Input while the log overlay is open, over whatever screen is underneath
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('~') => app.show_log = false,
+        KeyCode::Char('k') | KeyCode::Up => {
+            let oldest = app.recent_log.entries().len().saturating_sub(1);
+            app.log_scroll = (app.log_scroll + 1).min(oldest);
+        }
+        KeyCode::Char('j') | KeyCode::Down => app.log_scroll = app.log_scroll.saturating_sub(1),
+        KeyCode::Char('G') | KeyCode::End => app.log_scroll = 0,
+        _ => {}
+    }
+    Vec::new()
+}
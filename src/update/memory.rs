@@ -0,0 +1,102 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+use crate::domain::text;
+use crate::domain::types::ReviewAction;
+
+pub fn open(app: &mut App) {
+    if app.memory_repo().is_none() {
+        return;
+    }
+    app.show_memory = true;
+    app.memory_selected = 0;
+    app.memory_cursor = None;
+}
+
+/// Input on the memory screen; while the themes are being edited, keys type into them
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    let Some(repo) = app.memory_repo() else {
+        app.show_memory = false;
+        return Vec::new();
+    };
+    if let Some(cursor) = app.memory_cursor {
+        return edit_themes(app, &repo, cursor, code);
+    }
+
+    let suggestions = app.memory.repo(&repo).map_or(0, |memory| memory.suggestions.len());
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('H') => app.show_memory = false,
+        KeyCode::Char('j') | KeyCode::Down if app.memory_selected < suggestions => {
+            app.memory_selected += 1;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.memory_selected = app.memory_selected.saturating_sub(1);
+        }
+        KeyCode::Enter | KeyCode::Char('e') if app.memory_selected == 0 => {
+            let themes = &app.memory.repo_mut(&repo).themes;
+            app.memory_cursor = Some(themes.len());
+        }
+        KeyCode::Char('d') if app.memory_selected > 0 => {
+            let memory = app.memory.repo_mut(&repo);
+            // Rows list the newest suggestion first
+            memory.suggestions.remove(suggestions - app.memory_selected);
+            app.memory_selected = app.memory_selected.min(memory.suggestions.len());
+            return save(app, &repo);
+        }
+        _ => {}
+    }
+    Vec::new()
+}
+
+fn edit_themes(app: &mut App, repo: &str, cursor: usize, code: KeyCode) -> Vec<Command> {
+    if code == KeyCode::Esc {
+        app.memory_cursor = None;
+        return save(app, repo);
+    }
+    let themes = &mut app.memory.repo_mut(repo).themes;
+    let cursor = cursor.min(themes.len());
+    let cursor = match code {
+        KeyCode::Char(c) => {
+            themes.insert(cursor, c);
+            cursor + c.len_utf8()
+        }
+        KeyCode::Enter => {
+            themes.insert(cursor, '\n');
+            cursor + 1
+        }
+        KeyCode::Backspace => {
+            let start = text::prev_boundary(themes, cursor);
+            themes.replace_range(start..cursor, "");
+            start
+        }
+        KeyCode::Left => text::prev_boundary(themes, cursor),
+        KeyCode::Right => text::next_boundary(themes, cursor),
+        _ => cursor,
+    };
+    app.memory_cursor = Some(cursor);
+    Vec::new()
+}
+
+/// Remember what a successfully posted Request Changes review asked for
+pub fn record_submission(app: &mut App, action: ReviewAction) -> Vec<Command> {
+    if action != ReviewAction::RequestChanges {
+        return Vec::new();
+    }
+    let Some(repo) = app.memory_repo() else {
+        return Vec::new();
+    };
+    app.memory.record(&repo, &app.action_texts.request_changes);
+    save(app, &repo)
+}
+
+/// Persist the memory, and use it for the next story generated for this PR
+fn save(app: &mut App, repo: &str) -> Vec<Command> {
+    let prompt = app.memory.prompt(repo);
+    if let Some(pr) = &mut app.pr {
+        pr.review_memory = prompt;
+    }
+    vec![Command::SaveMemory {
+        memory: app.memory.clone(),
+    }]
+}
@@ -1,38 +1,210 @@
 mod actions;
+mod activity;
+mod batch;
+mod clipboard;
+mod commits;
+mod compare;
+mod confirm;
+mod coverage;
+mod deep_dive;
 mod editing;
 mod error;
+mod file_view;
+mod go_to;
+mod help;
 mod helpers;
 mod loading;
+mod log;
+mod memory;
+mod notes;
+mod model_settings;
+mod outbox;
+mod owners;
+mod paging;
+mod pending_review;
+mod persona;
 mod picker;
+mod prompt_preview;
+mod queue;
 mod repo;
+mod reviewed;
+mod risks;
+mod scope;
+mod search;
+mod secrets;
+mod stack;
+mod stale;
+mod tabs;
+mod templates;
+#[cfg(test)]
+mod tests;
 mod viewing;
+mod workspace;
+
+use std::time::Instant;
+
+use crossterm::event::{KeyCode, KeyModifiers};
 
 use crate::action::Action;
 use crate::app::{App, AppState};
 use crate::command::Command;
 use crate::config::AppConfig;
+use crate::domain::crash;
 
 pub fn update(app: &mut App, action: Action, config: &AppConfig) -> Vec<Command> {
+    crash::record(action.summary());
+    let commands = dispatch(app, action, config);
+    app.tick_clock(Instant::now());
+    commands
+}
+
+fn dispatch(app: &mut App, action: Action, config: &AppConfig) -> Vec<Command> {
+    // Another tab may be showing by the time a PR's result arrives
+    if let Some(key) = action.pr_key()
+        && helpers::current_pr_key(app).as_ref() != Some(&key)
+    {
+        return tabs::handle_background_result(app, &key, action, config);
+    }
     match action {
+        Action::Input {
+            code: KeyCode::Char('z'),
+            modifiers,
+        } if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.suspend_requested = true;
+            Vec::new()
+        }
+        Action::Input { code, .. } if app.show_help => help::handle_input(app, code),
+        Action::Input { code, .. } if app.show_log => log::handle_input(app, code),
+        Action::Input { code, .. } if app.show_outbox => outbox::handle_input(app, code),
+        Action::Input { code, .. } if app.show_activity => activity::handle_input(app, code),
+        Action::Input { code, .. } if app.show_risks => risks::handle_input(app, code),
+        Action::Input { code, .. } if app.show_memory => memory::handle_input(app, code),
+        Action::Input { code, .. } if app.show_owners => owners::handle_input(app, code),
+        Action::Input { code, .. } if app.show_templates => templates::handle_input(app, code),
+        Action::Input { code, .. } if app.note_editing.is_some() => {
+            notes::handle_edit_input(app, code)
+        }
+        Action::Input { code, .. } if app.show_notes => notes::handle_input(app, code),
+        Action::Input { code, .. } if app.comment_editing.is_some() => {
+            pending_review::handle_edit_input(app, code)
+        }
+        Action::Input {
+            code: KeyCode::Char('?'),
+            ..
+        } if log::can_open(&app.state) => {
+            help::open(app);
+            Vec::new()
+        }
+        Action::Input {
+            code: KeyCode::Char('~'),
+            ..
+        } if log::can_open(&app.state) => {
+            log::open(app);
+            Vec::new()
+        }
+        Action::Input {
+            code: KeyCode::Char('R'),
+            ..
+        } if outbox::can_open(app) => {
+            outbox::open(app);
+            Vec::new()
+        }
         Action::Input { code, modifiers } => match &app.state {
             AppState::RepoSelector => repo::handle_input(app, code),
             AppState::PrPicker => picker::handle_input(app, code),
-            AppState::Viewing => viewing::handle_input(app, code, modifiers),
-            AppState::EditingAction(_) => editing::handle_input(app, code, modifiers),
+            AppState::ChoosingPrSort => picker::handle_sort_input(app, code),
+            AppState::ReviewQueue => queue::handle_input(app, code),
+            AppState::Viewing => viewing::handle_input(app, code, modifiers, config),
+            AppState::Searching => search::handle_input(app, code),
+            AppState::WorkspaceSearch => workspace::handle_query_input(app, code),
+            AppState::WorkspaceResults => workspace::handle_results_input(app, code),
+            AppState::FileView => file_view::handle_input(app, code, modifiers),
+            AppState::BlameView => file_view::handle_blame_input(app, code),
+            AppState::ComparingStories => compare::handle_input(app, code),
+            AppState::ChoosingPersona => persona::handle_input(app, code, config),
+            AppState::ModelSettings => model_settings::handle_input(app, code, config),
+            AppState::EditingScope => scope::handle_input(app, code, config),
+            AppState::SelectingCommits => commits::handle_input(app, code),
+            AppState::BatchSubmit => batch::handle_input(app, code),
+            AppState::ConfirmingSubmit => confirm::handle_input(app, code),
+            AppState::StaleCache => stale::handle_input(app, code, config),
+            AppState::SecretsFound => secrets::handle_input(app, code, config),
+            AppState::PreviewingPrompt => prompt_preview::handle_input(app, code, config),
+            AppState::EditingAction(_) => editing::handle_input(app, code, modifiers, config),
             AppState::Error(_) => error::handle_input(app, code),
             AppState::LoadingRepoList
             | AppState::LoadingPrList
+            | AppState::LoadingReviewQueue
+            | AppState::LoadingWorkspaceFiles
             | AppState::LoadingPr
             | AppState::GeneratingStory
             | AppState::Submitting(_) => loading::handle_input(app, code),
         },
+        Action::StartupLoaded { repos, orgs } => actions::handle_startup_loaded(app, repos, orgs),
         Action::RepoListLoaded(result) => actions::handle_repo_list_loaded(app, result),
+        Action::MoreReposLoaded { org, result } => {
+            paging::handle_more_repos_loaded(app, org, result)
+        }
+        Action::OrgListLoaded(orgs) => actions::handle_org_list_loaded(app, orgs),
         Action::PrListLoaded(result) => actions::handle_pr_list_loaded(app, result),
-        Action::PrLoaded(result) => actions::handle_pr_loaded(app, result),
-        Action::StoryGenerated(result) => actions::handle_story_generated(app, result, config),
-        Action::CacheLoaded(story) => actions::handle_cache_loaded(app, story),
-        Action::SubmissionResult { action, result } => {
-            actions::handle_submission_result(app, action, result)
+        Action::MorePrsLoaded {
+            owner,
+            repo,
+            states,
+            result,
+        } => paging::handle_more_prs_loaded(app, owner, repo, states, result),
+        Action::ReviewQueueLoaded(result) => actions::handle_review_queue_loaded(app, result),
+        Action::Progress(event) => actions::handle_progress(app, event),
+        Action::PrLoaded(result) => actions::handle_pr_loaded(app, result, config),
+        Action::StoryGenerated { model, result, .. } => {
+            actions::handle_story_generated(app, model, result, config)
+        }
+        Action::DeepDiveGenerated { feature, result, .. } => {
+            deep_dive::handle_generated(app, feature, result);
+            Vec::new()
+        }
+        Action::StoryGenerationTimedOut { pr, model } => {
+            actions::handle_story_generation_timed_out(app, pr, model, config)
+        }
+        Action::HeadFilesLoaded { result, .. } => actions::handle_head_files_loaded(app, result),
+        Action::BlameLoaded { path, result, .. } => actions::handle_blame_loaded(app, path, result),
+        Action::CacheLoaded { stories, .. } => actions::handle_cache_loaded(app, stories),
+        Action::SubmissionResult { action, result, .. } => {
+            actions::handle_submission_result(app, action, result, config)
+        }
+        Action::PollDue => activity::handle_poll_due(app),
+        Action::FocusChanged(focused) => {
+            app.terminal_focused = Some(focused);
+            Vec::new()
+        }
+        Action::Resized => {
+            // Rewrapping changed the document's height, so the old offset may be past the end
+            app.scroll_offset = app.scroll_offset.min(app.max_scroll());
+            Vec::new()
+        }
+        Action::ActivityLoaded { result, .. } => activity::handle_loaded(app, result),
+        Action::ViewedFilesLoaded { result, .. } => {
+            actions::handle_viewed_files_loaded(app, result)
+        }
+        Action::FileViewedSynced { path, result, .. } => {
+            actions::handle_file_viewed_synced(app, path, result)
+        }
+        Action::DryRunWritten { action, result } => {
+            actions::handle_dry_run_written(app, action, result)
+        }
+        Action::ReviewersRequested { result, .. } => owners::handle_requested(app, result),
+        Action::RateLimitLoaded(result) => actions::handle_rate_limit_loaded(app, result),
+        Action::Copied { what, result } => clipboard::handle_copied(app, what, result),
+        Action::SessionExported(result) => clipboard::handle_session_exported(app, result),
+        Action::BrowserOpened(result) => viewing::handle_browser_opened(app, result),
+        Action::CommitsLoaded { result, .. } => commits::handle_loaded(app, result),
+        Action::RangeDiffLoaded { range, result, .. } => {
+            commits::handle_range_diff(app, range, result, config)
         }
+        Action::ReviewDeltaLoaded {
+            reviewed_head,
+            result,
+            ..
+        } => reviewed::handle_delta_loaded(app, reviewed_head, result, config),
     }
 }
@@ -0,0 +1,114 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::prompt::{language_name, LANGUAGES};
+use crate::domain::types::{ModelParams, ReasoningEffort};
+
+use super::helpers;
+
+/// Rows in the overlay: reasoning effort, max output tokens, temperature, language
+pub const ROWS: usize = 4;
+
+/// Open the model settings with the current parameters
+pub fn open(app: &mut App) {
+    app.settings_draft = app.model_params;
+    app.settings_language = app.language.clone();
+    app.settings_selected = 0;
+    app.state = AppState::ModelSettings;
+}
+
+pub fn handle_input(app: &mut App, code: KeyCode, config: &AppConfig) -> Vec<Command> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.state = AppState::Viewing;
+            Vec::new()
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.settings_selected = (app.settings_selected + 1).min(ROWS - 1);
+            Vec::new()
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.settings_selected = app.settings_selected.saturating_sub(1);
+            Vec::new()
+        }
+        KeyCode::Char('l') | KeyCode::Right | KeyCode::Char(' ') => {
+            adjust(app, true);
+            Vec::new()
+        }
+        KeyCode::Char('h') | KeyCode::Left => {
+            adjust(app, false);
+            Vec::new()
+        }
+        KeyCode::Enter => apply(app, config),
+        _ => Vec::new(),
+    }
+}
+
+/// Step the selected parameter through its values, with the model's default
+/// before the first
+fn adjust(app: &mut App, forward: bool) {
+    let draft = &mut app.settings_draft;
+    match app.settings_selected {
+        0 => draft.reasoning_effort = step(draft.reasoning_effort, &ReasoningEffort::ALL, forward),
+        1 => {
+            let steps = ModelParams::MAX_OUTPUT_TOKENS_STEPS;
+            draft.max_output_tokens = step(draft.max_output_tokens, &steps, forward);
+        }
+        2 => draft.temperature = step(draft.temperature, &ModelParams::TEMPERATURE_STEPS, forward),
+        _ => app.settings_language = step_language(app.settings_language.as_deref(), forward),
+    }
+}
+
+/// The language after (or before) `current` in [`LANGUAGES`], the model's
+/// default past either end. One given with `--language` that isn't listed
+/// steps to the list's ends
+fn step_language(current: Option<&str>, forward: bool) -> Option<String> {
+    let codes: Vec<&str> = LANGUAGES.iter().map(|(code, _)| *code).collect();
+    let position = current.map(|current| codes.iter().position(|&code| code == current));
+    let next = match (position, forward) {
+        (None | Some(None), true) => codes.first(),
+        (None | Some(None), false) => codes.last(),
+        (Some(Some(i)), true) => codes.get(i + 1),
+        (Some(Some(i)), false) => i.checked_sub(1).and_then(|i| codes.get(i)),
+    };
+    next.map(|code| code.to_string())
+}
+
+/// The value after (or before) `current` in `steps`, None past either end.
+/// A value set on the command line between steps moves to its neighbour
+fn step<T: PartialOrd + Copy>(current: Option<T>, steps: &[T], forward: bool) -> Option<T> {
+    match (current, forward) {
+        (None, true) => steps.first().copied(),
+        (None, false) => steps.last().copied(),
+        (Some(current), true) => steps.iter().copied().find(|&value| value > current),
+        (Some(current), false) => steps.iter().copied().rev().find(|&value| value < current),
+    }
+}
+
+/// Keep the edited parameters and regenerate the story with them if they changed
+fn apply(app: &mut App, config: &AppConfig) -> Vec<Command> {
+    app.state = AppState::Viewing;
+    if app.settings_draft == app.model_params && app.settings_language == app.language {
+        return Vec::new();
+    }
+    app.model_params = app.settings_draft;
+    app.language = app.settings_language.clone();
+    let Some(pr) = helpers::regenerable_pr(app) else {
+        return Vec::new();
+    };
+    let commands = helpers::regenerate_stories(app, &pr, config);
+    let params = app.model_params.describe();
+    let language = app
+        .language
+        .as_deref()
+        .map(|language| format!("in {}", language_name(language)));
+    app.status = Some(match (params, language) {
+        (Some(params), Some(language)) => format!("Regenerating with {} {}", params, language),
+        (Some(params), None) => format!("Regenerating with {}", params),
+        (None, Some(language)) => format!("Regenerating {}", language),
+        (None, None) => "Regenerating with the model's defaults".to_string(),
+    });
+    commands
+}
@@ -0,0 +1,103 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+use crate::domain::review::append_list_item;
+use crate::domain::text;
+
+/// Start writing (or rewriting) the selected block's note
+pub fn edit(app: &mut App) {
+    if app.story.is_none() {
+        return;
+    }
+    let key = (app.selected_feature, app.selected_diff);
+    app.note_draft = app.block_notes.get(&key).cloned().unwrap_or_default();
+    app.note_editing = Some(key);
+}
+
+/// Input while a note is being written; an empty note removes it
+pub fn handle_edit_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc => app.note_editing = None,
+        KeyCode::Enter => {
+            let Some(key) = app.note_editing.take() else {
+                return Vec::new();
+            };
+            let note = std::mem::take(&mut app.note_draft).trim().to_string();
+            if note.is_empty() {
+                if app.block_notes.remove(&key).is_some() {
+                    app.status = Some("Note removed".to_string());
+                }
+            } else {
+                app.block_notes.insert(key, note);
+                app.status = Some("Note saved; Ctrl+N lists your notes".to_string());
+            }
+        }
+        KeyCode::Backspace => {
+            text::pop_grapheme(&mut app.note_draft);
+        }
+        KeyCode::Char(c) => app.note_draft.push(c),
+        _ => {}
+    }
+    Vec::new()
+}
+
+pub fn open(app: &mut App) {
+    if app.block_notes.is_empty() {
+        app.status = Some("No notes yet: m adds one to the selected block".to_string());
+        return;
+    }
+    app.note_selected = 0;
+    app.show_notes = true;
+}
+
+/// Input while the notes panel is open
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    let count = app.block_notes.len();
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.show_notes = false,
+        KeyCode::Char('j') | KeyCode::Down if app.note_selected + 1 < count => {
+            app.note_selected += 1;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.note_selected = app.note_selected.saturating_sub(1);
+        }
+        KeyCode::Enter if app.jump_to_selected_note() => app.show_notes = false,
+        KeyCode::Char('d') => {
+            if let Some(&(key, _, _)) = app.notes_in_diff_order().get(app.note_selected) {
+                app.block_notes.remove(&key);
+                app.note_selected = app.note_selected.min(count.saturating_sub(2));
+                app.show_notes = !app.block_notes.is_empty();
+            }
+        }
+        KeyCode::Char('c') => compile(app),
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// Add every note to the Request Changes draft in diff order, each citing its
+/// block's file and line
+fn compile(app: &mut App) {
+    let items: Vec<String> = app
+        .notes_in_diff_order()
+        .into_iter()
+        .map(|(_, location, note)| match location {
+            Some((path, line)) => format!("`{}:{}`: {}", path, line, note),
+            None => note.to_string(),
+        })
+        .collect();
+    let draft = &mut app.action_texts.request_changes;
+    let mut added = 0;
+    for item in items {
+        if !draft.contains(&item) {
+            append_list_item(draft, &item);
+            added += 1;
+        }
+    }
+    app.status = Some(match added {
+        0 => "Your notes are already in the Request Changes draft".to_string(),
+        1 => "Added 1 note to Request Changes".to_string(),
+        n => format!("Added {} notes to Request Changes", n),
+    });
+}
@@ -0,0 +1,129 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+use crate::domain::listings::now_secs;
+use crate::domain::outbox::OutboxItem;
+use crate::domain::types::ReviewAction;
+
+use super::log;
+
+/// Whether `R` opens the outbox here: there's something in it and no text field has focus
+pub fn can_open(app: &App) -> bool {
+    !app.outbox.items.is_empty() && log::can_open(&app.state)
+}
+
+pub fn open(app: &mut App) {
+    app.show_outbox = true;
+    app.outbox_selected = 0;
+}
+
+/// Input while the outbox overlay is open, over whatever screen is underneath
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        // Retries still land on their rows if the overlay is closed early
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('R') => app.show_outbox = false,
+        KeyCode::Char('j') | KeyCode::Down => {
+            let last = app.outbox.items.len().saturating_sub(1);
+            app.outbox_selected = (app.outbox_selected + 1).min(last);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.outbox_selected = app.outbox_selected.saturating_sub(1);
+        }
+        KeyCode::Enter => return retry(app, &[app.outbox_selected]),
+        KeyCode::Char('a') => {
+            let all: Vec<usize> = (0..app.outbox.items.len()).collect();
+            return retry(app, &all);
+        }
+        KeyCode::Char('d') => return discard(app),
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// Post these outbox items again, skipping any already retrying
+fn retry(app: &mut App, indices: &[usize]) -> Vec<Command> {
+    // A dry run promises nothing reaches GitHub
+    if app.dry_run {
+        app.status = Some("Dry run: nothing to retry".to_string());
+        return Vec::new();
+    }
+    let mut commands = Vec::new();
+    for &i in indices {
+        if let Some(item) = app.outbox.items.get_mut(i).filter(|item| !item.retrying) {
+            item.retrying = true;
+            commands.push(Command::post(item.submission.clone()));
+        }
+    }
+    commands
+}
+
+fn discard(app: &mut App) -> Vec<Command> {
+    let selected = app.outbox_selected;
+    if app.outbox.items.get(selected).is_none_or(|item| item.retrying) {
+        return Vec::new();
+    }
+    app.outbox.items.remove(selected);
+    app.outbox_selected = selected.min(app.outbox.items.len().saturating_sub(1));
+    if app.outbox.items.is_empty() {
+        app.show_outbox = false;
+    }
+    vec![Command::SaveOutbox {
+        outbox: app.outbox.clone(),
+    }]
+}
+
+/// Record a submission's result against the outbox: a retry is removed once it
+/// posts, and a fresh failure is kept. Returns the save command and whether the
+/// result belonged to a retry (which leaves the current screen alone).
+pub fn record_result(
+    app: &mut App,
+    action: ReviewAction,
+    result: &Result<(), String>,
+) -> (Vec<Command>, bool) {
+    if let Some(i) = app
+        .outbox
+        .items
+        .iter()
+        .position(|item| item.retrying && item.submission.action() == action)
+    {
+        match result {
+            Ok(()) => {
+                app.outbox.items.remove(i);
+                app.outbox_selected =
+                    app.outbox_selected.min(app.outbox.items.len().saturating_sub(1));
+            }
+            Err(err) => {
+                let item = &mut app.outbox.items[i];
+                item.retrying = false;
+                item.error = err.clone();
+                item.failed_at = now_secs();
+            }
+        }
+        return (save(app), true);
+    }
+
+    // Results arrive in the order their commands ran
+    let Some(i) = app.in_flight.iter().position(|submission| submission.action() == action) else {
+        return (Vec::new(), false);
+    };
+    let submission = app.in_flight.remove(i);
+    match result {
+        Ok(()) => (Vec::new(), false),
+        Err(err) => {
+            app.outbox.items.push(OutboxItem {
+                submission,
+                error: err.clone(),
+                failed_at: now_secs(),
+                retrying: false,
+            });
+            (save(app), false)
+        }
+    }
+}
+
+fn save(app: &App) -> Vec<Command> {
+    vec![Command::SaveOutbox {
+        outbox: app.outbox.clone(),
+    }]
+}
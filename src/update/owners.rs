@@ -0,0 +1,79 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+use crate::domain::codeowners::reviewer_name;
+
+use super::helpers;
+
+/// Open the reviewer suggestions, every owner checked
+pub fn open(app: &mut App) {
+    let suggestions = app.suggested_reviewers();
+    if suggestions.is_empty() {
+        app.status = Some("No CODEOWNERS entries cover this PR's files".to_string());
+        return;
+    }
+    app.reviewer_choices = suggestions.into_iter().map(|(owner, _)| (owner, true)).collect();
+    app.reviewer_selected = 0;
+    app.show_owners = true;
+}
+
+/// Input while the reviewer suggestions are open
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    let count = app.reviewer_choices.len();
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('W') => app.show_owners = false,
+        KeyCode::Char('j') | KeyCode::Down if app.reviewer_selected + 1 < count => {
+            app.reviewer_selected += 1;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.reviewer_selected = app.reviewer_selected.saturating_sub(1);
+        }
+        KeyCode::Char(' ') => {
+            if let Some((_, checked)) = app.reviewer_choices.get_mut(app.reviewer_selected) {
+                *checked = !*checked;
+            }
+        }
+        KeyCode::Enter => return request(app),
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// Ask the checked owners to review
+fn request(app: &mut App) -> Vec<Command> {
+    let reviewers: Vec<String> = app
+        .reviewer_choices
+        .iter()
+        .filter(|(_, checked)| *checked)
+        .map(|(owner, _)| reviewer_name(owner).to_string())
+        .collect();
+    if reviewers.is_empty() {
+        app.status = Some("Check at least one owner with Space".to_string());
+        return Vec::new();
+    }
+    let Some((owner, repo, number)) = helpers::current_pr_ref(app) else {
+        return Vec::new();
+    };
+    app.show_owners = false;
+    // A dry run promises nothing reaches GitHub
+    if app.dry_run {
+        app.status = Some(format!("Dry run: would request reviews from {}", reviewers.join(", ")));
+        return Vec::new();
+    }
+    app.status = Some(format!("Requesting reviews from {}...", reviewers.join(", ")));
+    vec![Command::RequestReviewers {
+        owner,
+        repo,
+        number,
+        reviewers,
+    }]
+}
+
+pub fn handle_requested(app: &mut App, result: Result<Vec<String>, String>) -> Vec<Command> {
+    app.status = Some(match result {
+        Ok(reviewers) => format!("Requested reviews from {}", reviewers.join(", ")),
+        Err(err) => format!("Couldn't request reviewers: {}", err),
+    });
+    Vec::new()
+}
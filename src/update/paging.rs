@@ -0,0 +1,97 @@
+use crate::app::App;
+use crate::command::Command;
+use crate::domain::types::{Page, PrListItem, PrStateFilter, RepoListItem};
+
+/// Rows from the end of what's loaded at which the next page is fetched
+const PREFETCH_ROWS: usize = 5;
+
+fn near_end(selected: usize, loaded: usize) -> bool {
+    selected + PREFETCH_ROWS >= loaded
+}
+
+/// Fetch the next page of repos once the selection nears the end of those loaded
+pub fn more_repos(app: &mut App) -> Vec<Command> {
+    let paging = &mut app.repo_paging;
+    let Some(cursor) = paging.next_cursor.clone() else {
+        return Vec::new();
+    };
+    if paging.loading || !near_end(app.repo_selected, app.repo_list.len()) {
+        return Vec::new();
+    }
+    paging.loading = true;
+    vec![Command::FetchMoreRepos {
+        org: app.repo_org.clone(),
+        cursor,
+    }]
+}
+
+/// Fetch the next page of PRs once the selection nears the end of those loaded
+pub fn more_prs(app: &mut App) -> Vec<Command> {
+    let Some((owner, repo)) = app.current_repo.clone() else {
+        return Vec::new();
+    };
+    let paging = &mut app.pr_paging;
+    let Some(cursor) = paging.next_cursor.clone() else {
+        return Vec::new();
+    };
+    if paging.loading || !near_end(app.picker_selected, app.pr_list.len()) {
+        return Vec::new();
+    }
+    paging.loading = true;
+    vec![Command::FetchMorePrs {
+        owner,
+        repo,
+        states: app.pr_filter,
+        cursor,
+    }]
+}
+
+pub fn handle_more_repos_loaded(
+    app: &mut App,
+    org: Option<String>,
+    result: Result<Page<RepoListItem>, String>,
+) -> Vec<Command> {
+    // The scope changed while the page was loading
+    if org != app.repo_org {
+        return Vec::new();
+    }
+    app.repo_paging.loading = false;
+    match result {
+        Ok(page) => {
+            app.repo_paging.total = Some(page.total);
+            app.repo_paging.next_cursor = page.next_cursor;
+            app.repo_list.extend(page.items);
+            app.sort_repo_list();
+        }
+        Err(err) => app.status = Some(format!("Couldn't load more repos: {}", err)),
+    }
+    Vec::new()
+}
+
+pub fn handle_more_prs_loaded(
+    app: &mut App,
+    owner: String,
+    repo: String,
+    states: PrStateFilter,
+    result: Result<Page<PrListItem>, String>,
+) -> Vec<Command> {
+    // Another repo's list, or another filter, was opened while the page was loading
+    if app.current_repo.as_ref() != Some(&(owner, repo))
+        || states != app.pr_filter
+        || app.pr_list.is_empty()
+    {
+        return Vec::new();
+    }
+    app.pr_paging.loading = false;
+    match result {
+        Ok(page) => {
+            app.pr_paging.total = Some(page.total);
+            app.pr_paging.next_cursor = page.next_cursor;
+            app.pr_list.extend(page.items);
+            app.drop_hidden_prs();
+            app.sort_pr_list();
+        }
+        Err(err) => app.status = Some(format!("Couldn't load more PRs: {}", err)),
+    }
+    Vec::new()
+}
@@ -0,0 +1,63 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+use crate::domain::text;
+use crate::domain::types::InlineComment;
+
+/// Start writing (or rewriting) the pending review's comment on the selected block
+pub fn edit_comment(app: &mut App) {
+    if app.story.is_none() {
+        return;
+    }
+    let Some((path, line)) = app.block_location(app.selected_feature, app.selected_diff) else {
+        app.status = Some("This block can't be placed in the diff to comment on".to_string());
+        return;
+    };
+    app.comment_draft = app
+        .pending_comments
+        .iter()
+        .find(|comment| comment.path == path && comment.line == line)
+        .map(|comment| comment.body.clone())
+        .unwrap_or_default();
+    app.comment_editing = Some((path, line));
+}
+
+/// Input while an inline comment is being written; an empty one is removed
+pub fn handle_edit_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc => app.comment_editing = None,
+        KeyCode::Enter => {
+            let Some((path, line)) = app.comment_editing.take() else {
+                return Vec::new();
+            };
+            let body = std::mem::take(&mut app.comment_draft).trim().to_string();
+            let existing = app
+                .pending_comments
+                .iter()
+                .position(|comment| comment.path == path && comment.line == line);
+            match (existing, body.is_empty()) {
+                (Some(i), true) => {
+                    app.pending_comments.remove(i);
+                    app.status = Some("Comment removed from the pending review".to_string());
+                }
+                (Some(i), false) => app.pending_comments[i].body = body,
+                (None, true) => {}
+                (None, false) => {
+                    app.pending_comments.push(InlineComment { path, line, body });
+                    let label = app.pending_review_label().unwrap_or_default();
+                    app.status = Some(format!(
+                        "Added to the {}; Ctrl+S on Request Changes submits it",
+                        label
+                    ));
+                }
+            }
+        }
+        KeyCode::Backspace => {
+            text::pop_grapheme(&mut app.comment_draft);
+        }
+        KeyCode::Char(c) => app.comment_draft.push(c),
+        _ => {}
+    }
+    Vec::new()
+}
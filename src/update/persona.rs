@@ -0,0 +1,55 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::types::Persona;
+
+use super::helpers;
+
+/// Open the persona chooser with the current persona selected
+pub fn open_chooser(app: &mut App) {
+    app.persona_selected = app
+        .persona
+        .and_then(|current| Persona::ALL.iter().position(|&p| p == current))
+        .map_or(0, |i| i + 1);
+    app.state = AppState::ChoosingPersona;
+}
+
+/// Input while choosing a persona; row 0 is a general review, then `Persona::ALL`
+pub fn handle_input(app: &mut App, code: KeyCode, config: &AppConfig) -> Vec<Command> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.state = AppState::Viewing;
+            Vec::new()
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.persona_selected = (app.persona_selected + 1).min(Persona::ALL.len());
+            Vec::new()
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.persona_selected = app.persona_selected.saturating_sub(1);
+            Vec::new()
+        }
+        KeyCode::Enter => regenerate(app, config),
+        _ => Vec::new(),
+    }
+}
+
+/// Throw away the current stories and generate them again under the chosen persona
+fn regenerate(app: &mut App, config: &AppConfig) -> Vec<Command> {
+    let Some(pr) = helpers::regenerable_pr(app) else {
+        return Vec::new();
+    };
+
+    app.persona = app
+        .persona_selected
+        .checked_sub(1)
+        .and_then(|i| Persona::ALL.get(i).copied());
+    let commands = helpers::regenerate_stories(app, &pr, config);
+    app.status = Some(match app.persona {
+        Some(persona) => format!("Regenerating as a {} review", persona.label()),
+        None => "Regenerating as a general review".to_string(),
+    });
+    commands
+}
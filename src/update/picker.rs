@@ -1,9 +1,10 @@
 use crossterm::event::KeyCode;
 
-use crate::app::{App, AppState};
+use crate::app::{App, AppState, MAX_TABS};
 use crate::command::Command;
+use crate::domain::types::PrSort;
 
-use super::helpers;
+use super::{helpers, paging};
 
 pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
     match code {
@@ -27,36 +28,107 @@ pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
         }
         KeyCode::Char('j') | KeyCode::Down => {
             app.picker_down();
-            Vec::new()
+            paging::more_prs(app)
         }
         KeyCode::Char('k') | KeyCode::Up => {
             app.picker_up();
             Vec::new()
         }
-        KeyCode::Char('r') => {
-            let Some((owner, repo)) = helpers::current_repo(app) else {
-                return Vec::new();
-            };
-            vec![Command::FetchPrList { owner, repo }]
+        KeyCode::Char('r') => refetch(app),
+        KeyCode::Char('o') => {
+            app.pr_sort_selected = PrSort::ALL
+                .iter()
+                .position(|&sort| sort == app.pr_sort)
+                .unwrap_or(0);
+            app.state = AppState::ChoosingPrSort;
+            Vec::new()
         }
-        KeyCode::Enter => {
-            let Some(pr) = app.selected_pr() else {
-                return Vec::new();
-            };
-            let Some((owner, repo)) = helpers::current_repo(app) else {
+        KeyCode::Char('s') => {
+            app.pr_filter = app.pr_filter.next();
+            app.status = Some(format!("Showing {} PRs", app.pr_filter.label()));
+            refetch(app)
+        }
+        KeyCode::Char('d') => {
+            app.hide_drafts = !app.hide_drafts;
+            if app.hide_drafts {
+                app.status = Some("Drafts hidden".to_string());
+                app.drop_hidden_prs();
+                Vec::new()
+            } else {
+                app.status = Some("Drafts shown".to_string());
+                refetch(app)
+            }
+        }
+        KeyCode::Char('*') => {
+            let Some(key) = app.selected_pr().and_then(|pr| app.pr_key(pr.number)) else {
                 return Vec::new();
             };
-
-            let number = pr.number;
-            app.reset_for_new_pr();
-            app.current_pr_number = Some(number);
-            app.state = AppState::LoadingPr;
-            vec![Command::FetchPr {
-                owner,
-                repo,
-                number,
+            app.history.toggle_favorite_pr(&key);
+            app.sort_pr_list();
+            vec![Command::SaveHistory {
+                history: app.history.clone(),
             }]
         }
+        KeyCode::Enter => open_selected(app),
+        KeyCode::Char('t') if app.story.is_some() => {
+            if !app.open_tab() {
+                app.status = Some(format!("At most {} PRs can be open at once", MAX_TABS));
+                return Vec::new();
+            }
+            open_selected(app)
+        }
         _ => Vec::new(),
     }
 }
+
+/// Input while the sort menu is open over the picker
+pub fn handle_sort_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.state = AppState::PrPicker,
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.pr_sort_selected = (app.pr_sort_selected + 1).min(PrSort::ALL.len() - 1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.pr_sort_selected = app.pr_sort_selected.saturating_sub(1);
+        }
+        KeyCode::Enter => {
+            app.pr_sort = PrSort::ALL[app.pr_sort_selected];
+            app.sort_pr_list();
+            app.picker_selected = 0;
+            app.state = AppState::PrPicker;
+        }
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// Reload the PR list, e.g. after the filter changed
+fn refetch(app: &mut App) -> Vec<Command> {
+    let Some((owner, repo)) = helpers::current_repo(app) else {
+        return Vec::new();
+    };
+    vec![Command::FetchPrList {
+        owner,
+        repo,
+        states: app.pr_filter,
+    }]
+}
+
+fn open_selected(app: &mut App) -> Vec<Command> {
+    let Some(pr) = app.selected_pr() else {
+        return Vec::new();
+    };
+    let Some((owner, repo)) = helpers::current_repo(app) else {
+        return Vec::new();
+    };
+
+    let number = pr.number;
+    app.reset_for_new_pr();
+    app.current_pr_number = Some(number);
+    app.state = AppState::LoadingPr;
+    vec![Command::FetchPr {
+        owner,
+        repo,
+        number,
+    }]
+}
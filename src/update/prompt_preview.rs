@@ -0,0 +1,49 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::config::AppConfig;
+
+use super::{deep_dive, helpers, loading};
+
+/// Input while the prompt preview waits for approval
+pub fn handle_input(app: &mut App, code: KeyCode, config: &AppConfig) -> Vec<Command> {
+    match code {
+        KeyCode::Enter | KeyCode::Char('y') => {
+            app.prompt_approved = true;
+            if let Some(feature) = app.deep_dive_preview.take() {
+                app.state = AppState::Viewing;
+                app.selected_feature = feature;
+                return deep_dive::request(app, config);
+            }
+            let Some(pr) = app.pr.clone() else {
+                return Vec::new();
+            };
+            helpers::generate_stories(app, &pr, config)
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.prompt_preview_scroll = app.prompt_preview_scroll.saturating_add(1);
+            Vec::new()
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.prompt_preview_scroll = app.prompt_preview_scroll.saturating_sub(1);
+            Vec::new()
+        }
+        KeyCode::Char(' ') | KeyCode::PageDown => {
+            app.prompt_preview_scroll = app.prompt_preview_scroll.saturating_add(20);
+            Vec::new()
+        }
+        KeyCode::Char('b') | KeyCode::PageUp => {
+            app.prompt_preview_scroll = app.prompt_preview_scroll.saturating_sub(20);
+            Vec::new()
+        }
+        KeyCode::Esc | KeyCode::Char('q') if app.deep_dive_preview.is_some() => {
+            app.deep_dive_preview = None;
+            app.state = AppState::Viewing;
+            app.status = Some("Deep dive not sent".to_string());
+            Vec::new()
+        }
+        KeyCode::Esc | KeyCode::Char('q') => loading::cancel_pr_load(app),
+        _ => Vec::new(),
+    }
+}
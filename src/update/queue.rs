@@ -0,0 +1,60 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Char('q') => {
+            if app.story.is_some() {
+                app.state = AppState::Viewing;
+            } else {
+                app.should_quit = true;
+            }
+            Vec::new()
+        }
+        KeyCode::Esc | KeyCode::Backspace => {
+            if app.story.is_some() {
+                app.state = AppState::Viewing;
+                Vec::new()
+            } else {
+                app.state = AppState::LoadingRepoList;
+                vec![Command::FetchRepoList {
+                    org: app.repo_org.clone(),
+                }]
+            }
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.queue_down();
+            Vec::new()
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.queue_up();
+            Vec::new()
+        }
+        KeyCode::Char('r') => {
+            app.state = AppState::LoadingReviewQueue;
+            vec![Command::FetchReviewQueue]
+        }
+        KeyCode::Enter => {
+            let Some(item) = app.selected_queue_item() else {
+                return Vec::new();
+            };
+
+            let owner = item.owner.clone();
+            let repo = item.repo.clone();
+            let number = item.pr.number;
+            app.reset_for_new_pr();
+            app.pr = None;
+            app.current_repo = Some((owner.clone(), repo.clone()));
+            app.current_pr_number = Some(number);
+            app.state = AppState::LoadingPr;
+            vec![Command::FetchPr {
+                owner,
+                repo,
+                number,
+            }]
+        }
+        _ => Vec::new(),
+    }
+}
@@ -3,6 +3,8 @@ use crossterm::event::KeyCode;
 use crate::app::{App, AppState};
 use crate::command::Command;
 
+use super::{activity, helpers, paging};
+
 pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
     match code {
         KeyCode::Char('q') | KeyCode::Esc => {
@@ -11,7 +13,7 @@ pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
         }
         KeyCode::Char('j') | KeyCode::Down => {
             app.repo_selector_down();
-            Vec::new()
+            paging::more_repos(app)
         }
         KeyCode::Char('k') | KeyCode::Up => {
             app.repo_selector_up();
@@ -19,23 +21,74 @@ pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
         }
         KeyCode::Char('r') => {
             app.state = AppState::LoadingRepoList;
-            vec![Command::FetchRepoList]
+            vec![Command::FetchRepoList {
+                org: app.repo_org.clone(),
+            }]
+        }
+        KeyCode::Tab | KeyCode::BackTab => {
+            if !app.cycle_repo_org(code == KeyCode::Tab) {
+                return Vec::new();
+            }
+            app.state = AppState::LoadingRepoList;
+            vec![Command::FetchRepoList {
+                org: app.repo_org.clone(),
+            }]
         }
+        KeyCode::Char('Q') => {
+            app.state = AppState::LoadingReviewQueue;
+            vec![Command::FetchReviewQueue]
+        }
+        KeyCode::Enter if app.resume_selected => resume(app),
         KeyCode::Enter => {
             let Some(repo) = app.selected_repo() else {
                 return Vec::new();
             };
             let owner = repo.owner.clone();
             let repo_name = repo.name.clone();
+            app.history.touch_repo(&repo.full_name());
             app.current_repo = Some((owner.clone(), repo_name.clone()));
             app.current_pr_number = None;
             app.state = AppState::LoadingPrList;
             app.show_picker = false;
-            vec![Command::FetchPrList {
-                owner,
-                repo: repo_name,
+            vec![
+                Command::SaveHistory {
+                    history: app.history.clone(),
+                },
+                Command::FetchPrList {
+                    owner,
+                    repo: repo_name,
+                    states: app.pr_filter,
+                },
+            ]
+        }
+        KeyCode::Char('*') => {
+            let Some(repo) = app.selected_repo() else {
+                return Vec::new();
+            };
+            let key = repo.full_name();
+            app.history.toggle_favorite_repo(&key);
+            app.sort_repo_list();
+            vec![Command::SaveHistory {
+                history: app.history.clone(),
             }]
         }
         _ => Vec::new(),
     }
 }
+
+/// Reopen the last session's PR where it left off
+fn resume(app: &mut App) -> Vec<Command> {
+    let Some(session) = app.resume.take() else {
+        return Vec::new();
+    };
+    app.history.touch_pr(&session.target());
+    app.restore_session(session);
+    app.state = AppState::Viewing;
+    app.status = Some("Resumed from the last session".to_string());
+    let mut commands = vec![Command::SaveHistory {
+        history: app.history.clone(),
+    }];
+    commands.extend(helpers::fetch_viewed_files(app));
+    commands.extend(activity::fetch(app));
+    commands
+}
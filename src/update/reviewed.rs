@@ -0,0 +1,84 @@
+use crate::app::App;
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::types::{PrContext, ReviewDelta};
+
+use super::helpers;
+
+/// Fetch what changed since the reviewer's last review, when the head has moved since
+pub fn fetch_delta(app: &App, pr: &PrContext) -> Option<Command> {
+    // A commit range already limits the story to what's new
+    if pr.commit_range.is_some() {
+        return None;
+    }
+    let reviewed = app.reviewed.get(&app.open_pr_key()?)?;
+    if reviewed.head_sha == pr.head_sha {
+        return None;
+    }
+    Some(Command::FetchReviewDelta {
+        owner: pr.owner.clone(),
+        repo: pr.repo.clone(),
+        number: pr.number,
+        reviewed_head: reviewed.head_sha.clone(),
+        head: pr.head_sha.clone(),
+    })
+}
+
+/// Generate the story with the delta, or without it if it couldn't be fetched
+pub fn handle_delta_loaded(
+    app: &mut App,
+    reviewed_head: String,
+    result: Result<String, String>,
+    config: &AppConfig,
+) -> Vec<Command> {
+    let Some(pr) = &mut app.pr else {
+        return Vec::new();
+    };
+    // Lockfiles, generated, binary and oversized files are left out as they are
+    // from the PR's diff, including any only the delta touches
+    let result = result.map(|diff| config.fetch_options().filter.apply(&diff).0);
+    match result {
+        Ok(diff) if !diff.trim().is_empty() => {
+            pr.review_delta = Some(ReviewDelta {
+                reviewed_head,
+                diff,
+            });
+        }
+        Ok(_) => {}
+        Err(err) => {
+            app.status = Some(format!("Couldn't fetch changes since your last review: {}", err));
+        }
+    }
+    let pr = pr.clone();
+    helpers::generate_stories(app, &pr, config)
+}
+
+/// Remember the open PR's head as reviewed, with the blocks viewed at it
+pub fn record(app: &mut App) -> Vec<Command> {
+    let (Some(key), Some(reviewed)) = (app.open_pr_key(), app.reviewed_version()) else {
+        return Vec::new();
+    };
+    if app.reviewed.get(&key) == Some(&reviewed) {
+        return Vec::new();
+    }
+    app.reviewed.record(&key, reviewed);
+    vec![Command::SaveReviewed {
+        reviewed: app.reviewed.clone(),
+    }]
+}
+
+/// Keep blocks viewed in the last review viewed where their changes are the same
+pub fn restore(app: &mut App) {
+    if app.pr.as_ref().is_none_or(|pr| pr.review_delta.is_none()) {
+        return;
+    }
+    let Some(reviewed) = app.open_pr_key().and_then(|key| app.reviewed.get(&key).cloned()) else {
+        return;
+    };
+    let restored = app.restore_viewed_blocks(&reviewed.viewed_blocks);
+    app.status = Some(format!(
+        "Updated since your last review; {} unchanged block{} kept viewed",
+        restored,
+        if restored == 1 { "" } else { "s" }
+    ));
+}
@@ -0,0 +1,86 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+use crate::domain::review::{append_list_item, append_quote};
+use crate::domain::types::ReviewAction;
+
+pub fn open(app: &mut App) {
+    if findings(app).is_empty() {
+        app.status = Some("The story lists no risks or open questions".to_string());
+        return;
+    }
+    app.show_risks = true;
+    app.risk_selected = 0;
+}
+
+/// Input while the risk panel is open
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    let count = findings(app).len();
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('!') => app.show_risks = false,
+        KeyCode::Char('j') | KeyCode::Down if app.risk_selected + 1 < count => {
+            app.risk_selected += 1;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.risk_selected = app.risk_selected.saturating_sub(1);
+        }
+        KeyCode::Enter | KeyCode::Char('a') => promote(app),
+        KeyCode::Char('>') => quote(app),
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// The panel's rows: every risk, most severe first, with its feature's title,
+/// then the story's open questions, which belong to no feature
+fn findings(app: &App) -> Vec<(String, Option<String>)> {
+    let Some(story) = &app.story else {
+        return Vec::new();
+    };
+    let risks = story.risks_by_severity().into_iter().map(|(i, risk)| {
+        (risk.text.clone(), Some(story.narrative[i].title.clone()))
+    });
+    let questions = story.open_questions.iter().map(|question| (question.clone(), None));
+    risks.chain(questions).collect()
+}
+
+/// Append the selected risk to the Request Changes draft, or the selected open
+/// question to Clarification Questions, as its next numbered item
+fn promote(app: &mut App) {
+    let Some((text, feature)) = findings(app).into_iter().nth(app.risk_selected) else {
+        return;
+    };
+    let action = if feature.is_some() {
+        ReviewAction::RequestChanges
+    } else {
+        ReviewAction::ClarificationQuestions
+    };
+    let draft = app.action_texts.get_mut(action);
+    if draft.contains(&text) {
+        app.status = Some(format!("Already in the {} draft", action.title()));
+        return;
+    }
+    append_list_item(draft, &text);
+    app.status = Some(format!("Added to the {} draft", action.title()));
+}
+
+/// Quote the selected risk or open question into the selected action's draft,
+/// citing the feature it came from
+fn quote(app: &mut App) {
+    let Some((text, feature)) = findings(app).into_iter().nth(app.risk_selected) else {
+        return;
+    };
+    let attribution = match feature {
+        Some(title) => format!("risk in *{}*", title),
+        None => "open question".to_string(),
+    };
+    let action = app.selected_action;
+    let draft = app.action_texts.get_mut(action);
+    if draft.contains(&format!("> {}", text.lines().next().unwrap_or_default())) {
+        app.status = Some(format!("Already quoted in the {} draft", action.title()));
+        return;
+    }
+    append_quote(draft, &text, &attribution);
+    app.status = Some(format!("Quoted into the {} draft", action.title()));
+}
@@ -0,0 +1,52 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::filter::{apply_scope, parse_patterns};
+use crate::domain::text;
+
+use super::helpers;
+
+/// Open the scope editor with the current patterns
+pub fn open(app: &mut App) {
+    app.scope_input = app.scope.join(", ");
+    app.state = AppState::EditingScope;
+}
+
+pub fn handle_input(app: &mut App, code: KeyCode, config: &AppConfig) -> Vec<Command> {
+    match code {
+        KeyCode::Esc => app.state = AppState::Viewing,
+        KeyCode::Enter => return rescope(app, config),
+        KeyCode::Backspace => {
+            text::pop_grapheme(&mut app.scope_input);
+        }
+        KeyCode::Char(c) => app.scope_input.push(c),
+        _ => {}
+    }
+    Vec::new()
+}
+
+/// Apply the typed scope and regenerate the story for it
+fn rescope(app: &mut App, config: &AppConfig) -> Vec<Command> {
+    let scope = parse_patterns(&app.scope_input);
+    app.state = AppState::Viewing;
+    if scope == app.scope {
+        return Vec::new();
+    }
+    app.scope = scope;
+    if let Some(pr) = &mut app.pr {
+        apply_scope(pr, &app.scope);
+    }
+    app.index_changed_files();
+    let Some(pr) = helpers::regenerable_pr(app) else {
+        return Vec::new();
+    };
+    let commands = helpers::regenerate_stories(app, &pr, config);
+    app.status = Some(if app.scope.is_empty() {
+        "Regenerating for the whole PR".to_string()
+    } else {
+        format!("Regenerating for {}", app.scope.join(", "))
+    });
+    commands
+}
@@ -0,0 +1,33 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::domain::text;
+
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc => {
+            app.clear_search();
+            app.state = AppState::Viewing;
+            Vec::new()
+        }
+        KeyCode::Enter => {
+            app.state = AppState::Viewing;
+            if app.search_query.is_empty() {
+                return Vec::new();
+            }
+            app.jump_to_first_match();
+            app.status = Some(app.search_summary());
+            Vec::new()
+        }
+        KeyCode::Backspace => {
+            text::pop_grapheme(&mut app.search_query);
+            Vec::new()
+        }
+        KeyCode::Char(c) => {
+            app.search_query.push(c);
+            Vec::new()
+        }
+        _ => Vec::new(),
+    }
+}
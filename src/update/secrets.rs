@@ -0,0 +1,23 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::secrets::SecretsChoice;
+
+use super::{helpers, loading};
+
+/// Input while the likely-secrets warning holds back generation
+pub fn handle_input(app: &mut App, code: KeyCode, config: &AppConfig) -> Vec<Command> {
+    let choice = match code {
+        KeyCode::Char('r') | KeyCode::Enter => SecretsChoice::Redact,
+        KeyCode::Char('S') => SecretsChoice::Send,
+        KeyCode::Esc | KeyCode::Char('q') => return loading::cancel_pr_load(app),
+        _ => return Vec::new(),
+    };
+    app.secrets_choice = Some(choice);
+    let Some(pr) = app.pr.clone() else {
+        return Vec::new();
+    };
+    helpers::generate_stories(app, &pr, config)
+}
@@ -0,0 +1,29 @@
+use crate::app::{App, AppState};
+use crate::command::Command;
+
+use super::helpers;
+
+/// Open the PR `offset` steps up or down the stack
+pub fn jump(app: &mut App, offset: isize) -> Vec<Command> {
+    let Some(number) = app.stack_neighbor(offset).map(|entry| entry.number) else {
+        app.status = Some(
+            match (app.pr.as_ref().is_some_and(|pr| pr.stack.is_empty()), offset < 0) {
+                (true, _) => "This PR isn't stacked on another".to_string(),
+                (false, true) => "Already at the bottom of the stack".to_string(),
+                (false, false) => "Already at the top of the stack".to_string(),
+            },
+        );
+        return Vec::new();
+    };
+    let Some((owner, repo, _)) = helpers::current_pr_ref(app) else {
+        return Vec::new();
+    };
+    app.reset_for_new_pr();
+    app.current_pr_number = Some(number);
+    app.state = AppState::LoadingPr;
+    vec![Command::FetchPr {
+        owner,
+        repo,
+        number,
+    }]
+}
@@ -0,0 +1,51 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::cache::short_sha;
+use crate::domain::types::PrContext;
+
+use super::{actions, helpers};
+
+/// Show the cached stories if `pr` hasn't changed since they were generated;
+/// otherwise show them under a prompt to view them anyway or regenerate
+pub fn check(app: &mut App, pr: &PrContext, config: &AppConfig) -> Vec<Command> {
+    let stories = std::mem::take(&mut app.pending_cache);
+    let stale = stories
+        .iter()
+        .map(|(_, entry)| entry)
+        .find(|entry| entry.is_stale(pr))
+        .map(|entry| (entry.head_sha.clone(), entry.created_at));
+    let commands = actions::show_cached(app, stories, config);
+    if stale.is_some() {
+        app.stale_cache = stale;
+        app.state = AppState::StaleCache;
+        app.status = None;
+    }
+    commands
+}
+
+/// Input while the stale-story prompt is open
+pub fn handle_input(app: &mut App, code: KeyCode, config: &AppConfig) -> Vec<Command> {
+    match code {
+        KeyCode::Char('v') | KeyCode::Esc => {
+            let head = app.stale_cache.take().map(|(head, _)| head).unwrap_or_default();
+            app.state = AppState::Viewing;
+            app.status = Some(if head.is_empty() {
+                "Showing a stale story (Ctrl+R to regenerate)".to_string()
+            } else {
+                format!("Showing the story for {} (Ctrl+R to regenerate)", short_sha(&head))
+            });
+            Vec::new()
+        }
+        KeyCode::Char('r') | KeyCode::Enter => {
+            app.stale_cache = None;
+            let Some(pr) = helpers::regenerable_pr(app) else {
+                return Vec::new();
+            };
+            helpers::regenerate_stories(app, &pr, config)
+        }
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,194 @@
+use crate::action::Action;
+use crate::app::App;
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::types::ReviewAction;
+
+use super::{actions, activity, batch, helpers, outbox};
+
+/// Show the PR in tab `index`, checking it for activity missed while in the background
+pub fn switch(app: &mut App, index: usize) -> Vec<Command> {
+    if !app.switch_tab(index) {
+        return Vec::new();
+    }
+    app.status = app.tab_labels().get(index).map(|label| format!("Switched to {}", label));
+    activity::fetch(app)
+}
+
+pub fn close(app: &mut App) -> Vec<Command> {
+    if !app.close_tab() {
+        app.status = Some("Only one PR is open".to_string());
+        return Vec::new();
+    }
+    activity::fetch(app)
+}
+
+/// Keep a result for PR `key` in its background tab until it's switched back to.
+/// Submissions are recorded in the outbox and batch wherever their PR is. Other
+/// results for a PR no longer open are dropped, as are activity, viewed files,
+/// commits and results only a loading PR waits for, which are fetched again when
+/// needed
+pub fn handle_background_result(
+    app: &mut App,
+    key: &str,
+    action: Action,
+    config: &AppConfig,
+) -> Vec<Command> {
+    if let Action::SubmissionResult { action, result, .. } = action {
+        return handle_background_submission(app, key, action, result, config);
+    }
+    let Some(tab) = app.parked_tab_mut(key) else {
+        return Vec::new();
+    };
+    let mut commands = Vec::new();
+    let status =
+        match action {
+            Action::StoryGenerated {
+                model,
+                result: Ok(story),
+                ..
+            } => {
+                let language = tab.language.clone();
+                commands.extend(tab.pr.as_ref().map(|pr| {
+                    helpers::save_pr_to_cache(pr, language, config, &model, story.clone())
+                }));
+                tab.add_story_variant(model.clone(), story);
+                Some(format!("Story from {} ready in {}", model, tab.label()))
+            }
+            Action::StoryGenerated {
+                model,
+                result: Err(err),
+                ..
+            } => {
+                tab.pending_models.retain(|pending| *pending != model);
+                Some(format!("{} failed on {}: {}", model, tab.label(), err))
+            }
+            Action::StoryGenerationTimedOut { pr, model } => {
+                let pending = tab
+                    .pending_models
+                    .iter_mut()
+                    .find(|pending| **pending == model);
+                match pending {
+                    Some(pending) if model != config.fallback_model => {
+                        *pending = config.fallback_model.clone();
+                        commands.push(Command::GenerateStory {
+                            pr: Box::new(pr),
+                            model: config.fallback_model.clone(),
+                            persona: tab.persona,
+                            language: tab.language.clone(),
+                            params: tab.model_params,
+                        });
+                        None
+                    }
+                    _ => {
+                        tab.pending_models.retain(|pending| *pending != model);
+                        Some(format!("{} ran over budget on {}", model, tab.label()))
+                    }
+                }
+            }
+            // The story it was asked for has since been replaced
+            Action::DeepDiveGenerated {
+                feature, result, ..
+            } if tab.deep_dive_pending == Some(feature) => {
+                tab.deep_dive_pending = None;
+                if let Ok(deep_dive) = result {
+                    tab.deep_dives.insert(feature, deep_dive);
+                }
+                None
+            }
+            Action::HeadFilesLoaded {
+                result: Ok(files), ..
+            } => {
+                tab.head_files.extend(files);
+                None
+            }
+            Action::BlameLoaded {
+                path,
+                result: Ok(ranges),
+                ..
+            } => {
+                tab.blame.insert(path, ranges);
+                None
+            }
+            Action::FileViewedSynced {
+                path,
+                result: Err(err),
+                ..
+            } => Some(format!(
+                "Couldn't sync viewed state of {} on {} to GitHub: {}",
+                path,
+                tab.label(),
+                err
+            )),
+            Action::ReviewersRequested { result, .. } => Some(match result {
+                Ok(reviewers) => {
+                    format!("Requested reviews on {} from {}", tab.label(), reviewers.join(", "))
+                }
+                Err(err) => format!("Couldn't request reviewers on {}: {}", tab.label(), err),
+            }),
+            _ => None,
+        };
+    if status.is_some() {
+        app.status = status;
+    }
+    commands
+}
+
+/// What a submission for PR `key` leaves behind when another PR is showing: its
+/// tab, if still open, is marked submitted, its pending review cleared and the
+/// review remembered, and the repo's channel told, as for the PR showing
+fn handle_background_submission(
+    app: &mut App,
+    key: &str,
+    action: ReviewAction,
+    result: Result<(), String>,
+    config: &AppConfig,
+) -> Vec<Command> {
+    let (mut commands, retried) = outbox::record_result(app, action, &result);
+    if retried {
+        return commands;
+    }
+    let batched = batch::record_result(app, action, &result);
+    let Some(tab) = app.parked_tab_mut(key) else {
+        return commands;
+    };
+    let label = tab.label();
+    if result.is_ok() {
+        if !tab.submitted_actions.contains(&action) {
+            tab.submitted_actions.push(action);
+        }
+        if action == ReviewAction::RequestChanges {
+            tab.pending_comments.clear();
+        }
+        let text = tab.action_texts.get(action).to_string();
+        if let Some(pr) = &tab.pr {
+            let pr_ref = (pr.owner.as_str(), pr.repo.as_str(), pr.number);
+            let event = tab.review_event;
+            commands.extend(actions::webhook_command(config, pr_ref, action, event, &text));
+        }
+        let repo = tab.pr.as_ref().map(|pr| format!("{}/{}", pr.owner, pr.repo));
+        if let Some(repo) = repo.filter(|_| action == ReviewAction::RequestChanges) {
+            app.memory.record(&repo, &text);
+            let prompt = app.memory.prompt(&repo);
+            if let Some(pr) = app.parked_tab_mut(key).and_then(|tab| tab.pr.as_mut()) {
+                pr.review_memory = prompt;
+            }
+            commands.push(Command::SaveMemory {
+                memory: app.memory.clone(),
+            });
+        }
+    }
+    if batched {
+        return commands;
+    }
+    let (title, status) = match result {
+        Ok(()) => ("Submitted", format!("{} submitted on {}", action.title(), label)),
+        Err(err) => (
+            "Submission failed",
+            format!("Error on {}: {} (kept in the outbox; R to retry)", label, err),
+        ),
+    };
+    commands.extend(helpers::notify_desktop(app, config, title, &status));
+    app.status = Some(status);
+    commands
+}
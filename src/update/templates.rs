@@ -0,0 +1,49 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::templates;
+
+/// Open the template picker over the draft, placeholders filled in for the open PR
+pub fn open(app: &mut App, config: &AppConfig) {
+    if config.templates.is_empty() {
+        app.status = Some(
+            "No templates yet: add template.\"name\" = \"text\" lines to config.toml".to_string(),
+        );
+        return;
+    }
+    let Some(pr) = &app.pr else {
+        return;
+    };
+    app.template_choices = config
+        .templates
+        .iter()
+        .map(|(name, text)| (name.clone(), templates::expand(text, pr)))
+        .collect();
+    app.template_selected = 0;
+    app.show_templates = true;
+}
+
+/// Input while the template picker is open
+pub fn handle_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    let count = app.template_choices.len();
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.show_templates = false,
+        KeyCode::Char('j') | KeyCode::Down if app.template_selected + 1 < count => {
+            app.template_selected += 1;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.template_selected = app.template_selected.saturating_sub(1);
+        }
+        KeyCode::Enter => {
+            app.show_templates = false;
+            if let Some((name, text)) = app.template_choices.get(app.template_selected).cloned() {
+                app.insert_text(&text);
+                app.status = Some(format!("Inserted {:?}", name));
+            }
+        }
+        _ => {}
+    }
+    Vec::new()
+}
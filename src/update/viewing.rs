@@ -1,12 +1,41 @@
 use crossterm::event::{KeyCode, KeyModifiers};
 
-use crate::app::App;
+use crate::app::{App, AppState, Count, SidebarMode};
 use crate::command::Command;
+use crate::config::AppConfig;
+use crate::domain::dry_run;
 use crate::domain::types::ReviewAction;
 
-use super::helpers;
+use super::{
+    activity, batch, clipboard, commits, coverage, deep_dive, go_to, helpers, memory,
+    model_settings, notes, owners, pending_review, persona, reviewed, risks, scope, stack, tabs,
+};
 
-pub fn handle_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Vec<Command> {
+/// Most times a count repeats a motion
+const MAX_COUNT: usize = 999;
+
+pub fn handle_input(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    config: &AppConfig,
+) -> Vec<Command> {
+    if app.go_to.is_some() {
+        return go_to::handle_input(app, code);
+    }
+    if let (KeyCode::Char(digit @ '0'..='9'), KeyModifiers::NONE) = (code, modifiers) {
+        push_count_digit(app, digit);
+        return Vec::new();
+    }
+    // Any other key drops a pending count
+    if let Some(count) = app.count.take()
+        && is_motion(code, modifiers)
+    {
+        app.selected_action = count.action;
+        return (0..count.n)
+            .flat_map(|_| handle_input(app, code, modifiers, config))
+            .collect();
+    }
     match (code, modifiers) {
         (KeyCode::Char('q'), _) => {
             app.should_quit = true;
@@ -16,35 +45,285 @@ pub fn handle_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Ve
             let Some((owner, repo)) = helpers::current_repo(app) else {
                 return Vec::new();
             };
-            vec![Command::FetchPrList { owner, repo }]
+            vec![Command::FetchPrList {
+                owner,
+                repo,
+                states: app.pr_filter,
+            }]
+        }
+        (KeyCode::Char('O'), KeyModifiers::SHIFT) => vec![Command::FetchRepoList {
+            org: app.repo_org.clone(),
+        }],
+        (KeyCode::Char('Q'), KeyModifiers::SHIFT) => vec![Command::FetchReviewQueue],
+        (KeyCode::Char('S'), KeyModifiers::SHIFT) => {
+            app.workspace_query.clear();
+            app.state = AppState::WorkspaceSearch;
+            Vec::new()
+        }
+        (KeyCode::Char('s' | 'S'), m) if m.contains(KeyModifiers::CONTROL) => {
+            batch::open(app);
+            Vec::new()
+        }
+        (KeyCode::Char('/'), _) => {
+            app.start_search();
+            Vec::new()
+        }
+        (KeyCode::Char('n'), KeyModifiers::NONE) if app.has_active_search() => {
+            app.jump_to_match(true);
+            app.status = Some(app.search_summary());
+            Vec::new()
+        }
+        (KeyCode::Char('N'), KeyModifiers::SHIFT) if app.has_active_search() => {
+            app.jump_to_match(false);
+            app.status = Some(app.search_summary());
+            Vec::new()
+        }
+        (KeyCode::Esc, _) if app.has_active_search() => {
+            app.clear_search();
+            app.status = None;
+            Vec::new()
+        }
+        (KeyCode::Char('F'), KeyModifiers::SHIFT) => {
+            app.toggle_sidebar_mode();
+            if app.preferences.sidebar_hidden {
+                app.toggle_sidebar_hidden();
+                return save_preferences(app);
+            }
+            Vec::new()
+        }
+        (KeyCode::Char('['), _) => {
+            app.narrow_sidebar();
+            save_preferences(app)
+        }
+        (KeyCode::Char(']'), _) => {
+            app.widen_sidebar();
+            save_preferences(app)
+        }
+        (KeyCode::Char('w'), KeyModifiers::NONE) => {
+            let Some(path) = app.open_file_view() else {
+                return Vec::new();
+            };
+            let Some(pr) = &app.pr else {
+                return Vec::new();
+            };
+            vec![Command::FetchHeadFiles {
+                owner: pr.owner.clone(),
+                repo: pr.repo.clone(),
+                number: pr.number,
+                sha: pr.head_sha.clone(),
+                paths: vec![path],
+            }]
+        }
+        (KeyCode::Char('M'), KeyModifiers::SHIFT) => {
+            app.status = Some(match app.cycle_story_variant() {
+                Some(model) => format!("Showing the story from {}", model),
+                None if app.pending_models.is_empty() => {
+                    "Only one story; use --compare to generate more".to_string()
+                }
+                None => format!("Still generating with {}", app.pending_models.join(", ")),
+            });
+            Vec::new()
+        }
+        (KeyCode::Char('P'), KeyModifiers::SHIFT) => {
+            persona::open_chooser(app);
+            Vec::new()
+        }
+        (KeyCode::Char('L'), KeyModifiers::SHIFT) => {
+            model_settings::open(app);
+            Vec::new()
+        }
+        (KeyCode::Char('C'), KeyModifiers::SHIFT) => {
+            if app.story_variants.len() + app.pending_models.len() > 1 {
+                app.state = AppState::ComparingStories;
+            } else {
+                app.status = Some("Only one story; use --compare to generate more".to_string());
+            }
+            Vec::new()
+        }
+        (KeyCode::Char('B'), KeyModifiers::SHIFT) => {
+            let Some(path) = app.open_blame_view() else {
+                return Vec::new();
+            };
+            let Some(pr) = &app.pr else {
+                return Vec::new();
+            };
+            vec![Command::FetchBlame {
+                owner: pr.owner.clone(),
+                repo: pr.repo.clone(),
+                number: pr.number,
+                sha: pr.base_sha.clone(),
+                path,
+            }]
+        }
+        (KeyCode::Char('x'), KeyModifiers::NONE) => {
+            let Some(path) = app.toggle_expanded_context() else {
+                return Vec::new();
+            };
+            let Some(pr) = &app.pr else {
+                return Vec::new();
+            };
+            vec![Command::FetchHeadFiles {
+                owner: pr.owner.clone(),
+                repo: pr.repo.clone(),
+                number: pr.number,
+                sha: pr.head_sha.clone(),
+                paths: vec![path],
+            }]
+        }
+        (KeyCode::Char('\\'), _) => {
+            app.toggle_sidebar_hidden();
+            save_preferences(app)
+        }
+        (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, _)
+            if app.sidebar_mode == SidebarMode::Files =>
+        {
+            app.file_tree_down();
+            Vec::new()
+        }
+        (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, _)
+            if app.sidebar_mode == SidebarMode::Files =>
+        {
+            app.file_tree_up();
+            Vec::new()
+        }
+        (KeyCode::Enter, _) if app.sidebar_mode == SidebarMode::Files => {
+            if !app.jump_to_selected_file() {
+                app.status = Some("No diff block in the story covers this file".to_string());
+            }
+            Vec::new()
+        }
+        (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, _)
+            if app.sidebar_mode == SidebarMode::Symbols =>
+        {
+            app.move_symbol(1);
+            Vec::new()
+        }
+        (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, _)
+            if app.sidebar_mode == SidebarMode::Symbols =>
+        {
+            app.move_symbol(-1);
+            Vec::new()
+        }
+        (KeyCode::Enter, _) if app.sidebar_mode == SidebarMode::Symbols => {
+            let name = app.symbols.get(app.symbol_selected).map(|s| s.name.clone());
+            if let Some(name) = name {
+                app.status = Some(match app.jump_to_selected_symbol() {
+                    Some((k, n)) => format!("Block {} of {} using {}", k, n, name),
+                    None => format!("No diff block in the story uses {}", name),
+                });
+            }
+            Vec::new()
+        }
+        (KeyCode::Esc, _) if app.sidebar_mode != SidebarMode::Features => {
+            app.sidebar_mode = SidebarMode::Features;
+            Vec::new()
         }
-        (KeyCode::Char('O'), KeyModifiers::SHIFT) => vec![Command::FetchRepoList],
         (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, _) => {
-            app.scroll_offset = app.scroll_offset.saturating_add(1);
+            app.scroll_down(1);
             Vec::new()
         }
         (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, _) => {
-            app.scroll_offset = app.scroll_offset.saturating_sub(1);
+            app.scroll_up(1);
             Vec::new()
         }
         (KeyCode::Char('d'), KeyModifiers::CONTROL)
         | (KeyCode::Char(' '), KeyModifiers::NONE)
         | (KeyCode::PageDown, _) => {
-            app.scroll_offset = app.scroll_offset.saturating_add(20);
+            app.scroll_down(20);
             Vec::new()
         }
         (KeyCode::Char('u'), KeyModifiers::CONTROL)
         | (KeyCode::Char('b'), KeyModifiers::NONE)
         | (KeyCode::PageUp, _) => {
-            app.scroll_offset = app.scroll_offset.saturating_sub(20);
+            app.scroll_up(20);
             Vec::new()
         }
         (KeyCode::Tab, _) | (KeyCode::Char('n'), KeyModifiers::NONE) => {
-            app.next_feature();
+            if app.guided {
+                app.guided_move(true);
+            } else {
+                app.next_feature();
+            }
             Vec::new()
         }
         (KeyCode::BackTab, _) | (KeyCode::Char('p'), KeyModifiers::NONE) => {
-            app.prev_feature();
+            if app.guided {
+                app.guided_move(false);
+            } else {
+                app.prev_feature();
+            }
+            Vec::new()
+        }
+        (KeyCode::Char('r'), m) if m.contains(KeyModifiers::CONTROL) => {
+            let Some(pr) = helpers::regenerable_pr(app) else {
+                return Vec::new();
+            };
+            let commands = helpers::regenerate_stories(app, &pr, config);
+            app.status = Some("Regenerating the story".to_string());
+            commands
+        }
+        (KeyCode::Char('r'), KeyModifiers::NONE) => {
+            app.status = Some(if app.follow_related() {
+                format!(
+                    "Related: {} (r for the next, Ctrl+O to go back)",
+                    app.related_note().unwrap_or_default()
+                )
+            } else {
+                "No related blocks here".to_string()
+            });
+            Vec::new()
+        }
+        (KeyCode::Char('o'), m) if m.contains(KeyModifiers::CONTROL) => {
+            if app.return_from_related() {
+                app.status = None;
+            }
+            Vec::new()
+        }
+        (KeyCode::Char('c'), KeyModifiers::NONE) => {
+            coverage::ask_about_gaps(app);
+            Vec::new()
+        }
+        (KeyCode::Char('!'), _) => {
+            risks::open(app);
+            Vec::new()
+        }
+        (KeyCode::Char('s'), KeyModifiers::NONE) => {
+            scope::open(app);
+            Vec::new()
+        }
+        (KeyCode::Char('H'), KeyModifiers::SHIFT) => {
+            memory::open(app);
+            Vec::new()
+        }
+        (KeyCode::Char('W'), KeyModifiers::SHIFT) => {
+            owners::open(app);
+            Vec::new()
+        }
+        (KeyCode::Char('t'), KeyModifiers::NONE) => commits::open(app),
+        (KeyCode::Char('y'), KeyModifiers::NONE) => clipboard::copy_block(app),
+        (KeyCode::Char('Y'), KeyModifiers::SHIFT) => clipboard::copy_story(app),
+        (KeyCode::Char('E'), KeyModifiers::SHIFT) => clipboard::export_session(app),
+        (KeyCode::Char('i'), KeyModifiers::NONE) => open_next_attachment(app),
+        (KeyCode::Char('J'), KeyModifiers::SHIFT) => stack::jump(app, -1),
+        (KeyCode::Char('K'), KeyModifiers::SHIFT) => stack::jump(app, 1),
+        (KeyCode::Char('{'), _) => {
+            app.feature_boundary(false);
+            Vec::new()
+        }
+        (KeyCode::Char('}'), _) => {
+            app.feature_boundary(true);
+            Vec::new()
+        }
+        (KeyCode::Home, _) => {
+            app.scroll_offset = 0;
+            Vec::new()
+        }
+        (KeyCode::Char('G'), KeyModifiers::SHIFT) | (KeyCode::End, _) => {
+            app.scroll_offset = app.max_scroll();
+            Vec::new()
+        }
+        (KeyCode::Char('g'), KeyModifiers::NONE) => {
+            go_to::start(app);
             Vec::new()
         }
         (KeyCode::Char('l'), KeyModifiers::NONE) | (KeyCode::Right, _) => {
@@ -56,21 +335,79 @@ pub fn handle_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Ve
             Vec::new()
         }
         (KeyCode::Char('v'), KeyModifiers::NONE) => {
-            app.toggle_viewed();
+            let mut commands = match (app.toggle_viewed(), &app.pr_node_id, &app.pr) {
+                (Some((path, viewed)), Some(pr_id), Some(pr)) => vec![Command::SetFileViewed {
+                    owner: pr.owner.clone(),
+                    repo: pr.repo.clone(),
+                    number: pr.number,
+                    pr_id: pr_id.clone(),
+                    path,
+                    viewed,
+                }],
+                _ => Vec::new(),
+            };
+            commands.extend(reviewed::record(app));
+            commands
+        }
+        (KeyCode::Char('f'), KeyModifiers::NONE) => {
+            app.toggle_follow_up();
+            Vec::new()
+        }
+        (KeyCode::Char('u'), KeyModifiers::NONE) => {
+            if !app.next_unviewed() {
+                app.status = Some("All diffs viewed".to_string());
+            }
+            Vec::new()
+        }
+        (KeyCode::Char('U'), KeyModifiers::SHIFT) => activity::refresh(app),
+        (KeyCode::Char('A'), KeyModifiers::SHIFT) => {
+            activity::open(app);
             Vec::new()
         }
-        (KeyCode::Char('1'), _) => {
-            app.selected_action = ReviewAction::RequestChanges;
+        (KeyCode::Char('e'), KeyModifiers::NONE) => deep_dive::request(app, config),
+        (KeyCode::Char('m'), KeyModifiers::NONE) => {
+            notes::edit(app);
             Vec::new()
         }
-        (KeyCode::Char('2'), _) => {
-            app.selected_action = ReviewAction::ClarificationQuestions;
+        (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+            notes::open(app);
             Vec::new()
         }
-        (KeyCode::Char('3'), _) => {
-            app.selected_action = ReviewAction::NextPr;
+        (KeyCode::Char('a'), KeyModifiers::NONE) => {
+            pending_review::edit_comment(app);
             Vec::new()
         }
+        (KeyCode::Char('D'), KeyModifiers::SHIFT) => {
+            app.dry_run = !app.dry_run;
+            app.status = Some(if app.dry_run {
+                format!("Dry run: submissions will be written to {}", dry_run::OUTBOX_DIR)
+            } else {
+                "Submissions will be posted to GitHub".to_string()
+            });
+            Vec::new()
+        }
+        (KeyCode::Char('T'), KeyModifiers::SHIFT) => {
+            app.thread_questions = !app.thread_questions;
+            app.status = Some(if app.thread_questions {
+                "Clarification questions will be posted as inline threads".to_string()
+            } else {
+                "Clarification questions will be posted as one comment".to_string()
+            });
+            Vec::new()
+        }
+        (KeyCode::Char('N'), KeyModifiers::SHIFT) => {
+            app.toggle_skip_noise();
+            app.status = Some(if app.skip_noise {
+                "Skipping noise blocks".to_string()
+            } else {
+                "Showing noise blocks in navigation".to_string()
+            });
+            Vec::new()
+        }
+        (KeyCode::Char(digit @ '1'..='9'), m) if m.contains(KeyModifiers::ALT) => {
+            tabs::switch(app, digit as usize - '1' as usize)
+        }
+        (KeyCode::Char('X'), KeyModifiers::SHIFT) => tabs::close(app),
         (KeyCode::Enter, _) => {
             app.start_editing();
             Vec::new()
@@ -78,3 +415,77 @@ pub fn handle_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Ve
         _ => Vec::new(),
     }
 }
+
+/// Add a digit to the count for the next motion. `1`-`3` still select their
+/// action straight away, in case no motion follows
+fn push_count_digit(app: &mut App, digit: char) {
+    let action = match app.count {
+        Some(count) => count.action,
+        None => app.selected_action,
+    };
+    let n = app.count.map_or(0, |count| count.n);
+    if n == 0 {
+        match digit {
+            '0' => return,
+            '1' => app.selected_action = ReviewAction::RequestChanges,
+            '2' => app.selected_action = ReviewAction::ClarificationQuestions,
+            '3' => app.selected_action = ReviewAction::NextPr,
+            _ => {}
+        }
+    }
+    let n = (n * 10 + digit.to_digit(10).unwrap_or(0) as usize).min(MAX_COUNT);
+    app.count = Some(Count { n, action });
+}
+
+/// Keys a count repeats
+fn is_motion(code: KeyCode, modifiers: KeyModifiers) -> bool {
+    matches!(
+        (code, modifiers),
+        (
+            KeyCode::Char('j' | 'k' | 'h' | 'l' | 'n' | 'p' | 'b' | ' '),
+            KeyModifiers::NONE
+        ) | (KeyCode::Char('{' | '}'), _)
+            | (
+                KeyCode::Down
+                    | KeyCode::Up
+                    | KeyCode::Left
+                    | KeyCode::Right
+                    | KeyCode::Tab
+                    | KeyCode::BackTab
+                    | KeyCode::PageDown
+                    | KeyCode::PageUp,
+                _
+            )
+    )
+}
+
+/// Open the PR body's next image in the browser, cycling through them
+fn open_next_attachment(app: &mut App) -> Vec<Command> {
+    let count = app.attachments().len();
+    let Some((index, attachment)) = app.next_attachment() else {
+        app.status = Some("The PR description has no images".to_string());
+        return Vec::new();
+    };
+    app.status = Some(format!(
+        "Opening image {} of {}: {}",
+        index + 1,
+        count,
+        attachment.label
+    ));
+    vec![Command::OpenInBrowser {
+        url: attachment.url,
+    }]
+}
+
+pub fn handle_browser_opened(app: &mut App, result: Result<(), String>) -> Vec<Command> {
+    if let Err(err) = result {
+        app.status = Some(format!("Couldn't open the browser: {}", err));
+    }
+    Vec::new()
+}
+
+fn save_preferences(app: &App) -> Vec<Command> {
+    vec![Command::SavePreferences {
+        preferences: app.preferences.clone(),
+    }]
+}
@@ -0,0 +1,85 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppState};
+use crate::command::Command;
+use crate::domain::text;
+
+/// Input while typing a workspace search query
+pub fn handle_query_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc => {
+            app.state = AppState::Viewing;
+            Vec::new()
+        }
+        KeyCode::Enter => {
+            if app.workspace_query.is_empty() {
+                app.state = AppState::Viewing;
+                return Vec::new();
+            }
+            let missing: Vec<String> = app
+                .changed_files
+                .iter()
+                .map(|f| f.path.clone())
+                .filter(|path| !app.head_files.contains_key(path))
+                .collect();
+            if missing.is_empty() {
+                app.run_workspace_search();
+                return Vec::new();
+            }
+
+            let Some(pr) = &app.pr else {
+                app.status = Some("Missing PR context".to_string());
+                app.state = AppState::Viewing;
+                return Vec::new();
+            };
+            let command = Command::FetchHeadFiles {
+                owner: pr.owner.clone(),
+                repo: pr.repo.clone(),
+                number: pr.number,
+                sha: pr.head_sha.clone(),
+                paths: missing,
+            };
+            app.state = AppState::LoadingWorkspaceFiles;
+            vec![command]
+        }
+        KeyCode::Backspace => {
+            text::pop_grapheme(&mut app.workspace_query);
+            Vec::new()
+        }
+        KeyCode::Char(c) => {
+            app.workspace_query.push(c);
+            Vec::new()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Input while browsing workspace search results
+pub fn handle_results_input(app: &mut App, code: KeyCode) -> Vec<Command> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.state = AppState::Viewing;
+            Vec::new()
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.workspace_down();
+            Vec::new()
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.workspace_up();
+            Vec::new()
+        }
+        KeyCode::Char('S') | KeyCode::Char('/') => {
+            app.state = AppState::WorkspaceSearch;
+            Vec::new()
+        }
+        KeyCode::Enter => {
+            if !app.jump_to_workspace_match() {
+                app.status = Some("No diff block in the story covers this file".to_string());
+            }
+            app.state = AppState::Viewing;
+            Vec::new()
+        }
+        _ => Vec::new(),
+    }
+}